@@ -50,6 +50,12 @@ hidden_imports = [
     'fastapi',
     'speech_recognition',
     'pyaudio',
+    'msoffcrypto',
+    'keyring',
+    'keyring.backends.Windows',
+    'win32com',
+    'win32com.client',
+    'win32wnet',
 ]
 
 # Add all chromadb submodules
@@ -77,6 +83,56 @@ a = Analysis(
         ('resource_monitor.py', '.'),
         ('ocr_processor.py', '.'),
         ('batch_processor.py', '.'),
+        ('collection_analysis.py', '.'),
+        ('directory_scanner.py', '.'),
+        ('trash_store.py', '.'),
+        ('processing_presets.py', '.'),
+        ('document_diff.py', '.'),
+        ('retrieval_eval.py', '.'),
+        ('shadow_index.py', '.'),
+        ('collection_migration.py', '.'),
+        ('static_export.py', '.'),
+        ('usage_stats.py', '.'),
+        ('crash_reporter.py', '.'),
+        ('workload_lanes.py', '.'),
+        ('audit_log.py', '.'),
+        ('keyword_scoring.py', '.'),
+        ('ocr_languages.py', '.'),
+        ('page_thumbnails.py', '.'),
+        ('retention_policy.py', '.'),
+        ('collection_quota.py', '.'),
+        ('enterprise_policy.py', '.'),
+        ('setup_wizard.py', '.'),
+        ('app_data.py', '.'),
+        ('legacy_data.py', '.'),
+        ('jan_threads.py', '.'),
+        ('assistant_bindings.py', '.'),
+        ('webhooks.py', '.'),
+        ('capabilities.py', '.'),
+        ('error_catalog.py', '.'),
+        ('progress_narration.py', '.'),
+        ('chunk_tuning.py', '.'),
+        ('collection_export.py', '.'),
+        ('external_import.py', '.'),
+        ('app_paths.py', '.'),
+        ('data_relocation.py', '.'),
+        ('job_workspace.py', '.'),
+        ('request_context.py', '.'),
+        ('event_log.py', '.'),
+        ('collection_optimizer.py', '.'),
+        ('history_export.py', '.'),
+        ('file_type_handlers.py', '.'),
+        ('safe_mode.py', '.'),
+        ('python_status.py', '.'),
+        ('settings_store.py', '.'),
+        ('collection_names.py', '.'),
+        ('collection_appearance.py', '.'),
+        ('source_search.py', '.'),
+        ('duplicate_check.py', '.'),
+        ('document_passwords.py', '.'),
+        ('collection_holds.py', '.'),
+    ('network_shares.py', '.'),
+    ('scan_snapshots.py', '.'),
     ] + sentence_transformers_datas + chromadb_datas,
     hiddenimports=hidden_imports,
     hookspath=[],
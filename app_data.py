@@ -0,0 +1,299 @@
+"""
+App Data Management for Jan Document Plugin
+
+Everything the plugin writes lives in the storage folder (STORAGE_DIR),
+plus an optional autostart entry added by the installer. This module:
+- Backs the storage folder up to a zip archive
+- Prepares an uninstall: removes caches and, unless the knowledge base
+  is kept, the vector store and everything else in the storage folder
+  (optionally saving a final backup first), then removes the autostart
+  entry and reports exactly what was removed
+- Resets the knowledge base: the collection is replaced by an empty one
+  and processing history is wiped, while settings stay
+
+Caches (thumbnails, crash reports, evaluation reports, usage counters,
+batch state, webhook delivery log, event log, job temp files) can always be removed;
+the rest is the knowledge base. Job temp files are left out of backups.
+"""
+
+import sys
+import shutil
+import zipfile
+import logging
+from pathlib import Path
+from typing import Dict, List, Optional
+from dataclasses import dataclass, field, asdict
+from datetime import datetime
+
+logger = logging.getLogger(__name__)
+
+# Job temp folder (see job_workspace), never backed up
+TEMP_ENTRY = "work"
+
+# Storage folder entries that can be rebuilt or are only diagnostic
+CACHE_ENTRIES = (
+    "thumbnails",
+    "crash_reports",
+    "eval_reports",
+    "usage_stats.json",
+    "processing_state.json",
+    "webhook_deliveries.jsonl",
+    "event_log.jsonl",
+    "startup_state.json",
+    TEMP_ENTRY,
+)
+
+# Autostart value written by the installer (installer/setup.iss)
+AUTOSTART_REGISTRY_KEY = r"Software\Microsoft\Windows\CurrentVersion\Run"
+AUTOSTART_VALUE_NAME = "JanDocumentPlugin"
+
+# Processing history wiped by a knowledge base reset (settings are kept)
+HISTORY_ENTRIES = ("trash", "processing_state.json", "jan_threads_cursor.json")
+
+# Suffixes of the collections used while swapping in the empty collection
+RESET_SUFFIX = "_reset"
+RESET_PREVIOUS_SUFFIX = "_before_reset"
+
+
+def _size(path: Path) -> int:
+    if path.is_file():
+        return path.stat().st_size
+    return sum(p.stat().st_size for p in path.rglob("*") if p.is_file())
+
+
+def create_backup(storage_directory: Path, destination: Path) -> Dict:
+    """
+    Zip the storage folder into destination.
+
+    Args:
+        storage_directory: Folder to back up
+        destination: Folder the archive is written to (created if needed)
+
+    Returns:
+        Dict with path, files and size_bytes of the archive
+
+    Raises:
+        ValueError: If destination is inside the storage folder
+        FileNotFoundError: If the storage folder doesn't exist
+    """
+    storage_directory = Path(storage_directory).resolve()
+    destination = Path(destination).resolve()
+    if not storage_directory.is_dir():
+        raise FileNotFoundError(f"Storage folder not found: {storage_directory}")
+    if destination == storage_directory or storage_directory in destination.parents:
+        raise ValueError("The backup must be written outside the storage folder")
+
+    destination.mkdir(parents=True, exist_ok=True)
+    archive = destination / f"{storage_directory.name}_backup_{datetime.now().strftime('%Y%m%d_%H%M%S')}.zip"
+    files = 0
+    with zipfile.ZipFile(archive, "w", compression=zipfile.ZIP_DEFLATED) as zf:
+        for path in sorted(storage_directory.rglob("*")):
+            relative = path.relative_to(storage_directory)
+            if relative.parts[0] == TEMP_ENTRY:
+                continue
+            if path.is_file():
+                zf.write(path, relative)
+                files += 1
+
+    logger.info(f"Backed up {files} file(s) from {storage_directory} to {archive}")
+    return {"path": str(archive), "files": files, "size_bytes": archive.stat().st_size}
+
+
+def remove_autostart_entry() -> List[str]:
+    """Remove the installer's autostart value. Returns the values removed."""
+    if sys.platform != "win32":
+        return []
+    import winreg
+
+    try:
+        with winreg.OpenKey(
+            winreg.HKEY_CURRENT_USER, AUTOSTART_REGISTRY_KEY, 0, winreg.KEY_SET_VALUE
+        ) as key:
+            winreg.DeleteValue(key, AUTOSTART_VALUE_NAME)
+    except FileNotFoundError:
+        return []
+    return [f"HKCU\\{AUTOSTART_REGISTRY_KEY}\\{AUTOSTART_VALUE_NAME}"]
+
+
+@dataclass
+class UninstallReport:
+    """What prepare_uninstall removed and kept."""
+    storage_directory: str
+    keep_knowledge: bool
+    removed: List[Dict] = field(default_factory=list)   # path, size_bytes
+    kept: List[str] = field(default_factory=list)
+    backup: Optional[Dict] = None
+    registry_values_removed: List[str] = field(default_factory=list)
+    errors: List[str] = field(default_factory=list)
+
+    @property
+    def removed_bytes(self) -> int:
+        return sum(r["size_bytes"] for r in self.removed)
+
+    def to_dict(self) -> Dict:
+        return {**asdict(self), "removed_bytes": self.removed_bytes}
+
+
+def prepare_uninstall(
+    storage_directory: Path,
+    keep_knowledge: bool = False,
+    backup_directory: Optional[Path] = None
+) -> UninstallReport:
+    """
+    Remove the plugin's data ahead of an uninstall.
+
+    Callers must stop background work and release the vector store
+    first (open database files cannot be removed on Windows).
+
+    Args:
+        storage_directory: The storage folder (STORAGE_DIR)
+        keep_knowledge: Remove only caches, keeping the knowledge base
+        backup_directory: Save a final backup here before removing anything
+
+    Raises:
+        ValueError: If backup_directory is inside the storage folder
+    """
+    storage_directory = Path(storage_directory).resolve()
+    report = UninstallReport(storage_directory=str(storage_directory), keep_knowledge=keep_knowledge)
+
+    if storage_directory.is_dir():
+        if backup_directory is not None:
+            report.backup = create_backup(storage_directory, backup_directory)
+
+        for entry in sorted(storage_directory.iterdir()):
+            if keep_knowledge and entry.name not in CACHE_ENTRIES:
+                report.kept.append(str(entry))
+                continue
+            size = _size(entry)
+            try:
+                if entry.is_dir():
+                    shutil.rmtree(entry)
+                else:
+                    entry.unlink()
+            except OSError as e:
+                report.errors.append(f"{entry}: {e}")
+                continue
+            report.removed.append({"path": str(entry), "size_bytes": size})
+
+        if not keep_knowledge and not report.errors:
+            try:
+                storage_directory.rmdir()
+                report.removed.append({"path": str(storage_directory), "size_bytes": 0})
+            except OSError as e:
+                report.errors.append(f"{storage_directory}: {e}")
+
+    try:
+        report.registry_values_removed = remove_autostart_entry()
+    except OSError as e:
+        report.errors.append(f"Autostart entry: {e}")
+
+    logger.info(
+        f"Uninstall cleanup: removed {len(report.removed)} item(s) "
+        f"({report.removed_bytes / (1024 * 1024):.1f}MB), kept {len(report.kept)}"
+    )
+    return report
+
+
+@dataclass
+class ResetReport:
+    """What reset_knowledge_base wiped."""
+    collection: str
+    documents_removed: int = 0
+    chunks_removed: int = 0
+    collections_deleted: List[str] = field(default_factory=list)
+    history_removed: List[str] = field(default_factory=list)
+    backup: Optional[Dict] = None
+    errors: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+def reset_knowledge_base(
+    processor,
+    storage_directory: Path,
+    backup_directory: Optional[Path] = None
+) -> ResetReport:
+    """
+    Wipe the knowledge base and start again with an empty collection.
+
+    An empty collection is created first and swapped in by renaming, so
+    if the swap fails the index is left as it was. Afterwards every other
+    collection in the store (shadow indexes, leftover staging
+    collections) is deleted, processed document records are forgotten
+    and processing history (trash, batch state) is removed. Settings,
+    presets and the audit log are kept.
+
+    Args:
+        processor: document_processor.DocumentProcessor to reset
+        storage_directory: The storage folder (STORAGE_DIR)
+        backup_directory: Save a backup here first; the reset is not
+                          started if the backup fails
+
+    Raises:
+        ValueError: If backup_directory is inside the storage folder
+        OSError: If the backup could not be written
+        CollectionReadOnlyError: If the collection is read-only
+    """
+    store = processor.vector_store
+    store.check_writable("reset")
+    client = store.client
+    name = store.collection.name
+    report = ResetReport(collection=name)
+
+    storage_directory = Path(storage_directory)
+    if backup_directory is not None:
+        report.backup = create_backup(storage_directory, backup_directory)
+
+    report.documents_removed = len(processor.processed_docs)
+    report.chunks_removed = store.get_document_count()
+
+    staging_name = f"{name}{RESET_SUFFIX}"
+    previous_name = f"{name}{RESET_PREVIOUS_SUFFIX}"
+    existing = [getattr(c, "name", c) for c in client.list_collections()]
+    for leftover in (staging_name, previous_name):
+        if leftover in existing:
+            client.delete_collection(leftover)
+
+    staging = client.get_or_create_collection(
+        name=staging_name,
+        metadata={"hnsw:space": "cosine", "embedding_model": store.embedding_model}
+    )
+    store.collection.modify(name=previous_name)
+    try:
+        staging.modify(name=name)
+    except Exception:
+        client.get_collection(previous_name).modify(name=name)
+        client.delete_collection(staging_name)
+        raise
+    store.collection = client.get_collection(name)
+    processor.processed_docs.clear()
+    processor.last_indexed_at = None
+
+    for other in [getattr(c, "name", c) for c in client.list_collections()]:
+        if other == name:
+            continue
+        try:
+            client.delete_collection(other)
+            report.collections_deleted.append(other)
+        except Exception as e:
+            report.errors.append(f"Collection {other}: {e}")
+
+    for entry_name in HISTORY_ENTRIES:
+        entry = storage_directory / entry_name
+        paths = list(entry.iterdir()) if entry.is_dir() else [entry] if entry.exists() else []
+        for path in paths:
+            try:
+                if path.is_dir():
+                    shutil.rmtree(path)
+                else:
+                    path.unlink()
+                report.history_removed.append(str(path))
+            except OSError as e:
+                report.errors.append(f"{path}: {e}")
+
+    logger.info(
+        f"Knowledge base reset: removed {report.documents_removed} document(s), "
+        f"{report.chunks_removed} chunk(s); {name} is empty"
+    )
+    return report
@@ -0,0 +1,167 @@
+"""
+Application Paths for Jan Document Plugin
+
+One place that decides where the plugin reads and writes files, so the
+launcher, the proxy and the setup wizard agree:
+
+- App folder: where the executable (or, from source, this file) lives;
+  config.env and the bundled tesseract / models folders are here
+- Resource folder: PyInstaller's unpacked bundle (the app folder when
+  running from source)
+- Data root: base for relative data paths such as STORAGE_DIR, and for
+  the embedding model cache in portable mode
+
+Portable mode keeps everything beside the executable (e.g. on a USB
+stick): the data root becomes <app>/data and downloaded embedding models
+are cached in <app>/data/models instead of the user's profile. It is on
+when a portable.flag file exists in the app folder, or PORTABLE=true is
+set in config.env or the environment.
+
+After the data was moved to another disk (see data_relocation), the new
+folders are recorded in <app>/data_location.json and take precedence
+over STORAGE_DIR and the portable model cache.
+"""
+
+import os
+import sys
+import json
+import logging
+from pathlib import Path
+from typing import Dict, Optional, Union
+
+logger = logging.getLogger(__name__)
+
+PORTABLE_FLAG = "portable.flag"
+PORTABLE_DATA_DIR = "data"
+PORTABLE_MODEL_CACHE = "models"
+
+# Folders moved by data relocation: {"storage_directory": ..., "model_cache": ...}
+DATA_LOCATION_FILE = "data_location.json"
+
+TRUE_VALUES = ("1", "true", "yes", "on")
+
+
+def get_app_path() -> Path:
+    """Folder of the executable (frozen) or of this file."""
+    if getattr(sys, "frozen", False):
+        return Path(sys.executable).parent
+    return Path(__file__).parent
+
+
+def get_resource_path() -> Path:
+    """Folder bundled resources are unpacked to (PyInstaller's _MEIPASS)."""
+    if getattr(sys, "frozen", False):
+        return Path(sys._MEIPASS)
+    return Path(__file__).parent
+
+
+def is_portable(setting: Optional[str] = None, app_path: Optional[Path] = None) -> bool:
+    """
+    Whether portable mode is on.
+
+    Args:
+        setting: PORTABLE value from config.env, if loaded
+        app_path: App folder to look for portable.flag in (None = this app's)
+    """
+    if ((app_path or get_app_path()) / PORTABLE_FLAG).exists():
+        return True
+    value = setting if setting is not None else os.environ.get("PORTABLE", "")
+    return value.strip().lower() in TRUE_VALUES
+
+
+def get_data_root(portable: Optional[bool] = None, app_path: Optional[Path] = None) -> Path:
+    """Base folder for relative data paths: <app>/data when portable, else <app>."""
+    app_path = app_path or get_app_path()
+    if portable is None:
+        portable = is_portable(app_path=app_path)
+    return app_path / PORTABLE_DATA_DIR if portable else app_path
+
+
+def resolve_data_path(
+    path: Union[str, Path],
+    portable: Optional[bool] = None,
+    app_path: Optional[Path] = None
+) -> Path:
+    """A configured data path; relative paths are resolved against the data root."""
+    path = Path(path)
+    if path.is_absolute():
+        return path
+    return get_data_root(portable, app_path) / path
+
+
+def load_location_override(app_path: Optional[Path] = None) -> Dict[str, str]:
+    """Folders recorded by data relocation ({} if the data was never moved)."""
+    path = (app_path or get_app_path()) / DATA_LOCATION_FILE
+    if not path.exists():
+        return {}
+    try:
+        data = json.loads(path.read_text(encoding="utf-8"))
+    except (OSError, ValueError) as e:
+        logger.warning(f"Could not read {DATA_LOCATION_FILE}: {e}")
+        return {}
+    return {k: v for k, v in data.items() if isinstance(v, str) and v}
+
+
+def save_location_override(override: Dict[str, str], app_path: Optional[Path] = None):
+    """Record moved folders; an empty override removes the file."""
+    path = (app_path or get_app_path()) / DATA_LOCATION_FILE
+    if not override:
+        path.unlink(missing_ok=True)
+        return
+    temp = path.with_suffix(".tmp")
+    temp.write_text(json.dumps(override, indent=2), encoding="utf-8")
+    temp.replace(path)
+
+
+def get_storage_directory(
+    configured: Union[str, Path],
+    portable: Optional[bool] = None,
+    app_path: Optional[Path] = None
+) -> Path:
+    """The storage folder: the relocated one if the data was moved, else STORAGE_DIR."""
+    override = load_location_override(app_path).get("storage_directory")
+    if override:
+        return Path(override)
+    return resolve_data_path(configured, portable, app_path)
+
+
+def get_model_cache_dir(portable: Optional[bool] = None, app_path: Optional[Path] = None) -> Optional[Path]:
+    """Embedding model cache folder (None = the library default in the user's profile)."""
+    override = load_location_override(app_path).get("model_cache")
+    if override:
+        return Path(override)
+    if portable is None:
+        portable = is_portable(app_path=app_path)
+    if not portable:
+        return None
+    return get_data_root(True, app_path) / PORTABLE_MODEL_CACHE
+
+
+def apply_model_cache_environment(portable: Optional[bool] = None, app_path: Optional[Path] = None):
+    """
+    Point sentence-transformers / Hugging Face downloads at the model cache.
+
+    Must run before the embedding model is loaded. Variables the user set
+    explicitly are left alone.
+    """
+    cache_dir = get_model_cache_dir(portable, app_path)
+    if cache_dir is None:
+        return
+    cache_dir.mkdir(parents=True, exist_ok=True)
+    os.environ.setdefault("SENTENCE_TRANSFORMERS_HOME", str(cache_dir))
+    os.environ.setdefault("HF_HOME", str(cache_dir / "huggingface"))
+
+
+def describe_paths(storage_directory: Union[str, Path], portable: Optional[bool] = None) -> Dict:
+    """Where the plugin keeps its files (for diagnostics)."""
+    if portable is None:
+        portable = is_portable()
+    cache_dir = get_model_cache_dir(portable)
+    return {
+        "portable": portable,
+        "relocated": bool(load_location_override()),
+        "app_path": str(get_app_path()),
+        "data_root": str(get_data_root(portable)),
+        "storage_directory": str(Path(storage_directory).resolve()),
+        "model_cache": str(cache_dir) if cache_dir else os.environ.get("SENTENCE_TRANSFORMERS_HOME")
+    }
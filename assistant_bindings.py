@@ -0,0 +1,121 @@
+"""
+Assistant Collection Bindings for Jan Document Plugin
+
+Records which knowledge collection each Jan assistant uses. The
+document store serves a single collection, so a binding can only name
+that collection and queries are never routed anywhere else: a query
+with an assistant_id checks the assistant's binding against the store's
+collection (404 if it no longer matches, e.g. after the plugin was
+started with another DEFAULT_COLLECTION) and reports the collection used.
+
+Bindings are stored as JSON keyed by assistant id:
+<storage>/assistant_bindings.json
+"""
+
+import json
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, List, Optional
+from datetime import datetime
+
+from collection_names import check_collection, validate_collection_name
+
+logger = logging.getLogger(__name__)
+
+
+class AssistantBindingStore:
+    """
+    Assistant id -> default collection, persisted as JSON.
+
+    Usage:
+        bindings = AssistantBindingStore(processor, Path("./jan_doc_store/assistant_bindings.json"))
+        bindings.bind_assistant_collection("jan", "jan_documents")
+        bindings.resolve_collection("jan")
+    """
+
+    def __init__(self, document_processor, binding_path: Path):
+        """
+        Initialize binding store.
+
+        Args:
+            document_processor: Processor whose collections can be bound
+            binding_path: JSON file holding bindings by assistant id
+        """
+        self.processor = document_processor
+        self.binding_path = Path(binding_path)
+        self._lock = threading.Lock()
+        self._bindings: Dict[str, Dict] = {}
+        if self.binding_path.exists():
+            try:
+                self._bindings = json.loads(self.binding_path.read_text(encoding="utf-8"))
+            except (OSError, ValueError) as e:
+                logger.warning(f"Could not read assistant bindings: {e}")
+
+    def _collection_name(self, collection: Optional[str]) -> str:
+        name = self.processor.vector_store.collection.name
+        check_collection(collection, name)
+        return name
+
+    def _save(self):
+        self.binding_path.parent.mkdir(parents=True, exist_ok=True)
+        self.binding_path.write_text(json.dumps(self._bindings, indent=2), encoding="utf-8")
+
+    def bind_assistant_collection(self, assistant_id: str, collection: str) -> Dict:
+        """
+        Make collection the assistant's default, replacing any previous binding.
+
+        Raises:
+            ValueError: If assistant_id is empty, or collection is not the
+                        store's collection (the only one queries can use)
+        """
+        assistant_id = assistant_id.strip()
+        if not assistant_id:
+            raise ValueError("assistant_id is required")
+        name = self.processor.vector_store.collection.name
+        validate_collection_name(collection)
+        if collection != name:
+            raise ValueError(
+                f"Only the store's collection ({name}) can be bound; queries can't be routed to {collection}"
+            )
+        binding = {
+            "assistant_id": assistant_id,
+            "collection": name,
+            "bound_at": datetime.now().isoformat()
+        }
+        with self._lock:
+            self._bindings[assistant_id] = binding
+            self._save()
+        logger.info(f"Assistant {assistant_id} bound to collection {binding['collection']}")
+        return binding
+
+    def unbind_assistant(self, assistant_id: str):
+        """
+        Raises:
+            KeyError: If the assistant has no binding
+        """
+        with self._lock:
+            if self._bindings.pop(assistant_id, None) is None:
+                raise KeyError(f"No collection bound to assistant: {assistant_id}")
+            self._save()
+
+    def list_assistant_bindings(self) -> List[Dict]:
+        """Bindings sorted by assistant id; stale ones (collection gone) are flagged."""
+        name = self.processor.vector_store.collection.name
+        return [
+            {**b, "available": b["collection"] == name}
+            for b in sorted(self._bindings.values(), key=lambda b: b["assistant_id"])
+        ]
+
+    def resolve_collection(self, assistant_id: Optional[str], collection: Optional[str] = None) -> str:
+        """
+        Collection a query uses, which is always the store's collection:
+        an explicit collection or the assistant's binding is only checked
+        against it.
+
+        Raises:
+            KeyError: If the collection asked for (or bound) is not the store's
+        """
+        if not collection and assistant_id in self._bindings:
+            collection = self._bindings[assistant_id]["collection"]
+        return self._collection_name(collection)
@@ -0,0 +1,83 @@
+"""
+Audit Log for Jan Document Plugin
+
+Append-only record of changes made to the index by hand or by policy
+(chunk edits and deletions, automatic pruning, ...), so it is always
+possible to see what was changed, when, and why.
+
+Entries are JSON lines in a single file: <storage>/audit_log.jsonl
+"""
+
+import json
+import logging
+import threading
+from pathlib import Path
+from typing import List, Dict, Optional
+from datetime import datetime
+
+logger = logging.getLogger(__name__)
+
+
+class AuditLog:
+    """
+    Append-only JSON-lines audit log.
+
+    Usage:
+        audit = AuditLog(Path("./jan_doc_store/audit_log.jsonl"))
+        audit.record("chunk_updated", "abc123_4", {"old_text": "...", "new_text": "..."})
+        audit.list_entries(action="chunk_updated")
+    """
+
+    def __init__(self, path: Path):
+        """
+        Initialize audit log.
+
+        Args:
+            path: JSON-lines file entries are appended to
+        """
+        self.path = Path(path)
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self._lock = threading.Lock()
+
+    def record(self, action: str, target: str, details: Optional[Dict] = None) -> Dict:
+        """Append an entry and return it."""
+        entry = {
+            "time": datetime.now().isoformat(),
+            "action": action,
+            "target": target,
+            "details": details or {}
+        }
+        with self._lock:
+            try:
+                with open(self.path, "a", encoding="utf-8") as f:
+                    f.write(json.dumps(entry, ensure_ascii=False) + "\n")
+            except OSError as e:
+                logger.warning(f"Could not write audit entry ({action} {target}): {e}")
+        return entry
+
+    def list_entries(
+        self,
+        limit: int = 100,
+        action: Optional[str] = None,
+        target: Optional[str] = None
+    ) -> List[Dict]:
+        """Most recent entries first, optionally filtered by action and/or target."""
+        if not self.path.exists():
+            return []
+
+        entries = []
+        with self._lock:
+            with open(self.path, encoding="utf-8") as f:
+                for line in f:
+                    try:
+                        entry = json.loads(line)
+                    except ValueError:
+                        continue
+                    if action and entry.get("action") != action:
+                        continue
+                    if target and entry.get("target") != target:
+                        continue
+                    entries.append(entry)
+
+        entries.reverse()
+        return entries[:limit]
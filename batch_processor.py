@@ -9,18 +9,25 @@ Handles multiple file uploads with:
 """
 
 import os
+import json
 import asyncio
 import logging
+import contextvars
 from pathlib import Path
-from typing import List, Dict, Optional, Callable, Any
-from dataclasses import dataclass, field
+from typing import List, Dict, Optional, Callable, Any, Sequence
+from dataclasses import dataclass, field, asdict
 from enum import Enum
 from datetime import datetime
 from concurrent.futures import ThreadPoolExecutor, as_completed
 import threading
 import time
 
-from document_processor import DocumentProcessor, ProcessedDocument
+from document_processor import DocumentProcessor, ProcessedDocument, PasswordRequiredError, WrongPasswordError
+from processing_presets import ProcessingPreset
+from duplicate_check import check_duplicate
+from workload_lanes import get_workload_lanes
+from progress_narration import ProgressNarrator
+from request_context import get_request_id
 from resource_monitor import (
     ResourceMonitor, 
     get_resource_monitor, 
@@ -29,6 +36,9 @@ from resource_monitor import (
     LoadCapacity
 )
 
+# Failures a batch can be resumed from once the passwords are stored
+PASSWORD_ERROR_TYPES = (PasswordRequiredError.error_type, WrongPasswordError.error_type)
+
 logger = logging.getLogger(__name__)
 
 
@@ -47,14 +57,20 @@ class FileProgress:
     filename: str
     file_path: str
     size_mb: float
+    source_path: Optional[str] = None  # Original path/filename when file_path is a temp copy
     status: FileStatus = FileStatus.QUEUED
     progress_percent: float = 0.0
     chunks_created: int = 0
     error_message: Optional[str] = None
+    error_type: Optional[str] = None  # Machine-readable failure kind, e.g. "file_locked"
     started_at: Optional[datetime] = None
     completed_at: Optional[datetime] = None
     ocr_used: bool = False
     ocr_pages: int = 0
+    ocr_page_numbers: List[int] = field(default_factory=list)
+    low_confidence_pages: List[Dict] = field(default_factory=list)
+    partial: bool = False  # Extraction timed out; partial content indexed
+    duplicate_of: Optional[Dict] = None  # Indexed document this file was skipped as a duplicate of
     
     def to_dict(self) -> Dict:
         return {
@@ -64,6 +80,7 @@ class FileProgress:
             "progress_percent": round(self.progress_percent, 1),
             "chunks_created": self.chunks_created,
             "error_message": self.error_message,
+            "error_type": self.error_type,
             "started_at": self.started_at.isoformat() if self.started_at else None,
             "completed_at": self.completed_at.isoformat() if self.completed_at else None,
             "duration_seconds": (
@@ -71,7 +88,11 @@ class FileProgress:
                 if self.started_at and self.completed_at else None
             ),
             "ocr_used": self.ocr_used,
-            "ocr_pages": self.ocr_pages
+            "ocr_pages": self.ocr_pages,
+            "ocr_page_numbers": self.ocr_page_numbers,
+            "low_confidence_pages": self.low_confidence_pages,
+            "partial": self.partial,
+            "duplicate_of": self.duplicate_of
         }
 
 
@@ -82,6 +103,7 @@ class BatchProgress:
     total_files: int
     completed_files: int = 0
     failed_files: int = 0
+    skipped_files: int = 0               # Skipped as near-duplicates
     total_chunks: int = 0
     files: List[FileProgress] = field(default_factory=list)
     processing_mode: ProcessingMode = ProcessingMode.SEQUENTIAL
@@ -90,16 +112,21 @@ class BatchProgress:
     completed_at: Optional[datetime] = None
     warnings: List[str] = field(default_factory=list)
     ocr_analysis: Optional[Dict] = None  # From resource_monitor.BatchOCRAnalysis
+    ignore_power_state: bool = False     # Run even on battery / battery saver
+    deferred_reason: Optional[str] = None  # Set while waiting for AC power
+    preset: Optional[ProcessingPreset] = None  # Settings applied to every file
+    request_id: Optional[str] = None     # API request that started the batch
+    duplicate_threshold: Optional[float] = None  # Skip files this similar to an indexed one (None = off)
     
     @property
     def progress_percent(self) -> float:
         if self.total_files == 0:
             return 100.0
-        return (self.completed_files + self.failed_files) / self.total_files * 100
+        return (self.completed_files + self.failed_files + self.skipped_files) / self.total_files * 100
     
     @property
     def is_complete(self) -> bool:
-        return (self.completed_files + self.failed_files) >= self.total_files
+        return (self.completed_files + self.failed_files + self.skipped_files) >= self.total_files
     
     def to_dict(self) -> Dict:
         return {
@@ -107,6 +134,7 @@ class BatchProgress:
             "total_files": self.total_files,
             "completed_files": self.completed_files,
             "failed_files": self.failed_files,
+            "skipped_files": self.skipped_files,
             "progress_percent": round(self.progress_percent, 1),
             "total_chunks": self.total_chunks,
             "processing_mode": self.processing_mode.value,
@@ -116,6 +144,58 @@ class BatchProgress:
             "is_complete": self.is_complete,
             "warnings": self.warnings,
             "ocr_analysis": self.ocr_analysis,
+            "deferred_reason": self.deferred_reason,
+            "preset": self.preset.name if self.preset else None,
+            "request_id": self.request_id,
+            "duplicate_threshold": self.duplicate_threshold,
+            "files": [f.to_dict() for f in self.files]
+        }
+
+
+@dataclass
+class FileDryRun:
+    """What indexing one file would do."""
+    filename: str
+    size_mb: float
+    status: str = "would_index"   # would_index, would_skip, failed
+    projected_chunks: int = 0
+    tokens_estimate: int = 0
+    ocr_pages: int = 0
+    warnings: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict:
+        return {**asdict(self), "size_mb": round(self.size_mb, 2)}
+
+
+@dataclass
+class BatchDryRunReport:
+    """Projection of a batch: extraction and chunking only, nothing stored."""
+    total_files: int
+    projected_chunks: int = 0
+    tokens_estimate: int = 0
+    estimated_embedding_seconds: float = 0.0
+    extraction_seconds: float = 0.0
+    processing_mode: ProcessingMode = ProcessingMode.SEQUENTIAL
+    worker_count: int = 1
+    warnings: List[str] = field(default_factory=list)
+    ocr_analysis: Optional[Dict] = None
+    files: List[FileDryRun] = field(default_factory=list)
+
+    def to_dict(self) -> Dict:
+        return {
+            "dry_run": True,
+            "total_files": self.total_files,
+            "files_to_index": sum(1 for f in self.files if f.status == "would_index"),
+            "files_to_skip": sum(1 for f in self.files if f.status == "would_skip"),
+            "failed_files": sum(1 for f in self.files if f.status == "failed"),
+            "projected_chunks": self.projected_chunks,
+            "tokens_estimate": self.tokens_estimate,
+            "estimated_embedding_seconds": round(self.estimated_embedding_seconds, 1),
+            "extraction_seconds": round(self.extraction_seconds, 1),
+            "processing_mode": self.processing_mode.value,
+            "worker_count": self.worker_count,
+            "warnings": self.warnings,
+            "ocr_analysis": self.ocr_analysis,
             "files": [f.to_dict() for f in self.files]
         }
 
@@ -134,7 +214,9 @@ class BatchProcessor:
     def __init__(
         self,
         document_processor: DocumentProcessor,
-        resource_monitor: Optional[ResourceMonitor] = None
+        resource_monitor: Optional[ResourceMonitor] = None,
+        state_path: Optional[Path] = None,
+        narrator: Optional[ProgressNarrator] = None
     ):
         """
         Initialize batch processor.
@@ -142,13 +224,107 @@ class BatchProcessor:
         Args:
             document_processor: DocumentProcessor instance for actual processing
             resource_monitor: Optional resource monitor (uses singleton if not provided)
+            state_path: Optional JSON file persisting the paused flag across restarts
+            narrator: Optional narrator turning progress into readable sentences
         """
         self.processor = document_processor
         self.monitor = resource_monitor or get_resource_monitor()
+        self.narrator = narrator
         
         self._active_batches: Dict[str, BatchProgress] = {}
         self._lock = threading.Lock()
         self._batch_counter = 0
+
+        # Set = running, cleared = paused. Workers wait on it before each file.
+        self._resume_event = threading.Event()
+        self._resume_event.set()
+        self._paused_at: Optional[datetime] = None
+        self._state_path = Path(state_path) if state_path else None
+        self._load_state()
+
+    # ------------------------------------------------------------------
+    # Pause / resume
+    # ------------------------------------------------------------------
+
+    def _load_state(self):
+        """Restore the persisted paused flag."""
+        if not self._state_path or not self._state_path.exists():
+            return
+        try:
+            state = json.loads(self._state_path.read_text(encoding="utf-8"))
+        except (OSError, ValueError) as e:
+            logger.warning(f"Could not read processing state: {e}")
+            return
+
+        if state.get("paused"):
+            self._resume_event.clear()
+            paused_at = state.get("paused_at")
+            self._paused_at = datetime.fromisoformat(paused_at) if paused_at else datetime.now()
+            logger.info("Batch processing is paused (restored from previous session)")
+
+    def _save_state(self):
+        """Persist the paused flag."""
+        if not self._state_path:
+            return
+        state = {
+            "paused": self.is_paused,
+            "paused_at": self._paused_at.isoformat() if self._paused_at else None
+        }
+        try:
+            self._state_path.parent.mkdir(parents=True, exist_ok=True)
+            self._state_path.write_text(json.dumps(state, indent=2), encoding="utf-8")
+        except OSError as e:
+            logger.warning(f"Could not save processing state: {e}")
+
+    @property
+    def is_paused(self) -> bool:
+        return not self._resume_event.is_set()
+
+    def pause(self):
+        """
+        Pause batch processing.
+
+        Files already being processed finish; queued files wait until resume().
+        """
+        if self.is_paused:
+            return
+        self._resume_event.clear()
+        self._paused_at = datetime.now()
+        self._save_state()
+        logger.info("Batch processing paused")
+        if self.narrator:
+            self.narrator.announce("paused", "Processing paused. Files in progress will finish; the rest wait.")
+
+    def resume(self):
+        """Resume batch processing."""
+        if not self.is_paused:
+            return
+        self._resume_event.set()
+        self._paused_at = None
+        self._save_state()
+        logger.info("Batch processing resumed")
+        if self.narrator:
+            self.narrator.announce("resumed", "Processing resumed.")
+
+    def _narrate(self, batch: BatchProgress):
+        if self.narrator:
+            self.narrator.observe(batch)
+
+    def get_processing_status(self) -> Dict:
+        """Paused flag, queued/processing file counts and query lane activity."""
+        with self._lock:
+            batches = [b for b in self._active_batches.values() if not b.is_complete]
+
+        files = [f for b in batches for f in b.files]
+        return {
+            "paused": self.is_paused,
+            "paused_at": self._paused_at.isoformat() if self._paused_at else None,
+            "active_batches": len(batches),
+            "deferred_reason": next((b.deferred_reason for b in batches if b.deferred_reason), None),
+            "queued_files": sum(1 for f in files if f.status == FileStatus.QUEUED),
+            "processing_files": sum(1 for f in files if f.status == FileStatus.PROCESSING),
+            "lanes": get_workload_lanes().get_status()
+        }
     
     def _generate_batch_id(self) -> str:
         """Generate unique batch ID."""
@@ -171,11 +347,33 @@ class BatchProcessor:
         """
         return self.monitor.create_processing_plan(file_infos)
     
+    def _wait_for_power(self, batch: BatchProgress):
+        """Block while the power state says heavy work should be deferred."""
+        if batch.ignore_power_state:
+            return
+        
+        interval = self.monitor.thresholds["power_check_interval_seconds"]
+        while True:
+            defer, reason = self.monitor.should_defer_heavy_work()
+            if not defer:
+                break
+            if batch.deferred_reason != reason:
+                logger.info(f"Batch {batch.batch_id} deferred: {reason}")
+                batch.deferred_reason = reason
+                self._narrate(batch)
+            time.sleep(interval)
+        
+        if batch.deferred_reason:
+            logger.info(f"Batch {batch.batch_id} continuing - power conditions OK")
+            batch.deferred_reason = None
+            self._narrate(batch)
+    
     def _process_single_file(
         self,
         file_path: str,
         file_progress: FileProgress,
-        force_reindex: bool = False
+        force_reindex: bool = False,
+        batch: Optional[BatchProgress] = None
     ) -> Optional[ProcessedDocument]:
         """
         Process a single file with progress tracking.
@@ -184,17 +382,54 @@ class BatchProcessor:
             file_path: Path to document
             file_progress: Progress tracker for this file
             force_reindex: Force reprocessing even if already indexed
+            batch: Owning batch (for power-state deferral, its preset and
+                   duplicate threshold)
             
         Returns:
-            ProcessedDocument or None if failed
+            ProcessedDocument or None if failed or skipped (see file_progress.status)
         """
+        if self.is_paused:
+            logger.info(f"Processing paused - {file_progress.filename} waiting to resume")
+        self._resume_event.wait()
+        
+        # Let in-flight queries finish before starting the next file
+        get_workload_lanes().yield_to_interactive()
+        
+        if batch is not None:
+            self._wait_for_power(batch)
+
         file_progress.status = FileStatus.PROCESSING
         file_progress.started_at = datetime.now()
         file_progress.progress_percent = 10.0
+        if batch is not None:
+            self._narrate(batch)
         
         try:
+            if batch is not None and batch.duplicate_threshold is not None and not force_reindex:
+                report = check_duplicate(
+                    self.processor,
+                    file_path,
+                    threshold=batch.duplicate_threshold,
+                    source_path=file_progress.source_path
+                )
+                if report["duplicate"]:
+                    match = report["matches"][0]
+                    file_progress.status = FileStatus.SKIPPED
+                    file_progress.duplicate_of = match
+                    file_progress.error_type = "duplicate"
+                    file_progress.error_message = f"Near-duplicate of {match['filename']} (score {match['score']})"
+                    file_progress.progress_percent = 100.0
+                    file_progress.completed_at = datetime.now()
+                    logger.info(f"Skipped {file_progress.filename}: {file_progress.error_message}")
+                    return None
+            
             # Process document
-            result = self.processor.ingest(file_path, force=force_reindex)
+            result = self.processor.ingest(
+                file_path,
+                force=force_reindex,
+                preset=batch.preset if batch else None,
+                source_path=file_progress.source_path
+            )
             
             file_progress.progress_percent = 100.0
             file_progress.status = FileStatus.COMPLETED
@@ -204,6 +439,9 @@ class BatchProcessor:
             # Track OCR usage from the result
             file_progress.ocr_used = result.ocr_used
             file_progress.ocr_pages = result.ocr_pages
+            file_progress.ocr_page_numbers = result.ocr_page_numbers
+            file_progress.low_confidence_pages = result.low_confidence_pages
+            file_progress.partial = result.partial
             
             ocr_info = f", OCR: {result.ocr_pages} pages" if result.ocr_used else ""
             logger.info(f"Processed {file_progress.filename}: {len(result.chunks)} chunks{ocr_info}")
@@ -212,6 +450,7 @@ class BatchProcessor:
         except Exception as e:
             file_progress.status = FileStatus.FAILED
             file_progress.error_message = str(e)
+            file_progress.error_type = getattr(e, "error_type", None)
             file_progress.completed_at = datetime.now()
             
             logger.error(f"Failed to process {file_progress.filename}: {e}")
@@ -221,7 +460,11 @@ class BatchProcessor:
         self,
         file_paths: List[str],
         force_reindex: bool = False,
-        progress_callback: Optional[Callable[[BatchProgress], None]] = None
+        progress_callback: Optional[Callable[[BatchProgress], None]] = None,
+        ignore_power_state: bool = False,
+        preset: Optional[ProcessingPreset] = None,
+        source_paths: Optional[List[str]] = None,
+        duplicate_threshold: Optional[float] = None
     ) -> BatchProgress:
         """
         Process multiple files synchronously with resource-aware parallelism.
@@ -230,22 +473,36 @@ class BatchProcessor:
             file_paths: List of file paths to process
             force_reindex: Force reprocessing of already indexed files
             progress_callback: Optional callback for progress updates
+            ignore_power_state: Process even on battery / in battery-saver mode
+            preset: Processing preset for every file (None = processor defaults)
+            source_paths: Original names for file_paths (same order), used as
+                          the document identity when file_paths are temp copies
+            duplicate_threshold: Skip files scoring at least this against an
+                                 indexed document (see duplicate_check;
+                                 None = index every file)
             
         Returns:
             BatchProgress with results
+        
+        Raises:
+            CollectionReadOnlyError: If the collection is read-only
+            ShareConnectionError: If a network share refuses its stored credentials
         """
+        self.processor.check_writable("process")
         batch_id = self._generate_batch_id()
         
         # Gather file info
         file_infos = []
-        for path in file_paths:
+        for i, path in enumerate(file_paths):
+            self.processor.connect_share(path)
             p = Path(path)
             if p.exists():
                 size_mb = p.stat().st_size / (1024 * 1024)
                 file_infos.append({
                     "path": str(p),
                     "size_mb": size_mb,
-                    "type": p.suffix.lower()
+                    "type": p.suffix.lower(),
+                    "source_path": source_paths[i] if source_paths else None
                 })
         
         if not file_infos:
@@ -255,6 +512,13 @@ class BatchProcessor:
                 warnings=["No valid files to process"]
             )
         
+        # Refuse (or warn) before writing anything if the store volume is nearly full
+        input_bytes = int(sum(info["size_mb"] for info in file_infos) * 1024 * 1024)
+        self.monitor.ensure_disk_space(
+            self.monitor.estimate_index_bytes(input_bytes),
+            operation="batch processing"
+        )
+        
         # Create processing plan (includes OCR analysis)
         plan = self.create_plan(file_infos)
         
@@ -266,19 +530,27 @@ class BatchProcessor:
             worker_count=plan.worker_count,
             started_at=datetime.now(),
             warnings=plan.warnings,
-            ocr_analysis=plan.ocr_analysis.to_dict() if plan.ocr_analysis else None
+            ocr_analysis=plan.ocr_analysis.to_dict() if plan.ocr_analysis else None,
+            ignore_power_state=ignore_power_state,
+            preset=preset,
+            request_id=get_request_id(),
+            duplicate_threshold=duplicate_threshold
         )
         
         # Create file progress trackers
         for info in file_infos:
             batch_progress.files.append(FileProgress(
-                filename=Path(info["path"]).name,
+                filename=Path(info["source_path"] or info["path"]).name,
                 file_path=info["path"],
-                size_mb=info["size_mb"]
+                size_mb=info["size_mb"],
+                source_path=info["source_path"]
             ))
         
         with self._lock:
             self._active_batches[batch_id] = batch_progress
+        self._narrate(batch_progress)
+        if progress_callback:
+            progress_callback(batch_progress)
         
         # Process based on mode
         if plan.mode == ProcessingMode.SEQUENTIAL or plan.worker_count <= 1:
@@ -287,6 +559,7 @@ class BatchProcessor:
             self._process_parallel(batch_progress, plan.worker_count, force_reindex, progress_callback)
         
         batch_progress.completed_at = datetime.now()
+        self._narrate(batch_progress)
         
         # Notify completion
         if progress_callback:
@@ -298,21 +571,26 @@ class BatchProcessor:
         self,
         batch: BatchProgress,
         force_reindex: bool,
-        callback: Optional[Callable]
+        callback: Optional[Callable],
+        files: Optional[List[FileProgress]] = None
     ):
-        """Process files one at a time."""
-        for file_progress in batch.files:
+        """Process files (all of the batch's unless given) one at a time."""
+        for file_progress in (batch.files if files is None else files):
             result = self._process_single_file(
                 file_progress.file_path,
                 file_progress,
-                force_reindex
+                force_reindex,
+                batch
             )
             
             if result:
                 batch.completed_files += 1
                 batch.total_chunks += len(result.chunks)
+            elif file_progress.status == FileStatus.SKIPPED:
+                batch.skipped_files += 1
             else:
                 batch.failed_files += 1
+            self._narrate(batch)
             
             if callback:
                 callback(batch)
@@ -326,13 +604,16 @@ class BatchProcessor:
     ):
         """Process files in parallel with limited workers."""
         with ThreadPoolExecutor(max_workers=worker_count) as executor:
-            # Submit all tasks
+            # Submit all tasks (in a copy of this context, so workers log
+            # under the batch's request id)
             future_to_progress = {
                 executor.submit(
+                    contextvars.copy_context().run,
                     self._process_single_file,
                     fp.file_path,
                     fp,
-                    force_reindex
+                    force_reindex,
+                    batch
                 ): fp
                 for fp in batch.files
             }
@@ -346,13 +627,17 @@ class BatchProcessor:
                     if result:
                         batch.completed_files += 1
                         batch.total_chunks += len(result.chunks)
+                    elif file_progress.status == FileStatus.SKIPPED:
+                        batch.skipped_files += 1
                     else:
                         batch.failed_files += 1
                 except Exception as e:
                     batch.failed_files += 1
                     file_progress.status = FileStatus.FAILED
                     file_progress.error_message = str(e)
+                    file_progress.error_type = getattr(e, "error_type", None)
                     logger.error(f"Parallel processing error: {e}")
+                self._narrate(batch)
                 
                 if callback:
                     callback(batch)
@@ -361,7 +646,11 @@ class BatchProcessor:
         self,
         file_paths: List[str],
         force_reindex: bool = False,
-        progress_callback: Optional[Callable[[BatchProgress], Any]] = None
+        progress_callback: Optional[Callable[[BatchProgress], Any]] = None,
+        ignore_power_state: bool = False,
+        preset: Optional[ProcessingPreset] = None,
+        source_paths: Optional[List[str]] = None,
+        duplicate_threshold: Optional[float] = None
     ) -> BatchProgress:
         """
         Process multiple files asynchronously.
@@ -370,6 +659,10 @@ class BatchProcessor:
             file_paths: List of file paths to process
             force_reindex: Force reprocessing
             progress_callback: Async callback for progress updates
+            ignore_power_state: Process even on battery / in battery-saver mode
+            preset: Processing preset for every file (None = processor defaults)
+            source_paths: Original names for file_paths (see process_batch_sync)
+            duplicate_threshold: Skip near-duplicates (see process_batch_sync)
             
         Returns:
             BatchProgress with results
@@ -383,13 +676,198 @@ class BatchProcessor:
             elif progress_callback:
                 progress_callback(progress)
         
+        context = contextvars.copy_context()   # Keeps the request id
         result = await loop.run_in_executor(
             None,
-            lambda: self.process_batch_sync(file_paths, force_reindex, sync_callback)
+            lambda: context.run(
+                self.process_batch_sync,
+                file_paths, force_reindex, sync_callback, ignore_power_state, preset, source_paths,
+                duplicate_threshold
+            )
         )
         
         return result
     
+    def dry_run_batch_sync(
+        self,
+        file_paths: List[str],
+        force_reindex: bool = False,
+        preset: Optional[ProcessingPreset] = None,
+        source_paths: Optional[List[str]] = None
+    ) -> BatchDryRunReport:
+        """
+        Simulate a batch: extract and chunk every file without embedding
+        or storing anything, and estimate how long embedding would take.
+        
+        Args:
+            file_paths: List of file paths to process
+            force_reindex: Project re-processing of already indexed files
+            preset: Processing preset for every file (None = processor defaults)
+            source_paths: Original names for file_paths (see process_batch_sync)
+        """
+        file_infos = []
+        for i, path in enumerate(file_paths):
+            self.processor.connect_share(path)
+            p = Path(path)
+            if p.exists():
+                file_infos.append({
+                    "path": str(p),
+                    "size_mb": p.stat().st_size / (1024 * 1024),
+                    "type": p.suffix.lower(),
+                    "source_path": source_paths[i] if source_paths else None
+                })
+        
+        report = BatchDryRunReport(total_files=len(file_infos))
+        if not file_infos:
+            report.warnings.append("No valid files to process")
+            return report
+        
+        plan = self.create_plan(file_infos)
+        report.processing_mode = plan.mode
+        report.worker_count = plan.worker_count
+        report.warnings.extend(plan.warnings)
+        report.ocr_analysis = plan.ocr_analysis.to_dict() if plan.ocr_analysis else None
+        
+        input_bytes = int(sum(info["size_mb"] for info in file_infos) * 1024 * 1024)
+        disk = self.monitor.check_disk_space(self.monitor.estimate_index_bytes(input_bytes))
+        if not disk.ok:
+            report.warnings.append(
+                f"Not enough disk space: {disk.required_bytes / (1024 * 1024):.0f}MB required, "
+                f"{disk.available_bytes / (1024 * 1024):.0f}MB available"
+            )
+        
+        def simulate(info: Dict):
+            get_workload_lanes().yield_to_interactive()
+            name = Path(info["source_path"] or info["path"]).name
+            file_report = FileDryRun(filename=name, size_mb=info["size_mb"])
+            try:
+                result = self.processor.ingest(
+                    info["path"],
+                    force=force_reindex,
+                    preset=preset,
+                    source_path=info["source_path"],
+                    dry_run=True
+                )
+            except Exception as e:
+                file_report.status = "failed"
+                file_report.warnings.append(str(e))
+                return file_report, []
+            
+            # A dry run never stores its record, so the stored one means "skipped"
+            if result is self.processor.processed_docs.get(result.doc_hash):
+                file_report.status = "would_skip"
+                file_report.warnings.append("Already indexed; set force_reindex to process it again")
+                return file_report, []
+            
+            file_report.projected_chunks = len(result.chunks)
+            file_report.tokens_estimate = result.total_tokens_estimate
+            file_report.ocr_pages = result.ocr_pages
+            if not result.chunks:
+                file_report.warnings.append("No text extracted")
+            if result.partial:
+                file_report.warnings.append(
+                    f"Extraction timed out after {result.pages_completed}/{result.total_pages} pages"
+                )
+            if result.low_confidence_pages:
+                file_report.warnings.append(
+                    f"{len(result.low_confidence_pages)} page(s) below OCR confidence threshold"
+                )
+            return file_report, [c.content for c in result.chunks[:8]]
+        
+        started = time.monotonic()
+        samples: List[str] = []
+        with ThreadPoolExecutor(max_workers=max(1, plan.worker_count)) as executor:
+            for file_report, texts in executor.map(simulate, file_infos):
+                report.files.append(file_report)
+                samples.extend(texts)
+        report.extraction_seconds = time.monotonic() - started
+        
+        report.projected_chunks = sum(f.projected_chunks for f in report.files)
+        report.tokens_estimate = sum(f.tokens_estimate for f in report.files)
+        report.estimated_embedding_seconds = self.processor.vector_store.estimate_embedding_seconds(
+            samples, report.projected_chunks
+        )
+        logger.info(
+            f"Dry run of {report.total_files} file(s): {report.projected_chunks} chunks, "
+            f"~{report.estimated_embedding_seconds:.0f}s to embed"
+        )
+        return report
+    
+    async def dry_run_batch_async(
+        self,
+        file_paths: List[str],
+        force_reindex: bool = False,
+        preset: Optional[ProcessingPreset] = None,
+        source_paths: Optional[List[str]] = None
+    ) -> BatchDryRunReport:
+        """Simulate a batch off the event loop (see dry_run_batch_sync)."""
+        loop = asyncio.get_event_loop()
+        return await loop.run_in_executor(
+            None,
+            lambda: self.dry_run_batch_sync(file_paths, force_reindex, preset, source_paths)
+        )
+    
+    def retry_failed_files(
+        self,
+        batch_id: str,
+        error_types: Sequence[str] = PASSWORD_ERROR_TYPES,
+        progress_callback: Optional[Callable[[BatchProgress], None]] = None
+    ) -> BatchProgress:
+        """
+        Process a finished batch's failed files again, e.g. once the
+        passwords of its password-protected files were stored.
+        
+        Only files that failed with one of error_types and still exist are
+        retried (one at a time); the batch's counts are updated in place.
+        
+        Raises:
+            KeyError: If the batch is unknown
+            ValueError: If the batch is still processing
+        """
+        batch = self.get_batch_status(batch_id)
+        if batch is None:
+            raise KeyError(f"Batch not found: {batch_id}")
+        if batch.completed_at is None:
+            raise ValueError(f"Batch {batch_id} is still processing")
+        
+        retry = [
+            f for f in batch.files
+            if f.status == FileStatus.FAILED and f.error_type in error_types and Path(f.file_path).exists()
+        ]
+        if not retry:
+            return batch
+        
+        logger.info(f"Retrying {len(retry)} file(s) of batch {batch_id}")
+        batch.completed_at = None
+        for file_progress in retry:
+            batch.failed_files -= 1
+            file_progress.status = FileStatus.QUEUED
+            file_progress.error_message = file_progress.error_type = None
+            file_progress.started_at = file_progress.completed_at = None
+        self._narrate(batch)
+        
+        self._process_sequential(batch, False, progress_callback, retry)
+        
+        batch.completed_at = datetime.now()
+        self._narrate(batch)
+        if progress_callback:
+            progress_callback(batch)
+        return batch
+    
+    async def retry_failed_files_async(
+        self,
+        batch_id: str,
+        error_types: Sequence[str] = PASSWORD_ERROR_TYPES,
+        progress_callback: Optional[Callable[[BatchProgress], None]] = None
+    ) -> BatchProgress:
+        """Retry a batch's failed files off the event loop (see retry_failed_files)."""
+        loop = asyncio.get_event_loop()
+        context = contextvars.copy_context()   # Keeps the request id
+        return await loop.run_in_executor(
+            None,
+            lambda: context.run(self.retry_failed_files, batch_id, error_types, progress_callback)
+        )
+    
     def get_batch_status(self, batch_id: str) -> Optional[BatchProgress]:
         """Get status of a batch by ID."""
         with self._lock:
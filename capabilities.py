@@ -0,0 +1,130 @@
+"""
+Capability Registry for Jan Document Plugin
+
+Machine-readable list of every action the API offers, so the UI can
+build its command palette from the running backend instead of a
+hard-coded list (and notice when it talks to an older or newer build).
+
+Actions and their parameter schemas come from the app's OpenAPI schema.
+Each action may also have requirements; an action whose requirements
+aren't met right now is listed with enabled = false and the reasons:
+
+- processor: the document processor is running
+- idle: no batch is processing (destructive maintenance actions)
+- feature:<name>: enterprise policy allows the feature
+- tesseract: Tesseract OCR is installed on this machine
+"""
+
+import logging
+from typing import Dict, List, Optional
+
+logger = logging.getLogger(__name__)
+
+# Path prefixes whose actions need the document processor
+PROCESSOR_PREFIXES = (
+    "/documents",
+    "/embeddings",
+    "/similarity",
+    "/chunks",
+    "/collections",
+    "/shadow-index",
+    "/trash",
+    "/eval",
+    "/jan/threads",
+    "/assistants",
+    "/knowledge-base",
+    "/legacy-data/migrate",
+)
+
+# Actions under those prefixes that work without the processor
+PROCESSOR_EXEMPT = ("GET /documents/capacity", "POST /documents/scan")
+
+# Requirements beyond the processor, by action id
+ACTION_REQUIREMENTS = {
+    "POST /documents/scan": ["feature:folder_scan"],
+    "POST /documents/export/static": ["feature:export"],
+    "POST /collections/export": ["feature:export"],
+    "POST /ocr/languages/{lang}": ["feature:ocr_language_install", "tesseract"],
+    "POST /debug/report/github": ["feature:crash_report_upload"],
+    "POST /usage/opt-in": ["feature:usage_upload"],
+    "POST /usage/upload": ["feature:usage_upload"],
+    "POST /knowledge-base/reset": ["idle"],
+    "POST /uninstall": ["idle"],
+    "POST /settings/data-location": ["idle"],
+}
+
+HTTP_METHODS = ("get", "post", "put", "patch", "delete")
+
+
+def action_requirements(action_id: str, path: str) -> List[str]:
+    """Requirements of one action."""
+    requirements = []
+    if path.startswith(PROCESSOR_PREFIXES) and action_id not in PROCESSOR_EXEMPT:
+        requirements.append("processor")
+    return requirements + ACTION_REQUIREMENTS.get(action_id, [])
+
+
+def _resolve(schema: Dict, openapi: Dict) -> Dict:
+    ref = schema.get("$ref")
+    if not ref:
+        return schema
+    node = openapi
+    for part in ref.lstrip("#/").split("/"):
+        node = node.get(part, {})
+    return node
+
+
+def _parameter_schema(operation: Dict, openapi: Dict) -> Dict:
+    """Path, query and body parameters merged into one JSON schema object."""
+    properties = {}
+    required = []
+    for param in operation.get("parameters", []):
+        properties[param["name"]] = {**param.get("schema", {}), "in": param.get("in")}
+        if param.get("required"):
+            required.append(param["name"])
+
+    content = operation.get("requestBody", {}).get("content", {})
+    for media_type, body in content.items():
+        schema = _resolve(body.get("schema", {}), openapi)
+        location = "body" if media_type == "application/json" else "form"
+        for name, prop in schema.get("properties", {}).items():
+            properties[name] = {**_resolve(prop, openapi), "in": location}
+        required.extend(schema.get("required", []))
+        break
+
+    return {"type": "object", "properties": properties, "required": required}
+
+
+def list_capabilities(openapi: Dict, unmet: Dict[str, Optional[str]]) -> List[Dict]:
+    """
+    Every action in the OpenAPI schema with its parameters and state.
+
+    Args:
+        openapi: The app's OpenAPI schema (app.openapi())
+        unmet: Requirement -> reason it is not met right now; requirements
+               that are met may be omitted or map to None
+
+    Returns:
+        Actions sorted by path, then method
+    """
+    capabilities = []
+    for path, operations in openapi.get("paths", {}).items():
+        for method in HTTP_METHODS:
+            operation = operations.get(method)
+            if operation is None:
+                continue
+            action_id = f"{method.upper()} {path}"
+            requirements = action_requirements(action_id, path)
+            reasons = [unmet[r] for r in requirements if unmet.get(r)]
+            description = (operation.get("description") or "").strip()
+            capabilities.append({
+                "id": action_id,
+                "method": method.upper(),
+                "path": path,
+                "summary": description.splitlines()[0] if description else operation.get("summary", ""),
+                "parameters": _parameter_schema(operation, openapi),
+                "requires": requirements,
+                "enabled": not reasons,
+                "disabled_reasons": reasons
+            })
+    return sorted(capabilities, key=lambda c: (c["path"], HTTP_METHODS.index(c["method"].lower())))
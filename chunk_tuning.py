@@ -0,0 +1,236 @@
+"""
+Chunk Size Tuning for Jan Document Plugin
+
+Takes the guesswork out of chunk settings: tune_chunking() tries several
+chunk size / overlap combinations on a sample of the user's documents
+and recommends one.
+
+- With queries (same format as retrieval_eval: query, expected_source,
+  optional expected_page), every candidate is indexed into a throwaway
+  store and scored by MRR / recall@k; the best MRR wins, ties going to
+  the candidate with fewer chunks (less to embed and store).
+- Without queries, candidates are compared on how they cut the text: how
+  many chunks end at a sentence boundary, how many are small fragments,
+  how much text is duplicated by overlap, and whether the usual number of
+  retrieved chunks fits the context budget. This is a heuristic; queries
+  give a much better answer.
+
+The recommendation can be saved as the processing preset
+"tuned-<collection>" and applied, so later uploads use it.
+"""
+
+import re
+import logging
+from pathlib import Path
+from typing import Dict, List, Optional, Sequence, Tuple
+from dataclasses import dataclass, field, asdict
+
+from document_processor import DocumentProcessor, SemanticChunker
+from processing_presets import ProcessingPreset, PresetStore
+from retrieval_eval import EvalQuery, evaluate_preset, DEFAULT_K_VALUES
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+# (chunk_size, chunk_overlap) in tokens
+DEFAULT_CANDIDATES = (
+    (250, 25), (250, 50),
+    (500, 50), (500, 100),
+    (1000, 100), (1000, 200),
+    (1500, 150), (1500, 300),
+)
+
+# Chunks shorter than this share of the target size count as fragments
+FRAGMENT_RATIO = 0.25
+
+# Chunks retrieved per query when judging the context budget
+RETRIEVED_CHUNKS = 5
+
+SENTENCE_END = re.compile(r'[.!?]["\')\]]?\s*$')
+
+
+@dataclass
+class ChunkingCandidate:
+    """One chunk size / overlap combination and how it scored."""
+    chunk_size: int
+    chunk_overlap: int
+    chunks: int = 0
+    avg_chunk_tokens: float = 0.0
+    sentence_boundary_ratio: float = 0.0
+    fragment_ratio: float = 0.0
+    overlap_overhead: float = 0.0       # Extra text stored because of overlap
+    fits_context: bool = True
+    mrr: Optional[float] = None
+    recall_at_k: Optional[Dict[str, float]] = None
+    score: float = 0.0
+    error: Optional[str] = None
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+@dataclass
+class TuningReport:
+    """Outcome of tune_chunking."""
+    collection: str
+    mode: str                           # "retrieval" or "heuristic"
+    sample_files: List[str]
+    query_count: int = 0
+    candidates: List[ChunkingCandidate] = field(default_factory=list)
+    recommended: Optional[Dict] = None  # chunk_size, chunk_overlap
+    current: Optional[Dict] = None
+    persisted_preset: Optional[str] = None
+    warnings: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict:
+        return {
+            "collection": self.collection,
+            "mode": self.mode,
+            "sample_files": self.sample_files,
+            "query_count": self.query_count,
+            "candidates": [c.to_dict() for c in self.candidates],
+            "recommended": self.recommended,
+            "current": self.current,
+            "persisted_preset": self.persisted_preset,
+            "warnings": self.warnings
+        }
+
+
+def _measure(candidate: ChunkingCandidate, texts: List[str], max_context_tokens: int, chars_per_token: float):
+    chunker = SemanticChunker(
+        chunk_size=candidate.chunk_size,
+        chunk_overlap=candidate.chunk_overlap,
+        chars_per_token=chars_per_token
+    )
+    lengths: List[int] = []
+    boundaries = fragments = 0
+    text_chars = 0
+    for number, text in enumerate(texts):
+        chunks = chunker.chunk(text, f"sample{number}")
+        text_chars += len(text.strip())
+        for i, chunk in enumerate(chunks):
+            lengths.append(len(chunk.content))
+            last = i == len(chunks) - 1
+            if last or SENTENCE_END.search(chunk.content):
+                boundaries += 1
+            if not last and len(chunk.content) < candidate.chunk_size * chars_per_token * FRAGMENT_RATIO:
+                fragments += 1
+
+    candidate.chunks = len(lengths)
+    if not lengths:
+        return
+    candidate.avg_chunk_tokens = round(sum(lengths) / len(lengths) / chars_per_token, 1)
+    candidate.sentence_boundary_ratio = round(boundaries / len(lengths), 4)
+    candidate.fragment_ratio = round(fragments / len(lengths), 4)
+    candidate.overlap_overhead = round(max(0.0, sum(lengths) / max(1, text_chars) - 1), 4)
+    candidate.fits_context = candidate.chunk_size * RETRIEVED_CHUNKS <= max_context_tokens
+
+
+def _heuristic_score(candidate: ChunkingCandidate) -> float:
+    score = candidate.sentence_boundary_ratio - candidate.fragment_ratio - 0.5 * candidate.overlap_overhead
+    return round(score - (0 if candidate.fits_context else 1), 4)
+
+
+def tune_chunking(
+    processor: DocumentProcessor,
+    sample_files: List[str],
+    queries: Optional[List[Dict]] = None,
+    candidates: Sequence[Tuple[int, int]] = DEFAULT_CANDIDATES,
+    max_context_tokens: int = 8000,
+    k_values: Sequence[int] = DEFAULT_K_VALUES,
+    collection: Optional[str] = None,
+    presets: Optional[PresetStore] = None,
+    persist: bool = False
+) -> TuningReport:
+    """
+    Compare chunk settings on sample files and recommend one.
+
+    Args:
+        processor: Live processor (embedding model, extraction settings)
+        sample_files: Documents representative of the collection
+        queries: Optional labeled queries (see retrieval_eval.EvalQuery)
+        candidates: (chunk_size, chunk_overlap) pairs to try
+        max_context_tokens: Context budget retrieved chunks must fit
+        collection: Collection the settings are for (None = the store's)
+        presets: Preset store the recommendation is saved to
+        persist: Save and apply the recommendation as a preset
+
+    Raises:
+        KeyError: If collection is not the store's collection
+        ValueError: If there are no sample files, a query is malformed or
+                    a candidate is invalid
+        FileNotFoundError: If a sample file doesn't exist
+    """
+    name = processor.vector_store.collection.name
+    check_collection(collection, name)
+    if not sample_files:
+        raise ValueError("At least one sample file is required")
+    paths = [Path(p) for p in sample_files]
+    missing = [str(p) for p in paths if not p.is_file()]
+    if missing:
+        raise FileNotFoundError(f"Sample file not found: {', '.join(missing)}")
+
+    eval_queries = [EvalQuery.from_dict(q) for q in queries or []]
+    trials = []
+    for size, overlap in candidates:
+        ProcessingPreset(name="candidate", chunk_size=size, chunk_overlap=overlap).validate()
+        trials.append(ChunkingCandidate(chunk_size=size, chunk_overlap=overlap))
+
+    report = TuningReport(
+        collection=name,
+        mode="retrieval" if eval_queries else "heuristic",
+        sample_files=[str(p) for p in paths],
+        query_count=len(eval_queries),
+        candidates=trials,
+        current={"chunk_size": processor.chunker.chunk_size, "chunk_overlap": processor.chunker.chunk_overlap}
+    )
+
+    texts = []
+    for path in paths:
+        try:
+            raw_text, _, _ = processor.extractor.extract(path, flatten_tables=not processor.extract_tables)
+            texts.append(raw_text)
+        except Exception as e:
+            report.warnings.append(f"{path.name}: {e}")
+    if not any(t.strip() for t in texts):
+        report.warnings.append("No text could be extracted from the sample files")
+
+    chars_per_token = processor.chunker.chars_per_token
+    for candidate in trials:
+        _measure(candidate, texts, max_context_tokens, chars_per_token)
+        if not eval_queries:
+            candidate.score = _heuristic_score(candidate)
+            continue
+        preset = ProcessingPreset(
+            name=f"{candidate.chunk_size}/{candidate.chunk_overlap}",
+            chunk_size=candidate.chunk_size,
+            chunk_overlap=candidate.chunk_overlap
+        )
+        result = evaluate_preset(processor, preset, paths, eval_queries, k_values)
+        if result.error:
+            candidate.error = result.error
+            continue
+        candidate.mrr = round(result.mrr, 4)
+        candidate.recall_at_k = {str(k): round(v, 4) for k, v in result.recall_at_k.items()}
+        candidate.score = candidate.mrr
+
+    scored = [c for c in trials if c.error is None and c.chunks]
+    if scored:
+        best = max(scored, key=lambda c: (c.score, -c.chunks))
+        report.recommended = {"chunk_size": best.chunk_size, "chunk_overlap": best.chunk_overlap}
+        logger.info(
+            f"Chunk tuning ({report.mode}) on {len(paths)} file(s): "
+            f"recommend {best.chunk_size}/{best.chunk_overlap}"
+        )
+
+    if persist and report.recommended:
+        store = presets or PresetStore()
+        preset = store.create_preset(ProcessingPreset(
+            name=f"tuned-{name}",
+            description=f"Tuned on {len(paths)} sample file(s) ({report.mode})",
+            **report.recommended
+        ))
+        store.apply_preset(preset.name)
+        report.persisted_preset = preset.name
+
+    return report
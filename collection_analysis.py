@@ -0,0 +1,399 @@
+"""
+Collection Analysis for Jan Document Plugin
+
+Collection-wide views built from the stored chunk embeddings:
+- Document similarity graph (nodes = source documents, edges = similar pairs)
+- Topic clusters (k-means over chunk embeddings, labeled by top keywords)
+- Cosine similarity of two texts or embeddings, with the store's model
+
+Results are plain dicts, ready to be returned as JSON for graph
+visualizations in the Chat UI.
+"""
+
+import re
+import math
+import logging
+import threading
+from collections import Counter
+from typing import Dict, List, Optional
+from dataclasses import dataclass
+from datetime import datetime
+
+import numpy as np
+
+from document_processor import LocalVectorStore
+
+logger = logging.getLogger(__name__)
+
+
+@dataclass
+class DocumentCentroid:
+    """Mean chunk embedding for a single source document."""
+    doc_hash: str
+    filename: str
+    chunk_count: int
+    vector: np.ndarray  # L2-normalized
+
+
+class SimilarityGraphBuilder:
+    """
+    Builds a document-to-document similarity graph for a vector store.
+
+    Each document is represented by the normalized centroid of its chunk
+    embeddings. Centroids are cached, so refreshing after new uploads only
+    loads embeddings for documents that were added since the last build.
+    The cache is dropped when the store's embedding model changes (shadow
+    index promotion, migration); chunk edits must call invalidate().
+    """
+
+    def __init__(self, vector_store: LocalVectorStore):
+        """
+        Initialize graph builder.
+
+        Args:
+            vector_store: Vector store whose documents form the graph nodes
+        """
+        self.vector_store = vector_store
+        self._centroids: Dict[str, DocumentCentroid] = {}
+        self._embedding_model = vector_store.embedding_model   # Model the centroids came from
+        self._lock = threading.Lock()
+        self._last_refresh: Optional[datetime] = None
+
+    def _compute_centroid(self, doc_hash: str, filename: str) -> Optional[DocumentCentroid]:
+        """Compute the normalized centroid for one document."""
+        embeddings = self.vector_store.get_document_embeddings(doc_hash)
+        if not embeddings:
+            return None
+
+        matrix = np.asarray(embeddings, dtype=np.float32)
+        centroid = matrix.mean(axis=0)
+        norm = np.linalg.norm(centroid)
+        if norm == 0:
+            return None
+
+        return DocumentCentroid(
+            doc_hash=doc_hash,
+            filename=filename,
+            chunk_count=len(embeddings),
+            vector=centroid / norm
+        )
+
+    def refresh(self) -> Dict[str, int]:
+        """
+        Sync cached centroids with the documents currently in the store.
+
+        Returns:
+            Counts of added and removed documents
+        """
+        with self._lock:
+            if self.vector_store.embedding_model != self._embedding_model:
+                logger.info(f"Embedding model changed to {self.vector_store.embedding_model}; recomputing centroids")
+                self._centroids.clear()
+                self._embedding_model = self.vector_store.embedding_model
+
+            current = {d["doc_hash"]: d["filename"] for d in self.vector_store.list_documents()}
+
+            removed = [h for h in self._centroids if h not in current]
+            for doc_hash in removed:
+                del self._centroids[doc_hash]
+
+            added = 0
+            for doc_hash, filename in current.items():
+                if doc_hash in self._centroids:
+                    continue
+                centroid = self._compute_centroid(doc_hash, filename)
+                if centroid is not None:
+                    self._centroids[doc_hash] = centroid
+                    added += 1
+
+            self._last_refresh = datetime.now()
+
+            if added or removed:
+                logger.info(f"Similarity graph cache: +{added} / -{len(removed)} documents")
+
+            return {"added": added, "removed": len(removed)}
+
+    def invalidate(self, doc_hash: Optional[str] = None):
+        """Drop cached centroids (one document, or all when doc_hash is None)."""
+        with self._lock:
+            if doc_hash is None:
+                self._centroids.clear()
+            else:
+                self._centroids.pop(doc_hash, None)
+
+    def build(self, threshold: float = 0.5) -> Dict:
+        """
+        Build the similarity graph.
+
+        Args:
+            threshold: Minimum cosine similarity for an edge (0-1)
+
+        Returns:
+            Dict with "nodes", "edges" and graph metadata
+        """
+        self.refresh()
+
+        with self._lock:
+            centroids = list(self._centroids.values())
+
+        nodes = [
+            {
+                "id": c.doc_hash,
+                "label": c.filename,
+                "chunk_count": c.chunk_count
+            }
+            for c in centroids
+        ]
+
+        edges = []
+        if len(centroids) > 1:
+            matrix = np.stack([c.vector for c in centroids])
+            similarities = matrix @ matrix.T
+
+            # Upper triangle only - edges are undirected
+            rows, cols = np.triu_indices(len(centroids), k=1)
+            for i, j in zip(rows, cols):
+                score = float(similarities[i, j])
+                if score >= threshold:
+                    edges.append({
+                        "source": centroids[i].doc_hash,
+                        "target": centroids[j].doc_hash,
+                        "weight": round(score, 4)
+                    })
+
+        edges.sort(key=lambda e: e["weight"], reverse=True)
+
+        return {
+            "nodes": nodes,
+            "edges": edges,
+            "threshold": threshold,
+            "node_count": len(nodes),
+            "edge_count": len(edges),
+            "built_at": self._last_refresh.isoformat() if self._last_refresh else None
+        }
+
+
+def build_similarity_graph(vector_store: LocalVectorStore, threshold: float = 0.5) -> Dict:
+    """
+    One-shot helper: build a similarity graph without keeping a cache.
+
+    Use SimilarityGraphBuilder directly to reuse centroids between calls.
+    """
+    return SimilarityGraphBuilder(vector_store).build(threshold)
+
+
+def compute_similarity(
+    vector_store: LocalVectorStore,
+    text_a: Optional[str] = None,
+    text_b: Optional[str] = None,
+    embedding_a: Optional[List[float]] = None,
+    embedding_b: Optional[List[float]] = None
+) -> Dict:
+    """
+    Cosine similarity of two items, each given as a text (embedded with the
+    store's model) or an embedding (e.g. from embed_texts).
+
+    Returns:
+        Dict with similarity (-1 to 1), model and dimension
+
+    Raises:
+        ValueError: If a side has neither or both a text and an embedding,
+                    or the vectors' dimensions differ
+    """
+    sides = []
+    for label, text, embedding in (("a", text_a, embedding_a), ("b", text_b, embedding_b)):
+        if (text is None) == (embedding is None):
+            raise ValueError(f"Give either text_{label} or embedding_{label}")
+        sides.append((text, embedding))
+
+    texts = [text for text, _ in sides if text is not None]
+    embedded = iter(vector_store.embed_texts(texts)["embeddings"]) if texts else iter(())
+    vectors = [
+        np.asarray(embedding if embedding is not None else next(embedded), dtype=np.float64)
+        for _, embedding in sides
+    ]
+    if any(v.ndim != 1 or not v.size for v in vectors):
+        raise ValueError("Embeddings must be non-empty lists of numbers")
+    if len(vectors[0]) != len(vectors[1]):
+        raise ValueError(f"Embeddings have different dimensions ({len(vectors[0])} and {len(vectors[1])})")
+    norms = np.linalg.norm(vectors[0]) * np.linalg.norm(vectors[1])
+    similarity = float(vectors[0] @ vectors[1] / norms) if norms else 0.0
+    return {
+        "similarity": round(similarity, 6),
+        "model": vector_store.embedding_model if texts else None,
+        "dimension": int(vectors[0].shape[0])
+    }
+
+
+# ============================================================================
+# Topic Clustering
+# ============================================================================
+
+# Common English words excluded from cluster keyword labels
+KEYWORD_STOPWORDS = {
+    "the", "and", "for", "that", "with", "this", "from", "are", "was", "were",
+    "have", "has", "had", "not", "but", "all", "can", "will", "would", "there",
+    "their", "they", "them", "been", "which", "when", "what", "who", "into",
+    "more", "also", "than", "then", "such", "these", "those", "its", "our",
+    "your", "you", "his", "her", "she", "him", "any", "may", "one", "two",
+    "other", "each", "only", "about", "over", "under", "between", "page",
+    "should", "could", "must", "some", "out", "use", "used", "using", "per",
+}
+
+WORD_PATTERN = re.compile(r"[a-zA-Z][a-zA-Z\-]{2,}")
+
+
+def _tokenize(text: str) -> List[str]:
+    """Lowercase word tokens with stopwords removed."""
+    return [
+        w for w in (m.lower() for m in WORD_PATTERN.findall(text))
+        if w not in KEYWORD_STOPWORDS
+    ]
+
+
+def _kmeans(vectors: np.ndarray, k: int, max_iter: int = 50, seed: int = 42) -> np.ndarray:
+    """
+    Spherical k-means (cosine) with k-means++ initialization.
+
+    Args:
+        vectors: L2-normalized row vectors
+        k: Number of clusters
+        max_iter: Iteration cap
+        seed: RNG seed for reproducible clusters
+
+    Returns:
+        Cluster index per row
+    """
+    rng = np.random.default_rng(seed)
+    n = len(vectors)
+
+    # k-means++ seeding on cosine distance
+    centers = [vectors[rng.integers(n)]]
+    for _ in range(1, k):
+        # float64 so the probabilities sum to 1 within numpy's tolerance
+        distances = 1 - np.max(vectors @ np.stack(centers).T, axis=1).astype(np.float64)
+        distances = np.clip(distances, 0, None)
+        total = distances.sum()
+        if total == 0:
+            break
+        centers.append(vectors[rng.choice(n, p=distances / total)])
+    centers = np.stack(centers)
+
+    labels = np.full(n, -1)
+    for _ in range(max_iter):
+        new_labels = np.argmax(vectors @ centers.T, axis=1)
+        if np.array_equal(new_labels, labels):
+            break
+        labels = new_labels
+
+        for c in range(len(centers)):
+            members = vectors[labels == c]
+            if len(members):
+                center = members.mean(axis=0)
+                norm = np.linalg.norm(center)
+                if norm > 0:
+                    centers[c] = center / norm
+
+    return labels
+
+
+def _default_cluster_count(doc_count: int) -> int:
+    """Heuristic cluster count: ~sqrt(documents), between 2 and 12."""
+    return max(2, min(12, int(math.sqrt(doc_count)) + 1))
+
+
+def cluster_collection(
+    vector_store: LocalVectorStore,
+    k: Optional[int] = None,
+    top_keywords: int = 5
+) -> Dict:
+    """
+    Group stored chunks into topic clusters.
+
+    Runs k-means over chunk embeddings, labels each cluster with the words
+    most characteristic of its chunks (frequency in cluster vs. overall),
+    and lists the documents contributing to each cluster.
+
+    Args:
+        vector_store: Vector store to cluster
+        k: Number of clusters (None = choose from document count)
+        top_keywords: Keywords to report per cluster
+
+    Returns:
+        Dict with "clusters" list and clustering metadata
+    """
+    chunks = vector_store.get_all_chunks(include_embeddings=True)
+    embeddings = chunks["embeddings"]
+
+    if not embeddings:
+        return {"clusters": [], "k": 0, "chunk_count": 0, "document_count": 0}
+
+    vectors = np.asarray(embeddings, dtype=np.float32)
+    norms = np.linalg.norm(vectors, axis=1, keepdims=True)
+    vectors = vectors / np.where(norms == 0, 1, norms)
+
+    doc_hashes = {m.get("doc_hash") for m in chunks["metadatas"]}
+    if k is None:
+        k = _default_cluster_count(len(doc_hashes))
+    k = max(1, min(k, len(vectors)))
+
+    labels = _kmeans(vectors, k)
+
+    # Document frequency across all chunks, for keyword distinctiveness
+    chunk_tokens = [set(_tokenize(text or "")) for text in chunks["documents"]]
+    overall = Counter()
+    for tokens in chunk_tokens:
+        overall.update(tokens)
+    total_chunks = len(chunk_tokens)
+
+    clusters = []
+    for c in range(k):
+        member_idx = [i for i, label in enumerate(labels) if label == c]
+        if not member_idx:
+            continue
+
+        in_cluster = Counter()
+        for i in member_idx:
+            in_cluster.update(chunk_tokens[i])
+
+        # Score = in-cluster rate weighted by how unusual the word is overall
+        scored = sorted(
+            (
+                (count / len(member_idx)) * math.log(total_chunks / overall[word] + 1),
+                word
+            )
+            for word, count in in_cluster.items()
+            if count > 1 or len(member_idx) == 1
+        )
+        keywords = [word for _, word in reversed(scored[-top_keywords:])]
+
+        documents: Dict[str, Dict] = {}
+        for i in member_idx:
+            meta = chunks["metadatas"][i]
+            doc_hash = meta.get("doc_hash")
+            if doc_hash not in documents:
+                documents[doc_hash] = {
+                    "doc_hash": doc_hash,
+                    "filename": meta.get("filename", "unknown"),
+                    "chunk_count": 0
+                }
+            documents[doc_hash]["chunk_count"] += 1
+
+        clusters.append({
+            "cluster_id": c,
+            "label": ", ".join(keywords[:3]) if keywords else f"Cluster {c + 1}",
+            "keywords": keywords,
+            "chunk_count": len(member_idx),
+            "documents": sorted(
+                documents.values(), key=lambda d: d["chunk_count"], reverse=True
+            )
+        })
+
+    clusters.sort(key=lambda cl: cl["chunk_count"], reverse=True)
+
+    return {
+        "clusters": clusters,
+        "k": k,
+        "chunk_count": len(vectors),
+        "document_count": len(doc_hashes)
+    }
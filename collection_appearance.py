@@ -0,0 +1,136 @@
+"""
+Collection Appearance for Jan Document Plugin
+
+How the library UI shows a collection besides its name and chunk count:
+a description, a color and an icon. Appearances are kept per collection
+in <storage>/collection_appearance.json and returned with the
+collection's stats and listing.
+"""
+
+import re
+import json
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, Optional
+from dataclasses import dataclass, asdict
+
+logger = logging.getLogger(__name__)
+
+MAX_DESCRIPTION_LENGTH = 500
+
+COLOR_PATTERN = re.compile(r"^#[0-9A-Fa-f]{6}$")
+
+# Icon names as the UI's icon set spells them (e.g. "book-open")
+ICON_PATTERN = re.compile(r"^[a-z0-9]+(?:-[a-z0-9]+)*$")
+MAX_ICON_LENGTH = 40
+
+
+@dataclass
+class CollectionAppearance:
+    """Display metadata of a collection (None = not set)."""
+    description: Optional[str] = None
+    color: Optional[str] = None        # "#rrggbb"
+    icon: Optional[str] = None         # Icon name, e.g. "book-open"
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+    @classmethod
+    def from_dict(cls, data: Dict) -> "CollectionAppearance":
+        return cls(
+            description=data.get("description"),
+            color=data.get("color"),
+            icon=data.get("icon")
+        )
+
+
+def validate_appearance(appearance: CollectionAppearance) -> CollectionAppearance:
+    """
+    Check an appearance, trimming the description and lowercasing the color.
+
+    Raises:
+        ValueError: If a field is invalid
+    """
+    for field_name, value in appearance.to_dict().items():
+        if value is not None and not isinstance(value, str):
+            raise ValueError(f"{field_name} must be a string")
+    description = appearance.description.strip() if appearance.description else None
+    if description and len(description) > MAX_DESCRIPTION_LENGTH:
+        raise ValueError(f"description is longer than {MAX_DESCRIPTION_LENGTH} characters")
+    color = appearance.color or None
+    if color is not None:
+        if not COLOR_PATTERN.match(color):
+            raise ValueError(f"Invalid color: {color} (expected #rrggbb)")
+        color = color.lower()
+    icon = appearance.icon or None
+    if icon is not None and (len(icon) > MAX_ICON_LENGTH or not ICON_PATTERN.match(icon)):
+        raise ValueError(f"Invalid icon: {icon} (expected a lowercase name such as book-open)")
+    return CollectionAppearance(description=description or None, color=color, icon=icon)
+
+
+class CollectionAppearanceStore:
+    """
+    Per-collection appearances persisted as JSON.
+
+    Usage:
+        store = CollectionAppearanceStore(Path("./jan_doc_store/collection_appearance.json"))
+        store.set_collection_appearance("jan_documents", {"color": "#3b82f6", "icon": "book-open"})
+        store.get("jan_documents")
+    """
+
+    def __init__(self, path: Optional[Path] = None):
+        """
+        Initialize appearance store.
+
+        Args:
+            path: JSON file holding appearances by collection name (None = in memory)
+        """
+        self.path = Path(path) if path else None
+        self._lock = threading.Lock()
+        self._appearances: Dict[str, CollectionAppearance] = {}
+        if self.path and self.path.exists():
+            try:
+                data = json.loads(self.path.read_text(encoding="utf-8"))
+                self._appearances = {name: CollectionAppearance.from_dict(a) for name, a in data.items()}
+            except (OSError, ValueError, AttributeError) as e:
+                logger.warning(f"Could not read collection appearance: {e}")
+
+    def get(self, collection: str) -> CollectionAppearance:
+        """Appearance of a collection (all None if none was set)."""
+        return self._appearances.get(collection) or CollectionAppearance()
+
+    def set_collection_appearance(self, collection: str, changes: Dict) -> CollectionAppearance:
+        """
+        Update some of a collection's description, color and icon; fields
+        not in changes keep their value, fields set to None are cleared.
+
+        Raises:
+            ValueError: If a field is unknown or invalid
+        """
+        unknown = set(changes) - {"description", "color", "icon"}
+        if unknown:
+            raise ValueError(f"Unknown appearance fields: {', '.join(sorted(unknown))}")
+        with self._lock:
+            appearance = validate_appearance(
+                CollectionAppearance.from_dict({**self.get(collection).to_dict(), **changes})
+            )
+            self._appearances[collection] = appearance
+            self._save()
+        logger.info(f"Appearance of {collection}: {appearance.to_dict()}")
+        return appearance
+
+    def delete(self, collection: str):
+        """Forget a collection's appearance."""
+        with self._lock:
+            if self._appearances.pop(collection, None) is not None:
+                self._save()
+
+    def _save(self):
+        if not self.path:
+            return
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self.path.write_text(
+            json.dumps({name: a.to_dict() for name, a in self._appearances.items()}, indent=2),
+            encoding="utf-8"
+        )
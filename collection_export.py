@@ -0,0 +1,283 @@
+"""
+Collection Export for Jan Document Plugin
+
+Writes the indexed collection in formats other RAG tools can load:
+
+- jsonl:     one {"id", "text", "metadata"} object per line
+- langchain: one {"page_content", "metadata", "type": "Document"} object
+             per line (load with Document(**json.loads(line)))
+- markdown:  a folder with one Markdown digest per source document, its
+             chunks in order under their section headings
+
+Chunks are read and written a page at a time, so large collections are
+never held in memory. Superseded document versions and indexed chat
+threads are left out.
+
+Indexing can continue while an export runs. The export first takes a
+snapshot of which chunks to write, while no document version is being
+written (LocalVectorStore.write_lock), and then writes exactly those:
+documents indexed later are left out, and a document re-indexed mid-export
+is exported as its version at the snapshot. The snapshot time is reported
+as snapshot_at; chunks deleted after it are counted as chunks_missing. Progress is reported the same way as collection
+migrations: a progress object with events, polled while the export runs.
+"""
+
+import re
+import json
+import uuid
+import logging
+from pathlib import Path
+from typing import Callable, Dict, List, Optional
+from dataclasses import dataclass, field
+from datetime import datetime
+
+from document_processor import LocalVectorStore, CHAT_SOURCE_TYPE, render_chunk_content
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+EXPORT_FORMATS = ("jsonl", "langchain", "markdown")
+
+# Chunks read from the store per call
+EXPORT_BATCH_SIZE = 256
+
+# Characters kept in Markdown digest file names
+MAX_DIGEST_NAME = 80
+
+
+@dataclass
+class ExportProgress:
+    """Progress and outcome of a collection export."""
+    export_id: str
+    collection: str
+    format: str
+    output_path: str
+    total_chunks: int = 0
+    chunks_done: int = 0
+    chunks_written: int = 0
+    files_written: int = 0
+    chunks_missing: int = 0  # In the snapshot but deleted before they were written
+    phase: str = "pending"   # pending, writing, completed, failed
+    error: Optional[str] = None
+    events: List[Dict] = field(default_factory=list)
+    snapshot_at: Optional[datetime] = None
+    started_at: Optional[datetime] = None
+    completed_at: Optional[datetime] = None
+
+    @property
+    def progress_percent(self) -> float:
+        if self.total_chunks == 0:
+            return 100.0 if self.phase == "completed" else 0.0
+        return self.chunks_done / self.total_chunks * 100
+
+    def to_dict(self) -> Dict:
+        return {
+            "export_id": self.export_id,
+            "collection": self.collection,
+            "format": self.format,
+            "output_path": self.output_path,
+            "total_chunks": self.total_chunks,
+            "chunks_done": self.chunks_done,
+            "chunks_written": self.chunks_written,
+            "files_written": self.files_written,
+            "chunks_missing": self.chunks_missing,
+            "progress_percent": round(self.progress_percent, 1),
+            "phase": self.phase,
+            "error": self.error,
+            "events": self.events,
+            "snapshot_at": self.snapshot_at.isoformat() if self.snapshot_at else None,
+            "started_at": self.started_at.isoformat() if self.started_at else None,
+            "completed_at": self.completed_at.isoformat() if self.completed_at else None
+        }
+
+
+def _exportable(meta: Dict) -> bool:
+    return meta.get("is_latest") is not False and meta.get("source_type") != CHAT_SOURCE_TYPE
+
+
+def _digest_name(filename: str, doc_hash: str) -> str:
+    stem = re.sub(r"[^\w.-]+", "_", Path(filename or "document").stem).strip("._") or "document"
+    return f"{stem[:MAX_DIGEST_NAME]}-{doc_hash[:8]}.md"
+
+
+class CollectionExporter:
+    """
+    Exports the live collection to files.
+
+    Usage:
+        exporter = CollectionExporter(processor.vector_store)
+        progress = exporter.export_collection_as(None, "langchain", Path("./export/docs.jsonl"))
+    """
+
+    def __init__(self, vector_store: LocalVectorStore):
+        self.store = vector_store
+        self.current: Optional[ExportProgress] = None   # Running or last export
+
+    def _event(
+        self,
+        progress: ExportProgress,
+        phase: str,
+        message: str,
+        callback: Optional[Callable[[ExportProgress], None]]
+    ):
+        progress.phase = phase
+        progress.events.append({
+            "time": datetime.now().isoformat(),
+            "phase": phase,
+            "message": message
+        })
+        logger.info(f"Export {progress.export_id}: {message}")
+        if callback:
+            callback(progress)
+
+    def export_collection_as(
+        self,
+        collection: Optional[str],
+        format: str,
+        output_path: Path,
+        progress_callback: Optional[Callable[[ExportProgress], None]] = None
+    ) -> ExportProgress:
+        """
+        Export a collection in one of EXPORT_FORMATS.
+
+        Args:
+            collection: Collection name (None = the store's collection)
+            format: "jsonl", "langchain" or "markdown"
+            output_path: File to write (jsonl, langchain) or folder to
+                         write digests into (markdown); replaced if it exists
+            progress_callback: Called with ExportProgress after each page
+
+        Returns:
+            ExportProgress with phase "completed" or "failed"
+
+        Raises:
+            KeyError: If collection is not the store's collection
+            ValueError: If the format is unknown or an export is running
+        """
+        name = self.store.collection.name
+        check_collection(collection, name)
+        if format not in EXPORT_FORMATS:
+            raise ValueError(f"Unknown export format: {format} (expected one of {', '.join(EXPORT_FORMATS)})")
+        if self.current and self.current.phase in ("pending", "writing"):
+            raise ValueError(f"Export {self.current.export_id} is already running")
+
+        output_path = Path(output_path)
+        progress = ExportProgress(
+            export_id=uuid.uuid4().hex[:12],
+            collection=name,
+            format=format,
+            output_path=str(output_path),
+            started_at=datetime.now()
+        )
+        self.current = progress
+
+        try:
+            ids = self._take_snapshot(progress)
+            self._event(
+                progress,
+                "writing",
+                f"Writing {progress.total_chunks} chunks as {format} (snapshot at {progress.snapshot_at.isoformat()})",
+                progress_callback
+            )
+            if format == "markdown":
+                self._write_markdown(progress, ids, output_path, progress_callback)
+            else:
+                self._write_lines(progress, ids, output_path, format, progress_callback)
+        except OSError as e:
+            progress.error = str(e)
+            progress.completed_at = datetime.now()
+            self._event(progress, "failed", f"Export failed: {e}", progress_callback)
+            return progress
+
+        progress.completed_at = datetime.now()
+        self._event(
+            progress,
+            "completed",
+            f"Wrote {progress.chunks_written} chunks to {progress.files_written} file(s)",
+            progress_callback
+        )
+        return progress
+
+    def _take_snapshot(self, progress: ExportProgress) -> List[str]:
+        """Ids of the chunks to export, read while no document version is being written."""
+        ids: List[str] = []
+        with self.store.write_lock:
+            progress.snapshot_at = datetime.now()
+            offset = 0
+            while True:
+                batch = self.store.collection.get(include=["metadatas"], limit=EXPORT_BATCH_SIZE, offset=offset)
+                ids.extend(i for i, meta in zip(batch["ids"], batch["metadatas"]) if _exportable(meta))
+                if len(batch["ids"]) < EXPORT_BATCH_SIZE:
+                    break
+                offset += EXPORT_BATCH_SIZE
+        progress.total_chunks = len(ids)
+        return ids
+
+    def _pages(self, progress: ExportProgress, ids: List[str], callback):
+        for start in range(0, len(ids), EXPORT_BATCH_SIZE):
+            page = ids[start:start + EXPORT_BATCH_SIZE]
+            batch = self.store.collection.get(ids=page, include=["documents", "metadatas"])
+            progress.chunks_missing += len(page) - len(batch["ids"])
+            yield zip(batch["ids"], batch["documents"], batch["metadatas"])
+            progress.chunks_done = min(start + EXPORT_BATCH_SIZE, progress.total_chunks)
+            if callback:
+                callback(progress)
+
+    def _write_lines(self, progress: ExportProgress, ids: List[str], output_path: Path, format: str, callback):
+        output_path.parent.mkdir(parents=True, exist_ok=True)
+        with open(output_path, "w", encoding="utf-8") as f:
+            for page in self._pages(progress, ids, callback):
+                for chunk_id, text, meta in page:
+                    content = render_chunk_content(text, meta)
+                    if format == "jsonl":
+                        record = {"id": chunk_id, "text": content, "metadata": meta}
+                    else:
+                        record = {"page_content": content, "metadata": {**meta, "id": chunk_id}, "type": "Document"}
+                    f.write(json.dumps(record, ensure_ascii=False, default=str) + "\n")
+                    progress.chunks_written += 1
+        progress.files_written = 1
+
+    def _write_markdown(self, progress: ExportProgress, ids: List[str], output_dir: Path, callback):
+        output_dir.mkdir(parents=True, exist_ok=True)
+        for old in output_dir.glob("*.md"):
+            old.unlink()
+
+        # First pass: which documents to write
+        documents: Dict[str, Dict] = {}
+        for page in self._pages(progress, ids, None):
+            for _, _, meta in page:
+                if meta.get("doc_hash"):
+                    documents.setdefault(meta["doc_hash"], meta)
+        in_snapshot = set(ids)
+
+        # Second pass: one digest per document, read one document at a time
+        progress.chunks_done = 0
+        for doc_hash, first in sorted(documents.items(), key=lambda d: d[1].get("filename") or ""):
+            chunks = self.store.get_all_chunks(doc_hash=doc_hash)
+            ordered = sorted(
+                (
+                    (text, meta)
+                    for chunk_id, text, meta in zip(chunks["ids"], chunks["documents"], chunks["metadatas"])
+                    if chunk_id in in_snapshot
+                ),
+                key=lambda c: c[1].get("chunk_index", 0)
+            )
+            lines = [f"# {first.get('filename') or doc_hash}", ""]
+            source = first.get("source_path")
+            if source:
+                lines += [f"Source: {source}", ""]
+            section = None
+            for text, meta in ordered:
+                heading = meta.get("heading_path")
+                if heading and heading != section:
+                    lines += [f"## {heading}", ""]
+                    section = heading
+                lines += [render_chunk_content(text, meta).strip(), ""]
+
+            (output_dir / _digest_name(first.get("filename"), doc_hash)).write_text("\n".join(lines), encoding="utf-8")
+            progress.files_written += 1
+            progress.chunks_written += len(ordered)
+            progress.chunks_done += len(ordered)
+            if callback:
+                callback(progress)
+        progress.chunks_done = progress.total_chunks
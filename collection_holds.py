@@ -0,0 +1,150 @@
+"""
+Collection Holds for Jan Document Plugin
+
+A read-only flag per collection, for legal holds and other compliance
+freezes: while it is set nothing in the collection can be added, edited
+or deleted - processing, chunk edits, deletes, retention, trash restores,
+imports, migrations, shadow index promotion and resets all refuse with a
+collection_readonly error. Queries and exports keep working.
+
+Holds are kept in <storage>/collection_holds.json. Refused attempts are
+recorded in the audit log by the processor (see
+DocumentProcessor.check_writable).
+"""
+
+import json
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, Optional
+from dataclasses import dataclass, asdict
+from datetime import datetime
+
+logger = logging.getLogger(__name__)
+
+MAX_REASON_LENGTH = 500
+
+
+@dataclass
+class CollectionHold:
+    """Why and since when a collection is read-only."""
+    since: str                       # ISO timestamp
+    reason: Optional[str] = None     # E.g. "Litigation hold, case 2026-114"
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+class CollectionReadOnlyError(RuntimeError):
+    """Raised when something tries to change a read-only collection."""
+
+    error_type = "collection_readonly"
+
+    def __init__(self, collection: str, operation: str, hold: CollectionHold):
+        super().__init__(f"Collection {collection} is read-only; {operation} is not allowed")
+        self.collection = collection
+        self.operation = operation
+        self.hold = hold
+
+    def to_dict(self) -> Dict:
+        return {
+            "error": self.error_type,
+            "message": str(self),
+            "collection": self.collection,
+            "operation": self.operation,
+            "reason": self.hold.reason,
+            "since": self.hold.since,
+            "remediation": "Clear the collection's read-only flag (PUT /collections/readonly) first"
+        }
+
+
+class CollectionHoldStore:
+    """
+    Read-only flags by collection name, persisted as JSON.
+
+    Usage:
+        holds = CollectionHoldStore(Path("./jan_doc_store/collection_holds.json"))
+        holds.set_collection_readonly("jan_documents", True, reason="Legal hold")
+        holds.check_writable("jan_documents", "delete")   # Raises CollectionReadOnlyError
+    """
+
+    def __init__(self, path: Optional[Path] = None):
+        """
+        Initialize hold store.
+
+        Args:
+            path: JSON file holding the holds by collection name (None = in memory)
+        """
+        self.path = Path(path) if path else None
+        self._lock = threading.Lock()
+        self._holds: Dict[str, CollectionHold] = {}
+        if self.path and self.path.exists():
+            try:
+                data = json.loads(self.path.read_text(encoding="utf-8"))
+                self._holds = {name: CollectionHold(**hold) for name, hold in data.items()}
+            except (OSError, ValueError, TypeError) as e:
+                logger.warning(f"Could not read collection holds: {e}")
+
+    def get(self, collection: str) -> Optional[CollectionHold]:
+        """The collection's hold, or None if it is writable."""
+        return self._holds.get(collection)
+
+    def is_readonly(self, collection: str) -> bool:
+        return collection in self._holds
+
+    def list_holds(self) -> Dict[str, Dict]:
+        """Holds of all read-only collections by name."""
+        return {name: hold.to_dict() for name, hold in self._holds.items()}
+
+    def set_collection_readonly(
+        self,
+        collection: str,
+        readonly: bool,
+        reason: Optional[str] = None
+    ) -> Optional[CollectionHold]:
+        """
+        Set or clear a collection's read-only flag. Setting it again keeps
+        the original since and replaces the reason if one is given.
+
+        Returns:
+            The hold, or None once cleared
+
+        Raises:
+            ValueError: If reason is too long
+        """
+        reason = reason.strip() if reason else None
+        if reason and len(reason) > MAX_REASON_LENGTH:
+            raise ValueError(f"reason is longer than {MAX_REASON_LENGTH} characters")
+        with self._lock:
+            if not readonly:
+                if self._holds.pop(collection, None) is not None:
+                    self._save()
+                    logger.info(f"Collection {collection} is writable again")
+                return None
+            existing = self._holds.get(collection)
+            hold = CollectionHold(
+                since=existing.since if existing else datetime.now().isoformat(),
+                reason=reason or (existing.reason if existing else None)
+            )
+            self._holds[collection] = hold
+            self._save()
+        logger.info(f"Collection {collection} is read-only{f': {reason}' if reason else ''}")
+        return hold
+
+    def check_writable(self, collection: str, operation: str):
+        """
+        Raises:
+            CollectionReadOnlyError: If the collection is read-only
+        """
+        hold = self._holds.get(collection)
+        if hold is not None:
+            raise CollectionReadOnlyError(collection, operation, hold)
+
+    def _save(self):
+        if not self.path:
+            return
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self.path.write_text(
+            json.dumps({name: hold.to_dict() for name, hold in self._holds.items()}, indent=2),
+            encoding="utf-8"
+        )
@@ -0,0 +1,257 @@
+"""
+Collection Migration for Jan Document Plugin
+
+Moves the live collection to a new configuration in one step:
+- New embedding model: every chunk is re-embedded from its stored text
+- Same model: chunks are copied with their stored vectors (rebuilds the index)
+
+The new collection is built next to the live one and verified before it
+replaces it:
+- Count check: every chunk made it across
+- Sample check: a spread of chunks must find themselves when their own
+  text is used as the query
+
+Any failure (including during the swap) rolls back: the staging
+collection is deleted and the live collection is left untouched.
+"""
+
+import uuid
+import logging
+from typing import List, Dict, Optional, Callable
+from dataclasses import dataclass, field
+from datetime import datetime
+
+from document_processor import LocalVectorStore
+from shadow_index import swap_in_collection
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+MIGRATION_SUFFIX = "_migration"
+
+# Only ChromaDB is available; target_backend exists so callers can be explicit
+SUPPORTED_BACKENDS = ("chroma",)
+
+# Chunks embedded/copied per call
+MIGRATION_BATCH_SIZE = 64
+
+# Sample check: chunks tested, results searched, and required hit rate
+VERIFY_SAMPLE_SIZE = 20
+VERIFY_TOP_K = 5
+VERIFY_MIN_HIT_RATE = 0.8
+
+
+class MigrationError(Exception):
+    """Migration failed verification (the live collection is unchanged)."""
+    pass
+
+
+@dataclass
+class MigrationProgress:
+    """Progress and outcome of a collection migration."""
+    migration_id: str
+    collection: str
+    source_model: str
+    target_model: str
+    target_backend: str
+    re_embed: bool
+    total_chunks: int = 0
+    chunks_done: int = 0
+    phase: str = "pending"   # pending, copying, verifying, swapping, completed, rolled_back
+    sample_hit_rate: Optional[float] = None
+    error: Optional[str] = None
+    events: List[Dict] = field(default_factory=list)
+    started_at: Optional[datetime] = None
+    completed_at: Optional[datetime] = None
+
+    @property
+    def progress_percent(self) -> float:
+        if self.total_chunks == 0:
+            return 100.0 if self.phase == "completed" else 0.0
+        return self.chunks_done / self.total_chunks * 100
+
+    def to_dict(self) -> Dict:
+        return {
+            "migration_id": self.migration_id,
+            "collection": self.collection,
+            "source_model": self.source_model,
+            "target_model": self.target_model,
+            "target_backend": self.target_backend,
+            "re_embed": self.re_embed,
+            "total_chunks": self.total_chunks,
+            "chunks_done": self.chunks_done,
+            "progress_percent": round(self.progress_percent, 1),
+            "phase": self.phase,
+            "sample_hit_rate": self.sample_hit_rate,
+            "error": self.error,
+            "events": self.events,
+            "started_at": self.started_at.isoformat() if self.started_at else None,
+            "completed_at": self.completed_at.isoformat() if self.completed_at else None
+        }
+
+
+class CollectionMigrator:
+    """
+    Migrates the live collection to another embedding model.
+
+    Usage:
+        migrator = CollectionMigrator(processor.vector_store)
+        progress = migrator.migrate_collection("jan_documents", {"target_model": "all-mpnet-base-v2"})
+    """
+
+    def __init__(self, vector_store: LocalVectorStore):
+        """
+        Initialize migrator.
+
+        Args:
+            vector_store: Live store; a successful migration swaps its
+                          collection and embedder
+        """
+        self.live = vector_store
+        self.current: Optional[MigrationProgress] = None   # Running or last migration
+
+    def _event(
+        self,
+        progress: MigrationProgress,
+        phase: str,
+        message: str,
+        callback: Optional[Callable[[MigrationProgress], None]]
+    ):
+        progress.phase = phase
+        progress.events.append({
+            "time": datetime.now().isoformat(),
+            "phase": phase,
+            "message": message
+        })
+        logger.info(f"Migration {progress.migration_id}: {message}")
+        if callback:
+            callback(progress)
+
+    def migrate_collection(
+        self,
+        collection: Optional[str],
+        target: Dict,
+        progress_callback: Optional[Callable[[MigrationProgress], None]] = None
+    ) -> MigrationProgress:
+        """
+        Migrate a collection to a new configuration.
+
+        Args:
+            collection: Live collection name (None = the processor's collection)
+            target: {"target_model": ..., "target_backend": ...}, both optional
+            progress_callback: Called with MigrationProgress after each step
+
+        Returns:
+            MigrationProgress with phase "completed" or "rolled_back"
+
+        Raises:
+            KeyError: If collection is not the live collection
+            ValueError: If the target is unsupported or a migration is running
+            CollectionReadOnlyError: If the collection is read-only
+        """
+        name = self.live.collection.name
+        check_collection(collection, name)
+        self.live.check_writable("migrate")
+
+        backend = target.get("target_backend") or "chroma"
+        if backend not in SUPPORTED_BACKENDS:
+            raise ValueError(f"Unsupported backend: {backend} (supported: {', '.join(SUPPORTED_BACKENDS)})")
+
+        if self.current and self.current.phase in ("pending", "copying", "verifying", "swapping"):
+            raise ValueError(f"Migration {self.current.migration_id} is already running")
+
+        target_model = target.get("target_model") or self.live.embedding_model
+        progress = MigrationProgress(
+            migration_id=uuid.uuid4().hex[:12],
+            collection=name,
+            source_model=self.live.embedding_model,
+            target_model=target_model,
+            target_backend=backend,
+            re_embed=target_model != self.live.embedding_model,
+            started_at=datetime.now()
+        )
+        self.current = progress
+
+        staging_name = f"{name}{MIGRATION_SUFFIX}"
+        client = self.live.client
+        staging = None
+
+        try:
+            if staging_name in [getattr(c, "name", c) for c in client.list_collections()]:
+                client.delete_collection(staging_name)   # Left over from an interrupted run
+
+            staging = LocalVectorStore(
+                embedding_model=target_model,
+                collection_name=staging_name,
+                client=client
+            )
+
+            chunks = self.live.get_all_chunks(include_embeddings=not progress.re_embed)
+            progress.total_chunks = len(chunks["ids"])
+            action = f"Re-embedding with {target_model}" if progress.re_embed else "Copying"
+            self._event(progress, "copying", f"{action}: {progress.total_chunks} chunks", progress_callback)
+
+            for start in range(0, progress.total_chunks, MIGRATION_BATCH_SIZE):
+                end = min(start + MIGRATION_BATCH_SIZE, progress.total_chunks)
+                batch = {key: values[start:end] for key, values in chunks.items()}
+                staging.restore_chunks(batch)   # Embeds when there are no stored vectors
+                progress.chunks_done = end
+                if progress_callback:
+                    progress_callback(progress)
+
+            self._event(progress, "verifying", "Checking chunk count and sample retrieval", progress_callback)
+            self._verify(staging, chunks, progress)
+            if self.live.get_document_count() != progress.total_chunks:
+                raise MigrationError("Collection changed during migration; run it again")
+
+            self._event(progress, "swapping", f"Replacing {name}", progress_callback)
+            swap_in_collection(self.live, staging)
+
+        except Exception as e:
+            progress.error = str(e)
+            if staging is not None:
+                try:
+                    client.delete_collection(staging_name)
+                except Exception:
+                    pass   # Never created, or already swapped back in
+            progress.completed_at = datetime.now()
+            self._event(progress, "rolled_back", f"Rolled back: {e}", progress_callback)
+            return progress
+
+        progress.completed_at = datetime.now()
+        self._event(progress, "completed", f"{name} now uses {target_model}", progress_callback)
+        return progress
+
+    def _verify(self, staging: LocalVectorStore, chunks: Dict, progress: MigrationProgress):
+        """
+        Raises:
+            MigrationError: If counts differ or too few sampled chunks find themselves
+        """
+        count = staging.get_document_count()
+        if count != progress.total_chunks:
+            raise MigrationError(f"Count check failed: {count} of {progress.total_chunks} chunks migrated")
+
+        total = progress.total_chunks
+        if total == 0:
+            progress.sample_hit_rate = 1.0
+            return
+
+        # Evenly spread sample across the collection
+        step = max(1, total // VERIFY_SAMPLE_SIZE)
+        sample = list(range(0, total, step))[:VERIFY_SAMPLE_SIZE]
+
+        hits = 0
+        for i in sample:
+            result = staging.collection.query(
+                query_embeddings=staging.embedder.encode([chunks["documents"][i]]).tolist(),
+                n_results=min(VERIFY_TOP_K, total),
+                include=[]
+            )
+            if chunks["ids"][i] in result["ids"][0]:
+                hits += 1
+
+        progress.sample_hit_rate = round(hits / len(sample), 4)
+        if progress.sample_hit_rate < VERIFY_MIN_HIT_RATE:
+            raise MigrationError(
+                f"Sample check failed: {hits} of {len(sample)} chunks retrieved themselves"
+            )
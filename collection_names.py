@@ -0,0 +1,102 @@
+"""
+Collection Names for Jan Document Plugin
+
+Rules every collection name must follow, checked on the server before a
+name reaches ChromaDB so process, query and stats requests all fail the
+same way:
+
+- 3 to 50 characters (ChromaDB allows 63; the rest is room for the
+  suffixes of derived collections)
+- Letters, digits, '.', '_' and '-', starting and ending with a letter or digit
+- No '..' (ChromaDB rejects it)
+- Not a reserved name, and not ending in a suffix the plugin uses for
+  its own staging collections (shadow indexes, migrations, resets)
+"""
+
+import re
+from typing import Dict, Optional
+
+DEFAULT_COLLECTION = "jan_documents"
+
+# Names requests can't use: they mean something else to the API or to ChromaDB
+RESERVED_NAMES = ("all", "default", "none", "system")
+
+# Suffixes of the staging collections left by shadow indexes, migrations and resets
+RESERVED_SUFFIXES = ("_shadow", "_previous", "_migration", "_reset", "_before_reset")
+
+MIN_NAME_LENGTH = 3
+# ChromaDB's limit (63) less the longest reserved suffix
+MAX_NAME_LENGTH = 63 - max(len(s) for s in RESERVED_SUFFIXES)
+
+NAME_PATTERN = re.compile(r"^[A-Za-z0-9](?:[A-Za-z0-9._-]*[A-Za-z0-9])?$")
+
+
+class InvalidCollectionNameError(ValueError):
+    """Raised when a collection name breaks the naming rules."""
+
+    error_type = "invalid_collection_name"
+
+    def __init__(self, name: str, reason: str):
+        super().__init__(f"Invalid collection name: {name} ({reason})")
+        self.name = name
+        self.reason = reason
+
+    def to_dict(self) -> Dict:
+        return {
+            "error": self.error_type,
+            "message": str(self),
+            "collection": self.name,
+            "reason": self.reason
+        }
+
+
+def validate_collection_name(name: str) -> str:
+    """
+    Check a collection name against the naming rules.
+
+    Returns:
+        The name
+
+    Raises:
+        InvalidCollectionNameError: If the name breaks a rule
+    """
+    if not isinstance(name, str):
+        raise InvalidCollectionNameError(str(name), "must be a string")
+    if not MIN_NAME_LENGTH <= len(name) <= MAX_NAME_LENGTH:
+        raise InvalidCollectionNameError(
+            name, f"must be {MIN_NAME_LENGTH} to {MAX_NAME_LENGTH} characters"
+        )
+    if not NAME_PATTERN.match(name):
+        raise InvalidCollectionNameError(
+            name, "use letters, digits, '.', '_' and '-', starting and ending with a letter or digit"
+        )
+    if ".." in name:
+        raise InvalidCollectionNameError(name, "must not contain '..'")
+    if name.lower() in RESERVED_NAMES:
+        raise InvalidCollectionNameError(name, "reserved name")
+    for suffix in RESERVED_SUFFIXES:
+        if name.lower().endswith(suffix):
+            raise InvalidCollectionNameError(name, f"names ending in {suffix} are reserved")
+    return name
+
+
+def check_collection(collection: Optional[str], name: str) -> str:
+    """
+    Resolve a requested collection against the store's collection.
+
+    Args:
+        collection: Collection asked for (None or empty = the store's)
+        name: The store's collection
+
+    Returns:
+        The store's collection name
+
+    Raises:
+        InvalidCollectionNameError: If collection breaks the naming rules
+        KeyError: If collection is not the store's collection
+    """
+    if collection:
+        validate_collection_name(collection)
+        if collection != name:
+            raise KeyError(f"Unknown collection: {collection}")
+    return name
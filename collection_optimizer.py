@@ -0,0 +1,223 @@
+"""
+Collection Optimizer for Jan Document Plugin
+
+ChromaDB keeps its records in <storage>/chroma.sqlite3, which doesn't
+shrink when chunks are deleted: after many deletes (retention pruning,
+emptying the trash, re-indexing) the file is mostly free pages. The
+optimizer compacts it:
+
+- Checkpoints the write-ahead log into the database
+- VACUUMs the database, rewriting it without free pages
+- Refreshes SQLite's query planner statistics
+
+It runs on request, and automatically once enough chunks were deleted
+(optimize_after_deleted chunks) - a short delay after the last delete,
+and only while no batch is indexing. Each run reports the storage
+folder and database sizes before and after, and how long it took.
+"""
+
+import time
+import uuid
+import shutil
+import sqlite3
+import logging
+import threading
+from pathlib import Path
+from typing import Callable, Dict, Optional
+from dataclasses import dataclass, asdict
+from datetime import datetime
+
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+CHROMA_DATABASE = "chroma.sqlite3"
+
+# Seconds after the last delete before an automatic run (deletes often come in bursts)
+AUTO_OPTIMIZE_DELAY = 60.0
+
+
+@dataclass
+class OptimizeReport:
+    """Outcome of one optimization run."""
+    optimize_id: str
+    collection: str
+    trigger: str                          # "manual" or "deletes"
+    phase: str = "running"                # running, completed, failed
+    database_bytes_before: int = 0
+    database_bytes_after: int = 0
+    storage_bytes_before: int = 0
+    storage_bytes_after: int = 0
+    deleted_chunks: int = 0               # Deleted since the previous run
+    duration_seconds: float = 0.0
+    error: Optional[str] = None
+    started_at: Optional[str] = None
+    completed_at: Optional[str] = None
+
+    @property
+    def bytes_reclaimed(self) -> int:
+        return max(0, self.storage_bytes_before - self.storage_bytes_after)
+
+    def to_dict(self) -> Dict:
+        return {**asdict(self), "bytes_reclaimed": self.bytes_reclaimed}
+
+
+def _folder_size(folder: Path) -> int:
+    return sum(p.stat().st_size for p in folder.rglob("*") if p.is_file())
+
+
+class CollectionOptimizer:
+    """
+    Compacts the Chroma database, on request or after large deletes.
+
+    Usage:
+        optimizer = CollectionOptimizer(Path("./jan_doc_store"), "jan_documents", optimize_after_deleted=5000)
+        processor.vector_store.delete_hook = optimizer.note_deleted
+        report = optimizer.optimize_collection(None)
+    """
+
+    def __init__(
+        self,
+        storage_directory: Path,
+        collection_name: str,
+        optimize_after_deleted: int = 0,
+        busy_check: Optional[Callable[[], Optional[str]]] = None
+    ):
+        """
+        Initialize optimizer.
+
+        Args:
+            storage_directory: Folder holding chroma.sqlite3
+            collection_name: The store's collection
+            optimize_after_deleted: Deleted chunks that trigger an automatic
+                                    run (0 = never run automatically)
+            busy_check: Returns why optimizing should wait (e.g. a batch is
+                        indexing), or None when it can run
+        """
+        self.storage_directory = Path(storage_directory)
+        self.collection_name = collection_name
+        self.optimize_after_deleted = optimize_after_deleted
+        self.busy_check = busy_check
+        self.deleted_since_optimize = 0
+        self.last: Optional[OptimizeReport] = None   # Running or last run
+        self._lock = threading.Lock()
+        self._timer: Optional[threading.Timer] = None
+
+    @property
+    def database_path(self) -> Path:
+        return self.storage_directory / CHROMA_DATABASE
+
+    def note_deleted(self, count: int):
+        """Count deleted chunks; schedules an automatic run past the threshold."""
+        with self._lock:
+            self.deleted_since_optimize += count
+            if not self.optimize_after_deleted or self.deleted_since_optimize < self.optimize_after_deleted:
+                return
+            self._schedule()
+
+    def _schedule(self):
+        if self._timer:
+            self._timer.cancel()   # Restart the delay while deletes keep coming
+        self._timer = threading.Timer(AUTO_OPTIMIZE_DELAY, self._auto_optimize)
+        self._timer.daemon = True
+        self._timer.start()
+
+    def _auto_optimize(self):
+        reason = self.busy_check() if self.busy_check else None
+        if reason:
+            logger.info(f"Automatic optimization postponed: {reason}")
+            with self._lock:
+                self._schedule()
+            return
+        try:
+            self.optimize_collection(None, trigger="deletes")
+        except (ValueError, FileNotFoundError) as e:
+            logger.info(f"Automatic optimization skipped: {e}")
+
+    def cancel(self):
+        """Drop a scheduled automatic run (e.g. on shutdown)."""
+        with self._lock:
+            if self._timer:
+                self._timer.cancel()
+                self._timer = None
+
+    def get_status(self) -> Dict:
+        return {
+            "deleted_since_optimize": self.deleted_since_optimize,
+            "optimize_after_deleted": self.optimize_after_deleted,
+            "scheduled": bool(self._timer and self._timer.is_alive()),
+            "last": self.last.to_dict() if self.last else None
+        }
+
+    def optimize_collection(self, collection: Optional[str], trigger: str = "manual") -> OptimizeReport:
+        """
+        Compact the database holding the collection.
+
+        Args:
+            collection: Collection name (None = the store's collection)
+            trigger: What started the run ("manual" or "deletes")
+
+        Returns:
+            OptimizeReport with phase "completed" or "failed"
+
+        Raises:
+            KeyError: If collection is not the store's collection
+            ValueError: If a run is already in progress
+            FileNotFoundError: If the store keeps no database file (in-memory store)
+        """
+        check_collection(collection, self.collection_name)
+        if not self.database_path.exists():
+            raise FileNotFoundError(f"No Chroma store at {self.storage_directory}")
+
+        with self._lock:
+            if self.last and self.last.phase == "running":
+                raise ValueError(f"Optimization {self.last.optimize_id} is already running")
+            if self._timer:
+                self._timer.cancel()
+                self._timer = None
+            report = OptimizeReport(
+                optimize_id=uuid.uuid4().hex[:12],
+                collection=self.collection_name,
+                trigger=trigger,
+                deleted_chunks=self.deleted_since_optimize,
+                started_at=datetime.now().isoformat()
+            )
+            self.last = report
+
+        started = time.monotonic()
+        report.database_bytes_before = self.database_path.stat().st_size
+        report.storage_bytes_before = _folder_size(self.storage_directory)
+        try:
+            # VACUUM writes a full copy of the database before replacing it
+            free = shutil.disk_usage(self.storage_directory).free
+            if free < report.database_bytes_before:
+                raise OSError(
+                    f"Not enough disk space to compact the database: "
+                    f"{report.database_bytes_before // (1024 * 1024)} MB needed, {free // (1024 * 1024)} MB free"
+                )
+            connection = sqlite3.connect(str(self.database_path), timeout=30)
+            try:
+                connection.execute("PRAGMA wal_checkpoint(TRUNCATE)")
+                connection.execute("VACUUM")
+                connection.execute("PRAGMA optimize")
+            finally:
+                connection.close()
+        except (sqlite3.Error, OSError) as e:
+            report.phase = "failed"
+            report.error = str(e)
+            logger.warning(f"Optimization {report.optimize_id} failed: {e}")
+        else:
+            report.phase = "completed"
+            with self._lock:
+                self.deleted_since_optimize = 0
+
+        report.database_bytes_after = self.database_path.stat().st_size
+        report.storage_bytes_after = _folder_size(self.storage_directory)
+        report.duration_seconds = round(time.monotonic() - started, 2)
+        report.completed_at = datetime.now().isoformat()
+        if report.phase == "completed":
+            logger.info(
+                f"Optimized {self.collection_name} in {report.duration_seconds}s: "
+                f"{report.storage_bytes_before // 1024} KB -> {report.storage_bytes_after // 1024} KB"
+            )
+        return report
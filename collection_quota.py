@@ -0,0 +1,254 @@
+"""
+Collection Quotas for Jan Document Plugin
+
+Admins deploying to many machines cap how large a knowledge base may
+grow. A quota limits a collection to:
+
+- max_documents: number of indexed documents
+- max_bytes: disk space used by the index
+
+Quotas are checked before processing starts, so an upload that would
+exceed one is refused up front (QuotaExceededError, reported as a
+"quota_exceeded" error) instead of failing halfway. Usage against the
+quota is included in the document stats.
+
+Quotas are stored as JSON keyed by collection name:
+<storage>/collection_quota.json
+"""
+
+import os
+import json
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, Optional
+from dataclasses import dataclass, asdict
+
+from document_processor import DocumentProcessor
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+# Storage subfolders that are not part of the index itself
+EXCLUDED_DIRECTORIES = {"trash", "thumbnails"}
+
+
+@dataclass
+class CollectionQuota:
+    """Limits for one collection (None = no limit)."""
+    max_documents: Optional[int] = None
+    max_bytes: Optional[int] = None
+
+    def validate(self):
+        """
+        Raises:
+            ValueError: If a limit is not a positive number
+        """
+        if self.max_documents is not None and self.max_documents < 1:
+            raise ValueError("max_documents must be at least 1")
+        if self.max_bytes is not None and self.max_bytes < 1:
+            raise ValueError("max_bytes must be at least 1")
+
+    @property
+    def active(self) -> bool:
+        return self.max_documents is not None or self.max_bytes is not None
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+class QuotaExceededError(Exception):
+    """Raised when an operation would take a collection over its quota."""
+
+    def __init__(
+        self,
+        collection: str,
+        limit: str,
+        quota: int,
+        used: int,
+        requested: int,
+        operation: str = "processing"
+    ):
+        self.collection = collection
+        self.limit = limit
+        self.quota = quota
+        self.used = used
+        self.requested = requested
+        self.operation = operation
+        super().__init__(
+            f"Quota exceeded for {operation}: collection {collection} would have "
+            f"{used + requested} of {quota} allowed ({limit})"
+        )
+
+    def to_dict(self) -> Dict:
+        return {
+            "error": "quota_exceeded",
+            "message": str(self),
+            "operation": self.operation,
+            "collection": self.collection,
+            "limit": self.limit,
+            "quota": self.quota,
+            "used": self.used,
+            "requested": self.requested
+        }
+
+
+class QuotaManager:
+    """
+    Stores collection quotas and checks operations against them.
+
+    Usage:
+        quotas = QuotaManager(processor, Path("./jan_doc_store/collection_quota.json"),
+                              Path("./jan_doc_store"))
+        quotas.set_quota("documents", CollectionQuota(max_documents=500))
+        quotas.check(additional_documents=1, additional_bytes=2_000_000)
+    """
+
+    def __init__(
+        self,
+        document_processor: DocumentProcessor,
+        quota_path: Path,
+        storage_directory: Optional[Path] = None
+    ):
+        """
+        Initialize quota manager.
+
+        Args:
+            document_processor: Processor whose collection is limited
+            quota_path: JSON file holding quotas by collection name
+            storage_directory: Folder of the persistent index, measured for
+                               disk usage (None = ephemeral, counts 0 bytes)
+        """
+        self.processor = document_processor
+        self.quota_path = Path(quota_path)
+        self.storage_directory = Path(storage_directory) if storage_directory else None
+        self._lock = threading.Lock()
+        self._quotas: Dict[str, CollectionQuota] = {}
+        if self.quota_path.exists():
+            try:
+                data = json.loads(self.quota_path.read_text(encoding="utf-8"))
+                self._quotas = {name: CollectionQuota(**q) for name, q in data.items()}
+            except (OSError, ValueError, TypeError) as e:
+                logger.warning(f"Could not read collection quotas: {e}")
+
+    def _collection_name(self, collection: Optional[str]) -> str:
+        name = self.processor.vector_store.collection.name
+        check_collection(collection, name)
+        return name
+
+    def get_quota(self, collection: Optional[str] = None) -> CollectionQuota:
+        """
+        Raises:
+            KeyError: If collection is not the store's collection
+        """
+        return self._quotas.get(self._collection_name(collection)) or CollectionQuota()
+
+    def set_quota(self, collection: Optional[str], quota: CollectionQuota) -> CollectionQuota:
+        """
+        Store a collection's quota (a quota without limits removes it).
+
+        Raises:
+            KeyError: If collection is not the store's collection
+            ValueError: If a limit is invalid
+        """
+        name = self._collection_name(collection)
+        quota.validate()
+        with self._lock:
+            if quota.active:
+                self._quotas[name] = quota
+            else:
+                self._quotas.pop(name, None)
+            self.quota_path.parent.mkdir(parents=True, exist_ok=True)
+            self.quota_path.write_text(
+                json.dumps({n: q.to_dict() for n, q in self._quotas.items()}, indent=2),
+                encoding="utf-8"
+            )
+        logger.info(f"Quota for {name}: {quota.to_dict()}")
+        return quota
+
+    def index_bytes(self) -> int:
+        """Bytes used by the index files (trash and caches excluded)."""
+        if self.storage_directory is None or not self.storage_directory.is_dir():
+            return 0
+        total = 0
+        for root, dirs, files in os.walk(self.storage_directory):
+            if Path(root) == self.storage_directory:
+                dirs[:] = [d for d in dirs if d not in EXCLUDED_DIRECTORIES]
+            for name in files:
+                try:
+                    total += os.path.getsize(os.path.join(root, name))
+                except OSError:
+                    continue
+        return total
+
+    def document_count(self) -> int:
+        """
+        Documents in the store, counted from the index itself (latest
+        versions only) so the count survives restarts.
+        """
+        return len(self.processor.vector_store.list_documents(latest_only=True))
+
+    def get_usage(self, collection: Optional[str] = None) -> Dict:
+        """
+        Current usage next to the collection's quota.
+
+        Raises:
+            KeyError: If collection is not the store's collection
+        """
+        name = self._collection_name(collection)
+        quota = self._quotas.get(name) or CollectionQuota()
+        documents = self.document_count()
+        size_bytes = self.index_bytes()
+        return {
+            "collection": name,
+            "documents": documents,
+            "size_bytes": size_bytes,
+            "quota": quota.to_dict(),
+            "documents_percent": (
+                round(documents / quota.max_documents * 100, 1) if quota.max_documents else None
+            ),
+            "bytes_percent": (
+                round(size_bytes / quota.max_bytes * 100, 1) if quota.max_bytes else None
+            )
+        }
+
+    def check(
+        self,
+        additional_documents: int = 1,
+        additional_bytes: int = 0,
+        collection: Optional[str] = None,
+        operation: str = "processing"
+    ):
+        """
+        Raise if adding documents would exceed the collection's quota.
+
+        Args:
+            additional_documents: Documents the operation will index
+            additional_bytes: Estimated index bytes the operation will write
+            collection: Collection name (None = the store's collection)
+            operation: Name used in the error message
+
+        Raises:
+            QuotaExceededError: If a limit would be exceeded
+            KeyError: If collection is not the store's collection
+        """
+        name = self._collection_name(collection)
+        quota = self._quotas.get(name)
+        if quota is None:
+            return
+
+        if quota.max_documents is not None:
+            documents = self.document_count()
+            if documents + additional_documents > quota.max_documents:
+                raise QuotaExceededError(
+                    name, "max_documents", quota.max_documents,
+                    documents, additional_documents, operation
+                )
+
+        if quota.max_bytes is not None:
+            size_bytes = self.index_bytes()
+            if size_bytes + additional_bytes > quota.max_bytes:
+                raise QuotaExceededError(
+                    name, "max_bytes", quota.max_bytes,
+                    size_bytes, additional_bytes, operation
+                )
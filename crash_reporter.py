@@ -0,0 +1,204 @@
+"""
+Crash Reporter for Jan Document Plugin
+
+Unhandled exceptions in request handlers and worker threads otherwise
+surface as a bare "500 Internal Server Error" (or only a console line for
+threads). The crash reporter:
+- Writes each crash with its full traceback to a local crash log
+- Gives callers a sanitized summary (no tracebacks, home directory masked)
+  with a crash id to look up the full report; the summary names the
+  exception type, its message, the frame it was raised in and the app
+  version, so the UI can show a short error with the details on demand
+- Lists recent reports for the debug report / diagnostics bundle
+
+Reports never leave the machine: <crash_dir>/<crash_id>.json
+"""
+
+import os
+import sys
+import json
+import uuid
+import logging
+import threading
+import traceback
+from pathlib import Path
+from typing import List, Dict, Optional
+from dataclasses import dataclass, asdict
+from datetime import datetime
+
+from request_context import get_request_id
+
+logger = logging.getLogger(__name__)
+
+# Oldest reports beyond this count are deleted
+MAX_CRASH_REPORTS = 50
+
+# Longest message kept in the summary
+MAX_SUMMARY_LENGTH = 300
+
+
+def last_frame(exc: BaseException) -> str:
+    """Where the exception was raised: "<file name>:<line> in <function>"."""
+    frames = traceback.extract_tb(exc.__traceback__)
+    if not frames:
+        return ""
+    frame = frames[-1]
+    return f"{Path(frame.filename).name}:{frame.lineno} in {frame.name}"
+
+
+def sanitize(text: str) -> str:
+    """Mask the user's home directory and trim to summary length."""
+    home = os.path.expanduser("~")
+    if home and home not in ("/", "~"):
+        text = text.replace(home, "~")
+    text = " ".join(text.split())
+    if len(text) > MAX_SUMMARY_LENGTH:
+        text = text[:MAX_SUMMARY_LENGTH - 3] + "..."
+    return text
+
+
+@dataclass
+class CrashReport:
+    """One captured crash."""
+    crash_id: str
+    occurred_at: str
+    source: str                      # "request", "thread" or "process"
+    context: str                     # e.g. "POST /documents" or the thread name
+    exception_type: str
+    message: str                     # Sanitized
+    traceback: str                   # Full traceback (local file only)
+    last_frame: str = ""             # e.g. "document_processor.py:812 in ingest"
+    app_version: str = ""
+    request_id: str = ""             # API request being handled, if any
+
+    def summary(self) -> Dict:
+        """What is safe to show in responses and events: no traceback."""
+        return {
+            "crash_id": self.crash_id,
+            "occurred_at": self.occurred_at,
+            "source": self.source,
+            "context": self.context,
+            "exception_type": self.exception_type,
+            "message": self.message,
+            "last_frame": self.last_frame,
+            "app_version": self.app_version,
+            "request_id": self.request_id
+        }
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+class CrashReporter:
+    """
+    Captures unhandled exceptions into a local crash log.
+
+    Usage:
+        crashes = CrashReporter(Path("./jan_doc_store/crash_reports"))
+        crashes.install_hooks()            # Threads and the main process
+        report = crashes.capture(exc, "request", "POST /documents")
+    """
+
+    def __init__(self, crash_directory: Path, max_reports: int = MAX_CRASH_REPORTS, app_version: str = ""):
+        """
+        Initialize crash reporter.
+
+        Args:
+            crash_directory: Directory holding crash report files
+            max_reports: Reports kept before the oldest are deleted
+            app_version: Plugin version recorded with each report
+        """
+        self.crash_directory = Path(crash_directory)
+        self.crash_directory.mkdir(parents=True, exist_ok=True)
+        self.max_reports = max_reports
+        self.app_version = app_version
+        self._lock = threading.Lock()
+
+    def capture(
+        self,
+        exc: BaseException,
+        source: str,
+        context: str = "",
+        request_id: Optional[str] = None
+    ) -> CrashReport:
+        """Record an exception and return its report (request_id defaults to the current request's)."""
+        report = CrashReport(
+            crash_id=uuid.uuid4().hex[:12],
+            occurred_at=datetime.now().isoformat(),
+            source=source,
+            context=sanitize(context),
+            exception_type=type(exc).__name__,
+            message=sanitize(str(exc)),
+            traceback="".join(traceback.format_exception(type(exc), exc, exc.__traceback__)),
+            last_frame=last_frame(exc),
+            app_version=self.app_version,
+            request_id=request_id or get_request_id() or ""
+        )
+
+        with self._lock:
+            try:
+                path = self.crash_directory / f"{report.crash_id}.json"
+                path.write_text(json.dumps(report.to_dict(), indent=2), encoding="utf-8")
+                self._prune()
+            except OSError as e:
+                logger.warning(f"Could not write crash report: {e}")
+
+        logger.error(
+            f"Crash {report.crash_id} in {source} {report.context}: "
+            f"{report.exception_type}: {report.message} ({report.last_frame})"
+        )
+        return report
+
+    def _prune(self):
+        files = sorted(self.crash_directory.glob("*.json"), key=lambda p: p.stat().st_mtime)
+        for path in files[:max(0, len(files) - self.max_reports)]:
+            path.unlink()
+
+    def get_crash_reports(self, limit: Optional[int] = None) -> List[CrashReport]:
+        """Crash reports, newest first."""
+        reports = []
+        for path in self.crash_directory.glob("*.json"):
+            try:
+                reports.append(CrashReport(**json.loads(path.read_text(encoding="utf-8"))))
+            except (OSError, ValueError, TypeError) as e:
+                logger.warning(f"Skipping unreadable crash report {path.name}: {e}")
+
+        reports.sort(key=lambda r: r.occurred_at, reverse=True)
+        return reports[:limit] if limit else reports
+
+    def get_crash_report(self, crash_id: str) -> CrashReport:
+        """
+        Raises:
+            KeyError: If there is no report with this id
+        """
+        path = self.crash_directory / f"{crash_id}.json"
+        if not path.exists():
+            raise KeyError(f"Crash report not found: {crash_id}")
+        return CrashReport(**json.loads(path.read_text(encoding="utf-8")))
+
+    def clear(self) -> int:
+        """Delete all crash reports. Returns the number removed."""
+        removed = 0
+        for path in self.crash_directory.glob("*.json"):
+            path.unlink()
+            removed += 1
+        return removed
+
+    def install_hooks(self):
+        """Capture uncaught exceptions in threads and the main process."""
+        previous_thread_hook = threading.excepthook
+        previous_process_hook = sys.excepthook
+
+        def thread_hook(args):
+            if args.exc_value is not None and not isinstance(args.exc_value, SystemExit):
+                name = args.thread.name if args.thread else "unknown thread"
+                self.capture(args.exc_value, "thread", name)
+            previous_thread_hook(args)
+
+        def process_hook(exc_type, exc_value, exc_traceback):
+            if not issubclass(exc_type, KeyboardInterrupt):
+                self.capture(exc_value, "process")
+            previous_process_hook(exc_type, exc_value, exc_traceback)
+
+        threading.excepthook = thread_hook
+        sys.excepthook = process_hook
@@ -0,0 +1,339 @@
+"""
+Data Relocation for Jan Document Plugin
+
+Moves the plugin's data to another folder or disk (e.g. off a small
+system drive) in one step:
+
+- Copying: the storage folder, and the embedding model cache when the
+  plugin manages it (portable mode or an earlier relocation), are copied
+  file by file into <new location>/<folder name>
+- Verifying: every copied file's SHA-256 is compared with the source's
+- Switching: the new folders are recorded in data_location.json (see
+  app_paths), which the launcher reads on the next start
+- Cleanup: the old folders are removed; if that fails the data is still
+  safe in its new location and the leftovers are reported
+
+Any failure before the switch rolls back: the partial copies are deleted
+and the data stays where it was. The caller must stop background work
+and close the document store first, and restart afterwards;
+StoreRelocation does that around a running server.
+"""
+
+import uuid
+import asyncio
+import shutil
+import hashlib
+import logging
+from pathlib import Path
+from typing import Callable, Dict, List, Optional, Tuple
+from dataclasses import dataclass, field
+from datetime import datetime
+
+from app_paths import load_location_override, save_location_override
+
+logger = logging.getLogger(__name__)
+
+# Free space kept on the destination beyond the data itself
+RELOCATION_RESERVE_BYTES = 200 * 1024 * 1024
+
+HASH_BLOCK_SIZE = 1024 * 1024
+
+
+class RelocationError(Exception):
+    """Relocation failed; the data was left where it was."""
+    pass
+
+
+@dataclass
+class RelocationProgress:
+    """Progress and outcome of a data relocation."""
+    relocation_id: str
+    destination: str
+    folders: Dict[str, str] = field(default_factory=dict)   # kind -> current folder
+    total_files: int = 0
+    files_copied: int = 0
+    files_verified: int = 0
+    total_bytes: int = 0
+    bytes_copied: int = 0
+    phase: str = "pending"   # pending, copying, verifying, switching, cleanup, completed, rolled_back
+    error: Optional[str] = None
+    leftovers: List[str] = field(default_factory=list)
+    events: List[Dict] = field(default_factory=list)
+    started_at: Optional[datetime] = None
+    completed_at: Optional[datetime] = None
+
+    @property
+    def progress_percent(self) -> float:
+        if self.total_bytes == 0:
+            return 100.0 if self.phase == "completed" else 0.0
+        return self.bytes_copied / self.total_bytes * 100
+
+    def to_dict(self) -> Dict:
+        return {
+            "relocation_id": self.relocation_id,
+            "destination": self.destination,
+            "folders": self.folders,
+            "total_files": self.total_files,
+            "files_copied": self.files_copied,
+            "files_verified": self.files_verified,
+            "total_bytes": self.total_bytes,
+            "bytes_copied": self.bytes_copied,
+            "progress_percent": round(self.progress_percent, 1),
+            "phase": self.phase,
+            "error": self.error,
+            "leftovers": self.leftovers,
+            "events": self.events,
+            "started_at": self.started_at.isoformat() if self.started_at else None,
+            "completed_at": self.completed_at.isoformat() if self.completed_at else None
+        }
+
+
+def _file_hash(path: Path) -> str:
+    hasher = hashlib.sha256()
+    with open(path, "rb") as f:
+        for block in iter(lambda: f.read(HASH_BLOCK_SIZE), b""):
+            hasher.update(block)
+    return hasher.hexdigest()
+
+
+def _files(folder: Path) -> List[Path]:
+    return [p for p in folder.rglob("*") if p.is_file()]
+
+
+class DataRelocator:
+    """
+    Moves the storage folder (and managed model cache) to a new location.
+
+    Usage:
+        relocator = DataRelocator()
+        progress = relocator.set_data_location(Path("D:/JanData"), {"storage_directory": Path("./jan_doc_store")})
+    """
+
+    def __init__(self, app_path: Optional[Path] = None):
+        """
+        Initialize relocator.
+
+        Args:
+            app_path: Folder holding data_location.json (None = app folder)
+        """
+        self.app_path = app_path
+        self.current: Optional[RelocationProgress] = None   # Running or last relocation
+
+    def _event(
+        self,
+        progress: RelocationProgress,
+        phase: str,
+        message: str,
+        callback: Optional[Callable[[RelocationProgress], None]]
+    ):
+        progress.phase = phase
+        progress.events.append({
+            "time": datetime.now().isoformat(),
+            "phase": phase,
+            "message": message
+        })
+        logger.info(f"Relocation {progress.relocation_id}: {message}")
+        if callback:
+            callback(progress)
+
+    def set_data_location(
+        self,
+        new_path: Path,
+        folders: Dict[str, Path],
+        progress_callback: Optional[Callable[[RelocationProgress], None]] = None
+    ) -> RelocationProgress:
+        """
+        Move data folders under new_path and record their new location.
+
+        Args:
+            new_path: Folder to move the data into (created if needed)
+            folders: Folders to move by kind ("storage_directory",
+                     "model_cache"); missing folders are skipped
+            progress_callback: Called with RelocationProgress after each file
+
+        Returns:
+            RelocationProgress with phase "completed" or "rolled_back"
+
+        Raises:
+            ValueError: If new_path is relative, inside a folder being
+                        moved, already holds the data, or a relocation is running
+        """
+        if self.current and self.current.phase in ("pending", "copying", "verifying", "switching", "cleanup"):
+            raise ValueError(f"Relocation {self.current.relocation_id} is already running")
+
+        new_path = Path(new_path)
+        if not new_path.is_absolute():
+            raise ValueError("The new data location must be an absolute path")
+        new_path = new_path.resolve()
+
+        moves: List[Tuple[str, Path, Path]] = []
+        for kind, folder in folders.items():
+            if folder is None or not Path(folder).is_dir():
+                continue
+            source = Path(folder).resolve()
+            target = new_path / source.name
+            if source == target:
+                raise ValueError(f"{source} is already in {new_path}")
+            if new_path == source or source in new_path.parents:
+                raise ValueError(f"The new location can't be inside {source}")
+            if target.exists() and any(target.iterdir()):
+                raise ValueError(f"{target} already exists and is not empty")
+            moves.append((kind, source, target))
+
+        progress = RelocationProgress(
+            relocation_id=uuid.uuid4().hex[:12],
+            destination=str(new_path),
+            folders={kind: str(source) for kind, source, _ in moves},
+            started_at=datetime.now()
+        )
+        self.current = progress
+        if not moves:
+            progress.completed_at = datetime.now()
+            self._event(progress, "completed", "Nothing to move", progress_callback)
+            return progress
+
+        plan = [(source, target, _files(source)) for _, source, target in moves]
+        progress.total_files = sum(len(files) for _, _, files in plan)
+        progress.total_bytes = sum(p.stat().st_size for _, _, files in plan for p in files)
+
+        created: List[Path] = []
+        previous = load_location_override(self.app_path)
+        try:
+            new_path.mkdir(parents=True, exist_ok=True)
+            free = shutil.disk_usage(new_path).free
+            if free < progress.total_bytes + RELOCATION_RESERVE_BYTES:
+                raise RelocationError(
+                    f"Not enough space in {new_path}: {progress.total_bytes // (1024 * 1024)} MB needed, "
+                    f"{free // (1024 * 1024)} MB free"
+                )
+
+            self._event(
+                progress, "copying",
+                f"Copying {progress.total_files} files ({progress.total_bytes // (1024 * 1024)} MB) to {new_path}",
+                progress_callback
+            )
+            hashes: Dict[Path, str] = {}
+            for source, target, files in plan:
+                target.mkdir(parents=True, exist_ok=True)
+                created.append(target)
+                for path in files:
+                    destination = target / path.relative_to(source)
+                    destination.parent.mkdir(parents=True, exist_ok=True)
+                    hashes[destination] = _file_hash(path)
+                    shutil.copy2(path, destination)
+                    progress.files_copied += 1
+                    progress.bytes_copied += path.stat().st_size
+                    if progress_callback:
+                        progress_callback(progress)
+
+            self._event(progress, "verifying", "Comparing copies with the originals", progress_callback)
+            for destination, expected in hashes.items():
+                if _file_hash(destination) != expected:
+                    raise RelocationError(f"Copy of {destination.name} doesn't match the original")
+                progress.files_verified += 1
+
+            self._event(progress, "switching", "Recording the new location", progress_callback)
+            save_location_override(
+                {**previous, **{kind: str(target) for kind, _, target in moves}},
+                self.app_path
+            )
+        except (OSError, RelocationError) as e:
+            progress.error = str(e)
+            for target in created:
+                shutil.rmtree(target, ignore_errors=True)
+            try:
+                save_location_override(previous, self.app_path)
+            except OSError as restore_error:
+                logger.error(f"Could not restore data location override: {restore_error}")
+            progress.completed_at = datetime.now()
+            self._event(progress, "rolled_back", f"Relocation failed, data left in place: {e}", progress_callback)
+            return progress
+
+        self._event(progress, "cleanup", "Removing the old folders", progress_callback)
+        for _, source, _ in moves:
+            try:
+                shutil.rmtree(source)
+            except OSError as e:
+                progress.leftovers.append(str(source))
+                logger.warning(f"Could not remove old folder {source}: {e}")
+
+        progress.completed_at = datetime.now()
+        self._event(
+            progress, "completed",
+            f"Moved {progress.total_files} files to {new_path}; restart to use the new location",
+            progress_callback
+        )
+        return progress
+
+
+class StoreRelocation:
+    """
+    Relocation of a running server's data: the store is closed while the
+    files are copied, and reopened where it was if the relocation fails.
+    block says why requests should be refused (None = accept them): while
+    the relocation runs, and after a successful one until the restart.
+
+    Usage:
+        relocation = StoreRelocation(DataRelocator(), close_store, open_store)
+        progress = await relocation.run(Path("D:/JanData"), {"storage_directory": Path("./jan_doc_store")})
+    """
+
+    def __init__(
+        self,
+        relocator: DataRelocator,
+        close_store: Callable[[], bool],
+        reopen_store: Callable[[], None]
+    ):
+        """
+        Initialize store relocation.
+
+        Args:
+            relocator: Moves the folders
+            close_store: Stops background work and closes the store;
+                         returns whether a store was open
+            reopen_store: Reopens the store in its old location (only
+                          called if one was open)
+        """
+        self.relocator = relocator
+        self.close_store = close_store
+        self.reopen_store = reopen_store
+        self.block: Optional[str] = None
+
+    async def run(self, new_path: Path, folders: Dict[str, Path]) -> RelocationProgress:
+        """
+        Close the store, move the folders and reopen the store on failure.
+
+        Returns:
+            RelocationProgress with phase "completed" or "rolled_back"
+
+        Raises:
+            ValueError: If a relocation is running or waiting for the
+                        restart, or new_path is rejected (the store is
+                        reopened first)
+        """
+        if self.block is not None:
+            raise ValueError(self.block)
+
+        self.block = "The data folder is being moved; try again when it has finished"
+        was_open = self.close_store()
+        loop = asyncio.get_event_loop()
+        try:
+            progress = await loop.run_in_executor(
+                None, lambda: self.relocator.set_data_location(Path(new_path), folders)
+            )
+        except BaseException:
+            self._reopen(was_open)
+            raise
+
+        if progress.phase != "completed":
+            self._reopen(was_open)
+            return progress
+        self.block = f"The data was moved to {progress.destination}; restart the server to use it"
+        return progress
+
+    def _reopen(self, was_open: bool):
+        try:
+            if was_open:
+                self.reopen_store()
+        finally:
+            self.block = None
@@ -0,0 +1,334 @@
+"""
+Directory Scanner for Jan Document Plugin
+
+Discovers supported documents under a directory before ingestion:
+- Filters by supported extension
+- Collects size / modified time
+- Detects duplicate files (identical content) so only one copy is indexed
+
+Duplicate detection is staged to stay fast on large trees:
+1. Group by file size (free - already stat'ed)
+2. Same-size files get a partial hash (size + first/last 64KB)
+3. Partial-hash collisions are confirmed with a full content hash
+   (skipped when partial_hashes_only=True)
+
+Files are found by one of two engines:
+- walk: list the directory tree (always available)
+- windows_search: ask the Windows Search index, which answers in
+  seconds where walking a large drive takes minutes. Needs Windows with
+  the search service and pywin32; roots outside the index (or an
+  unavailable service) fall back to walk, noted in the result's warnings.
+  The index can lag behind the disk by a few minutes.
+"""
+
+import sys
+import hashlib
+import logging
+from pathlib import Path
+from typing import List, Dict, Optional, Union
+from dataclasses import dataclass, field
+from datetime import datetime
+
+logger = logging.getLogger(__name__)
+
+# Bytes read from each end of a file for the partial hash
+PARTIAL_HASH_BYTES = 64 * 1024
+
+SCAN_ENGINES = ("walk", "windows_search")
+
+# OLE DB provider of the Windows Search index
+WINDOWS_SEARCH_CONNECTION = "Provider=Search.CollatorDSO;Extended Properties='Application=Windows';"
+
+
+@dataclass
+class ScannedFile:
+    """A supported document found during a directory scan."""
+    path: str
+    filename: str
+    extension: str
+    size_bytes: int
+    modified_at: datetime
+    content_hash: Optional[str] = None   # Same 16-char form as DocumentProcessor doc_hash
+    duplicate_of: Optional[str] = None   # Path of the copy that will be indexed
+
+    @property
+    def is_duplicate(self) -> bool:
+        return self.duplicate_of is not None
+
+    def to_dict(self) -> Dict:
+        return {
+            "path": self.path,
+            "filename": self.filename,
+            "extension": self.extension,
+            "size_bytes": self.size_bytes,
+            "modified_at": self.modified_at.isoformat(),
+            "content_hash": self.content_hash,
+            "is_duplicate": self.is_duplicate,
+            "duplicate_of": self.duplicate_of
+        }
+
+
+@dataclass
+class ScanResult:
+    """Result of scanning a directory."""
+    root: str
+    files: List[ScannedFile] = field(default_factory=list)
+    duplicate_groups: List[List[str]] = field(default_factory=list)
+    scanned_at: datetime = field(default_factory=datetime.now)
+    engine: str = "walk"                 # Engine that found the files
+    warnings: List[str] = field(default_factory=list)
+
+    @property
+    def unique_files(self) -> List[ScannedFile]:
+        """Files to index - one copy per duplicate group."""
+        return [f for f in self.files if not f.is_duplicate]
+
+    def to_dict(self) -> Dict:
+        duplicates = [f for f in self.files if f.is_duplicate]
+        return {
+            "root": self.root,
+            "scanned_at": self.scanned_at.isoformat(),
+            "engine": self.engine,
+            "warnings": self.warnings,
+            "total_files": len(self.files),
+            "unique_files": len(self.files) - len(duplicates),
+            "duplicate_files": len(duplicates),
+            "duplicate_bytes": sum(f.size_bytes for f in duplicates),
+            "duplicate_groups": self.duplicate_groups,
+            "files": [f.to_dict() for f in self.files]
+        }
+
+
+def compute_content_hash(path: Path) -> str:
+    """Full SHA-256 content hash (truncated like DocumentProcessor._compute_hash)."""
+    hasher = hashlib.sha256()
+    with open(path, 'rb') as f:
+        for chunk in iter(lambda: f.read(8192), b''):
+            hasher.update(chunk)
+    return hasher.hexdigest()[:16]
+
+
+def compute_partial_hash(path: Path, size_bytes: int) -> str:
+    """
+    Cheap fingerprint from file size plus the first and last 64KB. Files
+    under 128KB are covered in full (the tail starts where the head ends).
+    """
+    hasher = hashlib.sha256(str(size_bytes).encode())
+    with open(path, 'rb') as f:
+        hasher.update(f.read(PARTIAL_HASH_BYTES))
+        if size_bytes > PARTIAL_HASH_BYTES:
+            f.seek(max(PARTIAL_HASH_BYTES, size_bytes - PARTIAL_HASH_BYTES))
+            hasher.update(f.read(PARTIAL_HASH_BYTES))
+    return hasher.hexdigest()[:16]
+
+
+def _group_by(files: List[ScannedFile], key) -> Dict[str, List[ScannedFile]]:
+    """Group files by key(file), dropping files whose key can't be computed."""
+    groups: Dict[str, List[ScannedFile]] = {}
+    for f in files:
+        try:
+            k = key(f)
+        except OSError as e:
+            logger.warning(f"Could not hash {f.path}: {e}")
+            continue
+        groups.setdefault(k, []).append(f)
+    return groups
+
+
+def mark_duplicates(files: List[ScannedFile], partial_hashes_only: bool = False) -> List[List[str]]:
+    """
+    Mark files with identical content as duplicates of the first copy.
+
+    The first file of each group (by path) is kept; the others get
+    duplicate_of set to its path.
+
+    Args:
+        files: Scanned files (modified in place)
+        partial_hashes_only: Trust size + partial hash without a full hash
+
+    Returns:
+        List of duplicate groups (paths, kept copy first)
+    """
+    duplicate_groups = []
+
+    by_size = _group_by(files, lambda f: str(f.size_bytes))
+
+    for same_size in by_size.values():
+        if len(same_size) < 2:
+            continue
+
+        by_partial = _group_by(
+            same_size,
+            lambda f: compute_partial_hash(Path(f.path), f.size_bytes)
+        )
+
+        for candidates in by_partial.values():
+            if len(candidates) < 2:
+                continue
+
+            if partial_hashes_only:
+                confirmed = [candidates]
+            else:
+                def full_hash(f: ScannedFile) -> str:
+                    f.content_hash = compute_content_hash(Path(f.path))
+                    return f.content_hash
+                confirmed = [g for g in _group_by(candidates, full_hash).values() if len(g) > 1]
+
+            for group in confirmed:
+                group.sort(key=lambda f: f.path)
+                original = group[0]
+                for dup in group[1:]:
+                    dup.duplicate_of = original.path
+                duplicate_groups.append([f.path for f in group])
+
+    if duplicate_groups:
+        dup_count = sum(len(g) - 1 for g in duplicate_groups)
+        logger.info(f"Found {dup_count} duplicate file(s) in {len(duplicate_groups)} group(s)")
+
+    return duplicate_groups
+
+
+def _walk_files(dir_path: Path, recursive: bool, supported: set) -> List[ScannedFile]:
+    """Supported files found by listing the directory tree."""
+    files = []
+    for file_path in sorted(dir_path.glob("**/*" if recursive else "*")):
+        if not file_path.is_file() or file_path.suffix.lower() not in supported:
+            continue
+        try:
+            stat = file_path.stat()
+        except OSError as e:
+            logger.warning(f"Skipping unreadable file {file_path}: {e}")
+            continue
+
+        files.append(ScannedFile(
+            path=str(file_path.resolve()),
+            filename=file_path.name,
+            extension=file_path.suffix.lower(),
+            size_bytes=stat.st_size,
+            modified_at=datetime.fromtimestamp(stat.st_mtime)
+        ))
+    return files
+
+
+def _windows_search_files(dir_path: Path, recursive: bool, supported: set) -> List[ScannedFile]:
+    """
+    Supported files the Windows Search index knows under dir_path.
+
+    Raises:
+        OSError: If Windows Search can't be queried (not Windows, no
+                 pywin32, service stopped)
+    """
+    if sys.platform != "win32":
+        raise OSError("Windows Search is only available on Windows")
+    try:
+        import win32com.client
+    except ImportError:
+        raise OSError("Windows Search needs pywin32")
+
+    root = str(dir_path.resolve()).replace("'", "''")
+    scope = f"SCOPE='file:{root}'" if recursive else f"DIRECTORY='file:{root}'"
+    extensions = ", ".join(f"'{ext}'" for ext in sorted(supported) if "'" not in ext)
+    query = (
+        "SELECT System.ItemPathDisplay, System.Size, System.DateModified FROM SYSTEMINDEX "
+        f"WHERE {scope} AND System.FileExtension IN ({extensions})"
+    )
+
+    files = []
+    try:
+        connection = win32com.client.Dispatch("ADODB.Connection")
+        connection.Open(WINDOWS_SEARCH_CONNECTION)
+        try:
+            records = win32com.client.Dispatch("ADODB.Recordset")
+            records.Open(query, connection)
+            while not records.EOF:
+                path = Path(records.Fields.Item("System.ItemPathDisplay").Value)
+                size = records.Fields.Item("System.Size").Value
+                modified = records.Fields.Item("System.DateModified").Value
+                records.MoveNext()
+                if path.suffix.lower() not in supported:
+                    continue
+                if modified:
+                    # Reported in UTC; converted to local time like the walk engine's
+                    modified_ts = modified.timestamp()
+                else:
+                    try:
+                        modified_ts = path.stat().st_mtime
+                    except OSError:
+                        continue   # Index lags behind a deleted file
+                files.append(ScannedFile(
+                    path=str(path),
+                    filename=path.name,
+                    extension=path.suffix.lower(),
+                    size_bytes=int(size or 0),
+                    modified_at=datetime.fromtimestamp(modified_ts)
+                ))
+            records.Close()
+        finally:
+            connection.Close()
+    except Exception as e:   # COM errors (pywintypes.com_error) aren't OSErrors
+        raise OSError(f"Windows Search query failed: {e}")
+
+    files.sort(key=lambda f: f.path)
+    return files
+
+
+def scan_directory(
+    directory: Union[str, Path],
+    recursive: bool = True,
+    extensions: Optional[set] = None,
+    detect_duplicates: bool = True,
+    partial_hashes_only: bool = False,
+    engine: str = "walk"
+) -> ScanResult:
+    """
+    Scan a directory for supported documents.
+
+    Args:
+        directory: Directory path
+        recursive: Include subdirectories
+        extensions: Filter to specific extensions (None = all supported)
+        detect_duplicates: Group identical files and mark extra copies
+        partial_hashes_only: Faster duplicate check using partial hashes only
+        engine: "walk" or "windows_search" (see module docstring)
+
+    Returns:
+        ScanResult with discovered files, duplicate groups and the engine used
+
+    Raises:
+        NotADirectoryError: If directory is not a directory
+        ValueError: If engine is unknown
+    """
+    from document_processor import DocumentExtractor
+
+    if engine not in SCAN_ENGINES:
+        raise ValueError(f"Unknown scan engine: {engine} (expected one of {', '.join(SCAN_ENGINES)})")
+
+    dir_path = Path(directory)
+    if not dir_path.is_dir():
+        raise NotADirectoryError(f"Not a directory: {directory}")
+
+    supported = extensions or DocumentExtractor.get_supported_extensions()
+
+    result = ScanResult(root=str(dir_path.resolve()), engine=engine)
+
+    if engine == "windows_search":
+        try:
+            result.files = _windows_search_files(dir_path, recursive, supported)
+            if not result.files:
+                result.warnings.append(
+                    "Windows Search found no files here (the folder may not be indexed); walked the directory instead"
+                )
+        except OSError as e:
+            logger.warning(f"{e} - walking {result.root} instead")
+            result.warnings.append(f"{e}; walked the directory instead")
+        if not result.files:
+            result.engine = "walk"
+
+    if result.engine == "walk":
+        result.files = _walk_files(dir_path, recursive, supported)
+
+    if detect_duplicates:
+        result.duplicate_groups = mark_duplicates(result.files, partial_hashes_only)
+
+    logger.info(f"Scanned {result.root} ({result.engine}): {len(result.files)} supported file(s)")
+    return result
@@ -0,0 +1,210 @@
+"""
+Document Diff for Jan Document Plugin
+
+Compares two documents section by section (e.g. two versions of a
+contract). Either side can be:
+- A file path (extracted fresh)
+- An indexed document hash (text rebuilt from its stored chunks)
+- "<source path>@<version>" for a version kept by versioning mode
+
+Sections come from the document's headings (see
+document_processor.find_headings); documents without headings are
+compared as a single section, paragraph by paragraph.
+"""
+
+import difflib
+import logging
+import re
+from pathlib import Path
+from typing import List, Dict, Tuple
+from dataclasses import dataclass, field
+
+from document_processor import DocumentProcessor, find_headings, heading_path_at, HEADING_PATH_SEPARATOR
+
+logger = logging.getLogger(__name__)
+
+# Title used for text before the first heading / documents without headings
+PREAMBLE_SECTION = "(preamble)"
+
+# Shorter prefix/suffix matches between chunks are treated as coincidence
+MIN_OVERLAP_CHARS = 8
+
+
+@dataclass
+class SectionChange:
+    """Paragraph-level changes inside a section present in both documents."""
+    heading: str
+    added: List[str] = field(default_factory=list)
+    removed: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict:
+        return {"heading": self.heading, "added": self.added, "removed": self.removed}
+
+
+@dataclass
+class DocumentDiff:
+    """Structured diff between two documents."""
+    source_a: str
+    source_b: str
+    added_sections: List[Dict] = field(default_factory=list)     # {"heading", "text"}
+    removed_sections: List[Dict] = field(default_factory=list)   # {"heading", "text"}
+    changed_sections: List[SectionChange] = field(default_factory=list)
+    unchanged_sections: int = 0
+    similarity: float = 1.0        # 0-1 share of matching paragraphs
+
+    @property
+    def identical(self) -> bool:
+        return not (self.added_sections or self.removed_sections or self.changed_sections)
+
+    def to_dict(self) -> Dict:
+        return {
+            "source_a": self.source_a,
+            "source_b": self.source_b,
+            "identical": self.identical,
+            "similarity": round(self.similarity, 4),
+            "added_sections": self.added_sections,
+            "removed_sections": self.removed_sections,
+            "changed_sections": [c.to_dict() for c in self.changed_sections],
+            "unchanged_sections": self.unchanged_sections
+        }
+
+
+def _stitch_chunks(contents: List[str], overlap_chars: int) -> str:
+    """Rejoin overlapping chunk texts, dropping the repeated overlap."""
+    text = ""
+    for content in contents:
+        # Longest chunk prefix that the text so far already ends with
+        shared = 0
+        for k in range(min(len(content), overlap_chars + 200), MIN_OVERLAP_CHARS - 1, -1):
+            if text.endswith(content[:k]):
+                shared = k
+                break
+        text += ("" if shared or not text else "\n") + content[shared:]
+    return text
+
+
+def load_document_text(processor: DocumentProcessor, ref: str) -> Tuple[str, str]:
+    """
+    Resolve a diff operand to (label, text).
+
+    Raises:
+        KeyError: If ref is neither a file, an indexed hash, nor a known version
+    """
+    path = Path(ref)
+    if path.is_file():
+        text, _, _ = processor.extractor.extract(path.resolve())
+        return str(path.resolve()), text
+
+    doc_hash = ref
+    versioned = re.match(r'^(.*)@v?(\d+)$', ref)
+    if versioned:
+        source, number = versioned.group(1), int(versioned.group(2))
+        matches = [
+            v for v in processor.list_document_versions(source)
+            if v["version"] == number
+        ]
+        if not matches:
+            raise KeyError(f"No version {number} of {source}")
+        doc_hash = matches[0]["doc_hash"]
+
+    chunks = processor.vector_store.get_all_chunks(doc_hash=doc_hash)
+    text_chunks = sorted(
+        (
+            (meta.get("start_char", meta.get("chunk_index", 0)), content)
+            for content, meta in zip(chunks["documents"], chunks["metadatas"])
+            if meta.get("chunk_type", "text") == "text"
+        ),
+        key=lambda item: item[0]
+    )
+    if not text_chunks:
+        raise KeyError(f"Document not found: {ref}")
+
+    overlap_chars = int(processor.chunker.chunk_overlap * processor.chunker.chars_per_token)
+    return ref, _stitch_chunks([content for _, content in text_chunks], overlap_chars)
+
+
+def split_sections(text: str) -> Dict[str, str]:
+    """Map heading path -> section body (in document order)."""
+    headings = find_headings(text)
+    if not headings:
+        return {PREAMBLE_SECTION: text.strip()}
+
+    sections: Dict[str, str] = {}
+    bounds = [(0, None)] + [(offset, offset) for offset, _, _ in headings]
+    for i, (start, heading_offset) in enumerate(bounds):
+        end = bounds[i + 1][0] if i + 1 < len(bounds) else len(text)
+        body = text[start:end]
+        if heading_offset is None:
+            key = PREAMBLE_SECTION
+        else:
+            key = HEADING_PATH_SEPARATOR.join(heading_path_at(headings, heading_offset))
+            body = body.split("\n", 1)[1] if "\n" in body else ""  # Drop the heading line
+        body = body.strip()
+        if body or heading_offset is not None:
+            # Repeated headings: keep them apart rather than merging bodies
+            while key in sections:
+                key += " (continued)"
+            sections[key] = body
+    return sections
+
+
+def _paragraphs(text: str) -> List[str]:
+    return [" ".join(p.split()) for p in re.split(r'\n\s*\n', text) if p.strip()]
+
+
+def diff_texts(text_a: str, text_b: str, source_a: str = "a", source_b: str = "b") -> DocumentDiff:
+    """Section-by-section diff of two texts."""
+    sections_a = split_sections(text_a)
+    sections_b = split_sections(text_b)
+
+    result = DocumentDiff(
+        source_a=source_a,
+        source_b=source_b,
+        similarity=difflib.SequenceMatcher(
+            None, _paragraphs(text_a), _paragraphs(text_b), autojunk=False
+        ).ratio()
+    )
+
+    for heading, body in sections_a.items():
+        if heading not in sections_b:
+            result.removed_sections.append({"heading": heading, "text": body})
+
+    for heading, body in sections_b.items():
+        if heading not in sections_a:
+            result.added_sections.append({"heading": heading, "text": body})
+            continue
+
+        paras_a = _paragraphs(sections_a[heading])
+        paras_b = _paragraphs(body)
+        if paras_a == paras_b:
+            result.unchanged_sections += 1
+            continue
+
+        change = SectionChange(heading=heading)
+        matcher = difflib.SequenceMatcher(None, paras_a, paras_b, autojunk=False)
+        for tag, a1, a2, b1, b2 in matcher.get_opcodes():
+            if tag in ("replace", "delete"):
+                change.removed.extend(paras_a[a1:a2])
+            if tag in ("replace", "insert"):
+                change.added.extend(paras_b[b1:b2])
+        result.changed_sections.append(change)
+
+    return result
+
+
+def diff_documents(processor: DocumentProcessor, ref_a: str, ref_b: str) -> DocumentDiff:
+    """
+    Diff two documents (file paths, indexed hashes, or "path@version").
+
+    Raises:
+        KeyError: If either operand can't be resolved
+    """
+    label_a, text_a = load_document_text(processor, ref_a)
+    label_b, text_b = load_document_text(processor, ref_b)
+
+    result = diff_texts(text_a, text_b, label_a, label_b)
+    logger.info(
+        f"Diffed {label_a} -> {label_b}: +{len(result.added_sections)} "
+        f"-{len(result.removed_sections)} ~{len(result.changed_sections)} sections"
+    )
+    return result
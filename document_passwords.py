@@ -0,0 +1,116 @@
+"""
+Document Passwords for Jan Document Plugin
+
+Passwords of encrypted PDF, DOCX and XLSX files, by the document's
+source path (for uploads, its file name). Processing looks a password up
+here when none is passed, so a batch whose files failed with
+password_required can be resumed once the user has supplied them.
+
+Passwords go to the system keychain (Windows Credential Manager, macOS
+Keychain, Secret Service) through the keyring package. Without a usable
+keychain they are kept in memory for this session only; they are never
+written to the store directory.
+"""
+
+import logging
+import threading
+from typing import Dict, Optional
+
+logger = logging.getLogger(__name__)
+
+KEYRING_SERVICE = "jan-document-plugin/documents"
+
+
+def load_keyring():
+    """The keyring module if a real keychain backend is available."""
+    try:
+        import keyring
+        from keyring.backends.fail import Keyring as FailKeyring
+    except ImportError:
+        return None
+    try:
+        if isinstance(keyring.get_keyring(), FailKeyring):
+            return None
+    except Exception as e:
+        logger.warning(f"Keychain unavailable: {e}")
+        return None
+    return keyring
+
+
+class DocumentPasswordStore:
+    """
+    Per-document passwords kept in the system keychain.
+
+    Usage:
+        store = DocumentPasswordStore()
+        store.set("C:/Contracts/lease.docx", "hunter2")
+        store.get("C:/Contracts/lease.docx")
+    """
+
+    def __init__(self, service: str = KEYRING_SERVICE, use_keychain: bool = True):
+        """
+        Initialize password store.
+
+        Args:
+            service: Keychain service name the passwords are filed under
+            use_keychain: Use the system keychain if available (False = memory only)
+        """
+        self.service = service
+        self._keyring = load_keyring() if use_keychain else None
+        self._lock = threading.Lock()
+        self._memory: Dict[str, str] = {}
+        if use_keychain and self._keyring is None:
+            logger.info("No system keychain - document passwords are kept for this session only")
+
+    @property
+    def backend(self) -> str:
+        """"keychain" or "memory"."""
+        return "keychain" if self._keyring else "memory"
+
+    def get(self, source_path: str) -> Optional[str]:
+        """Password stored for a document, or None."""
+        with self._lock:
+            if source_path in self._memory:
+                return self._memory[source_path]
+        if self._keyring:
+            try:
+                return self._keyring.get_password(self.service, source_path)
+            except Exception as e:
+                logger.warning(f"Could not read password for {source_path} from keychain: {e}")
+        return None
+
+    def set(self, source_path: str, password: str):
+        """
+        Store a document's password.
+
+        Raises:
+            ValueError: If source_path or password is empty
+        """
+        if not source_path:
+            raise ValueError("source_path is required")
+        if not password:
+            raise ValueError("password must not be empty")
+        if self._keyring:
+            try:
+                self._keyring.set_password(self.service, source_path, password)
+                with self._lock:
+                    self._memory.pop(source_path, None)
+                logger.info(f"Stored password for {source_path} in keychain")
+                return
+            except Exception as e:
+                logger.warning(f"Could not store password in keychain, keeping it for this session: {e}")
+        with self._lock:
+            self._memory[source_path] = password
+
+    def delete(self, source_path: str) -> bool:
+        """Forget a document's password; returns whether one was stored."""
+        with self._lock:
+            found = self._memory.pop(source_path, None) is not None
+        if self._keyring:
+            try:
+                if self._keyring.get_password(self.service, source_path) is not None:
+                    self._keyring.delete_password(self.service, source_path)
+                    found = True
+            except Exception as e:
+                logger.warning(f"Could not delete password for {source_path} from keychain: {e}")
+        return found
@@ -7,14 +7,29 @@ No internet required - fully offline operation.
 """
 
 import os
+import re
+import json
+import time
+import queue
+import threading
+import errno
+import shutil
 import hashlib
+import tempfile
+import zipfile
+import statistics
+import xml.etree.ElementTree as ET
 import logging
 from pathlib import Path
-from typing import List, Dict, Optional, Union
-from dataclasses import dataclass, field
+from typing import List, Dict, Optional, Union, Tuple, Callable, Any, Sequence, Iterator
+from dataclasses import dataclass, field, asdict
 from enum import Enum
 from datetime import datetime
 
+from processing_presets import ProcessingPreset
+from keyword_scoring import RetrievalConfig, hybrid_rerank, highlight_snippet
+from collection_names import DEFAULT_COLLECTION, check_collection, validate_collection_name
+
 # Extraction
 import fitz  # pymupdf
 from docx import Document as DocxDocument
@@ -40,6 +55,303 @@ from chromadb.config import Settings
 
 logger = logging.getLogger(__name__)
 
+# Chunk metadata keys holding document properties are prefixed so they
+# can't collide with chunker/store keys
+PROPERTY_PREFIX = "prop_"
+MAX_PROPERTY_LENGTH = 500
+
+# "Figure 3: Q3 revenue by region", "Fig. 2.1 - Setup", "Chart 4. Growth"
+FIGURE_CAPTION_PATTERN = re.compile(
+    r'^\s*((?:Figure|Fig\.|Chart|Graph|Diagram|Exhibit|Illustration)\s*[A-Z]?\d+(?:\.\d+)*[a-z]?)'
+    r'\s*[.:\-\u2013\u2014]\s*([^\d\s].*)$',
+    re.IGNORECASE
+)
+
+# Footnote/endnote reference markers inserted into extracted text: [^3], [^e2]
+NOTE_MARKER_PATTERN = re.compile(r'\[\^(e?\d+)\]')
+
+# Chunks embedded per encode() call when indexing
+EMBED_BATCH_SIZE = 64
+
+# Queries accepted by one batch query (see query_batch)
+MAX_BATCH_QUERIES = 200
+
+# Texts accepted by one embed_texts call
+MAX_EMBED_TEXTS = 256
+
+# Page headers the PDF extractor puts before each page's text
+PAGE_HEADER_PATTERN = re.compile(r'^\[Page (\d+)\]', re.MULTILINE)
+
+# ...and the marker following it on pages whose text came from OCR
+OCR_PAGE_PATTERN = re.compile(r'^\[Page (\d+)\]\n\[OCR\]', re.MULTILINE)
+
+# PDF pages with fewer text-layer characters than this are treated as scanned
+MIN_TEXT_LAYER_CHARS = 50
+
+# In salvage mode, PDF pages are chunked and stored every this many pages
+SALVAGE_CHECKPOINT_PAGES = 10
+
+# Seconds a timed-out extraction waits for its worker to finish the page
+# it is on; a worker still running after that closes the file on its own
+EXTRACTION_CANCEL_GRACE = 1.0
+
+# Encrypted DOCX/XLSX files are OLE compound files instead of ZIP packages
+OLE_SIGNATURE = b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1"
+ENCRYPTABLE_OFFICE_TYPES = ('.docx', '.xlsx')
+
+# "auto" OCRs only pages lacking a text layer, "force" OCRs every page
+OCR_MODES = ("auto", "force", "off")
+
+# Chunks indexed from Jan chat threads carry this source_type (see jan_threads)
+CHAT_SOURCE_TYPE = "jan_thread"
+
+# What a query searches: indexed files, chat history or both
+QUERY_SOURCES = ("documents", "chat", "both")
+
+# Chunks matching an exclusion are dropped after retrieval; fetch this
+# many candidates per requested result so enough survive
+EXCLUSION_FETCH_FACTOR = 10
+
+# Settings that change what indexing produces; a source indexed under a
+# different combination (see settings_fingerprint) needs re-indexing
+FINGERPRINT_SETTINGS = (
+    "chunk_size", "chunk_overlap", "smart_mode", "ocr_enabled",
+    "extract_tables", "index_figures", "footnote_mode", "embedding_model"
+)
+
+# Document properties a chunk's document_date is taken from, in order
+DOCUMENT_DATE_PROPERTIES = ("modified", "created", "date")
+
+# WordprocessingML namespace (DOCX XML parts)
+W_NS = "{http://schemas.openxmlformats.org/wordprocessingml/2006/main}"
+
+# Headings: Markdown "## Title", or numbered "3 Methods" / "3.2 Data Collection"
+MARKDOWN_HEADING_PATTERN = re.compile(r'^(#{1,6})\s+(\S.*?)\s*#*\s*$')
+NUMBERED_HEADING_PATTERN = re.compile(r'^((?:\d+\.)+\d+|\d+)\.?\s+([A-Z][^\n]{0,80})$')
+HEADING_PATH_SEPARATOR = " > "
+
+
+def find_headings(text: str) -> List[Tuple[int, int, str]]:
+    """
+    Locate headings in extracted text.
+
+    Numbered headings need a capitalized title with no closing
+    punctuation, and single numbers may not be followed by a period, so
+    numbered list items ("1. Buy milk.") aren't mistaken for headings.
+
+    Returns:
+        List of (char_offset, level, title) in document order
+    """
+    headings = []
+    offset = 0
+
+    for line in text.splitlines(keepends=True):
+        stripped = line.strip()
+        md = MARKDOWN_HEADING_PATTERN.match(stripped)
+        if md:
+            headings.append((offset, len(md.group(1)), md.group(2)))
+        elif len(stripped) <= 100 and not stripped.endswith(('.', ',', ';', ':')):
+            numbered = NUMBERED_HEADING_PATTERN.match(stripped)
+            if numbered and not re.match(r'^\d+\.\s', stripped):
+                number = numbered.group(1)
+                headings.append((offset, number.count('.') + 1, f"{number} {numbered.group(2)}"))
+        offset += len(line)
+
+    return headings
+
+
+def heading_path_at(headings: List[Tuple[int, int, str]], position: int) -> List[str]:
+    """Titles of the enclosing headings (outermost first) at a text offset."""
+    stack: List[Tuple[int, str]] = []
+    for offset, level, title in headings:
+        if offset > position:
+            break
+        while stack and stack[-1][0] >= level:
+            stack.pop()
+        stack.append((level, title))
+    return [title for _, title in stack]
+
+
+FRONTMATTER_PATTERN = re.compile(r'\A---[ \t]*\r?\n(.*?)\r?\n---[ \t]*(?:\r?\n|\Z)', re.DOTALL)
+
+
+def _unquote(value: str) -> str:
+    value = value.strip()
+    if len(value) >= 2 and value[0] == value[-1] and value[0] in "'\"":
+        return value[1:-1]
+    return value
+
+
+def parse_frontmatter(text: str) -> Tuple[Dict[str, str], str]:
+    """
+    Split YAML frontmatter off a Markdown document.
+
+    Handles the flat subset used in practice: "key: value" pairs, inline
+    lists ("tags: [a, b]") and block lists ("- item"). Lists become
+    comma-separated strings.
+
+    Returns:
+        Tuple of (properties, text without the frontmatter block)
+    """
+    match = FRONTMATTER_PATTERN.match(text)
+    if not match:
+        return {}, text
+
+    properties: Dict[str, str] = {}
+    key = None
+
+    for line in match.group(1).splitlines():
+        if not line.strip() or line.lstrip().startswith('#'):
+            continue
+
+        item = re.match(r'^\s*-\s+(.*)$', line)
+        if item and key:
+            values = [properties[key], _unquote(item.group(1))]
+            properties[key] = ", ".join(v for v in values if v)
+            continue
+
+        pair = re.match(r'^([A-Za-z0-9_-]+)\s*:\s*(.*)$', line)
+        if pair:
+            key = pair.group(1).lower().replace('-', '_')
+            value = pair.group(2).strip()
+            if value.startswith('[') and value.endswith(']'):
+                value = ", ".join(_unquote(v) for v in value[1:-1].split(',') if v.strip())
+            properties[key] = _unquote(value)
+
+    return properties, text[match.end():]
+
+
+def _format_pdf_date(value: str) -> str:
+    """Convert a PDF date ("D:20240131093000+01'00'") to ISO form when possible."""
+    digits = re.match(r'^D?:?(\d{4})(\d{2})?(\d{2})?(\d{2})?(\d{2})?(\d{2})?', value or "")
+    if not digits:
+        return value
+    year, month, day, hour, minute, second = digits.groups()
+    if not month:
+        return year
+    date = f"{year}-{month}-{day or '01'}"
+    if hour:
+        date += f"T{hour}:{minute or '00'}:{second or '00'}"
+    return date
+
+
+def parse_document_date(value: str) -> Optional[datetime]:
+    """Parse an ISO date ("2024-01-31", "2024-01-31T09:30:00+01:00", "2024") as naive local time."""
+    value = (value or "").strip()
+    if re.fullmatch(r"\d{4}", value):
+        value += "-01-01"
+    try:
+        parsed = datetime.fromisoformat(value.replace("Z", "+00:00"))
+    except ValueError:
+        return None
+    if parsed.tzinfo is not None:
+        parsed = parsed.astimezone().replace(tzinfo=None)
+    return parsed
+
+
+def recency_multiplier(date: Optional[datetime], half_life_days: float, now: Optional[datetime] = None) -> float:
+    """
+    Score multiplier that halves every half_life_days of age.
+
+    Undated content and dates in the future are not penalized.
+    """
+    if date is None:
+        return 1.0
+    age_days = ((now or datetime.now()) - date).total_seconds() / 86400
+    return 0.5 ** (max(0.0, age_days) / half_life_days)
+
+
+def score_distribution(scores: List[float]) -> Dict:
+    """count / min / max / mean / median of relevance scores (rounded)."""
+    if not scores:
+        return {"count": 0}
+    return {
+        "count": len(scores),
+        "min": round(min(scores), 4),
+        "max": round(max(scores), 4),
+        "mean": round(statistics.fmean(scores), 4),
+        "median": round(statistics.median(scores), 4)
+    }
+
+
+def is_excluded(
+    content: str,
+    metadata: Dict,
+    exclude_terms: Optional[List[str]] = None,
+    exclude_filters: Optional[Dict[str, str]] = None
+) -> bool:
+    """
+    Whether a chunk matches a query exclusion.
+
+    Terms match whole words or phrases, case-insensitively. Filters match
+    a metadata key (e.g. filename) or document property (e.g. author)
+    exactly, ignoring case.
+    """
+    for term in exclude_terms or []:
+        if re.search(rf"\b{re.escape(term.strip())}\b", content, re.IGNORECASE):
+            return True
+    for key, value in (exclude_filters or {}).items():
+        key = key.lower()
+        actual = metadata.get(key, metadata.get(f"{PROPERTY_PREFIX}{key}"))
+        if actual is not None and str(actual).lower() == str(value).lower():
+            return True
+    return False
+
+
+# Metadata the store relies on (identity, versioning, freshness checks);
+# bulk metadata updates can't change these
+PROTECTED_METADATA_KEYS = (
+    "doc_hash", "chunk_index", "version", "is_latest", "superseded_at", "indexed_at",
+    "source_path", "source_hash", "source_size", "source_mtime", "source_type", "chunk_type"
+)
+
+# Chunks whose metadata is written per call in bulk updates
+METADATA_UPDATE_BATCH_SIZE = 256
+
+
+def _metadata_key(metadata: Dict, key: str) -> str:
+    """The stored key for key: itself, or its document property (prop_) key."""
+    key = key.lower()
+    if key not in metadata and f"{PROPERTY_PREFIX}{key}" in metadata:
+        return f"{PROPERTY_PREFIX}{key}"
+    return key
+
+
+def matches_metadata_filter(metadata: Dict, filters: Dict[str, str]) -> bool:
+    """
+    Whether a chunk matches every filter. Like exclusion filters, each
+    matches a metadata key or document property exactly, ignoring case.
+    """
+    for key, value in filters.items():
+        actual = metadata.get(_metadata_key(metadata, key))
+        if actual is None or str(actual).lower() != str(value).lower():
+            return False
+    return True
+
+
+def apply_merge_patch(metadata: Dict, patch: Dict) -> Dict:
+    """
+    Chunk metadata with a JSON merge patch applied: null removes a key,
+    anything else sets it. Keys naming a document property (e.g. author)
+    update the property.
+
+    Raises:
+        ValueError: If the patch touches a protected key or sets a value
+                    that isn't a string, number or boolean
+    """
+    patched = dict(metadata)
+    for key, value in patch.items():
+        stored = _metadata_key(metadata, key)
+        if stored in PROTECTED_METADATA_KEYS:
+            raise ValueError(f"Metadata key {key} can't be changed")
+        if value is None:
+            patched.pop(stored, None)
+        elif isinstance(value, (str, int, float, bool)):
+            patched[stored] = value
+        else:
+            raise ValueError(f"Metadata value for {key} must be a string, number or boolean")
+    return patched
+
 
 class DocumentType(Enum):
     PDF = "pdf"
@@ -51,6 +363,233 @@ class DocumentType(Enum):
     UNKNOWN = "unknown"
 
 
+class ExtractionTimeoutError(TimeoutError):
+    """
+    Raised when extraction exceeds its deadline.
+
+    Carries whatever text was extracted before the deadline so callers can
+    salvage a partial result instead of discarding the work.
+    """
+
+    def __init__(
+        self,
+        message: str,
+        partial_text: str = "",
+        pages_completed: int = 0,
+        total_pages: int = 0,
+        ocr_used: bool = False,
+        ocr_pages: int = 0
+    ):
+        super().__init__(message)
+        self.partial_text = partial_text
+        self.pages_completed = pages_completed
+        self.total_pages = total_pages
+        self.ocr_used = ocr_used
+        self.ocr_pages = ocr_pages
+
+
+class FileLockedError(PermissionError):
+    """
+    Raised when a file stays locked by another process (Word, antivirus,
+    sync clients) after all read retries.
+    """
+
+    error_type = "file_locked"
+
+    def __init__(self, path: Path, attempts: int):
+        super().__init__(
+            f"File is locked by another program: {path.name} "
+            f"(gave up after {attempts} attempts). Close it and try again."
+        )
+        self.path = path
+        self.attempts = attempts
+
+
+class PasswordRequiredError(RuntimeError):
+    """
+    Raised when a document is encrypted and no password was given (or
+    stored in the password store) for it.
+    """
+
+    error_type = "password_required"
+
+    def __init__(self, filename: str, message: Optional[str] = None):
+        super().__init__(message or f"{filename} is password-protected. Supply its password and try again.")
+        self.filename = filename
+
+    def to_dict(self) -> Dict:
+        return {
+            "error": self.error_type,
+            "message": str(self),
+            "filename": self.filename,
+            "remediation": "Store the document's password (PUT /documents/passwords) and process it again"
+        }
+
+
+class WrongPasswordError(PasswordRequiredError):
+    """Raised when the password given for an encrypted document is wrong."""
+
+    error_type = "wrong_password"
+
+    def __init__(self, filename: str):
+        super().__init__(filename, f"Wrong password for {filename}")
+
+
+class EmbeddingModelMismatchError(RuntimeError):
+    """
+    Raised when the embedding model makes vectors of a different dimension
+    than the ones stored in the collection (e.g. EMBEDDING_MODEL was changed
+    without migrating), so searching or adding to it would fail or return
+    meaningless scores.
+    """
+
+    error_type = "model_mismatch"
+
+    def __init__(self, collection: str, embedding_model: str, model_dimension: int, stored_dimension: int):
+        super().__init__(
+            f"Collection {collection} holds {stored_dimension}-dimension vectors, but "
+            f"{embedding_model} makes {model_dimension}-dimension vectors"
+        )
+        self.collection = collection
+        self.embedding_model = embedding_model
+        self.model_dimension = model_dimension
+        self.stored_dimension = stored_dimension
+
+    def to_dict(self) -> Dict:
+        return {
+            "error": self.error_type,
+            "message": str(self),
+            "collection": self.collection,
+            "embedding_model": self.embedding_model,
+            "model_dimension": self.model_dimension,
+            "stored_dimension": self.stored_dimension,
+            "remediation": (
+                f"Migrate the collection to {self.embedding_model} (POST /collections/migrate), "
+                f"or set EMBEDDING_MODEL to the model the collection was built with"
+            )
+        }
+
+
+def is_sharing_violation(error: OSError) -> bool:
+    """True for transient lock errors (Windows sharing/lock violation, EBUSY)."""
+    # ERROR_SHARING_VIOLATION = 32, ERROR_LOCK_VIOLATION = 33
+    if getattr(error, "winerror", None) in (32, 33):
+        return True
+    return error.errno in (errno.EBUSY, errno.ETXTBSY)
+
+
+@dataclass
+class ExtractedTable:
+    """A table pulled out of a document with its structure intact."""
+    headers: List[str]
+    rows: List[List[str]]
+    page: Optional[int] = None     # 1-based page number (PDF only)
+
+    def to_json(self) -> str:
+        return json.dumps({"headers": self.headers, "rows": self.rows, "page": self.page})
+
+    @classmethod
+    def from_json(cls, data: str) -> "ExtractedTable":
+        parsed = json.loads(data)
+        return cls(headers=parsed["headers"], rows=parsed["rows"], page=parsed.get("page"))
+
+    def to_markdown(self) -> str:
+        """Render as a Markdown table."""
+        def cell(value: str) -> str:
+            return str(value).replace("|", "\\|").replace("\n", " ").strip()
+
+        width = max([len(self.headers)] + [len(r) for r in self.rows])
+        headers = [cell(h) for h in self.headers] + [""] * (width - len(self.headers))
+
+        lines = [
+            "| " + " | ".join(headers) + " |",
+            "|" + "---|" * width
+        ]
+        for row in self.rows:
+            padded = [cell(v) for v in row] + [""] * (width - len(row))
+            lines.append("| " + " | ".join(padded) + " |")
+        return "\n".join(lines)
+
+
+@dataclass
+class ExtractedFigure:
+    """A figure caption, optionally with the figure image and a generated description."""
+    label: str                         # e.g. "Figure 3"
+    caption: str
+    page: Optional[int] = None         # 1-based page number (PDF only)
+    image: Optional[bytes] = None      # Figure image, when requested (PDF only)
+    image_ext: Optional[str] = None    # e.g. "png"
+    description: Optional[str] = None  # From an image describer (vision model)
+
+    def to_text(self) -> str:
+        text = f"{self.label}: {self.caption}"
+        if self.description:
+            text += f"\n[Image description] {self.description}"
+        return text
+
+
+@dataclass
+class ExtractedNote:
+    """A footnote or endnote."""
+    note_id: str                   # Marker id: "3" (footnote), "e2" (endnote)
+    kind: str                      # "footnote" or "endnote"
+    text: str
+    page: Optional[int] = None     # 1-based page number (PDF only)
+
+    def to_dict(self) -> Dict:
+        return {"id": self.note_id, "kind": self.kind, "text": self.text, "page": self.page}
+
+
+def attach_notes(chunks: List["DocumentChunk"], notes: List[ExtractedNote]):
+    """
+    Link notes to the text chunks that cite them.
+
+    DOCX notes are matched by their [^id] marker in the chunk text. PDF
+    notes have no recoverable marker, so they are linked to the chunks
+    containing their page's "[Page N]" header. Linked notes are stored as
+    JSON in the chunk's "notes" metadata.
+    """
+    by_id = {n.note_id: n for n in notes if n.page is None}
+    by_page: Dict[int, List[ExtractedNote]] = {}
+    for note in notes:
+        if note.page is not None:
+            by_page.setdefault(note.page, []).append(note)
+
+    for chunk in chunks:
+        if chunk.metadata.get("chunk_type", "text") != "text":
+            continue
+
+        linked = [by_id[i] for i in dict.fromkeys(NOTE_MARKER_PATTERN.findall(chunk.content)) if i in by_id]
+        for page in dict.fromkeys(int(p) for p in re.findall(r'\[Page (\d+)\]', chunk.content)):
+            linked.extend(by_page.get(page, []))
+
+        if linked:
+            chunk.metadata["notes"] = json.dumps([n.to_dict() for n in linked])
+            chunk.metadata["note_ids"] = ",".join(n.note_id for n in linked)
+
+
+def render_chunk_content(content: str, metadata: Dict) -> str:
+    """
+    Readable form of a stored chunk: table chunks (stored as JSON) become
+    Markdown, and text chunks get their linked footnotes appended.
+    """
+    if metadata.get("chunk_type") == "table":
+        try:
+            return ExtractedTable.from_json(content).to_markdown()
+        except (ValueError, KeyError):
+            return content
+
+    if metadata.get("notes"):
+        try:
+            notes = json.loads(metadata["notes"])
+        except ValueError:
+            return content
+        lines = [f"[^{n['id']}]: {n['text']}" for n in notes]
+        return content + "\n\nNotes:\n" + "\n".join(lines)
+
+    return content
+
+
 @dataclass
 class DocumentChunk:
     """Single chunk of extracted document content."""
@@ -72,7 +611,19 @@ class ProcessedDocument:
     extracted_at: datetime = field(default_factory=datetime.now)
     ocr_used: bool = False
     ocr_pages: int = 0
-    
+    ocr_page_numbers: List[int] = field(default_factory=list)  # PDF pages whose text came from OCR
+    ocr_mode: str = "auto"
+    ocr_languages: Optional[str] = None  # Tesseract languages requested, e.g. "eng+deu"
+    low_confidence_pages: List[Dict] = field(default_factory=list)  # Below min_ocr_confidence
+    partial: bool = False              # Extraction hit its timeout; only part was indexed
+    pages_completed: int = 0
+    total_pages: int = 0
+    preset: Optional[str] = None       # Processing preset used, if any
+    properties: Dict[str, str] = field(default_factory=dict)  # Title, author, dates...
+    source_path: Optional[str] = None  # Identity across versions (defaults to file_path)
+    version: int = 1
+    is_latest: bool = True             # False once a newer version is indexed
+
     def to_dict(self) -> Dict:
         return {
             "doc_hash": self.doc_hash,
@@ -83,7 +634,83 @@ class ProcessedDocument:
             "total_tokens_estimate": self.total_tokens_estimate,
             "extracted_at": self.extracted_at.isoformat(),
             "ocr_used": self.ocr_used,
-            "ocr_pages": self.ocr_pages
+            "ocr_pages": self.ocr_pages,
+            "ocr_page_numbers": self.ocr_page_numbers,
+            "ocr_mode": self.ocr_mode,
+            "ocr_languages": self.ocr_languages,
+            "low_confidence_pages": self.low_confidence_pages,
+            "partial": self.partial,
+            "pages_completed": self.pages_completed,
+            "total_pages": self.total_pages,
+            "preset": self.preset,
+            "properties": self.properties,
+            "source_path": self.source_path,
+            "version": self.version,
+            "is_latest": self.is_latest
+        }
+
+    @classmethod
+    def from_dict(cls, data: Dict, chunks: List[DocumentChunk]) -> "ProcessedDocument":
+        """Rebuild a record from to_dict() output and its chunks."""
+        return cls(
+            doc_hash=data["doc_hash"],
+            filename=data["filename"],
+            file_path=data.get("file_path", ""),
+            doc_type=DocumentType(data.get("doc_type", DocumentType.UNKNOWN.value)),
+            chunks=chunks,
+            total_tokens_estimate=data.get("total_tokens_estimate", 0),
+            extracted_at=(
+                datetime.fromisoformat(data["extracted_at"])
+                if data.get("extracted_at") else datetime.now()
+            ),
+            ocr_used=data.get("ocr_used", False),
+            ocr_pages=data.get("ocr_pages", 0),
+            ocr_page_numbers=data.get("ocr_page_numbers", []),
+            ocr_mode=data.get("ocr_mode", "auto"),
+            ocr_languages=data.get("ocr_languages"),
+            low_confidence_pages=data.get("low_confidence_pages", []),
+            partial=data.get("partial", False),
+            pages_completed=data.get("pages_completed", 0),
+            total_pages=data.get("total_pages", 0),
+            preset=data.get("preset"),
+            properties=data.get("properties", {}),
+            source_path=data.get("source_path"),
+            version=data.get("version", 1),
+            is_latest=data.get("is_latest", True)
+        )
+
+
+@dataclass
+class PageTextLayer:
+    """Text layer check for one PDF page."""
+    page: int
+    text_chars: int
+    image_count: int
+    has_text_layer: bool
+
+
+@dataclass
+class DocumentAnalysis:
+    """Fast pre-check of whether a document needs OCR (see analyze_document)."""
+    filename: str
+    file_type: str
+    total_pages: int
+    pages: List[PageTextLayer]
+    text_layer_coverage: float          # Share of pages with a usable text layer
+    needs_ocr: str                      # "none", "partial", "full" ("unknown" while locked)
+    pages_needing_ocr: List[int]
+    requires_password: bool = False     # Encrypted; pages can't be read without the password
+
+    def to_dict(self) -> Dict:
+        return {
+            "filename": self.filename,
+            "file_type": self.file_type,
+            "total_pages": self.total_pages,
+            "text_layer_coverage": round(self.text_layer_coverage, 3),
+            "needs_ocr": self.needs_ocr,
+            "pages_needing_ocr": self.pages_needing_ocr,
+            "requires_password": self.requires_password,
+            "pages": [asdict(p) for p in self.pages]
         }
 
 
@@ -93,17 +720,34 @@ class DocumentExtractor:
     SUPPORTED_IMAGES = {'.png', '.jpg', '.jpeg', '.tiff', '.bmp', '.gif', '.webp'}
     SUPPORTED_DOCS = {'.pdf', '.docx', '.doc', '.xlsx', '.xls', '.txt', '.md', '.csv'}
     
-    def __init__(self, tesseract_path: Optional[str] = None):
+    def __init__(
+        self,
+        tesseract_path: Optional[str] = None,
+        min_ocr_confidence: Optional[float] = None,
+        index_low_confidence_ocr: bool = False
+    ):
         """
         Initialize extractor.
         
         Args:
             tesseract_path: Path to tesseract executable (e.g., 
                            "C:/Program Files/Tesseract-OCR/tesseract.exe" on Windows)
+            min_ocr_confidence: Mean word confidence (0-100) below which OCR'd
+                                pages/images are flagged (None = no gating)
+            index_low_confidence_ocr: Still index flagged text instead of
+                                      leaving it out
         """
         if tesseract_path:
             pytesseract.pytesseract.tesseract_cmd = tesseract_path
         
+        self.min_ocr_confidence = min_ocr_confidence
+        self.index_low_confidence_ocr = index_low_confidence_ocr
+        
+        # Workers of timed-out extractions that still have their file open,
+        # by path (see _extract_pdf_watched and release_file)
+        self._abandoned_workers: Dict[str, Dict] = {}
+        self._abandoned_lock = threading.Lock()
+        
         self._tesseract_available = self._check_tesseract()
     
     def _check_tesseract(self) -> bool:
@@ -115,17 +759,198 @@ class DocumentExtractor:
             logger.warning(f"Tesseract not available: {e}. OCR disabled.")
             return False
     
+    @property
+    def ocr_available(self) -> bool:
+        return self._tesseract_available
+    
+    def _accept_ocr_text(self, confidence: Optional[float], page: int, ocr_report: Optional[List[Dict]]) -> bool:
+        """
+        Apply min_ocr_confidence to one OCR'd page (or image).
+        
+        Low-confidence pages are logged and added to ocr_report; returns
+        False if their text should be left out of the index.
+        """
+        if self.min_ocr_confidence is None or confidence is None or confidence >= self.min_ocr_confidence:
+            return True
+        
+        excluded = not self.index_low_confidence_ocr
+        logger.warning(
+            f"Low OCR confidence on page {page}: {confidence:.0f} < {self.min_ocr_confidence:.0f}"
+            f"{' - not indexed' if excluded else ''}"
+        )
+        if ocr_report is not None:
+            ocr_report.append({"page": page, "confidence": round(confidence, 1), "excluded": excluded})
+        return not excluded
+    
+    def check_ocr_languages(self, languages: List[str]) -> str:
+        """
+        Validate OCR languages and return them in Tesseract's "eng+deu" form.
+        
+        Raises:
+            ValueError: If a code is invalid or its language pack isn't installed
+        """
+        from ocr_languages import validate_language_code, installed_ocr_languages
+        
+        codes = [validate_language_code(lang) for lang in languages]
+        if self._tesseract_available:
+            installed = set(installed_ocr_languages())
+            missing = [code for code in codes if code not in installed]
+            if missing:
+                raise ValueError(
+                    f"OCR language pack not installed: {', '.join(missing)} "
+                    f"(see GET /ocr/languages to install it)"
+                )
+        return "+".join(codes)
+    
+    def analyze_document(self, file_path: Path) -> DocumentAnalysis:
+        """
+        Report text layer coverage per page without extracting or OCRing.
+        
+        Only reads each PDF page's text layer and image list, so it is fast
+        enough to run before deciding how (or whether) to process a file.
+        Encrypted PDF, DOCX and XLSX files are reported with
+        requires_password and needs_ocr "unknown".
+        
+        Raises:
+            FileNotFoundError: If file doesn't exist
+            ValueError: If file type not supported
+        """
+        file_path = Path(file_path)
+        if not file_path.exists():
+            raise FileNotFoundError(f"File not found: {file_path}")
+        
+        suffix = file_path.suffix.lower()
+        if suffix in self.SUPPORTED_IMAGES:
+            page = PageTextLayer(page=1, text_chars=0, image_count=1, has_text_layer=False)
+            return DocumentAnalysis(file_path.name, suffix, 1, [page], 0.0, "full", [1])
+        if self.requires_password(file_path):
+            return DocumentAnalysis(file_path.name, suffix, 0, [], 0.0, "unknown", [], requires_password=True)
+        if suffix != '.pdf':
+            if suffix not in self.SUPPORTED_DOCS:
+                raise ValueError(f"Unsupported file type: {suffix}")
+            return DocumentAnalysis(file_path.name, suffix, 0, [], 1.0, "none", [])
+        
+        pages = []
+        with fitz.open(file_path) as doc:
+            for page_num, page in enumerate(doc):
+                chars = len(page.get_text().strip())
+                pages.append(PageTextLayer(
+                    page=page_num + 1,
+                    text_chars=chars,
+                    image_count=len(page.get_images()),
+                    has_text_layer=chars >= MIN_TEXT_LAYER_CHARS
+                ))
+        
+        missing = [p.page for p in pages if not p.has_text_layer]
+        coverage = 1 - len(missing) / len(pages) if pages else 1.0
+        if not missing:
+            needs_ocr = "none"
+        elif len(missing) == len(pages):
+            needs_ocr = "full"
+        else:
+            needs_ocr = "partial"
+        return DocumentAnalysis(file_path.name, suffix, len(pages), pages, coverage, needs_ocr, missing)
+    
+    def requires_password(self, file_path: Path) -> bool:
+        """
+        Whether a document is encrypted with an open password.
+
+        PDFs are checked with PyMuPDF; an encrypted DOCX/XLSX is not a ZIP
+        package but an OLE container holding the encrypted package.
+        """
+        suffix = file_path.suffix.lower()
+        try:
+            if suffix == '.pdf':
+                with fitz.open(file_path) as doc:
+                    return bool(doc.needs_pass)
+            if suffix in ENCRYPTABLE_OFFICE_TYPES:
+                with open(file_path, 'rb') as f:
+                    return f.read(len(OLE_SIGNATURE)) == OLE_SIGNATURE
+        except (OSError, RuntimeError) as e:
+            logger.debug(f"Could not check encryption of {file_path.name}: {e}")
+        return False
+    
+    def decrypt(self, file_path: Path, password: Optional[str], name: Optional[str] = None) -> Path:
+        """
+        Write a decrypted temp copy of an encrypted document (caller removes it).
+        
+        name is the document's name in error messages (default: the file's).
+        
+        Raises:
+            PasswordRequiredError: If password is None
+            WrongPasswordError: If the password is wrong
+            ValueError: If msoffcrypto-tool (needed for DOCX/XLSX) isn't installed
+        """
+        name = name or file_path.name
+        if not password:
+            raise PasswordRequiredError(name)
+        
+        fd, decrypted = tempfile.mkstemp(suffix=file_path.suffix, prefix="jdp_decrypted_")
+        os.close(fd)
+        try:
+            if file_path.suffix.lower() == '.pdf':
+                with fitz.open(file_path) as doc:
+                    if not doc.authenticate(password):
+                        raise WrongPasswordError(name)
+                    doc.save(decrypted)
+            else:
+                try:
+                    import msoffcrypto
+                    from msoffcrypto.exceptions import InvalidKeyError
+                except ImportError:
+                    raise ValueError(
+                        f"{name} is password-protected; install msoffcrypto-tool to open it"
+                    )
+                with open(file_path, 'rb') as f:
+                    office_file = msoffcrypto.OfficeFile(f)
+                    try:
+                        office_file.load_key(password=password, verify_password=True)
+                        with open(decrypted, 'wb') as out:
+                            office_file.decrypt(out)
+                    except InvalidKeyError:
+                        raise WrongPasswordError(name)
+        except BaseException:
+            os.unlink(decrypted)
+            raise
+        return Path(decrypted)
+    
     @classmethod
     def get_supported_extensions(cls) -> set:
         """Return all supported file extensions."""
         return cls.SUPPORTED_IMAGES | cls.SUPPORTED_DOCS
     
-    def extract(self, file_path: Path) -> str:
+    def extract(
+        self,
+        file_path: Path,
+        deadline: Optional[float] = None,
+        ocr_enabled: bool = True,
+        flatten_tables: bool = True,
+        note_markers: bool = False,
+        ocr_languages: Optional[str] = None,
+        force_ocr: bool = False,
+        ocr_report: Optional[List[Dict]] = None,
+        inactivity_timeout: Optional[float] = None,
+        on_page: Optional[Callable[[int, int, str], None]] = None
+    ) -> str:
         """
         Extract text from document.
         
         Args:
             file_path: Path to the document
+            deadline: Optional time.monotonic() value after which PDF
+                      extraction is abandoned
+            ocr_enabled: OCR scanned PDF pages and images (False = text layer only)
+            flatten_tables: Include DOCX tables in the text as "a | b" rows
+                            (False when tables are indexed via extract_tables)
+            note_markers: Insert [^id] markers at DOCX footnote/endnote references
+            ocr_languages: Tesseract languages, e.g. "eng+deu" (None = Tesseract default)
+            force_ocr: OCR every PDF page, not just pages lacking a text layer
+            ocr_report: If given, low-confidence OCR pages are appended to it as
+                        {"page", "confidence", "excluded"} (see min_ocr_confidence)
+            inactivity_timeout: Seconds without a finished PDF page before
+                                extraction is abandoned (None = no limit)
+            on_page: Called with (page_number, total_pages, page_text) as each
+                     PDF page finishes; page_text is "" for blank pages
             
         Returns:
             Tuple of (extracted_text, ocr_used, ocr_page_count)
@@ -133,17 +958,13 @@ class DocumentExtractor:
         Raises:
             ValueError: If file type not supported
             FileNotFoundError: If file doesn't exist
+            ExtractionTimeoutError: If the deadline passed or a page stalled mid-document
         """
         if not file_path.exists():
             raise FileNotFoundError(f"File not found: {file_path}")
         
         suffix = file_path.suffix.lower()
         
-        # Methods that return OCR metadata
-        ocr_extractors = {
-            '.pdf': self._extract_pdf,
-        }
-        
         # Methods that don't use OCR (return just text)
         text_extractors = {
             '.docx': self._extract_docx,
@@ -151,92 +972,274 @@ class DocumentExtractor:
             '.xlsx': self._extract_xlsx,
             '.xls': self._extract_xlsx,
             '.txt': self._extract_txt,
-            '.md': self._extract_txt,
+            '.md': self._extract_markdown,
             '.csv': self._extract_csv,
         }
         
-        if suffix in ocr_extractors:
-            return ocr_extractors[suffix](file_path)
+        if suffix == '.pdf':
+            options = dict(
+                ocr_enabled=ocr_enabled, ocr_languages=ocr_languages,
+                force_ocr=force_ocr, ocr_report=ocr_report
+            )
+            if deadline is None and inactivity_timeout is None:
+                return self._extract_pdf(file_path, on_page=on_page, **options)
+            return self._extract_pdf_watched(file_path, deadline, inactivity_timeout, on_page, **options)
+        elif suffix == '.docx':
+            return self._extract_docx(file_path, flatten_tables, note_markers), False, 0
         elif suffix in text_extractors:
             # Wrap result in tuple with OCR=False
             text = text_extractors[suffix](file_path)
             return text, False, 0
         elif suffix in self.SUPPORTED_IMAGES:
-            return self._extract_image_ocr(file_path)
+            if not ocr_enabled:
+                raise ValueError(f"OCR is disabled; cannot extract text from image: {file_path.name}")
+            return self._extract_image_ocr(file_path, ocr_languages, ocr_report)
         else:
             raise ValueError(f"Unsupported file type: {suffix}")
     
-    def _extract_pdf(self, path: Path) -> tuple[str, bool, int]:
+    def _extract_pdf_watched(
+        self,
+        path: Path,
+        deadline: Optional[float],
+        inactivity_timeout: Optional[float],
+        on_page: Optional[Callable[[int, int, str], None]] = None,
+        **options
+    ) -> tuple[str, bool, int]:
         """
-        Extract text from PDF with OCR fallback for scanned pages.
-
-        Uses two-step OCR processing:
-        1. Pre-processing: Image enhancement (contrast, denoise, deskew)
-        2. Post-processing: Text cleanup (fix artifacts, normalize whitespace)
+        Run _extract_pdf in a worker thread and enforce the deadline and
+        the inactivity timeout from here.
 
-        Returns:
-            Tuple of (extracted_text, ocr_was_used, ocr_page_count)
+        The worker sends a heartbeat as each page finishes, so a page stuck
+        in OCR or rendering is noticed while it is still running. A long
+        document keeps going as long as its pages keep finishing; when
+        either limit passes, the worker is told to stop and the pages
+        received so far are returned inside an ExtractionTimeoutError.
+        on_page is called here, on the calling thread.
         """
-        doc = fitz.open(path)
-        text_parts = []
-        ocr_used = False
-        ocr_page_count = 0
+        events: queue.Queue = queue.Queue()
+        cancel = threading.Event()
+        # The worker reports low-confidence OCR pages into its own list; they
+        # reach the caller's ocr_report with the heartbeat of their page, so
+        # an abandoned worker never touches the caller's list
+        ocr_report = options.pop("ocr_report", None)
+        worker_report: List[Dict] = []
+        reported = [0]
+        worker_state = {"release": False}
 
-        # Initialize OCR pipeline if available
-        ocr_pipeline = None
-        if OCR_PIPELINE_AVAILABLE and self._tesseract_available:
-            ocr_pipeline = OCRPipeline()
+        def heartbeat(page_number: int, total_pages: int, page_text: str):
+            new_entries = worker_report[reported[0]:]
+            reported[0] = len(worker_report)
+            events.put(("page", page_number, total_pages, page_text, new_entries))
 
-        for page_num, page in enumerate(doc):
-            text = page.get_text()
+        def work():
+            try:
+                events.put(("done", self._extract_pdf(
+                    path, on_page=heartbeat, cancel=cancel, ocr_report=worker_report, **options
+                ), worker_report[reported[0]:]))
+            except BaseException as e:
+                events.put(("error", e))
+            finally:
+                with self._abandoned_lock:
+                    if self._abandoned_workers.get(str(path)) is worker_state:
+                        del self._abandoned_workers[str(path)]
+                    release = worker_state["release"]
+                if release:
+                    self._unlink(path)
 
-            # If page has minimal text, attempt OCR with pre/post processing
-            if len(text.strip()) < 50 and self._tesseract_available:
-                try:
-                    # Render page to image at higher DPI for better OCR
-                    pix = page.get_pixmap(dpi=200)
-                    img = Image.frombytes("RGB", [pix.width, pix.height], pix.samples)
-
-                    if ocr_pipeline:
-                        # Full pipeline: preprocess -> OCR -> postprocess
-                        ocr_text, metadata = ocr_pipeline.process_image(img)
-                        logger.debug(
-                            f"OCR page {page_num + 1}: "
-                            f"{metadata['raw_length']} -> {metadata['clean_length']} chars "
-                            f"({metadata['reduction_pct']}% reduction)"
-                        )
-                    else:
-                        # Fallback: basic OCR without pre/post processing
-                        ocr_text = pytesseract.image_to_string(img)
-
-                    if ocr_text.strip():
-                        text = f"[OCR]\n{ocr_text}"
-                        ocr_used = True
-                        ocr_page_count += 1
-                        logger.debug(f"OCR applied to page {page_num + 1}")
-                except Exception as e:
-                    logger.warning(f"OCR failed for page {page_num + 1}: {e}")
-            elif text.strip() and OCR_PIPELINE_AVAILABLE:
-                # Even for native text, apply post-processing to clean up
-                text = postprocess_text(text)
+        worker = threading.Thread(target=work, name=f"extract-{path.name}", daemon=True)
+        worker.start()
 
-            if text.strip():
-                text_parts.append(f"[Page {page_num + 1}]\n{text.strip()}")
+        def abandon():
+            # Tell the worker to stop and give it a moment to close the
+            # file; if it is still inside a page, release_file leaves the
+            # file for the worker to delete when it exits
+            with self._abandoned_lock:
+                self._abandoned_workers[str(path)] = worker_state
+            cancel.set()
+            worker.join(EXTRACTION_CANCEL_GRACE)
+            if not worker.is_alive():
+                with self._abandoned_lock:
+                    if self._abandoned_workers.get(str(path)) is worker_state:
+                        del self._abandoned_workers[str(path)]
 
-        doc.close()
-        return "\n\n".join(text_parts), ocr_used, ocr_page_count
-    
-    def _extract_docx(self, path: Path) -> str:
+        text_parts = []
+        pages_completed = total_pages = 0
+        last_beat = time.monotonic()
+        while True:
+            now = time.monotonic()
+            limits = []
+            if inactivity_timeout is not None:
+                limits.append(last_beat + inactivity_timeout - now)
+            if deadline is not None:
+                limits.append(deadline - now)
+            try:
+                event = events.get(timeout=max(0.0, min(limits)))
+            except queue.Empty:
+                abandon()
+                stalled = (
+                    inactivity_timeout is not None
+                    and time.monotonic() - last_beat >= inactivity_timeout
+                    and (deadline is None or time.monotonic() < deadline)
+                )
+                cause = f"stalled (no page finished for {inactivity_timeout:g}s)" if stalled else "timed out"
+                partial_text = "\n\n".join(text_parts)
+                ocr_pages = len(OCR_PAGE_PATTERN.findall(partial_text))
+                raise ExtractionTimeoutError(
+                    f"Extraction {cause} after {pages_completed} of {total_pages or '?'} pages: {path.name}",
+                    partial_text=partial_text,
+                    pages_completed=pages_completed,
+                    total_pages=total_pages,
+                    ocr_used=ocr_pages > 0,
+                    ocr_pages=ocr_pages
+                )
+
+            if event[0] == "done":
+                if ocr_report is not None:
+                    ocr_report.extend(event[2])
+                return event[1]
+            if event[0] == "error":
+                raise event[1]
+            _, pages_completed, total_pages, page_text, new_entries = event
+            last_beat = time.monotonic()
+            if ocr_report is not None:
+                ocr_report.extend(new_entries)
+            if page_text:
+                text_parts.append(page_text)
+            if on_page:
+                try:
+                    on_page(pages_completed, total_pages, page_text)
+                except BaseException:
+                    abandon()
+                    raise
+
+    def release_file(self, path: Union[str, Path]):
+        """
+        Delete a temp file (snapshot or decrypted copy) once extraction is
+        done with it. If a timed-out extraction's worker still has it open,
+        the worker deletes it when it exits.
+        """
+        with self._abandoned_lock:
+            worker_state = self._abandoned_workers.get(str(Path(path)))
+            if worker_state is not None:
+                worker_state["release"] = True
+                return
+        self._unlink(path)
+
+    @staticmethod
+    def _unlink(path: Union[str, Path]):
+        try:
+            os.unlink(path)
+        except OSError:
+            pass
+
+    def _extract_pdf(
+        self,
+        path: Path,
+        ocr_enabled: bool = True,
+        ocr_languages: Optional[str] = None,
+        force_ocr: bool = False,
+        ocr_report: Optional[List[Dict]] = None,
+        on_page: Optional[Callable[[int, int, str], None]] = None,
+        cancel: Optional[threading.Event] = None
+    ) -> tuple[str, bool, int]:
+        """
+        Extract text from PDF with OCR fallback for scanned pages.
+
+        Uses two-step OCR processing:
+        1. Pre-processing: Image enhancement (contrast, denoise, deskew)
+        2. Post-processing: Text cleanup (fix artifacts, normalize whitespace)
+
+        Stops before the next page once cancel is set (see
+        _extract_pdf_watched); the result is then discarded by the caller.
+
+        Returns:
+            Tuple of (extracted_text, ocr_was_used, ocr_page_count)
+        """
+        doc = fitz.open(path)
+        text_parts = []
+        ocr_used = False
+        ocr_page_count = 0
+
+        # Initialize OCR pipeline if available
+        use_ocr = ocr_enabled and self._tesseract_available
+        ocr_pipeline = None
+        if OCR_PIPELINE_AVAILABLE and use_ocr:
+            ocr_pipeline = OCRPipeline()
+        with_confidence = self.min_ocr_confidence is not None
+
+        try:
+            for page_num, page in enumerate(doc):
+                if cancel is not None and cancel.is_set():
+                    break
+
+                text = page.get_text()
+
+                # If page has minimal text, attempt OCR with pre/post processing
+                if use_ocr and (force_ocr or len(text.strip()) < MIN_TEXT_LAYER_CHARS):
+                    try:
+                        # Render page to image at higher DPI for better OCR
+                        pix = page.get_pixmap(dpi=200)
+                        img = Image.frombytes("RGB", [pix.width, pix.height], pix.samples)
+
+                        confidence = None
+                        if ocr_pipeline:
+                            # Full pipeline: preprocess -> OCR -> postprocess
+                            ocr_text, metadata = ocr_pipeline.process_image(
+                                img, lang=ocr_languages, with_confidence=with_confidence
+                            )
+                            confidence = metadata['confidence']
+                            logger.debug(
+                                f"OCR page {page_num + 1}: "
+                                f"{metadata['raw_length']} -> {metadata['clean_length']} chars "
+                                f"({metadata['reduction_pct']}% reduction)"
+                            )
+                        else:
+                            # Fallback: basic OCR without pre/post processing
+                            ocr_text = pytesseract.image_to_string(img, lang=ocr_languages)
+
+                        if ocr_text.strip() and self._accept_ocr_text(confidence, page_num + 1, ocr_report):
+                            text = f"[OCR]\n{ocr_text}"
+                            ocr_used = True
+                            ocr_page_count += 1
+                            logger.debug(f"OCR applied to page {page_num + 1}")
+                    except Exception as e:
+                        logger.warning(f"OCR failed for page {page_num + 1}: {e}")
+                elif text.strip() and OCR_PIPELINE_AVAILABLE:
+                    # Even for native text, apply post-processing to clean up
+                    text = postprocess_text(text)
+
+                page_text = f"[Page {page_num + 1}]\n{text.strip()}" if text.strip() else ""
+                if page_text:
+                    text_parts.append(page_text)
+                if on_page:
+                    on_page(page_num + 1, len(doc), page_text)
+
+        finally:
+            doc.close()
+        return "\n\n".join(text_parts), ocr_used, ocr_page_count
+    
+    def _extract_docx(self, path: Path, flatten_tables: bool = True, note_markers: bool = False) -> str:
         """Extract text from DOCX."""
         doc = DocxDocument(path)
         paragraphs = []
         
         for para in doc.paragraphs:
-            if para.text.strip():
-                paragraphs.append(para.text.strip())
+            text = self._docx_paragraph_text(para) if note_markers else para.text
+            if not text.strip():
+                continue
+            
+            # Mark Word headings Markdown-style so the chunker can track sections
+            style = para.style.name if para.style is not None else ""
+            level = re.match(r'^Heading (\d)$', style)
+            if level:
+                text = f"{'#' * int(level.group(1))} {text.strip()}"
+            elif style == "Title":
+                text = f"# {text.strip()}"
+            
+            paragraphs.append(text.strip())
         
         # Also extract from tables
-        for table in doc.tables:
+        for table in (doc.tables if flatten_tables else []):
             for row in table.rows:
                 row_text = " | ".join(
                     cell.text.strip() for cell in row.cells if cell.text.strip()
@@ -246,6 +1249,22 @@ class DocumentExtractor:
         
         return "\n\n".join(paragraphs)
     
+    def _docx_paragraph_text(self, para) -> str:
+        """Paragraph text with [^id] markers where footnotes/endnotes are referenced."""
+        parts = []
+        for el in para._p.iter():
+            if el.tag == f"{W_NS}t":
+                parts.append(el.text or "")
+            elif el.tag == f"{W_NS}tab":
+                parts.append("\t")
+            elif el.tag in (f"{W_NS}br", f"{W_NS}cr"):
+                parts.append("\n")
+            elif el.tag == f"{W_NS}footnoteReference":
+                parts.append(f"[^{el.get(f'{W_NS}id')}]")
+            elif el.tag == f"{W_NS}endnoteReference":
+                parts.append(f"[^e{el.get(f'{W_NS}id')}]")
+        return "".join(parts)
+    
     def _extract_doc_legacy(self, path: Path) -> str:
         """
         Handle legacy .doc format.
@@ -302,6 +1321,11 @@ class DocumentExtractor:
         # Last resort: ignore errors
         return path.read_text(encoding='utf-8', errors='replace')
     
+    def _extract_markdown(self, path: Path) -> str:
+        """Extract text from Markdown, dropping YAML frontmatter (see extract_properties)."""
+        _, text = parse_frontmatter(self._extract_txt(path))
+        return text
+    
     def _extract_csv(self, path: Path) -> str:
         """Extract text from CSV file."""
         import csv
@@ -319,7 +1343,289 @@ class DocumentExtractor:
         
         return "\n".join(formatted)
     
-    def _extract_image_ocr(self, path: Path) -> tuple[str, bool, int]:
+    def extract_tables(self, file_path: Path) -> List[ExtractedTable]:
+        """
+        Extract tables with their row/column structure (DOCX and PDF).
+        
+        The first row of each table is treated as its header. PDF tables
+        are detected with PyMuPDF's find_tables(). Other formats return [].
+        """
+        suffix = file_path.suffix.lower()
+        
+        try:
+            if suffix == '.docx':
+                raw_tables = [
+                    (None, [[cell.text.strip() for cell in row.cells] for row in table.rows])
+                    for table in DocxDocument(file_path).tables
+                ]
+            elif suffix == '.pdf':
+                raw_tables = self._pdf_tables(file_path)
+            else:
+                return []
+        except Exception as e:
+            logger.warning(f"Table extraction failed for {file_path.name}: {e}")
+            return []
+        
+        tables = []
+        for page, rows in raw_tables:
+            rows = [[("" if v is None else str(v).strip()) for v in row] for row in rows]
+            rows = [row for row in rows if any(row)]
+            if len(rows) < 2:
+                continue
+            tables.append(ExtractedTable(headers=rows[0], rows=rows[1:], page=page))
+        
+        if tables:
+            logger.info(f"Extracted {len(tables)} table(s) from {file_path.name}")
+        return tables
+    
+    def _pdf_tables(self, path: Path) -> List[tuple]:
+        """(page_number, rows) for each table PyMuPDF finds in a PDF."""
+        found = []
+        with fitz.open(path) as doc:
+            for page_num, page in enumerate(doc):
+                if not hasattr(page, "find_tables"):  # PyMuPDF < 1.23
+                    break
+                for table in page.find_tables().tables:
+                    found.append((page_num + 1, table.extract()))
+        return found
+    
+    def extract_figures(self, file_path: Path, include_images: bool = False) -> List[ExtractedFigure]:
+        """
+        Find figure captions ("Figure 3: ...", "Chart 2. ...").
+        
+        DOCX paragraphs styled "Caption" count even without a label. For
+        PDFs, include_images also attaches the image nearest each caption
+        on the same page so it can be described.
+        """
+        suffix = file_path.suffix.lower()
+        
+        try:
+            if suffix == '.pdf':
+                figures = self._pdf_figures(file_path, include_images)
+            elif suffix == '.docx':
+                figures = []
+                for para in DocxDocument(file_path).paragraphs:
+                    text = para.text.strip()
+                    match = FIGURE_CAPTION_PATTERN.match(text)
+                    if match:
+                        figures.append(ExtractedFigure(label=match.group(1), caption=match.group(2)))
+                    elif text and para.style is not None and para.style.name.lower().startswith("caption"):
+                        figures.append(ExtractedFigure(label="Figure", caption=text))
+            elif suffix in ('.txt', '.md'):
+                figures = []
+                for line in self._extract_txt(file_path).splitlines():
+                    match = FIGURE_CAPTION_PATTERN.match(line)
+                    if match:
+                        figures.append(ExtractedFigure(label=match.group(1), caption=match.group(2).strip()))
+            else:
+                return []
+        except Exception as e:
+            logger.warning(f"Figure extraction failed for {file_path.name}: {e}")
+            return []
+        
+        # The same caption can be repeated (e.g. list of figures); keep the first
+        seen = set()
+        unique = []
+        for fig in figures:
+            key = (fig.label.lower(), fig.caption.lower())
+            if key not in seen:
+                seen.add(key)
+                unique.append(fig)
+        return unique
+    
+    def _pdf_figures(self, path: Path, include_images: bool) -> List[ExtractedFigure]:
+        """Caption blocks of a PDF, with the nearest image on the page if requested."""
+        figures = []
+        with fitz.open(path) as doc:
+            for page_num, page in enumerate(doc):
+                images = page.get_image_info(xrefs=True) if include_images else []
+                
+                for block in page.get_text("blocks"):
+                    x0, y0, x1, y1, text = block[:5]
+                    if len(block) > 6 and block[6] != 0:  # Not a text block
+                        continue
+                    text = " ".join(text.split())
+                    match = FIGURE_CAPTION_PATTERN.match(text)
+                    if not match:
+                        continue
+                    
+                    figure = ExtractedFigure(label=match.group(1), caption=match.group(2), page=page_num + 1)
+                    
+                    # Nearest image by vertical gap to the caption block
+                    candidates = [img for img in images if img.get("xref")]
+                    if candidates:
+                        nearest = min(
+                            candidates,
+                            key=lambda img: min(abs(img["bbox"][3] - y0), abs(img["bbox"][1] - y1))
+                        )
+                        extracted = doc.extract_image(nearest["xref"])
+                        if extracted:
+                            figure.image = extracted["image"]
+                            figure.image_ext = extracted.get("ext", "png")
+                    
+                    figures.append(figure)
+        return figures
+    
+    def extract_notes(self, file_path: Path) -> List[ExtractedNote]:
+        """
+        Extract footnotes and endnotes (DOCX and PDF).
+        
+        DOCX notes come from word/footnotes.xml and word/endnotes.xml. PDF
+        footnotes are detected heuristically: numbered lines in the lower
+        part of a page set in a smaller font than the body text.
+        """
+        suffix = file_path.suffix.lower()
+        
+        try:
+            if suffix == '.docx':
+                return self._docx_notes(file_path)
+            elif suffix == '.pdf':
+                return self._pdf_notes(file_path)
+        except Exception as e:
+            logger.warning(f"Footnote extraction failed for {file_path.name}: {e}")
+        return []
+    
+    def _docx_notes(self, path: Path) -> List[ExtractedNote]:
+        notes = []
+        with zipfile.ZipFile(path) as archive:
+            names = set(archive.namelist())
+            for part, kind, prefix in (
+                ("word/footnotes.xml", "footnote", ""),
+                ("word/endnotes.xml", "endnote", "e"),
+            ):
+                if part not in names:
+                    continue
+                root = ET.fromstring(archive.read(part))
+                for note in root.findall(f"{W_NS}{kind}"):
+                    # Skip the built-in separator "notes"
+                    if note.get(f"{W_NS}type") in ("separator", "continuationSeparator", "continuationNotice"):
+                        continue
+                    paragraphs = [
+                        "".join(t.text or "" for t in p.iter(f"{W_NS}t"))
+                        for p in note.iter(f"{W_NS}p")
+                    ]
+                    text = " ".join(p.strip() for p in paragraphs if p.strip())
+                    if text:
+                        notes.append(ExtractedNote(f"{prefix}{note.get(f'{W_NS}id')}", kind, text))
+        return notes
+    
+    def _pdf_notes(self, path: Path) -> List[ExtractedNote]:
+        notes = []
+        with fitz.open(path) as doc:
+            for page_num, page in enumerate(doc):
+                lines = [
+                    line
+                    for block in page.get_text("dict")["blocks"] if block.get("type", 0) == 0
+                    for line in block["lines"] if line["spans"]
+                ]
+                sizes = [s["size"] for line in lines for s in line["spans"] if s["text"].strip()]
+                if not sizes:
+                    continue
+                body_size = statistics.median(sizes)
+                footer_top = page.rect.height * 0.6
+                
+                current = None
+                for line in lines:
+                    text = "".join(s["text"] for s in line["spans"]).strip()
+                    size = max(s["size"] for s in line["spans"])
+                    if not text or line["bbox"][1] < footer_top or size > body_size * 0.9:
+                        current = None
+                        continue
+                    
+                    match = re.match(r'^(\d{1,3})[.)]?\s*([^\d\s].*)$', text)
+                    if match:
+                        current = ExtractedNote(match.group(1), "footnote", match.group(2), page_num + 1)
+                        notes.append(current)
+                    elif current is not None:
+                        current.text += " " + text  # Note continues on the next line
+        return notes
+    
+    def extract_properties(self, file_path: Path) -> Dict[str, str]:
+        """
+        Extract document properties (title, author, dates, keywords...).
+        
+        Sources: DOCX core properties, PDF info dictionary, XLSX workbook
+        properties and Markdown YAML frontmatter. Other formats return {}.
+        
+        Returns:
+            Dict of lowercase property name -> string value (empty values dropped)
+        """
+        suffix = file_path.suffix.lower()
+        
+        try:
+            if suffix == '.docx':
+                props = self._properties_docx(file_path)
+            elif suffix == '.pdf':
+                props = self._properties_pdf(file_path)
+            elif suffix in ('.xlsx', '.xls'):
+                props = self._properties_xlsx(file_path)
+            elif suffix == '.md':
+                props, _ = parse_frontmatter(self._extract_txt(file_path))
+            else:
+                return {}
+        except Exception as e:
+            logger.warning(f"Could not read properties of {file_path.name}: {e}")
+            return {}
+        
+        properties = {}
+        for key, value in props.items():
+            if value is None:
+                continue
+            value = value.isoformat() if isinstance(value, datetime) else str(value).strip()
+            if value:
+                properties[key] = value[:MAX_PROPERTY_LENGTH]
+        return properties
+    
+    def _properties_docx(self, path: Path) -> Dict:
+        """Core properties (docProps/core.xml) of a DOCX file."""
+        core = DocxDocument(path).core_properties
+        return {
+            "title": core.title,
+            "author": core.author,
+            "subject": core.subject,
+            "keywords": core.keywords,
+            "category": core.category,
+            "last_modified_by": core.last_modified_by,
+            "created": core.created,
+            "modified": core.modified
+        }
+    
+    def _properties_pdf(self, path: Path) -> Dict:
+        """Info dictionary of a PDF file."""
+        with fitz.open(path) as doc:
+            meta = doc.metadata or {}
+        return {
+            "title": meta.get("title"),
+            "author": meta.get("author"),
+            "subject": meta.get("subject"),
+            "keywords": meta.get("keywords"),
+            "creator": meta.get("creator"),
+            "created": _format_pdf_date(meta.get("creationDate", "")),
+            "modified": _format_pdf_date(meta.get("modDate", ""))
+        }
+    
+    def _properties_xlsx(self, path: Path) -> Dict:
+        """Workbook properties of an XLSX file."""
+        wb = openpyxl.load_workbook(path, read_only=True)
+        props = wb.properties
+        wb.close()
+        return {
+            "title": props.title,
+            "author": props.creator,
+            "subject": props.subject,
+            "keywords": props.keywords,
+            "category": props.category,
+            "last_modified_by": props.lastModifiedBy,
+            "created": props.created,
+            "modified": props.modified
+        }
+    
+    def _extract_image_ocr(
+        self,
+        path: Path,
+        ocr_languages: Optional[str] = None,
+        ocr_report: Optional[List[Dict]] = None
+    ) -> tuple[str, bool, int]:
         """
         Extract text from image using OCR with pre/post processing.
 
@@ -345,7 +1651,11 @@ class DocumentExtractor:
         if OCR_PIPELINE_AVAILABLE:
             # Full pipeline: preprocess -> OCR -> postprocess
             ocr_pipeline = OCRPipeline()
-            text, metadata = ocr_pipeline.process_image(img)
+            text, metadata = ocr_pipeline.process_image(
+                img, lang=ocr_languages, with_confidence=self.min_ocr_confidence is not None
+            )
+            if not self._accept_ocr_text(metadata['confidence'], 1, ocr_report):
+                text = ""
             logger.info(
                 f"OCR image {path.name}: "
                 f"{metadata['raw_length']} -> {metadata['clean_length']} chars "
@@ -353,7 +1663,7 @@ class DocumentExtractor:
             )
         else:
             # Fallback: basic OCR
-            text = pytesseract.image_to_string(img)
+            text = pytesseract.image_to_string(img, lang=ocr_languages)
 
         img.close()
 
@@ -373,11 +1683,13 @@ class SemanticChunker:
         self,
         chunk_size: int = 1000,       # target tokens per chunk
         chunk_overlap: int = 100,      # overlap tokens for continuity
-        chars_per_token: float = 4.0   # rough estimate (varies by model)
+        chars_per_token: float = 4.0,  # rough estimate (varies by model)
+        respect_sentences: bool = True # break at sentence boundaries
     ):
         self.chunk_size = chunk_size
         self.chunk_overlap = chunk_overlap
         self.chars_per_token = chars_per_token
+        self.respect_sentences = respect_sentences
     
     def chunk(self, text: str, doc_hash: str) -> List[DocumentChunk]:
         """
@@ -395,6 +1707,8 @@ class SemanticChunker:
         
         char_chunk_size = int(self.chunk_size * self.chars_per_token)
         char_overlap = int(self.chunk_overlap * self.chars_per_token)
+        headings = find_headings(text)
+        pages = [(m.start(), int(m.group(1))) for m in PAGE_HEADER_PATTERN.finditer(text)]
         
         chunks = []
         start = 0
@@ -404,7 +1718,7 @@ class SemanticChunker:
             end = start + char_chunk_size
             
             # Try to break at sentence boundary
-            if end < len(text):
+            if end < len(text) and self.respect_sentences:
                 boundary = self._find_sentence_boundary(text, end)
                 if boundary > start + (char_chunk_size // 2):  # Ensure minimum chunk size
                     end = boundary
@@ -412,13 +1726,29 @@ class SemanticChunker:
             chunk_text = text[start:end].strip()
             
             if chunk_text:
+                metadata = {
+                    "chunk_type": "text",
+                    "start_char": start,
+                    "end_char": end,
+                    "char_count": len(chunk_text)
+                }
+                
+                # Section breadcrumb at the chunk's first character
+                first_char = start + (len(text[start:end]) - len(text[start:end].lstrip()))
+                path = heading_path_at(headings, first_char)
+                if path:
+                    metadata["heading_path"] = HEADING_PATH_SEPARATOR.join(path)
+                    metadata["heading"] = path[-1]
+                    metadata["heading_level"] = len(path)
+                
+                # PDF page the chunk starts on
+                page = next((num for offset, num in reversed(pages) if offset <= first_char), None)
+                if page is not None:
+                    metadata["page"] = page
+                
                 chunks.append(DocumentChunk(
                     content=chunk_text,
-                    metadata={
-                        "start_char": start,
-                        "end_char": end,
-                        "char_count": len(chunk_text)
-                    },
+                    metadata=metadata,
                     chunk_index=chunk_index,
                     doc_hash=doc_hash
                 ))
@@ -431,6 +1761,109 @@ class SemanticChunker:
         
         return chunks
     
+    def chunk_tables(
+        self,
+        tables: List[ExtractedTable],
+        doc_hash: str,
+        start_index: int = 0
+    ) -> List[DocumentChunk]:
+        """
+        Turn tables into dedicated chunks (content is the table as JSON).
+        
+        Tables larger than the chunk size are split into row groups, each
+        repeating the header row.
+        
+        Args:
+            tables: Tables from DocumentExtractor.extract_tables
+            doc_hash: Document identifier
+            start_index: chunk_index of the first table chunk
+        """
+        char_chunk_size = int(self.chunk_size * self.chars_per_token)
+        chunks = []
+        
+        for table_index, table in enumerate(tables):
+            groups = [[]]
+            group_chars = len(json.dumps(table.headers))
+            for row in table.rows:
+                row_chars = len(json.dumps(row))
+                if groups[-1] and group_chars + row_chars > char_chunk_size:
+                    groups.append([])
+                    group_chars = len(json.dumps(table.headers))
+                groups[-1].append(row)
+                group_chars += row_chars
+            
+            for part, rows in enumerate(groups):
+                piece = ExtractedTable(headers=table.headers, rows=rows, page=table.page)
+                metadata = {
+                    "chunk_type": "table",
+                    "table_index": table_index,
+                    "table_part": part,
+                    "row_count": len(rows),
+                    "column_count": len(table.headers)
+                }
+                if table.page is not None:
+                    metadata["page"] = table.page
+                
+                chunks.append(DocumentChunk(
+                    content=piece.to_json(),
+                    metadata=metadata,
+                    chunk_index=start_index + len(chunks),
+                    doc_hash=doc_hash
+                ))
+        
+        return chunks
+    
+    def chunk_figures(
+        self,
+        figures: List[ExtractedFigure],
+        doc_hash: str,
+        start_index: int = 0
+    ) -> List[DocumentChunk]:
+        """Turn figure captions (plus any image description) into dedicated chunks."""
+        chunks = []
+        
+        for figure_index, figure in enumerate(figures):
+            metadata = {
+                "chunk_type": "figure",
+                "figure_index": figure_index,
+                "figure_label": figure.label,
+                "has_description": bool(figure.description)
+            }
+            if figure.page is not None:
+                metadata["page"] = figure.page
+            
+            chunks.append(DocumentChunk(
+                content=figure.to_text(),
+                metadata=metadata,
+                chunk_index=start_index + len(chunks),
+                doc_hash=doc_hash
+            ))
+        
+        return chunks
+    
+    def chunk_notes(
+        self,
+        notes: List[ExtractedNote],
+        doc_hash: str,
+        start_index: int = 0
+    ) -> List[DocumentChunk]:
+        """Turn footnotes/endnotes into their own chunks."""
+        chunks = []
+        
+        for note in notes:
+            metadata = {"chunk_type": note.kind, "note_id": note.note_id}
+            if note.page is not None:
+                metadata["page"] = note.page
+            
+            chunks.append(DocumentChunk(
+                content=f"[^{note.note_id}]: {note.text}",
+                metadata=metadata,
+                chunk_index=start_index + len(chunks),
+                doc_hash=doc_hash
+            ))
+        
+        return chunks
+    
     def _find_sentence_boundary(self, text: str, pos: int, window: int = 200) -> int:
         """Find nearest sentence boundary near position."""
         search_start = max(0, pos - window)
@@ -464,25 +1897,30 @@ class LocalVectorStore:
         self,
         persist_directory: Optional[str] = None,
         embedding_model: str = "all-MiniLM-L6-v2",
-        collection_name: str = "jan_documents"
+        collection_name: str = DEFAULT_COLLECTION,
+        client=None
     ):
         """
         Initialize vector store.
         
+        If the collection already records the embedding model it was built
+        with (e.g. after a shadow index was promoted), that model is used
+        instead of embedding_model so queries match the stored vectors.
+        
         Args:
             persist_directory: Path for persistent storage (None for ephemeral)
             embedding_model: sentence-transformers model name
             collection_name: ChromaDB collection name
+            client: Existing ChromaDB client to share (persist_directory is ignored)
         """
-        logger.info(f"Loading embedding model: {embedding_model}")
-        self.embedder = SentenceTransformer(embedding_model)
-        
         settings = Settings(
             anonymized_telemetry=False,
             allow_reset=True
         )
         
-        if persist_directory:
+        if client is not None:
+            self.client = client
+        elif persist_directory:
             os.makedirs(persist_directory, exist_ok=True)
             self.client = chromadb.PersistentClient(
                 path=persist_directory,
@@ -495,19 +1933,119 @@ class LocalVectorStore:
         
         self.collection = self.client.get_or_create_collection(
             name=collection_name,
-            metadata={"hnsw:space": "cosine"}
+            metadata={"hnsw:space": "cosine", "embedding_model": embedding_model}
         )
+        
+        recorded_model = (self.collection.metadata or {}).get("embedding_model")
+        if recorded_model and recorded_model != embedding_model:
+            logger.warning(
+                f"Collection {collection_name} was built with {recorded_model}; "
+                f"using it instead of {embedding_model}"
+            )
+            embedding_model = recorded_model
+        
+        logger.info(f"Loading embedding model: {embedding_model}")
+        self.embedding_model = embedding_model
+        self.embedder = SentenceTransformer(embedding_model)
+        
+        # Called between embedding batches while indexing (see workload_lanes)
+        self.yield_hook: Optional[Callable[[], Any]] = None
+        
+        # Called with the number of chunks deleted (see collection_optimizer)
+        self.delete_hook: Optional[Callable[[int], Any]] = None
+        
+        # Called with the operation before anything is written; raises to
+        # refuse it (see DocumentProcessor.check_writable)
+        self.write_guard: Optional[Callable[[str], Any]] = None
+        
+        # Held while a document version is written; exports take their snapshot under it
+        self.write_lock = threading.RLock()
+        
+        # Stopwords/boosts/keyword weight for this collection (see keyword_scoring)
+        self.retrieval_config = RetrievalConfig()
+        
+        self._model_dimension: Optional[int] = None
+        self._stored_dimension: Optional[Tuple[Any, int]] = None   # (collection, dimension)
+        
+        # Last other model asked for by embed_texts: (name, model)
+        self._other_embedder: Optional[Tuple[str, Any]] = None
+        self._other_embedder_lock = threading.Lock()
+    
+    @property
+    def model_dimension(self) -> int:
+        """Dimension of the vectors the embedding model makes."""
+        if self._model_dimension is None:
+            self._model_dimension = len(self.embedder.encode(["dimension check"]).tolist()[0])
+        return self._model_dimension
+    
+    def stored_dimension(self) -> Optional[int]:
+        """Dimension of the vectors in the collection (None while it is empty)."""
+        cached = self._stored_dimension
+        if cached and cached[0] is self.collection:
+            return cached[1]
+        sample = self.collection.get(limit=1, include=["embeddings"])
+        embeddings = sample.get("embeddings")
+        if embeddings is None or len(embeddings) == 0:
+            return None
+        self._stored_dimension = (self.collection, len(embeddings[0]))
+        return self._stored_dimension[1]
+    
+    def check_embedding_dimension(self, dimension: Optional[int] = None):
+        """
+        Make sure vectors of a dimension can be searched against / added to the collection.
+        
+        Args:
+            dimension: Vector dimension (None = the embedding model's)
+        
+        Raises:
+            EmbeddingModelMismatchError: If the collection holds vectors of another dimension
+        """
+        stored = self.stored_dimension()
+        model_dimension = self.model_dimension
+        if dimension is None:
+            dimension = model_dimension
+        if stored is not None and stored != dimension:
+            raise EmbeddingModelMismatchError(self.collection.name, self.embedding_model, dimension, stored)
     
-    def add_chunks(self, chunks: List[DocumentChunk], filename: str):
-        """Add document chunks to vector store."""
+    def add_chunks(
+        self,
+        chunks: List[DocumentChunk],
+        filename: str,
+        supersede: Sequence[str] = (),
+        superseded_at: Optional[datetime] = None
+    ):
+        """
+        Add document chunks to vector store.
+        
+        Args:
+            chunks: Chunks of one document
+            filename: Recorded filename
+            supersede: doc_hashes of older versions to mark superseded in the
+                       same write, so snapshots never see neither or both
+            superseded_at: When they were superseded (default now)
+        
+        Raises:
+            EmbeddingModelMismatchError: If the collection was built with another model
+        """
+        self.check_writable("add")
         if not chunks:
+            with self.write_lock:
+                for doc_hash in supersede:
+                    self.mark_superseded(doc_hash, superseded_at or datetime.now())
             return
+        self.check_embedding_dimension()
         
         ids = [f"{chunks[0].doc_hash}_{c.chunk_index}" for c in chunks]
         documents = [c.content for c in chunks]
         
         logger.info(f"Embedding {len(chunks)} chunks...")
-        embeddings = self.embedder.encode(documents, show_progress_bar=True).tolist()
+        embeddings = []
+        for start in range(0, len(documents), EMBED_BATCH_SIZE):
+            if self.yield_hook and start:
+                self.yield_hook()  # Let waiting queries use the embedder first
+            embeddings.extend(
+                self.embedder.encode(documents[start:start + EMBED_BATCH_SIZE]).tolist()
+            )
         
         metadatas = [
             {
@@ -519,20 +2057,52 @@ class LocalVectorStore:
             for c in chunks
         ]
         
-        self.collection.add(
-            ids=ids,
-            documents=documents,
-            embeddings=embeddings,
-            metadatas=metadatas
-        )
+        with self.write_lock:
+            for doc_hash in supersede:
+                self.mark_superseded(doc_hash, superseded_at or datetime.now())
+            self.collection.add(
+                ids=ids,
+                documents=documents,
+                embeddings=embeddings,
+                metadatas=metadatas
+            )
         
         logger.info(f"Added {len(chunks)} chunks from {filename}")
     
+    def check_writable(self, operation: str):
+        """
+        Raises:
+            CollectionReadOnlyError: If write_guard refuses the operation
+        """
+        if self.write_guard:
+            self.write_guard(operation)
+    
+    def estimate_embedding_seconds(self, sample_texts: List[str], total_chunks: int) -> float:
+        """
+        Time to embed total_chunks, extrapolated from embedding one batch
+        of sample_texts on this machine.
+        """
+        sample = sample_texts[:EMBED_BATCH_SIZE]
+        if not sample or total_chunks <= 0:
+            return 0.0
+        started = time.monotonic()
+        self.embedder.encode(sample)
+        return (time.monotonic() - started) / len(sample) * total_chunks
+    
     def query(
         self,
         query_text: str,
         n_results: int = 5,
-        filter_doc_hash: Optional[str] = None
+        filter_doc_hash: Optional[str] = None,
+        filter_properties: Optional[Dict[str, str]] = None,
+        filter_heading: Optional[str] = None,
+        include_old_versions: bool = False,
+        sources: str = "both",
+        recency_boost: Optional[float] = None,
+        exclude_terms: Optional[List[str]] = None,
+        exclude_filters: Optional[Dict[str, str]] = None,
+        explain: Optional[Dict] = None,
+        query_embedding: Optional[List[float]] = None
     ) -> List[Dict]:
         """
         Query for similar chunks.
@@ -541,30 +2111,120 @@ class LocalVectorStore:
             query_text: Search query
             n_results: Number of results to return
             filter_doc_hash: Optionally filter to specific document
+            filter_properties: Optionally require exact document property
+                               values, e.g. {"author": "J. Smith"}
+            filter_heading: Optionally keep only chunks whose heading path
+                            contains this text (case-insensitive)
+            include_old_versions: Also return chunks of superseded document versions
+            sources: "documents", "chat" (indexed Jan threads) or "both"
+            recency_boost: Optionally favor recent content: scores halve
+                           every this many days of age (document_date for
+                           files, message timestamp for chat)
+            exclude_terms: Drop chunks containing any of these words/phrases
+            exclude_filters: Drop chunks whose metadata or document
+                             properties match, e.g. {"author": "J. Smith"}
+            explain: Optional dict filled with retrieval diagnostics: model,
+                     candidates kept after each stage, score distributions,
+                     filters/boosts applied and timings
+            query_embedding: query_text's embedding, if already computed
+                             (see query_batch)
             
         Returns:
-            List of result dicts with content, metadata, distance
+            List of result dicts with content, metadata, distance,
+            source_type ("document" or "chat") and a highlighted snippet
+            (plus keyword/vector scores when the retrieval config blends
+            in keyword scoring, and document_date / recency_boost - the
+            multiplier applied - when recency_boost is set)
         """
-        query_embedding = self.embedder.encode([query_text]).tolist()
+        if sources not in QUERY_SOURCES:
+            raise ValueError(f"Invalid sources: {sources} (expected one of {', '.join(QUERY_SOURCES)})")
+        if recency_boost is not None and recency_boost <= 0:
+            raise ValueError("recency_boost must be a positive number of days")
+        exclude_terms = [t for t in (exclude_terms or []) if t.strip()]
+        excluding = bool(exclude_terms or exclude_filters)
+        
+        self.check_embedding_dimension()
+        started = time.perf_counter()
+        if query_embedding is None:
+            query_embedding = self.embedder.encode([query_text]).tolist()[0]
+        embedded = time.perf_counter()
+        
+        conditions = [{"doc_hash": filter_doc_hash}] if filter_doc_hash else []
+        for key, value in (filter_properties or {}).items():
+            conditions.append({f"{PROPERTY_PREFIX}{key.lower()}": str(value)})
+        
+        if len(conditions) > 1:
+            where_filter = {"$and": conditions}
+        else:
+            where_filter = conditions[0] if conditions else None
         
-        where_filter = {"doc_hash": filter_doc_hash} if filter_doc_hash else None
+        # Heading/version filtering and keyword reranking happen after
+        # retrieval, so fetch extra candidates (chunks indexed before
+        # versioning have no is_latest key, which a where filter would exclude)
+        hybrid = self.retrieval_config.keyword_weight > 0
+        post_filter = (
+            bool(filter_heading) or not include_old_versions or hybrid
+            or sources != "both" or recency_boost is not None
+        )
+        fetch_count = n_results * 5 if post_filter else n_results
+        if excluding:
+            fetch_count = n_results * EXCLUSION_FETCH_FACTOR
         
         results = self.collection.query(
-            query_embeddings=query_embedding,
-            n_results=n_results,
+            query_embeddings=[query_embedding],
+            n_results=fetch_count,
             where=where_filter,
             include=["documents", "metadatas", "distances"]
         )
+        searched = time.perf_counter()
+        
+        if explain is not None:
+            explain.update({
+                "embedding_model": self.embedding_model,
+                "collection": self.collection.name,
+                "collection_chunks": self.collection.count(),
+                "candidates_requested": fetch_count,
+                "filters": {
+                    "doc_hash": filter_doc_hash,
+                    "properties": filter_properties or {},
+                    "heading": filter_heading,
+                    "sources": sources,
+                    "include_old_versions": include_old_versions,
+                    "exclude_terms": exclude_terms,
+                    "exclude_filters": exclude_filters or {}
+                },
+                "boosts": {
+                    "keyword_weight": self.retrieval_config.keyword_weight,
+                    "term_boosts": dict(self.retrieval_config.boosts),
+                    "recency_half_life_days": recency_boost
+                },
+                "stages": []
+            })
+        
+        def record(stage: str):
+            if explain is not None:
+                explain["stages"].append({"stage": stage, "candidates": len(matches)})
         
         if not results["documents"] or not results["documents"][0]:
+            if explain is not None:
+                explain["stages"].append({"stage": "retrieved", "candidates": 0})
+                explain["candidate_scores"] = explain["result_scores"] = score_distribution([])
+                explain["timing_ms"] = {
+                    "embed": round((embedded - started) * 1000, 1),
+                    "search": round((searched - embedded) * 1000, 1),
+                    "rerank": 0.0,
+                    "total": round((searched - started) * 1000, 1)
+                }
             return []
         
-        return [
+        matches = [
             {
-                "content": doc,
+                "content": render_chunk_content(doc, meta),
                 "metadata": meta,
+                "heading_path": meta.get("heading_path"),
                 "distance": dist,
-                "relevance_score": 1 - dist  # Convert distance to similarity
+                "relevance_score": 1 - dist,  # Convert distance to similarity
+                "source_type": "chat" if meta.get("source_type") == CHAT_SOURCE_TYPE else "document"
             }
             for doc, meta, dist in zip(
                 results["documents"][0],
@@ -572,37 +2232,471 @@ class LocalVectorStore:
                 results["distances"][0]
             )
         ]
+        record("retrieved")
+        candidate_scores = [m["relevance_score"] for m in matches]
+        
+        if not include_old_versions:
+            matches = [m for m in matches if m["metadata"].get("is_latest", True)]
+            record("latest_versions")
+        
+        if sources != "both":
+            wanted = "chat" if sources == "chat" else "document"
+            matches = [m for m in matches if m["source_type"] == wanted]
+            record("sources")
+        
+        if filter_heading:
+            needle = filter_heading.lower()
+            matches = [m for m in matches if needle in (m["heading_path"] or "").lower()]
+            record("heading")
+        
+        if excluding:
+            matches = [
+                m for m in matches
+                if not is_excluded(m["content"], m["metadata"], exclude_terms, exclude_filters)
+            ]
+            record("exclusions")
+        
+        if hybrid:
+            matches = hybrid_rerank(matches, query_text, self.retrieval_config)
+        
+        if recency_boost is not None:
+            matches = self._apply_recency(matches, recency_boost)
+        
+        matches = matches[:n_results]
+        record("returned")
+        for m in matches:
+            m["snippet"] = highlight_snippet(m["content"], query_text, self.retrieval_config)
+        
+        if explain is not None:
+            finished = time.perf_counter()
+            explain["candidate_scores"] = score_distribution(candidate_scores)
+            explain["result_scores"] = score_distribution([m["relevance_score"] for m in matches])
+            explain["timing_ms"] = {
+                "embed": round((embedded - started) * 1000, 1),
+                "search": round((searched - embedded) * 1000, 1),
+                "rerank": round((finished - searched) * 1000, 1),
+                "total": round((finished - started) * 1000, 1)
+            }
+        return matches
     
-    def delete_document(self, doc_hash: str):
-        """Delete all chunks for a document."""
-        self.collection.delete(where={"doc_hash": doc_hash})
-        logger.info(f"Deleted document: {doc_hash}")
+    def _embedder_for(self, model: Optional[str]):
+        """The store's embedding model, or another one (the last one asked for is kept loaded)."""
+        if not model or model == self.embedding_model:
+            return self.embedder
+        with self._other_embedder_lock:
+            if self._other_embedder is None or self._other_embedder[0] != model:
+                logger.info(f"Loading embedding model for embed_texts: {model}")
+                try:
+                    self._other_embedder = (model, SentenceTransformer(model))
+                except (OSError, ValueError) as e:
+                    raise ValueError(f"Could not load embedding model {model}: {e}")
+            return self._other_embedder[1]
     
-    def get_document_count(self) -> int:
-        """Get total number of chunks in store."""
-        return self.collection.count()
+    def embed_texts(self, texts: List[str], model: Optional[str] = None, normalize: bool = False) -> Dict:
+        """
+        Embeddings of arbitrary texts, for callers with their own similarity logic.
+        
+        Args:
+            texts: Texts to embed (at most MAX_EMBED_TEXTS), embedded in batches
+            model: sentence-transformers model (None = the store's)
+            normalize: Scale vectors to unit length (dot product = cosine similarity)
+            
+        Returns:
+            Dict with model, dimension, normalized and embeddings (one
+            vector per text, in order)
+            
+        Raises:
+            ValueError: If there are no texts or too many, a text is not a
+                        string, or the model can't be loaded
+        """
+        if not texts:
+            raise ValueError("At least one text is required")
+        if len(texts) > MAX_EMBED_TEXTS:
+            raise ValueError(f"At most {MAX_EMBED_TEXTS} texts per call (got {len(texts)})")
+        if not all(isinstance(t, str) for t in texts):
+            raise ValueError("texts must be strings")
+        
+        embedder = self._embedder_for(model)
+        vectors = embedder.encode(
+            list(texts), batch_size=EMBED_BATCH_SIZE, normalize_embeddings=normalize
+        ).tolist()
+        return {
+            "model": model or self.embedding_model,
+            "dimension": len(vectors[0]),
+            "normalized": normalize,
+            "embeddings": vectors
+        }
     
-    def list_documents(self) -> List[Dict]:
-        """List all unique documents in store."""
-        # Get all metadata
-        results = self.collection.get(include=["metadatas"])
+    def query_batch(self, query_texts: List[str], **query_args) -> List[List[Dict]]:
+        """
+        Run many queries, embedding them all in one go instead of one
+        encode() call per query.
         
-        if not results["metadatas"]:
-            return []
+        Args:
+            query_texts: Search queries (at most MAX_BATCH_QUERIES)
+            **query_args: Other query() arguments, applied to every query
+                          (explain is not supported)
+            
+        Returns:
+            query()'s results for each query, in order
+            
+        Raises:
+            ValueError: If there are no queries or too many, or as for query()
+        """
+        if not query_texts:
+            raise ValueError("At least one query is required")
+        if len(query_texts) > MAX_BATCH_QUERIES:
+            raise ValueError(f"At most {MAX_BATCH_QUERIES} queries per batch (got {len(query_texts)})")
+        if query_args.get("explain") is not None:
+            raise ValueError("explain is not supported for batch queries")
         
-        # Deduplicate by doc_hash
-        docs = {}
-        for meta in results["metadatas"]:
+        self.check_embedding_dimension()
+        embeddings = self.embedder.encode(list(query_texts), batch_size=EMBED_BATCH_SIZE).tolist()
+        return [
+            self.query(text, query_embedding=embedding, **query_args)
+            for text, embedding in zip(query_texts, embeddings)
+        ]
+    
+    def _apply_recency(self, matches: List[Dict], half_life_days: float) -> List[Dict]:
+        """Decay relevance scores by content age and re-sort."""
+        now = datetime.now()
+        for m in matches:
+            meta = m["metadata"]
+            # Chunks indexed before document dates were stored fall back to indexed_at
+            raw_date = meta.get("timestamp") if m["source_type"] == "chat" else None
+            raw_date = raw_date or meta.get("document_date") or meta.get("indexed_at")
+            date = parse_document_date(raw_date or "")
+            multiplier = recency_multiplier(date, half_life_days, now)
+            m["document_date"] = date.isoformat() if date else None
+            m["recency_boost"] = round(multiplier, 4)
+            m["relevance_score"] = m["relevance_score"] * multiplier
+        return sorted(matches, key=lambda m: m["relevance_score"], reverse=True)
+    
+    def get_document_versions(self, source_path: str) -> List[Dict]:
+        """
+        Indexed versions of a source file, oldest first.
+        
+        Returns:
+            List of dicts with doc_hash, version, indexed_at, is_latest,
+            superseded_at, filename and chunk_count
+        """
+        results = self.collection.get(where={"source_path": source_path}, include=["metadatas"])
+        
+        versions: Dict[str, Dict] = {}
+        for meta in results.get("metadatas") or []:
             doc_hash = meta.get("doc_hash")
-            if doc_hash and doc_hash not in docs:
-                docs[doc_hash] = {
+            if doc_hash not in versions:
+                versions[doc_hash] = {
                     "doc_hash": doc_hash,
-                    "filename": meta.get("filename", "unknown")
+                    "version": meta.get("version", 1),
+                    "indexed_at": meta.get("indexed_at"),
+                    "is_latest": meta.get("is_latest", True),
+                    "superseded_at": meta.get("superseded_at"),
+                    "filename": meta.get("filename"),
+                    "chunk_count": 0
+                }
+            versions[doc_hash]["chunk_count"] += 1
+        
+        return sorted(versions.values(), key=lambda v: v["version"])
+    
+    def update_metadatas(self, ids: List[str], metadatas: List[Dict], removed_keys: Optional[List[str]] = None):
+        """
+        Replace chunks' metadata without re-embedding.
+
+        Args:
+            ids: Chunk ids
+            metadatas: New metadata per chunk
+            removed_keys: Keys to delete (Chroma merges updated metadata,
+                          so removals are sent as None)
+        """
+        self.check_writable("update")
+        for start in range(0, len(ids), METADATA_UPDATE_BATCH_SIZE):
+            batch = metadatas[start:start + METADATA_UPDATE_BATCH_SIZE]
+            if removed_keys:
+                batch = [{**{k: None for k in removed_keys if k not in meta}, **meta} for meta in batch]
+            self.collection.update(ids=ids[start:start + METADATA_UPDATE_BATCH_SIZE], metadatas=batch)
+    
+    def mark_superseded(self, doc_hash: str, superseded_at: datetime):
+        """Flag a document's chunks as an old version (is_latest=False)."""
+        self.check_writable("update")
+        results = self.collection.get(where={"doc_hash": doc_hash}, include=["metadatas"])
+        if not results.get("ids"):
+            return
+        
+        self.collection.update(
+            ids=results["ids"],
+            metadatas=[
+                {**meta, "is_latest": False, "superseded_at": superseded_at.isoformat()}
+                for meta in results["metadatas"]
+            ]
+        )
+    
+    def restore_chunks(self, chunks: Dict):
+        """
+        Re-add chunks previously read with get_all_chunks(include_embeddings=True).
+
+        Stored embeddings are reused, so nothing is re-embedded (chunks
+        without an "embeddings" list are embedded again).
+        
+        Raises:
+            EmbeddingModelMismatchError: If the embeddings (or the model, when
+                                         re-embedding) don't match the collection
+        """
+        if not chunks.get("ids"):
+            return
+        self.check_writable("restore")
+        embeddings = chunks.get("embeddings")
+        if embeddings is None:
+            self.check_embedding_dimension()
+            embeddings = self.embedder.encode(chunks["documents"]).tolist()
+        else:
+            self.check_embedding_dimension(len(embeddings[0]))
+        self.collection.add(
+            ids=chunks["ids"],
+            documents=chunks["documents"],
+            embeddings=embeddings,
+            metadatas=chunks["metadatas"]
+        )
+        logger.info(f"Restored {len(chunks['ids'])} chunks")
+    
+    def get_chunks(
+        self,
+        source_path: Optional[str] = None,
+        doc_hash: Optional[str] = None,
+        page: Optional[int] = None
+    ) -> List[Dict]:
+        """
+        Stored chunks exactly as indexed, in document order.
+        
+        Args:
+            source_path: Source path or filename of the document
+            doc_hash: Document hash (alternative to source_path)
+            page: Only chunks that start on this PDF page
+        
+        Returns:
+            List of dicts with chunk_id, content and metadata
+        """
+        conditions = []
+        if doc_hash:
+            conditions.append({"doc_hash": doc_hash})
+        if source_path:
+            conditions.append({"$or": [{"source_path": source_path}, {"filename": source_path}]})
+        if page is not None:
+            conditions.append({"page": page})
+        
+        if len(conditions) > 1:
+            where_filter = {"$and": conditions}
+        else:
+            where_filter = conditions[0] if conditions else None
+        
+        results = self.collection.get(where=where_filter, include=["documents", "metadatas"])
+        chunks = [
+            {"chunk_id": chunk_id, "content": content, "metadata": meta}
+            for chunk_id, content, meta in zip(
+                results.get("ids") or [], results.get("documents") or [], results.get("metadatas") or []
+            )
+        ]
+        chunks.sort(key=lambda c: (c["metadata"].get("doc_hash", ""), c["metadata"].get("chunk_index", 0)))
+        return chunks
+    
+    def get_chunk(self, chunk_id: str) -> Dict:
+        """
+        Raises:
+            KeyError: If no chunk has this id
+        """
+        results = self.collection.get(ids=[chunk_id], include=["documents", "metadatas"])
+        if not results.get("ids"):
+            raise KeyError(f"Chunk not found: {chunk_id}")
+        return {
+            "chunk_id": chunk_id,
+            "content": results["documents"][0],
+            "metadata": results["metadatas"][0]
+        }
+    
+    def update_chunk(self, chunk_id: str, content: str, metadata: Dict):
+        """Replace a chunk's text and metadata, re-embedding the text."""
+        self.check_writable("update")
+        self.check_embedding_dimension()
+        self.collection.update(
+            ids=[chunk_id],
+            documents=[content],
+            embeddings=self.embedder.encode([content]).tolist(),
+            metadatas=[metadata]
+        )
+    
+    def delete_chunks(self, ids: List[str]):
+        """Delete chunks by id."""
+        if ids:
+            self.check_writable("delete")
+            with self.write_lock:
+                self.collection.delete(ids=ids)
+            if self.delete_hook:
+                self.delete_hook(len(ids))
+    
+    def delete_document(self, doc_hash: str):
+        """Delete all chunks for a document."""
+        self.check_writable("delete")
+        with self.write_lock:
+            before = self.collection.count()
+            self.collection.delete(where={"doc_hash": doc_hash})
+            deleted = before - self.collection.count()
+        if self.delete_hook:
+            self.delete_hook(deleted)
+        logger.info(f"Deleted document: {doc_hash}")
+    
+    def get_document_count(self) -> int:
+        """Get total number of chunks in store."""
+        return self.collection.count()
+
+    def get_document_embeddings(self, doc_hash: str) -> List[List[float]]:
+        """Get the chunk embeddings stored for a single document."""
+        results = self.collection.get(
+            where={"doc_hash": doc_hash},
+            include=["embeddings"]
+        )
+
+        # ChromaDB may return a numpy array here - avoid truthiness checks
+        embeddings = results.get("embeddings")
+        if embeddings is None:
+            return []
+
+        return [list(e) for e in embeddings]
+
+    def get_all_chunks(self, include_embeddings: bool = False, doc_hash: Optional[str] = None) -> Dict:
+        """
+        Get every stored chunk (optionally only those of one document).
+
+        Returns:
+            Dict with parallel "ids", "documents", "metadatas" lists
+            (plus "embeddings" when include_embeddings is True)
+        """
+        include = ["documents", "metadatas"]
+        if include_embeddings:
+            include.append("embeddings")
+
+        if doc_hash:
+            results = self.collection.get(where={"doc_hash": doc_hash}, include=include)
+        else:
+            results = self.collection.get(include=include)
+
+        chunks = {
+            "ids": results.get("ids") or [],
+            "documents": results.get("documents") or [],
+            "metadatas": results.get("metadatas") or []
+        }
+        if include_embeddings:
+            embeddings = results.get("embeddings")
+            chunks["embeddings"] = [] if embeddings is None else [list(e) for e in embeddings]
+
+        return chunks
+    
+    def list_documents(self, latest_only: bool = False) -> List[Dict]:
+        """
+        List all unique documents in store.
+        
+        Args:
+            latest_only: Skip superseded versions of re-indexed documents
+        """
+        # Get all metadata
+        results = self.collection.get(include=["metadatas"])
+        
+        if not results["metadatas"]:
+            return []
+        
+        # Deduplicate by doc_hash
+        docs = {}
+        for meta in results["metadatas"]:
+            doc_hash = meta.get("doc_hash")
+            if latest_only and not meta.get("is_latest", True):
+                continue
+            if doc_hash and doc_hash not in docs:
+                docs[doc_hash] = {
+                    "doc_hash": doc_hash,
+                    "filename": meta.get("filename", "unknown"),
+                    "properties": {
+                        k[len(PROPERTY_PREFIX):]: v
+                        for k, v in meta.items() if k.startswith(PROPERTY_PREFIX)
+                    }
                 }
         
         return list(docs.values())
 
 
+class IndexCheckpoint:
+    """
+    Chunks and stores a PDF's pages while it is being extracted (salvage
+    mode), so a timeout keeps everything stored up to the last checkpoint
+    instead of only the extracted text.
+    
+    Pages are chunked every SALVAGE_CHECKPOINT_PAGES pages, so chunks
+    don't span checkpoints; their chunk_index and character offsets still
+    run across the whole document.
+    
+    Usage:
+        checkpoint = IndexCheckpoint(chunker, doc_hash, store=write_chunks)
+        extractor.extract(path, on_page=checkpoint.add_page)
+        remaining = checkpoint.flush()   # Tail after the last checkpoint, not stored
+    """
+    
+    def __init__(
+        self,
+        chunker: "SemanticChunker",
+        doc_hash: str,
+        store: Callable[[List[DocumentChunk]], None],
+        every_pages: int = SALVAGE_CHECKPOINT_PAGES
+    ):
+        """
+        Initialize checkpoint.
+        
+        Args:
+            chunker: Chunker for the document's text
+            doc_hash: Document the chunks belong to
+            store: Finishes and writes a checkpoint's chunks
+            every_pages: Pages between checkpoints
+        """
+        self.chunker = chunker
+        self.doc_hash = doc_hash
+        self.store = store
+        self.every_pages = max(1, every_pages)
+        self.pages: List[str] = []               # Text of every extracted page
+        self.stored: List[DocumentChunk] = []    # Chunks written so far
+        self._pending: List[str] = []
+        self._chunk_count = 0
+        self._chars = 0                          # Length of the text chunked so far
+    
+    @property
+    def text(self) -> str:
+        """Text of the pages extracted so far, as extract() would return it."""
+        return "\n\n".join(self.pages)
+    
+    def add_page(self, page_number: int, total_pages: int, page_text: str):
+        """extract() on_page callback: store a checkpoint every every_pages pages."""
+        if page_text:
+            self.pages.append(page_text)
+            self._pending.append(page_text)
+        if page_number % self.every_pages == 0 and page_number < total_pages and self._pending:
+            chunks = self.flush()
+            self.store(chunks)
+            self.stored.extend(chunks)
+    
+    def flush(self) -> List[DocumentChunk]:
+        """Chunk the pages not chunked yet (the caller stores them)."""
+        if not self._pending:
+            return []
+        offset = self._chars + 2 if self._chars else 0   # "\n\n" between pages
+        segment = "\n\n".join(self._pending)
+        self._pending = []
+        self._chars = offset + len(segment)
+        
+        chunks = self.chunker.chunk(segment, self.doc_hash)
+        for chunk in chunks:
+            chunk.chunk_index += self._chunk_count
+            chunk.metadata["start_char"] += offset
+            chunk.metadata["end_char"] += offset
+        self._chunk_count += len(chunks)
+        return chunks
+
+
 class DocumentProcessor:
     """
     Main interface for offline document processing.
@@ -620,8 +2714,22 @@ class DocumentProcessor:
         persist_directory: Optional[str] = None,
         tesseract_path: Optional[str] = None,
         embedding_model: str = "all-MiniLM-L6-v2",
+        collection_name: str = DEFAULT_COLLECTION,
         chunk_size: int = 1000,
-        chunk_overlap: int = 100
+        chunk_overlap: int = 100,
+        extraction_timeout: Optional[float] = None,
+        extraction_inactivity_timeout: Optional[float] = None,
+        salvage_partial: bool = True,
+        lock_retries: int = 4,
+        lock_retry_delay: float = 0.5,
+        snapshot_files: bool = False,
+        extract_tables: bool = False,
+        index_figures: bool = True,
+        image_describer: Optional[Callable[[bytes, str, str], Optional[str]]] = None,
+        footnote_mode: str = "metadata",
+        versioning: bool = False,
+        min_ocr_confidence: Optional[float] = None,
+        index_low_confidence_ocr: bool = False
     ):
         """
         Initialize document processor.
@@ -630,19 +2738,151 @@ class DocumentProcessor:
             persist_directory: Path for persistent vector storage
             tesseract_path: Path to tesseract executable for OCR
             embedding_model: sentence-transformers model for embeddings
+            collection_name: Collection documents are indexed into (must
+                             follow the collection_names rules)
             chunk_size: Target tokens per chunk
             chunk_overlap: Overlap tokens between chunks
+            extraction_timeout: Seconds allowed per document extraction (None = no limit)
+            extraction_inactivity_timeout: Seconds without PDF page progress; extraction
+                                           continues while pages keep finishing,
+                                           up to extraction_timeout (None = no limit)
+            salvage_partial: On timeout, index the pages extracted so far
+                             instead of failing the document; PDF chunks
+                             are stored every SALVAGE_CHECKPOINT_PAGES pages
+                             and a partial document is indexed again in
+                             full the next time it is ingested
+            lock_retries: Attempts to open a file locked by another program
+            lock_retry_delay: Initial delay between attempts (doubles each retry)
+            snapshot_files: Copy each file to a temp snapshot before reading it,
+                            so the original is only held open briefly
+            extract_tables: Index DOCX/PDF tables as structured table chunks
+                            instead of flattened text
+            index_figures: Index figure captions as dedicated figure chunks
+            image_describer: Optional callable(image_bytes, image_ext, caption)
+                             returning a description of a PDF figure image
+            footnote_mode: "off", "metadata" (link footnotes/endnotes to the
+                           chunks citing them) or "chunks" (also index each
+                           note as its own chunk)
+            versioning: Keep earlier versions of a changed file (flagged
+                        is_latest=False) instead of leaving them indistinguishable
+            min_ocr_confidence: Mean OCR word confidence (0-100) below which a
+                                page is flagged and, by default, not indexed
+            index_low_confidence_ocr: Index flagged pages anyway (their chunks
+                                      get ocr_low_confidence=True)
         """
-        self.extractor = DocumentExtractor(tesseract_path=tesseract_path)
+        self.extractor = DocumentExtractor(
+            tesseract_path=tesseract_path,
+            min_ocr_confidence=min_ocr_confidence,
+            index_low_confidence_ocr=index_low_confidence_ocr
+        )
         self.chunker = SemanticChunker(
             chunk_size=chunk_size,
             chunk_overlap=chunk_overlap
         )
         self.vector_store = LocalVectorStore(
             persist_directory=persist_directory,
-            embedding_model=embedding_model
+            embedding_model=embedding_model,
+            collection_name=validate_collection_name(collection_name)
         )
         self.processed_docs: Dict[str, ProcessedDocument] = {}
+        self.extraction_timeout = extraction_timeout
+        self.extraction_inactivity_timeout = extraction_inactivity_timeout
+        self.salvage_partial = salvage_partial
+        self.lock_retries = max(1, lock_retries)
+        self.lock_retry_delay = lock_retry_delay
+        self.snapshot_files = snapshot_files
+        self.extract_tables = extract_tables
+        self.index_figures = index_figures
+        self.image_describer = image_describer
+        if footnote_mode not in ("off", "metadata", "chunks"):
+            raise ValueError(f"Invalid footnote_mode: {footnote_mode}")
+        self.footnote_mode = footnote_mode
+        self.versioning = versioning
+        
+        # Index freshness, reported with query results (see get_index_state)
+        self._state_lock = threading.Lock()
+        self._ingests_in_progress: Dict[str, str] = {}   # source_path -> started_at
+        self.last_indexed_at: Optional[datetime] = None
+        
+        # (source_path, size, mtime) -> content hash, for staleness checks
+        self._source_hashes: Dict[Tuple[str, int, float], str] = {}
+        
+        # Optional audit_log.AuditLog recording manual chunk edits
+        self.audit_log = None
+        
+        # Optional keyword_scoring.RetrievalConfigStore persisting set_retrieval_config
+        self.retrieval_configs = None
+        
+        # Optional file_type_handlers.FileTypeHandlerRegistry (skip / force OCR per extension)
+        self.file_type_handlers = None
+        
+        # Optional document_passwords.DocumentPasswordStore, consulted for
+        # encrypted documents processed without a password
+        self.password_store = None
+        
+        # Optional network_shares.ShareCredentialStore, for connecting
+        # network shares before their files are read
+        self.share_credentials = None
+        
+        # Optional processing_presets.PresetStore, for the current settings of
+        # the preset a source was indexed with (see get_source_stats)
+        self.preset_store = None
+        
+        # Description, color and icon per collection (in memory unless replaced
+        # by a persisted collection_appearance.CollectionAppearanceStore)
+        from collection_appearance import CollectionAppearanceStore
+        self.collection_appearances = CollectionAppearanceStore()
+        
+        # Read-only flags (legal holds) per collection, in memory unless
+        # replaced by a persisted collection_holds.CollectionHoldStore;
+        # the vector store checks them before every write
+        from collection_holds import CollectionHoldStore
+        self.collection_holds = CollectionHoldStore()
+        self.vector_store.write_guard = self.check_writable
+    
+    def supported_extensions(self) -> set:
+        """Extensions this processor accepts (file types set to skip are left out)."""
+        if self.file_type_handlers:
+            return self.file_type_handlers.enabled_extensions()
+        return DocumentExtractor.get_supported_extensions()
+    
+    def _acquire_source(self, path: Path) -> Path:
+        """
+        Get a readable path for a document, retrying while it is locked.
+        
+        Returns the original path, or a temp snapshot copy when
+        snapshot_files is enabled (caller removes it).
+        
+        Raises:
+            FileLockedError: If the file is still locked after all retries
+        """
+        delay = self.lock_retry_delay
+        
+        for attempt in range(1, self.lock_retries + 1):
+            try:
+                if not self.snapshot_files:
+                    with open(path, 'rb'):
+                        return path
+                
+                fd, snapshot = tempfile.mkstemp(suffix=path.suffix, prefix="jdp_snapshot_")
+                os.close(fd)
+                try:
+                    shutil.copyfile(path, snapshot)
+                except OSError:
+                    os.unlink(snapshot)
+                    raise
+                return Path(snapshot)
+            
+            except OSError as e:
+                if not is_sharing_violation(e):
+                    raise
+                if attempt == self.lock_retries:
+                    break
+                logger.info(f"{path.name} is locked, retrying in {delay:.1f}s ({attempt}/{self.lock_retries})")
+                time.sleep(delay)
+                delay *= 2
+        
+        raise FileLockedError(path, self.lock_retries)
     
     def _compute_hash(self, file_path: Path) -> str:
         """Compute unique hash for file content."""
@@ -674,65 +2914,360 @@ class DocumentProcessor:
         else:
             return DocumentType.UNKNOWN
     
-    def ingest(self, file_path: Union[str, Path], force: bool = False) -> ProcessedDocument:
+    def ingest(
+        self,
+        file_path: Union[str, Path],
+        force: bool = False,
+        preset: Optional[ProcessingPreset] = None,
+        source_path: Optional[str] = None,
+        languages: Optional[List[str]] = None,
+        ocr_mode: Optional[str] = None,
+        dry_run: bool = False,
+        password: Optional[str] = None
+    ) -> ProcessedDocument:
         """
         Ingest and index a document.
         
         Args:
             file_path: Path to document
             force: Re-process even if already indexed
+            preset: Chunking/OCR settings to use instead of the processor defaults
+            source_path: Identity of the document across versions and the
+                         recorded filename (defaults to file_path; uploads
+                         pass upload_source_path for their temp file)
+            languages: OCR languages for scanned pages/images, e.g. ["eng", "deu"]
+                       (None = Tesseract default)
+            ocr_mode: "auto" (OCR only pages lacking a text layer), "force"
+                      (OCR every page) or "off"; None = "auto", or "off"
+                      if the preset disables OCR
+            dry_run: Extract and chunk only; nothing is embedded, stored or
+                     described by the image describer
+            password: Password of an encrypted PDF/DOCX/XLSX (None = the one
+                      stored for source_path in password_store, if any)
             
         Returns:
-            ProcessedDocument with metadata including OCR info
+            ProcessedDocument with metadata including OCR info (for an
+            already indexed document, its existing record)
+        
+        Raises:
+            ValueError: If ocr_mode is invalid, a requested OCR language
+                        pack isn't installed or the file type is set to skip
+            PasswordRequiredError: If the document is encrypted and no password is known
+            WrongPasswordError: If the password is wrong
+            CollectionReadOnlyError: If the collection is read-only (unless dry_run)
+            ShareConnectionError: If the file is on a network share that
+                                  refuses its stored credentials
         """
+        self.connect_share(file_path)
         path = Path(file_path).resolve()
         
         if not path.exists():
             raise FileNotFoundError(f"Document not found: {path}")
+        if not dry_run:
+            self.check_writable("process")
         
-        doc_hash = self._compute_hash(path)
+        handler = self.file_type_handlers.get_handler(path.suffix) if self.file_type_handlers else None
+        if handler == "skip":
+            raise ValueError(f"File type {path.suffix.lower()} is disabled (file type handler: skip)")
+        if handler == "ocr-forced" and ocr_mode in (None, "auto"):
+            ocr_mode = "force"
         
-        # Skip if already processed (unless forced)
-        if not force and doc_hash in self.processed_docs:
-            logger.info(f"Document already indexed: {path.name}")
-            return self.processed_docs[doc_hash]
+        if preset and preset.embedding_model and preset.embedding_model != self.vector_store.embedding_model:
+            raise ValueError(
+                f"Preset '{preset.name}' expects embedding model {preset.embedding_model}, "
+                f"but the store uses {self.vector_store.embedding_model}"
+            )
         
-        logger.info(f"Processing: {path.name}")
+        if ocr_mode is None:
+            ocr_mode = "off" if preset and not preset.ocr_enabled else "auto"
+        if ocr_mode not in OCR_MODES:
+            raise ValueError(f"Invalid ocr_mode: {ocr_mode} (expected one of {', '.join(OCR_MODES)})")
+        ocr_languages = self.extractor.check_ocr_languages(languages) if languages else None
         
-        # Extract text (now returns OCR metadata)
-        raw_text, ocr_used, ocr_pages = self.extractor.extract(path)
+        source_path = source_path or str(path)
+        if not dry_run:
+            with self._state_lock:
+                self._ingests_in_progress[source_path] = datetime.now().isoformat()
         
-        if ocr_used:
-            logger.info(f"OCR applied to {path.name}: {ocr_pages} page(s)")
+        try:
+            source = self._acquire_source(path)
+            readable = source
+            try:
+                if self.extractor.requires_password(source):
+                    if password is None and self.password_store is not None:
+                        password = (self.password_store.get(source_path)
+                                    or self.password_store.get(Path(source_path).name))
+                    readable = self.extractor.decrypt(source, password, Path(source_path).name)
+                return self._ingest_source(
+                    path, source, force, preset, source_path, ocr_languages, ocr_mode, dry_run, readable
+                )
+            finally:
+                for temp in {source, readable} - {path}:
+                    self.extractor.release_file(temp)
+        finally:
+            if not dry_run:
+                with self._state_lock:
+                    self._ingests_in_progress.pop(source_path, None)
+    
+    def _document_date(self, path: Path, source: Path, properties: Dict[str, str]) -> Optional[datetime]:
+        """Date a document is about: its modified/created property, else the file's mtime."""
+        for key in DOCUMENT_DATE_PROPERTIES:
+            date = parse_document_date(properties.get(key, ""))
+            if date is not None:
+                return date
+        for candidate in (path, source):
+            try:
+                return datetime.fromtimestamp(candidate.stat().st_mtime)
+            except OSError:
+                continue
+        return None
+    
+    def _ingest_source(
+        self,
+        path: Path,
+        source: Path,
+        force: bool,
+        preset: Optional[ProcessingPreset] = None,
+        source_path: Optional[str] = None,
+        ocr_languages: Optional[str] = None,
+        ocr_mode: str = "auto",
+        dry_run: bool = False,
+        readable: Optional[Path] = None
+    ) -> ProcessedDocument:
+        """
+        Index a document read from source (the original path or a snapshot
+        of it); content is extracted from readable, its decrypted copy if
+        source is encrypted.
+        """
+        source_path = source_path or str(path)
+        readable = readable or source
+        filename = Path(source_path).name
+        doc_hash = self._compute_hash(source)
         
-        if not raw_text.strip():
-            logger.warning(f"No text extracted from: {path.name}")
+        # Skip if already processed (unless forced); a document salvaged
+        # after a timeout is indexed again in full
+        existing = self.processed_docs.get(doc_hash)
+        if existing is not None and not force and not existing.partial:
+            logger.info(f"Document already indexed: {filename}")
+            return existing
+        if existing is not None and existing.partial:
+            logger.info(f"Completing partially indexed document: {filename}")
         
-        # Chunk
-        chunks = self.chunker.chunk(raw_text, doc_hash)
+        logger.info(f"Processing: {filename}")
         
-        # Create record with OCR metadata
-        processed = ProcessedDocument(
-            doc_hash=doc_hash,
-            filename=path.name,
-            file_path=str(path),
-            doc_type=self._detect_type(path),
-            chunks=chunks,
-            total_tokens_estimate=int(len(raw_text) / 4),
-            ocr_used=ocr_used,
-            ocr_pages=ocr_pages
+        deadline = (
+            time.monotonic() + self.extraction_timeout
+            if self.extraction_timeout else None
         )
+        partial = False
+        pages_completed = total_pages = 0
+        table_mode = self.extract_tables
+        if preset and preset.extract_tables is not None:
+            table_mode = preset.extract_tables
+        
+        chunker = self.chunker
+        if preset:
+            chunker = SemanticChunker(
+                chunk_size=preset.chunk_size,
+                chunk_overlap=preset.chunk_overlap,
+                respect_sentences=preset.smart_mode
+            )
+        
+        # Document-level metadata is settled before extraction, since salvage
+        # mode stores chunks while the document is still being extracted
+        properties = self.extractor.extract_properties(readable)
+        notes = self.extractor.extract_notes(readable) if self.footnote_mode != "off" else []
+        
+        # Version bookkeeping: a changed file gets the next version number
+        now = datetime.now()
+        document_date = self._document_date(path, source, properties) or now
+        # Provenance: doc_hash is the content checksum; size and mtime let
+        # query-time staleness checks skip rehashing unchanged files
+        try:
+            stat = path.stat()
+            provenance = {"source_size": stat.st_size, "source_mtime": stat.st_mtime}
+        except OSError:
+            provenance = {}
+        previous = [
+            v for v in self.vector_store.get_document_versions(source_path)
+            if v["doc_hash"] != doc_hash
+        ]
+        version = max((v["version"] for v in previous), default=0) + 1
+        settings = {"settings_fingerprint": self.settings_fingerprint(preset)}
+        if preset:
+            settings["preset"] = preset.name
+        
+        low_confidence_pages: List[Dict] = []
+        
+        def finish_text_chunks(chunks: List[DocumentChunk]):
+            # Flag chunks from low-confidence OCR pages that were indexed anyway
+            flagged_pages = {p["page"] for p in low_confidence_pages if not p["excluded"]}
+            for chunk in chunks:
+                if chunk.metadata.get("page") in flagged_pages:
+                    chunk.metadata["ocr_low_confidence"] = True
+            if notes:
+                attach_notes(chunks, notes)
+        
+        def finish_chunks(chunks: List[DocumentChunk]):
+            # Document properties go on every chunk so queries can filter on them
+            for chunk in chunks:
+                chunk.metadata.update({f"{PROPERTY_PREFIX}{k}": v for k, v in properties.items()})
+                chunk.metadata.update({
+                    "source_path": source_path,
+                    "version": version,
+                    "is_latest": True,
+                    "indexed_at": now.isoformat(),
+                    "document_date": document_date.isoformat(),
+                    **settings,
+                    **provenance
+                })
+        
+        stored_any = False
+        
+        def store(chunks: List[DocumentChunk], supersede: Sequence[str] = ()):
+            nonlocal stored_any
+            if not stored_any:
+                # Replace what an earlier (partial or forced) run stored for this content
+                if self.vector_store.collection.get(where={"doc_hash": doc_hash}, limit=1, include=[])["ids"]:
+                    self.vector_store.delete_document(doc_hash)
+                stored_any = True
+            self.vector_store.add_chunks(chunks, filename, supersede=supersede, superseded_at=now)
+            # Only stored chunks make results fresher; failed ingests don't count
+            with self._state_lock:
+                self.last_indexed_at = datetime.now()
+        
+        def store_checkpoint(chunks: List[DocumentChunk]):
+            finish_text_chunks(chunks)
+            finish_chunks(chunks)
+            store(chunks)
+            logger.info(f"{filename}: checkpoint stored ({len(checkpoint.pages)} pages with text)")
+        
+        # Salvage mode stores PDF pages as they are extracted
+        checkpoint = None
+        if self.salvage_partial and not dry_run and readable.suffix.lower() == ".pdf":
+            checkpoint = IndexCheckpoint(chunker, doc_hash, store_checkpoint)
+        
+        try:
+            # Extract text (now returns OCR metadata)
+            try:
+                raw_text, ocr_used, ocr_pages = self.extractor.extract(
+                    readable,
+                    deadline=deadline,
+                    ocr_enabled=ocr_mode != "off",
+                    flatten_tables=not table_mode,
+                    note_markers=self.footnote_mode != "off",
+                    ocr_languages=ocr_languages,
+                    force_ocr=ocr_mode == "force",
+                    ocr_report=low_confidence_pages,
+                    inactivity_timeout=self.extraction_inactivity_timeout,
+                    on_page=checkpoint.add_page if checkpoint else None
+                )
+            except ExtractionTimeoutError as e:
+                if not self.salvage_partial:
+                    raise
+                logger.warning(f"{e} - indexing partial content")
+                raw_text, ocr_used, ocr_pages = e.partial_text, e.ocr_used, e.ocr_pages
+                if checkpoint:
+                    raw_text = checkpoint.text
+                partial = True
+                pages_completed, total_pages = e.pages_completed, e.total_pages
+            
+            ocr_page_numbers = [int(n) for n in OCR_PAGE_PATTERN.findall(raw_text)]
+            if ocr_used:
+                logger.info(f"OCR applied to {filename}: {ocr_pages} page(s)")
+            if low_confidence_pages:
+                logger.warning(
+                    f"{filename}: {len(low_confidence_pages)} page(s) below OCR confidence "
+                    f"{self.extractor.min_ocr_confidence:.0f} - review them in the processing report"
+                )
+            
+            if not raw_text.strip():
+                logger.warning(f"No text extracted from: {filename}")
+            
+            # Chunk (with checkpoints, only the pages after the last one)
+            stored = checkpoint.stored if checkpoint else []
+            new_chunks = checkpoint.flush() if checkpoint else chunker.chunk(raw_text, doc_hash)
+            finish_text_chunks(new_chunks)
+            chunks = stored + new_chunks
+            
+            if self.footnote_mode == "chunks":
+                new_chunks.extend(chunker.chunk_notes(notes, doc_hash, start_index=len(chunks)))
+                chunks = stored + new_chunks
+            
+            # Tables become their own structured chunks (skipped after a timeout)
+            if table_mode and not partial:
+                tables = self.extractor.extract_tables(readable)
+                new_chunks.extend(chunker.chunk_tables(tables, doc_hash, start_index=len(chunks)))
+                chunks = stored + new_chunks
+            
+            if self.index_figures and not partial:
+                figures = self.extractor.extract_figures(readable, include_images=self.image_describer is not None)
+                for figure in figures:
+                    if figure.image is None or dry_run:
+                        continue
+                    try:
+                        figure.description = self.image_describer(figure.image, figure.image_ext, figure.caption)
+                    except Exception as e:
+                        logger.warning(f"Could not describe {figure.label} in {filename}: {e}")
+                new_chunks.extend(chunker.chunk_figures(figures, doc_hash, start_index=len(chunks)))
+                chunks = stored + new_chunks
+            
+            finish_chunks(new_chunks)
+            
+            # Create record with OCR metadata
+            processed = ProcessedDocument(
+                doc_hash=doc_hash,
+                filename=filename,
+                file_path=str(path),
+                doc_type=self._detect_type(path),
+                chunks=chunks,
+                total_tokens_estimate=int(len(raw_text) / 4),
+                ocr_used=ocr_used,
+                ocr_pages=ocr_pages,
+                ocr_page_numbers=ocr_page_numbers,
+                ocr_mode=ocr_mode,
+                ocr_languages=ocr_languages,
+                low_confidence_pages=low_confidence_pages,
+                partial=partial,
+                pages_completed=pages_completed,
+                total_pages=total_pages,
+                preset=preset.name if preset else None,
+                properties=properties,
+                source_path=source_path,
+                version=version,
+                extracted_at=now
+            )
+            
+            if dry_run:
+                logger.info(f"Dry run {filename}: {len(chunks)} chunks, ~{processed.total_tokens_estimate} tokens")
+                return processed
+            
+            # Store in vector DB; older versions are superseded in the same write
+            superseded = [old["doc_hash"] for old in previous if old["is_latest"]] if self.versioning else []
+            if new_chunks or superseded:
+                store(new_chunks, supersede=superseded)
+        except Exception:
+            # Don't leave the checkpoints of a document that failed for other reasons
+            if stored_any:
+                try:
+                    self.vector_store.delete_document(doc_hash)
+                except Exception as e:
+                    logger.warning(f"Could not remove checkpoints of {filename}: {e}")
+            raise
         
-        # Store in vector DB
-        if chunks:
-            self.vector_store.add_chunks(chunks, path.name)
+        for old_hash in superseded:
+            if old_hash in self.processed_docs:
+                self.processed_docs[old_hash].is_latest = False
+        if self.versioning and previous:
+            logger.info(f"{filename}: indexed as version {version}")
         
         self.processed_docs[doc_hash] = processed
         
         ocr_info = f", OCR: {ocr_pages} pages" if ocr_used else ""
+        partial_info = f", PARTIAL: {pages_completed}/{total_pages} pages" if partial else ""
         logger.info(
-            f"Indexed {path.name}: {len(chunks)} chunks, "
-            f"~{processed.total_tokens_estimate} tokens{ocr_info}"
+            f"Indexed {filename}: {len(chunks)} chunks, "
+            f"~{processed.total_tokens_estimate} tokens{ocr_info}{partial_info}"
         )
         
         return processed
@@ -741,36 +3276,39 @@ class DocumentProcessor:
         self,
         directory: Union[str, Path],
         recursive: bool = True,
-        extensions: Optional[set] = None
+        extensions: Optional[set] = None,
+        skip_duplicates: bool = True
     ) -> List[ProcessedDocument]:
         """
         Ingest all supported documents in a directory.
-        
+
         Args:
             directory: Directory path
             recursive: Include subdirectories
             extensions: Filter to specific extensions (None = all supported)
-            
+            skip_duplicates: Index only one copy of files with identical content
+
         Returns:
             List of ProcessedDocument objects
         """
-        dir_path = Path(directory)
-        if not dir_path.is_dir():
-            raise NotADirectoryError(f"Not a directory: {directory}")
-        
-        supported = extensions or DocumentExtractor.get_supported_extensions()
+        from directory_scanner import scan_directory
+
+        self.connect_share(directory)
+        scan = scan_directory(
+            directory,
+            recursive=recursive,
+            extensions=extensions or self.supported_extensions(),
+            detect_duplicates=skip_duplicates
+        )
         results = []
-        
-        pattern = "**/*" if recursive else "*"
-        
-        for file_path in dir_path.glob(pattern):
-            if file_path.is_file() and file_path.suffix.lower() in supported:
-                try:
-                    result = self.ingest(file_path)
-                    results.append(result)
-                except Exception as e:
-                    logger.error(f"Failed to process {file_path}: {e}")
-        
+
+        for scanned in scan.unique_files:
+            try:
+                result = self.ingest(scanned.path)
+                results.append(result)
+            except Exception as e:
+                logger.error(f"Failed to process {scanned.path}: {e}")
+
         return results
     
     def get_context(
@@ -778,7 +3316,16 @@ class DocumentProcessor:
         query: str,
         n_chunks: int = 5,
         max_tokens: int = 8000,
-        doc_hash: Optional[str] = None
+        doc_hash: Optional[str] = None,
+        properties: Optional[Dict[str, str]] = None,
+        heading: Optional[str] = None,
+        include_old_versions: bool = False,
+        sources: str = "both",
+        recency_boost: Optional[float] = None,
+        exclude_terms: Optional[List[str]] = None,
+        exclude_filters: Optional[Dict[str, str]] = None,
+        explain: Optional[Dict] = None,
+        verify_sources: bool = False
     ) -> str:
         """
         Retrieve relevant context for LLM prompt injection.
@@ -788,16 +3335,40 @@ class DocumentProcessor:
             n_chunks: Max chunks to retrieve
             max_tokens: Token budget for context
             doc_hash: Optionally filter to specific document
+            properties: Optionally filter by document properties (exact match)
+            heading: Optionally filter to sections whose heading path contains this text
+            include_old_versions: Also use chunks of superseded document versions
+            sources: "documents", "chat" (indexed Jan threads) or "both"
+            recency_boost: Optional half-life in days favoring recent content
+            exclude_terms: Leave out chunks containing these words/phrases
+            exclude_filters: Leave out chunks with these metadata/property values
+            explain: Optional dict filled with retrieval diagnostics (see
+                     LocalVectorStore.query) plus how many chunks fit the budget
+            verify_sources: Mark chunks whose source file changed since indexing
             
         Returns:
             Formatted context string ready for prompt injection
+            
+        Raises:
+            ValueError: If sources or recency_boost is invalid
         """
         results = self.vector_store.query(
             query,
             n_results=n_chunks,
-            filter_doc_hash=doc_hash
+            filter_doc_hash=doc_hash,
+            filter_properties=properties,
+            filter_heading=heading,
+            include_old_versions=include_old_versions,
+            sources=sources,
+            recency_boost=recency_boost,
+            exclude_terms=exclude_terms,
+            exclude_filters=exclude_filters,
+            explain=explain
         )
         
+        if explain is not None:
+            explain["context_chunks"] = 0
+            explain["max_context_tokens"] = max_tokens
         if not results:
             return ""
         
@@ -811,38 +3382,750 @@ class DocumentProcessor:
                 break
             
             # Format with source attribution
-            source = r["metadata"].get("filename", "unknown")
+            meta = r["metadata"]
             relevance = r.get("relevance_score", 0)
+            if r["source_type"] == "chat":
+                source = f"Chat: {meta.get('thread_title', 'unknown')}"
+                section = f" | {meta.get('role', 'unknown')}, {meta.get('timestamp') or 'undated'}"
+            else:
+                source = meta.get("filename", "unknown")
+                section = f" | Section: {r['heading_path']}" if r.get("heading_path") else ""
+            if "recency_boost" in r:
+                section += f" | Dated: {(r['document_date'] or 'undated')[:10]} | Recency boost: {r['recency_boost']:.2f}"
+            if verify_sources and r["source_type"] == "document":
+                state = self.check_source(meta)
+                if state["stale"]:
+                    section += f" | Stale: source {state['reason']} since indexing"
             
             context_parts.append(
-                f"[Source: {source} | Relevance: {relevance:.2f}]\n{r['content']}"
+                f"[Source: {source}{section} | Relevance: {relevance:.2f}]\n{r['content']}"
             )
             token_estimate += chunk_tokens
         
+        if explain is not None:
+            explain["context_chunks"] = len(context_parts)
+            explain["context_tokens_estimate"] = int(token_estimate)
         return "\n\n---\n\n".join(context_parts)
     
+    def search(
+        self,
+        query: str,
+        n_results: int = 5,
+        doc_hash: Optional[str] = None,
+        heading: Optional[str] = None,
+        sources: str = "both",
+        recency_boost: Optional[float] = None,
+        exclude_terms: Optional[List[str]] = None,
+        exclude_filters: Optional[Dict[str, str]] = None,
+        explain: Optional[Dict] = None,
+        verify_sources: bool = False
+    ) -> List[Dict]:
+        """
+        Ranked matches with highlighted snippets (for search result lists).
+        
+        Returns:
+            List of dicts with chunk_id, source_type, filename, heading_path,
+            page, relevance_score, snippet (keyword/vector scores when
+            hybrid; document_date and recency_boost when recency_boost is
+            set; stale and stale_reason for documents when verify_sources
+            is set; thread_title, role and timestamp for chat matches)
+            
+        Raises:
+            ValueError: If sources or recency_boost is invalid
+        """
+        pages = self.iter_search_pages(
+            query,
+            page_size=max(1, n_results),
+            n_results=n_results,
+            doc_hash=doc_hash,
+            heading=heading,
+            sources=sources,
+            recency_boost=recency_boost,
+            exclude_terms=exclude_terms,
+            exclude_filters=exclude_filters,
+            explain=explain,
+            verify_sources=verify_sources
+        )
+        return [result for page in pages for result in page]
+    
+    def iter_search_pages(
+        self,
+        query: str,
+        page_size: int = 50,
+        n_results: int = 5,
+        doc_hash: Optional[str] = None,
+        heading: Optional[str] = None,
+        sources: str = "both",
+        recency_boost: Optional[float] = None,
+        exclude_terms: Optional[List[str]] = None,
+        exclude_filters: Optional[Dict[str, str]] = None,
+        explain: Optional[Dict] = None,
+        verify_sources: bool = False
+    ) -> Iterator[List[Dict]]:
+        """
+        search() results in pages of page_size, for large n_results.
+        
+        Retrieval runs once, when the first page is requested; the
+        per-result work (formatting and, with verify_sources, checking the
+        source files) is done a page at a time, so a caller can send each
+        page as soon as it is ready.
+        
+        Raises:
+            ValueError: If page_size is not positive, or as for search()
+        """
+        if page_size < 1:
+            raise ValueError("page_size must be at least 1")
+        matches = self.vector_store.query(
+            query,
+            n_results=n_results,
+            filter_doc_hash=doc_hash,
+            filter_heading=heading,
+            sources=sources,
+            recency_boost=recency_boost,
+            exclude_terms=exclude_terms,
+            exclude_filters=exclude_filters,
+            explain=explain
+        )
+        for start in range(0, len(matches), page_size):
+            yield [self._search_result(m, verify_sources) for m in matches[start:start + page_size]]
+    
+    def search_batch(
+        self,
+        queries: List[str],
+        n_results: int = 5,
+        doc_hash: Optional[str] = None,
+        heading: Optional[str] = None,
+        sources: str = "both",
+        recency_boost: Optional[float] = None,
+        exclude_terms: Optional[List[str]] = None,
+        exclude_filters: Optional[Dict[str, str]] = None,
+        verify_sources: bool = False
+    ) -> List[Dict]:
+        """
+        search() for many queries at once (e.g. an evaluation set), with
+        the queries embedded together (see LocalVectorStore.query_batch).
+        
+        Returns:
+            List of {query, results, total}, in the order of queries
+            
+        Raises:
+            ValueError: If there are no queries or too many, or as for search()
+        """
+        matches_per_query = self.vector_store.query_batch(
+            queries,
+            n_results=n_results,
+            filter_doc_hash=doc_hash,
+            filter_heading=heading,
+            sources=sources,
+            recency_boost=recency_boost,
+            exclude_terms=exclude_terms,
+            exclude_filters=exclude_filters
+        )
+        batch = []
+        for query, matches in zip(queries, matches_per_query):
+            results = [self._search_result(m, verify_sources) for m in matches]
+            batch.append({"query": query, "results": results, "total": len(results)})
+        return batch
+    
+    def _search_result(self, m: Dict, verify_sources: bool) -> Dict:
+        """A vector store match as a search result."""
+        meta = m["metadata"]
+        result = {
+            "chunk_id": f"{meta.get('doc_hash')}_{meta.get('chunk_index')}",
+            "source_type": m["source_type"],
+            "filename": meta.get("filename"),
+            "heading_path": m["heading_path"],
+            "page": meta.get("page"),
+            "relevance_score": round(m["relevance_score"], 4),
+            "snippet": m["snippet"]
+        }
+        if "keyword_score" in m:
+            result["keyword_score"] = m["keyword_score"]
+            result["vector_score"] = round(m["vector_score"], 4)
+        if "recency_boost" in m:
+            result["document_date"] = m["document_date"]
+            result["recency_boost"] = m["recency_boost"]
+        if verify_sources and m["source_type"] == "document":
+            state = self.check_source(meta)
+            result["stale"] = state["stale"]
+            result["stale_reason"] = state["reason"]
+        if m["source_type"] == "chat":
+            for key in ("thread_title", "role", "timestamp"):
+                result[key] = meta.get(key)
+        return result
+    
     def remove_document(self, file_path: Union[str, Path]):
         """Remove a document from the index by file path."""
         path = Path(file_path).resolve()
         doc_hash = self._compute_hash(path)
         self.remove_document_by_hash(doc_hash)
 
+    def has_document(self, doc_hash: str) -> bool:
+        """Whether the index holds the document (also after a restart, when processed_docs is empty)."""
+        if doc_hash in self.processed_docs:
+            return True
+        return bool(self.vector_store.collection.get(where={"doc_hash": doc_hash}, limit=1, include=[])["ids"])
+    
+    def upload_source_path(
+        self,
+        file_path: Union[str, Path],
+        filename: str,
+        client_path: Optional[str] = None,
+        version_of: Optional[str] = None
+    ) -> str:
+        """
+        Identity an uploaded file is indexed and versioned under (see ingest).
+        
+        Uploads only become versions of an indexed document when the client
+        says so: version_of (the doc_hash of the document the upload
+        replaces) takes that document's identity, and client_path (the
+        file's path on the client) is used as given. Otherwise the upload
+        gets an identity of its own from its content, still ending in
+        filename, so unrelated files that share a name aren't versions of
+        each other.
+        
+        Raises:
+            KeyError: version_of isn't an indexed document
+        """
+        if version_of:
+            record = self.processed_docs.get(version_of)
+            if record is not None and record.source_path:
+                return record.source_path
+            metadatas = self.vector_store.collection.get(
+                where={"doc_hash": version_of}, limit=1, include=["metadatas"]
+            )["metadatas"]
+            if not metadatas:
+                raise KeyError(f"Document not found: {version_of}")
+            return metadatas[0].get("source_path") or metadatas[0].get("filename")
+        if client_path:
+            return client_path
+        return f"upload:{self._compute_hash(Path(file_path))}/{filename}"
+    
     def remove_document_by_hash(self, doc_hash: str):
         """Remove a document from the index by its hash."""
-        if doc_hash in self.processed_docs:
-            filename = self.processed_docs[doc_hash].filename
-            self.vector_store.delete_document(doc_hash)
-            del self.processed_docs[doc_hash]
-            logger.info(f"Removed: {filename} ({doc_hash})")
+        record = self.processed_docs.pop(doc_hash, None)
+        self.vector_store.delete_document(doc_hash)
+        logger.info(f"Removed: {record.filename if record else doc_hash} ({doc_hash})")
     
     def list_documents(self) -> List[Dict]:
         """List all indexed documents."""
         return [doc.to_dict() for doc in self.processed_docs.values()]
     
-    def get_stats(self) -> Dict:
-        """Get processor statistics."""
+    def get_chunks(
+        self,
+        source_path: Optional[str] = None,
+        doc_hash: Optional[str] = None,
+        page: Optional[int] = None
+    ) -> List[Dict]:
+        """Stored chunks of a document (see LocalVectorStore.get_chunks)."""
+        if source_path and Path(source_path).exists():
+            source_path = str(Path(source_path).resolve())
+        return self.vector_store.get_chunks(source_path=source_path, doc_hash=doc_hash, page=page)
+    
+    def _find_record_chunk(self, metadata: Dict) -> Optional[DocumentChunk]:
+        record = self.processed_docs.get(metadata.get("doc_hash"))
+        if record is None:
+            return None
+        return next((c for c in record.chunks if c.chunk_index == metadata.get("chunk_index")), None)
+    
+    def update_chunk_text(self, chunk_id: str, new_text: str) -> Dict:
+        """
+        Replace a chunk's text (e.g. fixing OCR garbage) and re-embed it.
+        
+        Raises:
+            KeyError: If the chunk does not exist
+            ValueError: If the text is empty or the chunk is a table
+        """
+        new_text = new_text.strip()
+        if not new_text:
+            raise ValueError("Chunk text cannot be empty (delete the chunk instead)")
+        
+        chunk = self.vector_store.get_chunk(chunk_id)
+        metadata = chunk["metadata"]
+        if metadata.get("chunk_type") == "table":
+            raise ValueError("Table chunks hold structured JSON and can't be edited as text")
+        
+        metadata = {
+            **metadata,
+            "char_count": len(new_text),
+            "edited_at": datetime.now().isoformat()
+        }
+        self.vector_store.update_chunk(chunk_id, new_text, metadata)
+        
+        record_chunk = self._find_record_chunk(metadata)
+        if record_chunk is not None:
+            record_chunk.content = new_text
+        
+        if self.audit_log:
+            self.audit_log.record("chunk_updated", chunk_id, {
+                "filename": metadata.get("filename"),
+                "old_text": chunk["content"],
+                "new_text": new_text
+            })
+        
+        logger.info(f"Updated chunk {chunk_id}")
+        return {"chunk_id": chunk_id, "content": new_text, "metadata": metadata}
+    
+    def update_metadata_by_filter(self, collection: Optional[str], filters: Dict[str, str], patch: Dict) -> Dict:
+        """
+        Apply a JSON merge patch to the metadata of every matching chunk,
+        e.g. retagging documents or fixing a wrong client name, without
+        re-indexing.
+        
+        Args:
+            collection: Collection name (None = the store's collection)
+            filters: Chunks to update: metadata keys / document properties
+                     and the values they must have, e.g. {"client": "Acme"}
+            patch: Keys to set; null removes a key
+        
+        Returns:
+            {"matched": chunks, "updated": chunks changed, "documents": documents touched}
+        
+        Raises:
+            KeyError: If collection is not the store's collection
+            ValueError: If filters or patch is empty, or the patch is invalid
+        """
+        name = self.vector_store.collection.name
+        check_collection(collection, name)
+        if not filters:
+            raise ValueError("A filter is required (it selects the chunks to update)")
+        if not patch:
+            raise ValueError("The patch is empty")
+        apply_merge_patch({}, patch)   # Reject a bad patch even if nothing matches
+        
+        chunks = self.vector_store.get_all_chunks()
+        ids, metadatas, removed, documents = [], [], set(), set()
+        matched = 0
+        for chunk_id, meta in zip(chunks["ids"], chunks["metadatas"]):
+            meta = {k: v for k, v in meta.items() if v is not None}
+            if not matches_metadata_filter(meta, filters):
+                continue
+            matched += 1
+            patched = apply_merge_patch(meta, patch)
+            if patched == meta:
+                continue
+            ids.append(chunk_id)
+            metadatas.append(patched)
+            removed.update(k for k in meta if k not in patched)
+            documents.add(meta.get("doc_hash"))
+            record_chunk = self._find_record_chunk(meta)
+            if record_chunk is not None:
+                record_chunk.metadata = apply_merge_patch(record_chunk.metadata, patch)
+        
+        self.vector_store.update_metadatas(ids, metadatas, sorted(removed))
+        
+        if self.audit_log and ids:
+            self.audit_log.record("metadata_updated", name, {
+                "filter": filters,
+                "patch": patch,
+                "chunks": len(ids),
+                "documents": len(documents)
+            })
+        
+        logger.info(f"Metadata patch matched {matched} chunks, updated {len(ids)} in {len(documents)} documents")
+        return {"matched": matched, "updated": len(ids), "documents": len(documents)}
+    
+    def delete_chunk(self, chunk_id: str) -> Dict:
+        """
+        Delete a single chunk.
+        
+        Raises:
+            KeyError: If the chunk does not exist
+        """
+        chunk = self.vector_store.get_chunk(chunk_id)
+        self.vector_store.delete_chunks([chunk_id])
+        
+        record = self.processed_docs.get(chunk["metadata"].get("doc_hash"))
+        record_chunk = self._find_record_chunk(chunk["metadata"])
+        if record is not None and record_chunk is not None:
+            record.chunks.remove(record_chunk)
+        
+        if self.audit_log:
+            self.audit_log.record("chunk_deleted", chunk_id, {
+                "filename": chunk["metadata"].get("filename"),
+                "text": chunk["content"]
+            })
+        
+        logger.info(f"Deleted chunk {chunk_id}")
+        return chunk
+    
+    def list_document_versions(self, source_path: str) -> List[Dict]:
+        """
+        Indexed versions of a file, oldest first.
+        
+        Args:
+            source_path: File path as ingested, or the source_path an upload
+                         was indexed under (see upload_source_path)
+        """
+        if Path(source_path).exists():
+            source_path = str(Path(source_path).resolve())
+        return self.vector_store.get_document_versions(source_path)
+    
+    def get_index_state(self) -> Dict:
+        """
+        How fresh query results are.
+        
+        Queries never wait for indexing, so while documents are being
+        ingested results may not include them yet (may_be_incomplete).
+        """
+        with self._state_lock:
+            in_progress = dict(self._ingests_in_progress)
+            last_indexed_at = self.last_indexed_at
         return {
+            "may_be_incomplete": bool(in_progress),
+            "documents_in_progress": len(in_progress),
+            "in_progress": [Path(p).name for p in in_progress],
+            "last_indexed_at": last_indexed_at.isoformat() if last_indexed_at else None,
+            "as_of": datetime.now().isoformat()
+        }
+    
+    def check_source(self, metadata: Dict) -> Dict:
+        """
+        Whether the file a chunk came from changed since it was indexed.
+        
+        Only chunks whose source_path is an absolute path can be checked
+        (uploads are identified by upload_source_path); for the rest
+        stale is None.
+        
+        Returns:
+            Dict with stale (True/False/None) and reason ("changed",
+            "missing" or "unverifiable"; None when not stale)
+        """
+        source_path = metadata.get("source_path") or ""
+        path = Path(source_path)
+        if not source_path or not path.is_absolute():
+            return {"stale": None, "reason": "unverifiable"}
+        try:
+            stat = path.stat()
+        except OSError:
+            return {"stale": True, "reason": "missing"}
+        
+        if (metadata.get("source_size") == stat.st_size
+                and metadata.get("source_mtime") == stat.st_mtime):
+            return {"stale": False, "reason": None}
+        
+        key = (source_path, stat.st_size, stat.st_mtime)
+        current = self._source_hashes.get(key)
+        if current is None:
+            try:
+                current = self._source_hashes[key] = self._compute_hash(path)
+            except OSError:
+                return {"stale": True, "reason": "missing"}
+        if current != metadata.get("doc_hash"):
+            return {"stale": True, "reason": "changed"}
+        return {"stale": False, "reason": None}
+    
+    def settings_fingerprint(self, preset: Optional[ProcessingPreset] = None) -> str:
+        """
+        Short hash of the settings (FINGERPRINT_SETTINGS) indexing with
+        preset, or the processor defaults, would use.
+        """
+        settings = {
+            "chunk_size": self.chunker.chunk_size,
+            "chunk_overlap": self.chunker.chunk_overlap,
+            "smart_mode": self.chunker.respect_sentences,
+            "ocr_enabled": True,
+            "extract_tables": self.extract_tables,
+            "index_figures": self.index_figures,
+            "footnote_mode": self.footnote_mode,
+            "embedding_model": self.vector_store.embedding_model
+        }
+        if preset:
+            settings.update({
+                "chunk_size": preset.chunk_size,
+                "chunk_overlap": preset.chunk_overlap,
+                "smart_mode": preset.smart_mode,
+                "ocr_enabled": preset.ocr_enabled
+            })
+            if preset.extract_tables is not None:
+                settings["extract_tables"] = preset.extract_tables
+        encoded = json.dumps({k: settings[k] for k in FINGERPRINT_SETTINGS}, sort_keys=True)
+        return hashlib.sha256(encoded.encode("utf-8")).hexdigest()[:16]
+    
+    def _current_fingerprint(self, preset_name: Optional[str]) -> str:
+        """Fingerprint re-indexing a source indexed with preset_name would get now."""
+        if not preset_name or self.preset_store is None:
+            return self.settings_fingerprint()
+        try:
+            preset = self.preset_store.get_preset(preset_name)
+        except KeyError:
+            # The preset was deleted: re-indexing uses the applied one
+            preset = self.preset_store.resolve()
+        return self.settings_fingerprint(preset)
+    
+    def get_source_stats(self, collection: Optional[str] = None) -> List[Dict]:
+        """
+        Every indexed document (latest version) with its chunk count, when
+        and with what settings it was indexed, and whether it is stale.
+        
+        Returns:
+            List of dicts with source_path, filename, doc_hash, version,
+            chunks, indexed_at, preset, settings_fingerprint, stale and
+            stale_reasons ("changed" or "missing" for the file, "settings"
+            when indexing now would use other settings). Sources indexed
+            before fingerprints were recorded have settings_fingerprint None
+            and are never stale for settings.
+            
+        Raises:
+            KeyError: If collection is not the store's collection
+        """
+        name = self.vector_store.collection.name
+        check_collection(collection, name)
+        
+        results = self.vector_store.collection.get(include=["metadatas"])
+        sources: Dict[str, Dict] = {}
+        chunk_counts: Dict[str, int] = {}
+        for meta in results.get("metadatas") or []:
+            if meta.get("source_type") == CHAT_SOURCE_TYPE or not meta.get("is_latest", True):
+                continue
+            source_path = meta.get("source_path") or ""
+            sources.setdefault(source_path, meta)
+            chunk_counts[source_path] = chunk_counts.get(source_path, 0) + 1
+        
+        fingerprints: Dict[Optional[str], str] = {}
+        stats = []
+        for source_path, meta in sorted(sources.items()):
+            reasons = []
+            state = self.check_source(meta)
+            if state["stale"]:
+                reasons.append(state["reason"])
+            fingerprint = meta.get("settings_fingerprint")
+            preset_name = meta.get("preset")
+            if preset_name not in fingerprints:
+                fingerprints[preset_name] = self._current_fingerprint(preset_name)
+            if fingerprint and fingerprint != fingerprints[preset_name]:
+                reasons.append("settings")
+            stats.append({
+                "source_path": source_path,
+                "filename": meta.get("filename"),
+                "doc_hash": meta.get("doc_hash"),
+                "version": meta.get("version"),
+                "chunks": chunk_counts[source_path],
+                "indexed_at": meta.get("indexed_at"),
+                "preset": preset_name,
+                "settings_fingerprint": fingerprint,
+                "stale": bool(reasons),
+                "stale_reasons": reasons
+            })
+        return stats
+    
+    def find_stale_sources(self, collection: Optional[str] = None) -> List[Dict]:
+        """
+        Indexed documents (latest versions) whose source file changed or
+        disappeared since indexing.
+        
+        Returns:
+            List of dicts with source_path, filename, doc_hash, indexed_at
+            and reason ("changed" or "missing")
+            
+        Raises:
+            KeyError: If collection is not the store's collection
+        """
+        name = self.vector_store.collection.name
+        check_collection(collection, name)
+        
+        results = self.vector_store.collection.get(include=["metadatas"])
+        sources: Dict[str, Dict] = {}
+        for meta in results.get("metadatas") or []:
+            if meta.get("source_type") == CHAT_SOURCE_TYPE or not meta.get("is_latest", True):
+                continue
+            sources.setdefault(meta.get("source_path") or "", meta)
+        
+        stale = []
+        for source_path, meta in sorted(sources.items()):
+            state = self.check_source(meta)
+            if state["stale"]:
+                stale.append({
+                    "source_path": source_path,
+                    "filename": meta.get("filename"),
+                    "doc_hash": meta.get("doc_hash"),
+                    "indexed_at": meta.get("indexed_at"),
+                    "reason": state["reason"]
+                })
+        return stale
+    
+    def get_retrieval_config(self, collection: Optional[str] = None) -> RetrievalConfig:
+        """
+        Keyword scoring config of the collection.
+        
+        Raises:
+            KeyError: If collection is not the store's collection
+        """
+        name = self.vector_store.collection.name
+        check_collection(collection, name)
+        return self.vector_store.retrieval_config
+    
+    def set_retrieval_config(self, collection: Optional[str], config: RetrievalConfig) -> RetrievalConfig:
+        """
+        Apply (and persist, if a config store is attached) stopwords,
+        boosts and keyword weight for the collection.
+        
+        Raises:
+            KeyError: If collection is not the store's collection
+        """
+        name = self.vector_store.collection.name
+        check_collection(collection, name)
+        self.vector_store.retrieval_config = config
+        if self.retrieval_configs is not None:
+            self.retrieval_configs.set_retrieval_config(name, config)
+        return config
+    
+    def set_collection_appearance(self, collection: Optional[str], changes: Dict) -> Dict:
+        """
+        Set the description, color and/or icon the library shows for the
+        collection; fields left out keep their value, None clears one.
+        
+        Returns:
+            The collection's appearance
+            
+        Raises:
+            KeyError: If collection is not the store's collection
+            ValueError: If a field is unknown or invalid
+        """
+        name = self.vector_store.collection.name
+        check_collection(collection, name)
+        return self.collection_appearances.set_collection_appearance(name, changes).to_dict()
+    
+    def connect_share(self, path: Union[str, Path]):
+        """
+        Connect the network share a path is on, if share_credentials has
+        credentials for it (see network_shares).
+        
+        Raises:
+            ShareConnectionError: If the share refuses the stored credentials
+        """
+        if self.share_credentials:
+            self.share_credentials.connect(path)
+    
+    def check_writable(self, operation: str):
+        """
+        Refuse an operation that would change a read-only collection,
+        recording the attempt in the audit log.
+        
+        Raises:
+            CollectionReadOnlyError: If the collection is read-only
+        """
+        from collection_holds import CollectionReadOnlyError
+        name = self.vector_store.collection.name
+        try:
+            self.collection_holds.check_writable(name, operation)
+        except CollectionReadOnlyError as e:
+            logger.warning(f"Refused {operation} on read-only collection {name}")
+            if self.audit_log:
+                self.audit_log.record("collection_write_refused", name, {
+                    "operation": operation,
+                    "reason": e.hold.reason
+                })
+            raise
+    
+    def set_collection_readonly(
+        self,
+        collection: Optional[str],
+        readonly: bool,
+        reason: Optional[str] = None
+    ) -> Dict:
+        """
+        Freeze a collection (legal hold) or make it writable again.
+        
+        Returns:
+            Dict with collection, readonly and hold (since, reason; None
+            once cleared)
+        
+        Raises:
+            KeyError: If collection is not the store's collection
+            ValueError: If reason is too long
+        """
+        name = self.vector_store.collection.name
+        check_collection(collection, name)
+        was_readonly = self.collection_holds.is_readonly(name)
+        hold = self.collection_holds.set_collection_readonly(name, readonly, reason)
+        if self.audit_log and (readonly or was_readonly):
+            self.audit_log.record(
+                "collection_readonly_set" if readonly else "collection_readonly_cleared",
+                name,
+                {"reason": hold.reason if hold else reason}
+            )
+        return {"collection": name, "readonly": readonly, "hold": hold.to_dict() if hold else None}
+    
+    def _collection_hold(self, name: str) -> Optional[Dict]:
+        hold = self.collection_holds.get(name)
+        return hold.to_dict() if hold else None
+    
+    def list_collections(self) -> List[Dict]:
+        """Collections with their chunk count, appearance and read-only hold."""
+        name = self.vector_store.collection.name
+        return [{
+            "name": name,
+            "chunks": self.vector_store.get_document_count(),
+            "appearance": self.collection_appearances.get(name).to_dict(),
+            "readonly": self._collection_hold(name)
+        }]
+    
+    def list_collections_detailed(self, index_bytes: Optional[int] = None) -> List[Dict]:
+        """
+        Collections with everything the library view shows, in one call.
+        
+        Args:
+            index_bytes: Size of the store's index files, split between the
+                         collections by chunk count (None = not estimated)
+        
+        Returns:
+            List of dicts with name, documents (latest versions, chat
+            threads excluded), chunks, size_bytes, embedding model and
+            dimension, last_updated (newest indexed_at), appearance and
+            readonly (the hold, None if writable)
+        """
+        name = self.vector_store.collection.name
+        results = self.vector_store.collection.get(include=["metadatas"])
+        documents = set()
+        last_updated = None
+        for meta in results.get("metadatas") or []:
+            indexed_at = meta.get("indexed_at")
+            if indexed_at and (last_updated is None or indexed_at > last_updated):
+                last_updated = indexed_at
+            if meta.get("source_type") != CHAT_SOURCE_TYPE and meta.get("is_latest", True):
+                documents.add(meta.get("doc_hash") or meta.get("source_path"))
+        
+        # One collection per store, so it holds all of the index
+        return [{
+            "name": name,
+            "documents": len(documents),
+            "chunks": len(results.get("ids") or []),
+            "size_bytes": index_bytes,
+            "embedding": {
+                "model": self.vector_store.embedding_model,
+                "dimension": self.vector_store.model_dimension
+            },
+            "last_updated": last_updated,
+            "appearance": self.collection_appearances.get(name).to_dict(),
+            "readonly": self._collection_hold(name)
+        }]
+    
+    def get_stats(self, include_sources: bool = False) -> Dict:
+        """
+        Get processor statistics.
+        
+        Args:
+            include_sources: Also list every indexed document with its chunk
+                             count, settings fingerprint and staleness
+                             (see get_source_stats)
+        """
+        stats = {
             "documents_indexed": len(self.processed_docs),
             "total_chunks": self.vector_store.get_document_count(),
-            "supported_extensions": list(DocumentExtractor.get_supported_extensions())
+            "supported_extensions": sorted(self.supported_extensions()),
+            "index_state": self.get_index_state(),
+            "collection": {
+                "name": self.vector_store.collection.name,
+                "appearance": self.collection_appearances.get(self.vector_store.collection.name).to_dict(),
+                "readonly": self._collection_hold(self.vector_store.collection.name)
+            },
+            "embedding": {
+                "model": self.vector_store.embedding_model,
+                "dimension": self.vector_store.model_dimension,
+                "stored_dimension": self.vector_store.stored_dimension()
+            }
         }
+        if include_sources:
+            stats["sources"] = self.get_source_stats()
+        return stats
@@ -0,0 +1,143 @@
+"""
+Duplicate Check for Jan Document Plugin
+
+Tells whether a file is essentially already in the collection before it
+is indexed:
+
+- An identical file (same content hash) under another name is an exact
+  duplicate (score 1.0)
+- Otherwise a sample of the file's chunks is embedded and searched; each
+  indexed document scores the mean, over the sampled chunks, of its best
+  match, so a document only scores high if it covers the whole file
+
+Earlier versions of the same source are not counted: re-indexing a
+changed file is an update, not a duplicate. Batches can skip files whose
+best score reaches a threshold (see batch_processor).
+"""
+
+import logging
+from pathlib import Path
+from typing import Dict, List, Optional, Union
+
+from document_processor import DocumentProcessor, CHAT_SOURCE_TYPE, EMBED_BATCH_SIZE
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+DEFAULT_DUPLICATE_THRESHOLD = 0.95
+
+# Chunks of the file embedded and searched
+DEFAULT_SAMPLE_SIZE = 8
+
+# Candidates fetched per sampled chunk
+CANDIDATES_PER_SAMPLE = 10
+
+
+def _sample(items: List, size: int) -> List:
+    """Up to size items spread evenly over the list."""
+    if len(items) <= size:
+        return list(items)
+    step = len(items) / size
+    return [items[int(i * step)] for i in range(size)]
+
+
+def check_duplicate(
+    processor: DocumentProcessor,
+    file_path: Union[str, Path],
+    collection: Optional[str] = None,
+    threshold: float = DEFAULT_DUPLICATE_THRESHOLD,
+    source_path: Optional[str] = None,
+    sample_size: int = DEFAULT_SAMPLE_SIZE,
+    limit: int = 5
+) -> Dict:
+    """
+    Compare a file with the indexed documents without indexing it.
+
+    Args:
+        processor: Processor whose store is searched
+        file_path: File to check
+        collection: Collection name (None = the store's collection)
+        threshold: Score (0-1) from which the file counts as a duplicate
+        source_path: The file's identity if it is a temp copy (see ingest)
+        sample_size: Chunks of the file to embed and search
+        limit: Most similar documents to report
+
+    Returns:
+        Dict with source_path, doc_hash, sampled_chunks, threshold,
+        duplicate and matches (source_path, filename, doc_hash, score,
+        matched_samples, exact), most similar first
+
+    Raises:
+        KeyError: If collection is not the store's collection
+        ValueError: If threshold is not between 0 and 1, or as for ingest
+        FileNotFoundError: If the file doesn't exist
+    """
+    check_collection(collection, processor.vector_store.collection.name)
+    if not 0 <= threshold <= 1:
+        raise ValueError("threshold must be between 0 and 1")
+
+    path = Path(file_path).resolve()
+    source_path = source_path or str(path)
+    processed = processor.ingest(path, force=True, dry_run=True, source_path=source_path)
+    store = processor.vector_store
+
+    matches: Dict[str, Dict] = {}
+    identical = store.collection.get(where={"doc_hash": processed.doc_hash}, include=["metadatas"])
+    for meta in identical.get("metadatas") or []:
+        other = meta.get("source_path") or meta.get("filename") or ""
+        if other != source_path and other not in matches:
+            matches[other] = {
+                "source_path": other,
+                "filename": meta.get("filename"),
+                "doc_hash": meta.get("doc_hash"),
+                "score": 1.0,
+                "matched_samples": None,
+                "exact": True
+            }
+
+    samples = _sample([c.content for c in processed.chunks if c.content.strip()], sample_size)
+    if samples and not matches:
+        store.check_embedding_dimension()
+        embeddings = store.embedder.encode(samples, batch_size=EMBED_BATCH_SIZE).tolist()
+        results = store.collection.query(
+            query_embeddings=embeddings,
+            n_results=CANDIDATES_PER_SAMPLE,
+            include=["metadatas", "distances"]
+        )
+        totals: Dict[str, float] = {}
+        counts: Dict[str, int] = {}
+        for metadatas, distances in zip(results.get("metadatas") or [], results.get("distances") or []):
+            best: Dict[str, float] = {}
+            for meta, distance in zip(metadatas, distances):
+                if meta.get("source_type") == CHAT_SOURCE_TYPE or not meta.get("is_latest", True):
+                    continue
+                other = meta.get("source_path") or meta.get("filename") or ""
+                if other == source_path:
+                    continue
+                if 1 - distance > best.get(other, float("-inf")):
+                    best[other] = 1 - distance
+                    matches.setdefault(other, {
+                        "source_path": other,
+                        "filename": meta.get("filename"),
+                        "doc_hash": meta.get("doc_hash"),
+                        "exact": False
+                    })
+            for other, similarity in best.items():
+                totals[other] = totals.get(other, 0.0) + similarity
+                counts[other] = counts.get(other, 0) + 1
+        for other, match in matches.items():
+            match["score"] = round(max(0.0, totals[other] / len(samples)), 4)
+            match["matched_samples"] = counts[other]
+
+    ranked = sorted(matches.values(), key=lambda m: -m["score"])[:limit]
+    duplicate = bool(ranked) and ranked[0]["score"] >= threshold
+    if duplicate:
+        logger.info(f"{Path(source_path).name} duplicates {ranked[0]['filename']} (score {ranked[0]['score']})")
+    return {
+        "source_path": source_path,
+        "doc_hash": processed.doc_hash,
+        "sampled_chunks": len(samples),
+        "threshold": threshold,
+        "duplicate": duplicate,
+        "matches": ranked
+    }
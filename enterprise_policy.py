@@ -0,0 +1,221 @@
+"""
+Enterprise Policy for Jan Document Plugin
+
+IT departments deploying the plugin centrally can disable features and
+pin settings. Policies come from two places:
+
+- Windows: HKLM\\SOFTWARE\\Policies\\Anywave\\JanDocumentPlugin
+    DisabledFeatures\\<feature> = 1 (DWORD) disables a feature
+    Settings\\<SETTING> = value pins a setting (config.env names, e.g. AUTO_INJECT)
+- Any platform: a JSON policy file in the machine-wide config folder
+    {"disabled_features": ["export"], "settings": {"AUTO_INJECT": false}}
+
+Registry values take precedence over the policy file. Pinned settings
+override config.env and cannot be changed at runtime; get_effective_policy()
+tells the UI which features and settings to grey out.
+"""
+
+import os
+import sys
+import json
+import logging
+from pathlib import Path
+from typing import Dict, List, Optional, Any
+from dataclasses import dataclass, field, asdict
+
+logger = logging.getLogger(__name__)
+
+POLICY_REGISTRY_KEY = r"SOFTWARE\Policies\Anywave\JanDocumentPlugin"
+
+# Features that can be disabled by policy
+FEATURES = {
+    "export": "Export collections as static search sites",
+    "chat_attachments": "Index files attached to chat messages",
+    "folder_scan": "Index documents from folders on disk",
+    "ocr_language_install": "Download and install OCR language packs",
+    "usage_upload": "Upload usage statistics",
+    "crash_report_upload": "File crash reports on GitHub",
+}
+
+
+def default_policy_file() -> Path:
+    """Machine-wide policy file location for this platform."""
+    if sys.platform == "win32":
+        base = Path(os.environ.get("PROGRAMDATA", r"C:\ProgramData"))
+        return base / "JanDocumentPlugin" / "policy.json"
+    if sys.platform == "darwin":
+        return Path("/Library/Application Support/JanDocumentPlugin/policy.json")
+    return Path("/etc/jan-document-plugin/policy.json")
+
+
+@dataclass
+class EffectivePolicy:
+    """Merged policy from all sources."""
+    disabled_features: List[str] = field(default_factory=list)
+    locked_settings: Dict[str, Any] = field(default_factory=dict)   # ProxyConfig field -> value
+    sources: List[str] = field(default_factory=list)
+
+    @property
+    def managed(self) -> bool:
+        return bool(self.disabled_features or self.locked_settings)
+
+    def to_dict(self) -> Dict:
+        return {**asdict(self), "managed": self.managed}
+
+
+class FeatureDisabledError(Exception):
+    """Raised when a feature is used that policy has disabled."""
+
+    def __init__(self, feature: str):
+        self.feature = feature
+        super().__init__(f"{FEATURES.get(feature, feature)} is disabled by your administrator")
+
+    def to_dict(self) -> Dict:
+        return {
+            "error": "feature_disabled",
+            "message": str(self),
+            "feature": self.feature
+        }
+
+
+class PolicyManager:
+    """
+    Reads enterprise policy and applies it to the proxy configuration.
+
+    Usage:
+        policy = PolicyManager()
+        policy.apply_to_config(config)
+        policy.require_feature("export")
+        policy.get_effective_policy()
+    """
+
+    def __init__(self, policy_file: Optional[Path] = None, registry_key: str = POLICY_REGISTRY_KEY):
+        """
+        Initialize policy manager.
+
+        Args:
+            policy_file: JSON policy file (None = platform default location)
+            registry_key: HKLM key read on Windows
+        """
+        self.policy_file = Path(policy_file) if policy_file else default_policy_file()
+        self.registry_key = registry_key
+        self.policy = EffectivePolicy()
+        self.reload()
+
+    def reload(self) -> EffectivePolicy:
+        """Re-read all policy sources."""
+        policy = EffectivePolicy()
+        self._merge(policy, self._read_policy_file(), str(self.policy_file))
+        self._merge(policy, self._read_registry(), f"HKLM\\{self.registry_key}")
+        self.policy = policy
+        if policy.managed:
+            logger.info(
+                f"Enterprise policy active: {len(policy.disabled_features)} feature(s) disabled, "
+                f"{len(policy.locked_settings)} setting(s) pinned ({', '.join(policy.sources)})"
+            )
+        return policy
+
+    @staticmethod
+    def _merge(policy: EffectivePolicy, source: Optional[Dict], name: str):
+        if not source:
+            return
+        policy.sources.append(name)
+        for feature, disabled in source.get("features", {}).items():
+            if feature not in FEATURES:
+                logger.warning(f"Ignoring unknown policy feature {feature!r} in {name}")
+                continue
+            if disabled and feature not in policy.disabled_features:
+                policy.disabled_features.append(feature)
+            elif not disabled and feature in policy.disabled_features:
+                policy.disabled_features.remove(feature)
+        for setting, value in source.get("settings", {}).items():
+            policy.locked_settings[setting.lower()] = value
+
+    def _read_policy_file(self) -> Optional[Dict]:
+        if not self.policy_file.exists():
+            return None
+        try:
+            data = json.loads(self.policy_file.read_text(encoding="utf-8"))
+        except (OSError, ValueError) as e:
+            logger.warning(f"Could not read policy file {self.policy_file}: {e}")
+            return None
+        return {
+            "features": {f: True for f in data.get("disabled_features", [])},
+            "settings": dict(data.get("settings", {}))
+        }
+
+    def _read_registry(self) -> Optional[Dict]:
+        if sys.platform != "win32":
+            return None
+        import winreg
+
+        result = {"features": {}, "settings": {}}
+        for subkey, target in (("DisabledFeatures", "features"), ("Settings", "settings")):
+            try:
+                key = winreg.OpenKey(winreg.HKEY_LOCAL_MACHINE, f"{self.registry_key}\\{subkey}")
+            except OSError:
+                continue
+            with key:
+                index = 0
+                while True:
+                    try:
+                        name, value, _ = winreg.EnumValue(key, index)
+                    except OSError:
+                        break
+                    result[target][name.lower() if target == "features" else name] = (
+                        bool(value) if target == "features" else value
+                    )
+                    index += 1
+        return result if result["features"] or result["settings"] else None
+
+    def apply_to_config(self, config) -> List[str]:
+        """
+        Pin locked settings on a ProxyConfig.
+
+        Values are validated against the config field types; invalid or
+        unknown settings are logged and skipped.
+
+        Returns:
+            Names of the settings applied
+        """
+        fields = type(config).model_fields
+        applied = []
+        for name, value in list(self.policy.locked_settings.items()):
+            if name not in fields:
+                logger.warning(f"Ignoring unknown policy setting {name!r}")
+                self.policy.locked_settings.pop(name)
+                continue
+            try:
+                validated = type(config).model_validate({**config.model_dump(), name: value})
+            except ValueError as e:
+                logger.warning(f"Ignoring invalid policy setting {name}={value!r}: {e}")
+                self.policy.locked_settings.pop(name)
+                continue
+            setattr(config, name, getattr(validated, name))
+            self.policy.locked_settings[name] = getattr(validated, name)
+            applied.append(name)
+        return applied
+
+    def is_feature_enabled(self, feature: str) -> bool:
+        return feature not in self.policy.disabled_features
+
+    def require_feature(self, feature: str):
+        """
+        Raises:
+            FeatureDisabledError: If policy disables the feature
+        """
+        if not self.is_feature_enabled(feature):
+            raise FeatureDisabledError(feature)
+
+    def is_setting_locked(self, name: str) -> bool:
+        return name.lower() in self.policy.locked_settings
+
+    def get_effective_policy(self) -> Dict:
+        """Merged policy plus per-feature state, for the UI."""
+        return {
+            **self.policy.to_dict(),
+            "features": {
+                name: {"description": description, "enabled": self.is_feature_enabled(name)}
+                for name, description in FEATURES.items()
+            }
+        }
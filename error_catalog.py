@@ -0,0 +1,174 @@
+"""
+Error Catalog for Jan Document Plugin
+
+Stable error codes for every user-facing error, so the UI can show
+translated messages instead of the English text the API produces.
+
+Every error response carries, next to the usual "detail":
+- code: stable identifier from ERROR_CATALOG (never renamed)
+- params: values to substitute into the message template
+- fallback_message: the English message, for UIs without a translation
+
+Structured errors (a dict detail with an "error" key, e.g. quota_exceeded)
+use that key as code and their other fields as params. Plain messages are
+matched against the catalog templates; anything unmatched gets a generic
+code for its HTTP status with the message as its only param.
+"""
+
+import re
+from typing import Any, Dict, Optional
+
+# code -> English message template ({name} placeholders are params)
+ERROR_CATALOG = {
+    # Service state
+    "processor_not_initialized": "Processor not initialized",
+    "batch_processor_not_initialized": "Batch processor not initialized",
+    "soul_registry_unavailable": "Soul registry not available",
+    "batch_in_progress": "A batch is still processing; wait for it or pause it first",
+    "backend_crash": "Internal error - see GET /debug/crashes/{crash_id} for details",
+
+    # Missing things
+    "unknown_collection": "Unknown collection: {collection}",
+    "document_not_found": "Document not found: {document}",
+    "document_not_indexed": "Document not indexed: {document}",
+    "chunk_not_found": "Chunk not found: {chunk_id}",
+    "version_not_found": "No version {number} of {source}",
+    "source_unavailable": "Source file is no longer available: {source_path}",
+    "trash_entry_not_found": "Trash entry not found: {trash_id}",
+    "batch_not_found": "Batch not found: {batch_id}",
+    "document_password_not_found": "No password stored for {source_path}",
+    "crash_report_not_found": "Crash report not found: {crash_id}",
+    "webhook_not_found": "Webhook not found: {webhook_id}",
+    "assistant_not_bound": "No collection bound to assistant: {assistant_id}",
+    "unknown_preset": "Unknown preset: {name}",
+    "unknown_user_preset": "Unknown user preset: {name}",
+    "unknown_soul": "Unknown soul: {soul_id}",
+    "shadow_index_missing": "No shadow index has been built",
+    "dataset_not_found": "Dataset not found: {dataset_path}",
+
+    # Invalid input
+    "invalid_parameters": "Invalid request parameters",
+    "unsupported_file_type": "Unsupported file type: {suffix}. Supported: {supported}",
+    "no_valid_files": "No valid files. Supported: {supported}",
+    "invalid_sources": "Invalid sources: {sources}",
+    "invalid_ocr_mode": "Invalid ocr_mode: {ocr_mode}",
+    "invalid_collection_name": "Invalid collection name: {collection} ({reason})",
+    "invalid_date": "Invalid date: {reason}",
+    "unknown_setup_step": "Unknown setup step: {step} (expected one of {expected})",
+    "setup_step_running": "Setup step {step} is already running",
+
+    # Failed operations
+    "export_failed": "Export failed: {reason}",
+    "import_failed": "Import failed: {reason}",
+    "upload_failed": "Upload failed: {reason}",
+    "download_failed": "Download failed: {reason}",
+    "reset_backup_failed": "Backup failed, nothing was reset: {reason}",
+    "backup_failed": "Backup failed: {reason}",
+    "jan_unreachable": "Cannot connect to Jan server at {url}",
+    "llm_server_unreachable": "Cannot connect to {server} server at {url}. Is {server} running?",
+    "jan_timeout": "Jan server response timed out",
+    "jan_server_error": "Jan server error: {response}",
+
+    # Structured errors (raised with their own fields as params)
+    "insufficient_disk": "Not enough disk space for {operation}",
+    "quota_exceeded": "Collection quota exceeded for {operation}",
+    "feature_disabled": "Disabled by policy: {feature}",
+    "file_locked": "File is locked by another program: {filename}",
+    "password_required": "{filename} is password-protected. Supply its password and try again.",
+    "wrong_password": "Wrong password for {filename}",
+    "collection_readonly": "Collection {collection} is read-only; {operation} is not allowed",
+    "share_connection_failed": "Could not connect to {root} as {username}: {reason}",
+    "indexing_failed": "Could not index {filename}: {reason}",
+    "model_mismatch": "Collection {collection} holds {stored_dimension}-dimension vectors, "
+                      "but {embedding_model} makes {model_dimension}-dimension vectors",
+
+    # Generic codes by HTTP status, for messages not in the catalog
+    "invalid_request": "{message}",
+    "forbidden": "{message}",
+    "not_found": "{message}",
+    "conflict": "{message}",
+    "payload_too_large": "{message}",
+    "locked": "{message}",
+    "internal_error": "{message}",
+    "bad_gateway": "{message}",
+    "service_unavailable": "{message}",
+    "gateway_timeout": "{message}",
+    "http_error": "{message}",
+}
+
+STATUS_CODES = {
+    400: "invalid_request",
+    403: "forbidden",
+    404: "not_found",
+    409: "conflict",
+    413: "payload_too_large",
+    423: "locked",
+    500: "internal_error",
+    502: "bad_gateway",
+    503: "service_unavailable",
+    504: "gateway_timeout",
+}
+
+_PLACEHOLDER = re.compile(r"\{(\w+)\}")
+
+
+def _template_pattern(template: str) -> re.Pattern:
+    pattern = ""
+    seen = set()
+    for i, part in enumerate(_PLACEHOLDER.split(template)):
+        if not i % 2:
+            pattern += re.escape(part)
+        elif part in seen:
+            pattern += f"(?P={part})"   # Repeated placeholder: same value
+        else:
+            pattern += f"(?P<{part}>.+?)"
+            seen.add(part)
+    return re.compile(f"^{pattern}$", re.DOTALL)
+
+
+# Templates plain messages are matched against (generic codes excluded)
+_PATTERNS = [
+    (code, _template_pattern(template))
+    for code, template in ERROR_CATALOG.items()
+    if template != "{message}"
+]
+
+
+def render(code: str, params: Dict[str, Any]) -> str:
+    """English message for code (the template if a param is missing)."""
+    template = ERROR_CATALOG.get(code, "{message}")
+    try:
+        return template.format(**params)
+    except (KeyError, IndexError):
+        return template
+
+
+def catalog_error(status_code: int, detail: Any, message: Optional[str] = None) -> Dict:
+    """
+    {code, params, fallback_message} for an error response.
+
+    Args:
+        status_code: HTTP status of the response
+        detail: HTTPException detail (message string or structured dict)
+        message: English message to use for structured errors without one
+    """
+    if isinstance(detail, dict) and "error" in detail:
+        params = {k: v for k, v in detail.items() if k not in ("error", "message")}
+        code = detail["error"]
+        return {
+            "code": code,
+            "params": params,
+            "fallback_message": detail.get("message") or message or render(code, params)
+        }
+
+    text = detail if isinstance(detail, str) else message or str(detail)
+    for code, pattern in _PATTERNS:
+        match = pattern.match(text)
+        if match:
+            return {"code": code, "params": match.groupdict(), "fallback_message": text}
+
+    return {
+        "code": STATUS_CODES.get(status_code, "http_error"),
+        "params": {"message": text},
+        "fallback_message": text
+    }
@@ -0,0 +1,245 @@
+"""
+Event Log for Jan Document Plugin
+
+Keeps the recent pipeline events (indexing, export, import - the same
+events webhooks receive) and batch progress, each with the id of the
+request that caused it, so a client can:
+
+- Ask what happened to one of its requests:
+      GET /events/requests/{request_id}
+- Rebuild its view after a reload (e.g. a batch still running):
+      GET /events/replay?since=<time>&request_id=<id>
+
+Events are appended to <storage>/event_log.jsonl and reloaded on start,
+so they survive restarts too. Only the newest MAX_EVENTS are kept; the
+file is rewritten once it holds twice that many lines.
+
+Batch progress is throttled: a batch over thousands of small files would
+otherwise flood the log and push everything else out. Each batch records
+at most progress_rate updates per second; updates in between are
+dropped (the next one recorded carries the latest counts). The first
+update of a batch and its completion are always recorded.
+"""
+
+import json
+import time
+import logging
+import threading
+from collections import deque
+from pathlib import Path
+from typing import Dict, List, Optional
+from dataclasses import dataclass, asdict
+from datetime import datetime
+
+from request_context import get_request_id
+
+logger = logging.getLogger(__name__)
+
+# Events kept (oldest are dropped first)
+MAX_EVENTS = 1000
+
+# Event recording batch progress; its data is a batch summary
+BATCH_PROGRESS_EVENT = "batch.progress"
+
+# Batch progress updates recorded per second, per batch
+DEFAULT_PROGRESS_RATE = 2.0
+
+
+@dataclass
+class LoggedEvent:
+    """One pipeline event."""
+    sequence: int
+    time: str
+    event: str                 # e.g. "indexing.completed"
+    request_id: Optional[str]
+    data: Dict
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+def batch_summary(batch) -> Dict:
+    """
+    Progress of a batch without its per-file list.
+
+    Args:
+        batch: batch_processor.BatchProgress
+    """
+    return {
+        "batch_id": batch.batch_id,
+        "total_files": batch.total_files,
+        "completed_files": batch.completed_files,
+        "failed_files": batch.failed_files,
+        "skipped_files": batch.skipped_files,
+        "total_chunks": batch.total_chunks,
+        "progress_percent": round(batch.progress_percent, 1),
+        "is_complete": batch.completed_at is not None,
+        "deferred_reason": batch.deferred_reason
+    }
+
+
+class EventLog:
+    """
+    Recent pipeline events, tagged with their request id.
+
+    Usage:
+        events = EventLog(Path("./jan_doc_store/event_log.jsonl"), progress_rate=2.0)
+        events.record("indexing.completed", {"filename": "a.pdf"})   # inside a request
+        events.get_events_for_request(request_id)
+        events.replay_events(since="2026-01-01T12:00:00")
+    """
+
+    def __init__(
+        self,
+        log_path: Optional[Path] = None,
+        max_events: int = MAX_EVENTS,
+        progress_rate: float = DEFAULT_PROGRESS_RATE
+    ):
+        """
+        Initialize event log.
+
+        Args:
+            log_path: JSONL file events are persisted to (None = memory only)
+            max_events: Events kept
+            progress_rate: Batch progress updates recorded per second,
+                           per batch (0 = record every update)
+        """
+        self._lock = threading.Lock()
+        self.set_progress_rate(progress_rate)
+        self._last_progress: Dict[str, float] = {}   # batch_id -> monotonic time of last update
+        self.progress_dropped = 0   # Updates skipped by throttling since start
+        self._events: deque = deque(maxlen=max_events)
+        self._sequence = 0
+        self._log_path = Path(log_path) if log_path else None
+        self._lines = 0   # Lines in the log file
+        self._load()
+
+    def _load(self):
+        """Reload the newest events from the log file."""
+        if not self._log_path or not self._log_path.exists():
+            return
+        try:
+            with open(self._log_path, encoding="utf-8") as f:
+                lines = f.readlines()
+        except OSError as e:
+            logger.warning(f"Could not read event log: {e}")
+            return
+
+        self._lines = len(lines)
+        for line in lines:
+            try:
+                self._events.append(LoggedEvent(**json.loads(line)))
+            except (ValueError, TypeError):
+                continue   # Torn last line after a crash
+        if self._events:
+            self._sequence = self._events[-1].sequence
+
+    def _persist(self, logged: LoggedEvent):
+        """Append to the log file, rewriting it when it grows too long."""
+        if not self._log_path:
+            return
+        try:
+            self._log_path.parent.mkdir(parents=True, exist_ok=True)
+            if self._lines >= 2 * self._events.maxlen:
+                temp = self._log_path.with_suffix(".tmp")
+                with open(temp, "w", encoding="utf-8") as f:
+                    for e in self._events:
+                        f.write(json.dumps(e.to_dict(), default=str) + "\n")
+                temp.replace(self._log_path)
+                self._lines = len(self._events)
+            else:
+                with open(self._log_path, "a", encoding="utf-8") as f:
+                    f.write(json.dumps(logged.to_dict(), default=str) + "\n")
+                self._lines += 1
+        except OSError as e:
+            logger.warning(f"Could not write event log: {e}")
+
+    def set_progress_rate(self, progress_rate: float):
+        """Batch progress updates recorded per second, per batch (0 = every update)."""
+        self._progress_interval = 1.0 / progress_rate if progress_rate > 0 else 0.0
+
+    @property
+    def last_sequence(self) -> int:
+        return self._sequence
+
+    def record(self, event: str, data: Dict, request_id: Optional[str] = None) -> LoggedEvent:
+        """
+        Add an event.
+
+        Args:
+            event: Event name
+            data: Event payload
+            request_id: Request that caused it (None = the current request)
+        """
+        with self._lock:
+            self._sequence += 1
+            logged = LoggedEvent(
+                sequence=self._sequence,
+                time=datetime.now().isoformat(),
+                event=event,
+                request_id=request_id or get_request_id(),
+                data=data
+            )
+            self._events.append(logged)
+            self._persist(logged)
+        return logged
+
+    def record_batch_progress(self, batch) -> Optional[LoggedEvent]:
+        """
+        Record a batch progress update (batch_processor progress callback).
+
+        Returns:
+            The event, or None if the update was dropped by throttling
+        """
+        summary = batch_summary(batch)
+        now = time.monotonic()
+        with self._lock:
+            last = self._last_progress.get(batch.batch_id)
+            if summary["is_complete"]:
+                self._last_progress.pop(batch.batch_id, None)
+            elif last is not None and now - last < self._progress_interval:
+                self.progress_dropped += 1
+                return None
+            else:
+                self._last_progress[batch.batch_id] = now
+        return self.record(BATCH_PROGRESS_EVENT, summary, batch.request_id)
+
+    def get_events_for_request(self, request_id: str) -> List[Dict]:
+        """Events caused by one request, oldest first."""
+        with self._lock:
+            return [e.to_dict() for e in self._events if e.request_id == request_id]
+
+    def replay_events(self, since: Optional[str] = None, request_id: Optional[str] = None) -> Dict:
+        """
+        Events to rebuild a client's view, and the last known state of each batch.
+
+        Args:
+            since: ISO timestamp; only later events are returned (None = all kept)
+            request_id: Only events caused by this request
+
+        Returns:
+            {"events": [...], "batches": {batch_id: summary}, "last_sequence": n,
+             "progress_dropped": throttled updates since start}
+
+        Raises:
+            ValueError: If since is not an ISO timestamp
+        """
+        since_time = datetime.fromisoformat(since) if since else None
+        with self._lock:
+            events = [
+                e for e in self._events
+                if (since_time is None or datetime.fromisoformat(e.time) > since_time)
+                and (request_id is None or e.request_id == request_id)
+            ]
+            last_sequence = self._sequence
+
+        batches = {}
+        for e in events:
+            if e.event == BATCH_PROGRESS_EVENT:
+                batches[e.data["batch_id"]] = e.data
+        return {
+            "events": [e.to_dict() for e in events],
+            "batches": batches,
+            "last_sequence": last_sequence,
+            "progress_dropped": self.progress_dropped
+        }
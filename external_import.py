@@ -0,0 +1,280 @@
+"""
+External Index Import for Jan Document Plugin
+
+Brings chunks indexed by other RAG tools (LlamaIndex, LangChain, plain
+ChromaDB) into the collection, so users migrating don't have to start
+over from the original files:
+
+- chroma: a ChromaDB persist directory (the folder holding chroma.sqlite3);
+          every collection in it is imported
+- jsonl:  one object per line with the text ("text", "page_content",
+          "content" or "document"), optional "id", "metadata" object and
+          "embedding" list - this also reads /collections/export output
+- csv:    a header row with a text column (same names as jsonl), optional
+          id, metadata (JSON object) and embedding (JSON list) columns;
+          any other column becomes metadata
+
+Stored vectors are reused only when they can be searched alongside the
+collection's own: the dimension must match the collection's embedding
+model and, when the source names its model (Chroma collection metadata or
+source_model), so must the model. Everything else is re-embedded.
+
+Chunks are grouped into documents by their "source" / "source_path" /
+"file_path" / "filename" metadata; chunks whose id is already in the
+collection are skipped, so an import can be repeated safely.
+"""
+
+import csv
+import json
+import hashlib
+import logging
+from pathlib import Path
+from typing import Dict, Iterator, List, Optional, Tuple
+from dataclasses import dataclass, field, asdict
+from datetime import datetime
+
+import chromadb_compat  # noqa: F401 - side-effect import for patching
+import chromadb
+from chromadb.config import Settings
+
+from document_processor import LocalVectorStore
+from legacy_data import STAGING_SUFFIXES
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+IMPORT_FORMATS = ("chroma", "jsonl", "csv")
+
+# Chunks added per call
+IMPORT_BATCH_SIZE = 256
+
+TEXT_FIELDS = ("text", "page_content", "content", "document")
+SOURCE_FIELDS = ("source", "source_path", "file_path", "filename", "file_name")
+
+# (id, text, metadata, embedding, model) read from the source
+Record = Tuple[Optional[str], str, Dict, Optional[List[float]], Optional[str]]
+
+
+@dataclass
+class ImportReport:
+    """What import_external_index added to the collection."""
+    path: str
+    format: str
+    collection: str
+    records: int = 0
+    added: int = 0
+    skipped: int = 0                    # Already in the collection
+    invalid: int = 0                    # No text, or unreadable
+    re_embedded: int = 0
+    vectors_reused: int = 0
+    documents: int = 0
+    warnings: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+def _text_of(record: Dict) -> str:
+    for key in TEXT_FIELDS:
+        if isinstance(record.get(key), str):
+            return record[key]
+    return ""
+
+
+def _scalar_metadata(metadata: Dict) -> Dict:
+    """Chroma only stores str/int/float/bool values; the rest become JSON."""
+    clean = {}
+    for key, value in metadata.items():
+        if value is None:
+            continue
+        if isinstance(value, (str, int, float, bool)):
+            clean[str(key)] = value
+        else:
+            clean[str(key)] = json.dumps(value, default=str)
+    return clean
+
+
+def _read_jsonl(path: Path, report: ImportReport) -> Iterator[Record]:
+    with open(path, encoding="utf-8") as f:
+        for number, line in enumerate(f, start=1):
+            if not line.strip():
+                continue
+            try:
+                record = json.loads(line)
+            except ValueError:
+                record = None
+            if not isinstance(record, dict):
+                report.records += 1
+                report.invalid += 1
+                report.warnings.append(f"Line {number}: not a JSON object")
+                continue
+            metadata = record.get("metadata") if isinstance(record.get("metadata"), dict) else {}
+            chunk_id = record.get("id") or metadata.get("id")
+            yield chunk_id, _text_of(record), metadata, record.get("embedding"), None
+
+
+def _read_csv(path: Path, report: ImportReport) -> Iterator[Record]:
+    with open(path, encoding="utf-8", newline="") as f:
+        for number, row in enumerate(csv.DictReader(f), start=2):
+            metadata = {}
+            if row.get("metadata"):
+                try:
+                    metadata = json.loads(row["metadata"])
+                except ValueError:
+                    report.warnings.append(f"Row {number}: metadata is not a JSON object")
+            if not isinstance(metadata, dict):
+                metadata = {}
+            embedding = None
+            if row.get("embedding"):
+                try:
+                    embedding = json.loads(row["embedding"])
+                except ValueError:
+                    report.warnings.append(f"Row {number}: embedding is not a JSON list")
+            for key, value in row.items():
+                if key not in TEXT_FIELDS + ("id", "metadata", "embedding") and value not in (None, ""):
+                    metadata.setdefault(key, value)
+            yield row.get("id") or None, _text_of(row), metadata, embedding, None
+
+
+def _read_chroma(path: Path, report: ImportReport) -> Iterator[Record]:
+    client = chromadb.PersistentClient(path=str(path), settings=Settings(anonymized_telemetry=False))
+    for listed in client.list_collections():
+        collection = client.get_collection(getattr(listed, "name", listed))
+        if collection.name.endswith(STAGING_SUFFIXES):
+            continue
+        model = (collection.metadata or {}).get("embedding_model")
+        total = collection.count()
+        for offset in range(0, total, IMPORT_BATCH_SIZE):
+            batch = collection.get(
+                include=["documents", "metadatas", "embeddings"], limit=IMPORT_BATCH_SIZE, offset=offset
+            )
+            embeddings = batch.get("embeddings")
+            for i, chunk_id in enumerate(batch["ids"]):
+                embedding = None if embeddings is None else list(embeddings[i])
+                yield chunk_id, batch["documents"][i] or "", batch["metadatas"][i] or {}, embedding, model
+
+
+READERS = {"chroma": _read_chroma, "jsonl": _read_jsonl, "csv": _read_csv}
+
+
+def import_external_index(
+    live: LocalVectorStore,
+    path: Path,
+    format: str,
+    collection: Optional[str] = None,
+    source_model: Optional[str] = None
+) -> ImportReport:
+    """
+    Add chunks from another tool's index to the collection.
+
+    Args:
+        live: Store to import into
+        path: Chroma persist directory, or JSONL / CSV file
+        format: "chroma", "jsonl" or "csv"
+        collection: Collection name (None = the store's collection)
+        source_model: Embedding model the source vectors were made with,
+                      when the source doesn't record it
+
+    Raises:
+        KeyError: If collection is not the store's collection
+        ValueError: If the format is unknown
+        FileNotFoundError: If path doesn't exist (or isn't a Chroma store)
+        CollectionReadOnlyError: If the collection is read-only
+    """
+    name = live.collection.name
+    check_collection(collection, name)
+    live.check_writable("import")
+    if format not in IMPORT_FORMATS:
+        raise ValueError(f"Unknown import format: {format} (expected one of {', '.join(IMPORT_FORMATS)})")
+
+    path = Path(path)
+    if format == "chroma" and not (path / "chroma.sqlite3").exists():
+        raise FileNotFoundError(f"No Chroma store at {path}")
+    if format != "chroma" and not path.is_file():
+        raise FileNotFoundError(f"Import file not found: {path}")
+
+    report = ImportReport(path=str(path), format=format, collection=name)
+    dimension = len(live.embedder.encode(["dimension check"]).tolist()[0])
+    now = datetime.now().isoformat()
+    chunk_counts: Dict[str, int] = {}
+    pending: List[Dict] = []
+    mismatched_dimensions = set()
+
+    def flush():
+        ids = [c["id"] for c in pending]
+        existing = set(live.collection.get(ids=ids, include=[])["ids"])
+        fresh = [c for c in pending if c["id"] not in existing]
+        report.skipped += len(pending) - len(fresh)
+        for group in (
+            [c for c in fresh if c["embedding"] is not None],
+            [c for c in fresh if c["embedding"] is None]
+        ):
+            if not group:
+                continue
+            chunks = {
+                "ids": [c["id"] for c in group],
+                "documents": [c["text"] for c in group],
+                "metadatas": [c["metadata"] for c in group]
+            }
+            if group[0]["embedding"] is not None:
+                chunks["embeddings"] = [c["embedding"] for c in group]
+                report.vectors_reused += len(group)
+            else:
+                report.re_embedded += len(group)
+            live.restore_chunks(chunks)   # Embeds chunks without vectors
+            report.added += len(group)
+        pending.clear()
+
+    for chunk_id, text, metadata, embedding, model in READERS[format](path, report):
+        report.records += 1
+        if not text.strip():
+            report.invalid += 1
+            continue
+
+        model = model or source_model
+        if embedding is not None:
+            if not isinstance(embedding, list) or len(embedding) != dimension:
+                mismatched_dimensions.add(len(embedding) if isinstance(embedding, list) else None)
+                embedding = None
+            elif model and model != live.embedding_model:
+                embedding = None
+
+        source = next((str(metadata[k]) for k in SOURCE_FIELDS if metadata.get(k)), str(path))
+        doc_hash = hashlib.sha256(f"import:{source}".encode("utf-8")).hexdigest()[:16]
+        index = chunk_counts.get(doc_hash, 0)
+        chunk_counts[doc_hash] = index + 1
+
+        meta = _scalar_metadata(metadata)
+        meta.update({
+            "doc_hash": doc_hash,
+            "chunk_index": index,
+            "filename": meta.get("filename") or Path(source).name,
+            "source_path": meta.get("source_path") or source,
+            "version": 1,
+            "is_latest": True,
+            "indexed_at": now,
+            "imported_from": str(path),
+            "import_format": format
+        })
+        pending.append({
+            "id": str(chunk_id) if chunk_id else f"{doc_hash}_{index}",
+            "text": text,
+            "metadata": meta,
+            "embedding": [float(v) for v in embedding] if embedding is not None else None
+        })
+        if len(pending) >= IMPORT_BATCH_SIZE:
+            flush()
+    if pending:
+        flush()
+
+    report.documents = len(chunk_counts)
+    if mismatched_dimensions:
+        report.warnings.append(
+            f"Vectors of dimension {', '.join(str(d) for d in sorted(mismatched_dimensions, key=str))} "
+            f"don't match {live.embedding_model} ({dimension}); those chunks were re-embedded"
+        )
+    logger.info(
+        f"Imported {report.added} chunks from {path} ({format}): "
+        f"{report.vectors_reused} vectors reused, {report.re_embedded} re-embedded, {report.skipped} skipped"
+    )
+    return report
@@ -0,0 +1,133 @@
+"""
+File Type Handlers for Jan Document Plugin
+
+Decides how each supported file extension is processed, so admins can
+disable risky formats or force OCR for types whose text layer can't be
+trusted:
+
+- python-default: the built-in extractor for the type (the default)
+- ocr-forced:     OCR every page, ignoring any text layer (PDFs and images)
+- skip:           the type is refused by uploads and batches and left out
+                  of folder scans
+
+Upload and batch validation, folder scans and ingestion all consult the
+registry. Overrides are kept in <storage>/file_type_handlers.json;
+extensions without one use python-default.
+"""
+
+import json
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, Optional, Set
+
+logger = logging.getLogger(__name__)
+
+DEFAULT_PIPELINE = "python-default"
+OCR_PIPELINE = "ocr-forced"
+SKIP_PIPELINE = "skip"
+HANDLER_PIPELINES = (DEFAULT_PIPELINE, OCR_PIPELINE, SKIP_PIPELINE)
+
+
+def _builtin_extensions():
+    from document_processor import DocumentExtractor
+    return DocumentExtractor.SUPPORTED_DOCS, DocumentExtractor.SUPPORTED_IMAGES
+
+
+def normalize_extension(extension: str) -> str:
+    """'PDF', 'pdf' and '.pdf' -> '.pdf'."""
+    extension = extension.strip().lower()
+    return extension if extension.startswith(".") else f".{extension}"
+
+
+class FileTypeHandlerRegistry:
+    """
+    Extension -> processing pipeline, with persisted overrides.
+
+    Usage:
+        handlers = FileTypeHandlerRegistry(Path("./jan_doc_store/file_type_handlers.json"))
+        handlers.set_file_type_handler(".doc", "skip")
+        handlers.set_file_type_handler(".pdf", "ocr-forced")
+        handlers.enabled_extensions()   # no .doc
+    """
+
+    def __init__(self, path: Optional[Path] = None):
+        """
+        Initialize registry.
+
+        Args:
+            path: JSON file holding the overrides (None = in memory)
+        """
+        self.path = Path(path) if path else None
+        self._lock = threading.Lock()
+        self._overrides: Dict[str, str] = {}
+        self._load()
+
+    def _load(self):
+        if not self.path or not self.path.exists():
+            return
+        try:
+            data = json.loads(self.path.read_text(encoding="utf-8"))
+        except (OSError, ValueError) as e:
+            logger.warning(f"Could not read file type handlers: {e}")
+            return
+        for extension, pipeline in (data.get("handlers") or {}).items():
+            if pipeline in HANDLER_PIPELINES:
+                self._overrides[extension] = pipeline
+
+    def _save(self):
+        if not self.path:
+            return
+        try:
+            self.path.parent.mkdir(parents=True, exist_ok=True)
+            self.path.write_text(json.dumps({"handlers": self._overrides}, indent=2), encoding="utf-8")
+        except OSError as e:
+            logger.warning(f"Could not save file type handlers: {e}")
+
+    def get_handler(self, extension: str) -> Optional[str]:
+        """Pipeline for an extension (None = not a supported type)."""
+        extension = normalize_extension(extension)
+        docs, images = _builtin_extensions()
+        if extension not in docs | images:
+            return None
+        return self._overrides.get(extension, DEFAULT_PIPELINE)
+
+    def set_file_type_handler(self, extension: str, pipeline: str) -> Dict:
+        """
+        Choose the pipeline for an extension.
+
+        Returns:
+            {"extension", "pipeline"}
+
+        Raises:
+            KeyError: If the extension is not a supported type
+            ValueError: If the pipeline is unknown, or ocr-forced is asked
+                        for a type that has no pages to OCR
+        """
+        extension = normalize_extension(extension)
+        docs, images = _builtin_extensions()
+        if extension not in docs | images:
+            raise KeyError(f"Unsupported file type: {extension}")
+        if pipeline not in HANDLER_PIPELINES:
+            raise ValueError(f"Unknown pipeline: {pipeline} (expected one of {', '.join(HANDLER_PIPELINES)})")
+        if pipeline == OCR_PIPELINE and extension not in images | {".pdf"}:
+            raise ValueError(f"{OCR_PIPELINE} applies to PDFs and images, not {extension}")
+
+        with self._lock:
+            if pipeline == DEFAULT_PIPELINE:
+                self._overrides.pop(extension, None)
+            else:
+                self._overrides[extension] = pipeline
+            self._save()
+        logger.info(f"File type handler for {extension}: {pipeline}")
+        return {"extension": extension, "pipeline": pipeline}
+
+    def enabled_extensions(self) -> Set[str]:
+        """Supported extensions not set to skip."""
+        docs, images = _builtin_extensions()
+        return {e for e in docs | images if self._overrides.get(e) != SKIP_PIPELINE}
+
+    def list_handlers(self) -> Dict[str, str]:
+        """Pipeline of every supported extension."""
+        docs, images = _builtin_extensions()
+        return {e: self._overrides.get(e, DEFAULT_PIPELINE) for e in sorted(docs | images)}
@@ -0,0 +1,220 @@
+"""
+History Export for Jan Document Plugin
+
+Writes what was indexed, and what was changed by hand or by policy, to a
+file an admin can archive or open in a spreadsheet:
+
+- processing: indexing and import events from the event log (file,
+  chunks, preset, OCR mode, chunk size, request id)
+- audit:      audit log entries (chunk edits, deletions, pruning,
+  metadata updates, resets)
+
+Rows of both sources are merged in time order and written as they are
+read, so long histories are never held in memory. Rows can be limited
+to a time range, to columns, and to rows whose columns have given
+values. Formats: "csv" (one row per entry, nested details as JSON) and
+"jsonl".
+
+The event log only keeps its newest events (see event_log.MAX_EVENTS);
+export regularly to keep a complete processing history.
+"""
+
+import csv
+import json
+import heapq
+import logging
+from pathlib import Path
+from typing import Dict, Iterator, List, Optional
+from dataclasses import dataclass, asdict, field
+from datetime import datetime
+
+logger = logging.getLogger(__name__)
+
+HISTORY_SOURCES = ("processing", "audit")
+HISTORY_FORMATS = ("csv", "jsonl")
+
+# Event log events that belong to the processing history
+PROCESSING_EVENT_PREFIXES = ("indexing.", "import.")
+
+HISTORY_COLUMNS = (
+    "time", "source", "event", "request_id", "target", "filename", "doc_hash",
+    "chunks", "preset", "ocr_mode", "chunk_size", "error", "details"
+)
+
+
+@dataclass
+class HistoryExportReport:
+    """Outcome of a history export."""
+    output_path: str
+    format: str
+    columns: List[str]
+    since: Optional[str] = None
+    until: Optional[str] = None
+    rows: int = 0
+    rows_by_source: Dict[str, int] = field(default_factory=dict)
+    bytes_written: int = 0
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+def _parse_time(value: Optional[str], name: str) -> Optional[datetime]:
+    if not value:
+        return None
+    try:
+        return datetime.fromisoformat(value)
+    except ValueError:
+        raise ValueError(f"Invalid date: {name} must be an ISO timestamp, got {value}")
+
+
+def _read_lines(path: Path) -> Iterator[Dict]:
+    if not path.exists():
+        return
+    with open(path, encoding="utf-8") as f:
+        for line in f:
+            try:
+                yield json.loads(line)
+            except ValueError:
+                continue   # Torn last line after a crash
+
+
+def processing_rows(event_log_path: Path) -> Iterator[Dict]:
+    """Indexing and import events as history rows, oldest first."""
+    for event in _read_lines(Path(event_log_path)):
+        name = event.get("event") or ""
+        if not name.startswith(PROCESSING_EVENT_PREFIXES):
+            continue
+        data = dict(event.get("data") or {})
+        yield {
+            "time": event.get("time"),
+            "source": "processing",
+            "event": name,
+            "request_id": event.get("request_id"),
+            "target": data.pop("batch_id", None) or data.get("filename") or data.get("path"),
+            "filename": data.pop("filename", None),
+            "doc_hash": data.pop("doc_hash", None),
+            "chunks": data.pop("chunks", None) or data.pop("total_chunks", None),
+            "preset": data.pop("preset", None),
+            "ocr_mode": data.pop("ocr_mode", None),
+            "chunk_size": data.pop("chunk_size", None),
+            "error": data.pop("error", None),
+            "details": data
+        }
+
+
+def audit_rows(audit_log_path: Path) -> Iterator[Dict]:
+    """Audit log entries as history rows, oldest first."""
+    for entry in _read_lines(Path(audit_log_path)):
+        details = dict(entry.get("details") or {})
+        yield {
+            "time": entry.get("time"),
+            "source": "audit",
+            "event": entry.get("action"),
+            "request_id": details.pop("request_id", None),
+            "target": entry.get("target"),
+            "filename": details.pop("filename", None),
+            "doc_hash": details.pop("doc_hash", None),
+            "chunks": details.pop("chunks", None),
+            "preset": None,
+            "ocr_mode": None,
+            "chunk_size": None,
+            "error": None,
+            "details": details
+        }
+
+
+def _matches(row: Dict, filters: Dict[str, str]) -> bool:
+    for column, value in filters.items():
+        cell = row.get(column)
+        if cell is None or str(cell) != str(value):
+            return False
+    return True
+
+
+def export_history(
+    event_log_path: Path,
+    audit_log_path: Path,
+    format: str,
+    output_path: Path,
+    since: Optional[str] = None,
+    until: Optional[str] = None,
+    sources: Optional[List[str]] = None,
+    columns: Optional[List[str]] = None,
+    filters: Optional[Dict[str, str]] = None
+) -> HistoryExportReport:
+    """
+    Write processing and audit history to a file.
+
+    Args:
+        event_log_path: The event log (processing history)
+        audit_log_path: The audit log
+        format: "csv" or "jsonl"
+        output_path: File to write; replaced if it exists
+        since: ISO timestamp; only later rows (None = from the start)
+        until: ISO timestamp; only earlier rows (None = up to now)
+        sources: Any of HISTORY_SOURCES (None = both)
+        columns: Columns to write, in order (None = HISTORY_COLUMNS)
+        filters: Column -> value; only rows with those values are written
+
+    Returns:
+        HistoryExportReport
+
+    Raises:
+        ValueError: If the format, a source, a column or a date is invalid
+        OSError: If the file can't be written
+    """
+    if format not in HISTORY_FORMATS:
+        raise ValueError(f"Unknown history format: {format} (expected one of {', '.join(HISTORY_FORMATS)})")
+    sources = list(sources or HISTORY_SOURCES)
+    unknown = [s for s in sources if s not in HISTORY_SOURCES]
+    if unknown:
+        raise ValueError(f"Unknown history source: {', '.join(unknown)} (expected {', '.join(HISTORY_SOURCES)})")
+    columns = list(columns or HISTORY_COLUMNS)
+    filters = filters or {}
+    unknown = [c for c in [*columns, *filters] if c not in HISTORY_COLUMNS]
+    if unknown:
+        raise ValueError(f"Unknown history column: {', '.join(unknown)} (expected {', '.join(HISTORY_COLUMNS)})")
+    since_time = _parse_time(since, "since")
+    until_time = _parse_time(until, "until")
+
+    readers = []
+    if "processing" in sources:
+        readers.append(processing_rows(event_log_path))
+    if "audit" in sources:
+        readers.append(audit_rows(audit_log_path))
+
+    output_path = Path(output_path)
+    report = HistoryExportReport(output_path=str(output_path), format=format, columns=columns, since=since, until=until)
+    output_path.parent.mkdir(parents=True, exist_ok=True)
+    partial = output_path.with_name(output_path.name + ".part")
+    try:
+        with open(partial, "w", encoding="utf-8", newline="") as f:
+            writer = None
+            if format == "csv":
+                writer = csv.DictWriter(f, fieldnames=columns, extrasaction="ignore")
+                writer.writeheader()
+            for row in heapq.merge(*readers, key=lambda r: r["time"] or ""):
+                row_time = datetime.fromisoformat(row["time"]) if row["time"] else None
+                if since_time and (row_time is None or row_time <= since_time):
+                    continue
+                if until_time and (row_time is None or row_time > until_time):
+                    continue
+                if not _matches(row, filters):
+                    continue
+                if writer:
+                    cells = {c: row.get(c) for c in columns}
+                    if "details" in cells:
+                        cells["details"] = json.dumps(row["details"], ensure_ascii=False, default=str) if row["details"] else ""
+                    writer.writerow(cells)
+                else:
+                    f.write(json.dumps({c: row.get(c) for c in columns}, ensure_ascii=False, default=str) + "\n")
+                report.rows += 1
+                report.rows_by_source[row["source"]] = report.rows_by_source.get(row["source"], 0) + 1
+        partial.replace(output_path)
+    finally:
+        if partial.exists():
+            partial.unlink()
+
+    report.bytes_written = output_path.stat().st_size
+    logger.info(f"Exported {report.rows} history rows to {output_path}")
+    return report
@@ -26,6 +26,7 @@ import json
 import logging
 import asyncio
 import tempfile
+import time
 from pathlib import Path
 import base64
 import re
@@ -38,6 +39,9 @@ import subprocess
 from fastapi import FastAPI, HTTPException, UploadFile, File, Form, Request, Response
 from fastapi.middleware.cors import CORSMiddleware
 from fastapi.responses import StreamingResponse, JSONResponse, FileResponse
+from fastapi.exceptions import RequestValidationError
+from fastapi.encoders import jsonable_encoder
+from starlette.exceptions import HTTPException as StarletteHTTPException
 from pydantic import BaseModel, Field
 import uvicorn
 
@@ -48,7 +52,16 @@ try:
 except ImportError:
     SPEECH_RECOGNITION_AVAILABLE = False
 
-from document_processor import DocumentProcessor, DocumentExtractor
+from document_processor import (
+    DocumentProcessor, FileLockedError, EmbeddingModelMismatchError, PasswordRequiredError,
+    OCR_MODES, QUERY_SOURCES
+)
+from resource_monitor import InsufficientDiskSpaceError
+from collection_quota import QuotaExceededError
+from collection_names import InvalidCollectionNameError
+from collection_holds import CollectionReadOnlyError
+from network_shares import ShareConnectionError
+from request_context import install_log_filter, request_scope, new_request_id, REQUEST_ID_HEADER
 
 # Consciousness Pipeline Integration
 try:
@@ -67,8 +80,9 @@ except ImportError:
 # Configure logging
 logging.basicConfig(
     level=logging.INFO,
-    format='%(asctime)s - %(name)s - %(levelname)s - %(message)s'
+    format='%(asctime)s - %(name)s - %(levelname)s - [%(request_id)s] %(message)s'
 )
+install_log_filter()
 logger = logging.getLogger("jan-proxy")
 
 
@@ -96,6 +110,36 @@ class ProxyConfig(BaseModel):
     persist_directory: str = "./jan_doc_store"
     tesseract_path: Optional[str] = None
     embedding_model: str = "all-MiniLM-L6-v2"
+    extraction_timeout: Optional[float] = None  # Seconds per document (None = no limit)
+    extraction_inactivity_timeout: Optional[float] = None  # Seconds without PDF page progress (None = no limit)
+    salvage_partial: bool = True                # On timeout, index pages extracted so far
+    disk_reserve_mb: int = 500                  # Free space kept on the storage volume
+    disk_guard_mode: str = "refuse"             # "refuse" or "warn" when space is short
+    lock_retries: int = 4                       # Attempts to read a file locked by another program
+    snapshot_files: bool = False                # Read from a temp copy instead of the original
+    trash_retention_days: int = 30              # Days deleted documents stay restorable
+    extract_tables: bool = False                # Index tables as structured table chunks
+    index_figures: bool = True                  # Index figure captions as figure chunks
+    describe_figures: bool = False              # Describe PDF figure images with a vision model via Jan
+    vision_model: Optional[str] = None          # Model for figure descriptions (None = chat_model)
+    footnote_mode: str = "metadata"             # "off", "metadata" or "chunks"
+    versioning: bool = False                    # Keep old versions of re-indexed files
+    usage_stats: bool = True                    # Count feature use locally
+    usage_upload_url: Optional[str] = None      # Opt-in upload target for usage counters
+    query_workers: int = 2                      # Threads reserved for queries during indexing
+    indexing_yield_seconds: float = 2.0         # Max pause of indexing while queries run (0 = off)
+    min_ocr_confidence: Optional[float] = None  # Flag OCR pages below this confidence (0-100)
+    index_low_confidence_ocr: bool = False      # Index flagged OCR pages anyway
+    thumbnail_cache_mb: int = 200               # Disk space for cached page thumbnails
+    retention_check_hours: float = 24.0         # How often retention policies are enforced (0 = never)
+    jan_data_dir: Optional[str] = None          # Jan's data folder for thread indexing (None = default)
+    narrate_progress: bool = False              # Narrate batch progress as sentences (screen readers)
+    progress_events_per_second: float = 2.0     # Batch progress events logged per batch per second (0 = all)
+    optimize_after_deleted: int = 5000          # Deleted chunks that trigger a database compaction (0 = never)
+    safe_mode_after_failed_starts: int = 3      # Failed starts in a row before starting in safe mode (0 = never)
+    default_collection: str = "jan_documents"   # Collection documents are indexed into
+    scan_engine: str = "walk"                   # Folder scan engine: walk or windows_search
+    portable: bool = False                      # Data and model cache kept beside the app (see app_paths)
 
     # Context injection settings
     auto_inject: bool = True           # Automatically inject context
@@ -125,6 +169,45 @@ Use this context to inform your response when relevant. If the context doesn't c
 config = ProxyConfig()
 
 
+def describe_image_via_jan(image: bytes, image_ext: str, caption: str) -> Optional[str]:
+    """
+    Describe a figure image with the configured vision model through Jan.
+
+    Called from document ingestion (worker threads), so it uses a
+    synchronous client. Returns None if Jan is unreachable or errors.
+    """
+    base_url = config.jan_ai_base_url if config.use_jan_ai_for_chat else config.jan_base_url
+    data_url = f"data:image/{image_ext or 'png'};base64,{base64.b64encode(image).decode('ascii')}"
+
+    request = {
+        "model": config.vision_model or config.chat_model,
+        "messages": [{
+            "role": "user",
+            "content": [
+                {
+                    "type": "text",
+                    "text": (
+                        "Describe this figure from a document in 2-4 sentences. "
+                        "Include chart type, axes, key values and trends. "
+                        f"Its caption is: {caption}"
+                    )
+                },
+                {"type": "image_url", "image_url": {"url": data_url}}
+            ]
+        }],
+        "temperature": 0.2,
+        "stream": False
+    }
+
+    try:
+        response = httpx.post(f"{base_url}/v1/chat/completions", json=request, timeout=120.0)
+        response.raise_for_status()
+        return response.json()["choices"][0]["message"]["content"].strip() or None
+    except Exception as e:
+        logger.warning(f"Figure description failed: {e}")
+        return None
+
+
 def detect_jan_version() -> Optional[str]:
     """Detect installed Jan version by reading its package.json."""
     try:
@@ -166,11 +249,312 @@ app.add_middleware(
     allow_credentials=True,
     allow_methods=["*"],
     allow_headers=["*"],
+    expose_headers=[REQUEST_ID_HEADER],
 )
 
 # Document processor - initialized on startup
 processor: Optional[DocumentProcessor] = None
 
+
+# Lazy usage statistics (local feature counters, opt-in upload)
+usage_stats = None
+
+def get_usage_stats():
+    """Get or create usage statistics instance."""
+    global usage_stats
+    if usage_stats is None:
+        from usage_stats import UsageStats
+        usage_stats = UsageStats(
+            Path(config.persist_directory) / "usage_stats.json",
+            upload_url=config.usage_upload_url,
+            app_version=app.version,
+            enabled=config.usage_stats
+        )
+    return usage_stats
+
+
+# Lazy enterprise policy (disabled features and pinned settings)
+policy_manager = None
+
+def get_policy_manager():
+    """Get or create enterprise policy manager instance."""
+    global policy_manager
+    if policy_manager is None:
+        from enterprise_policy import PolicyManager
+        policy_manager = PolicyManager()
+    return policy_manager
+
+
+# Lazy settings store (runtime changes to settings, on top of config.env)
+settings_store = None
+
+def get_settings_store():
+    """Get or create settings store instance."""
+    global settings_store
+    if settings_store is None:
+        from settings_store import SettingsStore
+        settings_store = SettingsStore(
+            Path(config.persist_directory) / "settings.json",
+            config,
+            is_locked=get_policy_manager().is_setting_locked
+        )
+        settings_store.subscribe(apply_changed_settings)
+    return settings_store
+
+
+def apply_changed_settings(changed: Dict[str, Any]):
+    """Hand changed settings to running components (settings store listener)."""
+    global retention_task
+    if processor is not None:
+        for name in ("extraction_timeout", "extraction_inactivity_timeout", "salvage_partial"):
+            if name in changed:
+                setattr(processor, name, changed[name])
+    if "optimize_after_deleted" in changed and collection_optimizer is not None:
+        collection_optimizer.optimize_after_deleted = changed["optimize_after_deleted"]
+    if "progress_events_per_second" in changed and event_log is not None:
+        event_log.set_progress_rate(changed["progress_events_per_second"])
+    if "disk_reserve_mb" in changed or "disk_guard_mode" in changed:
+        from resource_monitor import get_resource_monitor
+        thresholds = get_resource_monitor().thresholds
+        thresholds["disk_reserve_mb"] = config.disk_reserve_mb
+        thresholds["disk_guard_mode"] = config.disk_guard_mode
+    if "retention_check_hours" in changed and processor is not None:
+        if retention_task is not None:
+            retention_task.cancel()
+            retention_task = None
+        if config.retention_check_hours > 0:
+            retention_task = asyncio.get_event_loop().create_task(enforce_retention_periodically())
+    # Names only: values such as URLs stay on this machine
+    notify_webhooks("settings.changed", {"settings": sorted(changed)})
+
+
+def require_feature(feature: str):
+    """Raise 403 if enterprise policy disables the feature."""
+    from enterprise_policy import FeatureDisabledError
+    try:
+        get_policy_manager().require_feature(feature)
+    except FeatureDisabledError as e:
+        raise HTTPException(status_code=403, detail=e.to_dict())
+
+
+# Lazy OCR language pack manager
+ocr_language_manager = None
+
+def get_ocr_language_manager():
+    """Get or create OCR language pack manager instance."""
+    global ocr_language_manager
+    if ocr_language_manager is None:
+        from ocr_languages import OCRLanguageManager
+        ocr_language_manager = OCRLanguageManager()
+    return ocr_language_manager
+
+
+# Lazy page thumbnail cache
+thumbnail_cache = None
+
+def get_thumbnail_cache():
+    """Get or create page thumbnail cache instance."""
+    global thumbnail_cache
+    if thumbnail_cache is None:
+        from page_thumbnails import ThumbnailCache
+        thumbnail_cache = ThumbnailCache(
+            Path(config.persist_directory) / "thumbnails",
+            max_bytes=config.thumbnail_cache_mb * 1024 * 1024
+        )
+    return thumbnail_cache
+
+
+# Lazy audit log (manual and policy-driven changes to the index)
+audit_log = None
+
+def get_audit_log():
+    """Get or create audit log instance."""
+    global audit_log
+    if audit_log is None:
+        from audit_log import AuditLog
+        audit_log = AuditLog(Path(config.persist_directory) / "audit_log.jsonl")
+    return audit_log
+
+
+# Lazy webhook manager (pipeline event notifications)
+webhook_manager = None
+
+def get_webhook_manager():
+    """Get or create webhook manager instance."""
+    global webhook_manager
+    if webhook_manager is None:
+        from webhooks import WebhookManager
+        webhook_manager = WebhookManager(
+            Path(config.persist_directory) / "webhooks.json",
+            Path(config.persist_directory) / "webhook_deliveries.jsonl"
+        )
+    return webhook_manager
+
+
+# Lazy event log (recent pipeline events and batch progress, persisted)
+event_log = None
+
+def get_event_log():
+    """Get or create event log instance."""
+    global event_log
+    if event_log is None:
+        from event_log import EventLog
+        event_log = EventLog(
+            Path(config.persist_directory) / "event_log.jsonl",
+            progress_rate=config.progress_events_per_second
+        )
+    return event_log
+
+
+def notify_webhooks(event: str, data: Dict):
+    """Fire a pipeline event; webhook problems never fail the request."""
+    logged = get_event_log().record(event, data)
+    try:
+        get_webhook_manager().emit(event, data, request_id=logged.request_id)
+    except Exception as e:
+        logger.warning(f"Could not notify webhooks of {event}: {e}")
+
+
+# Lazy crash reporter (local crash log for unhandled exceptions)
+crash_reporter = None
+
+def get_crash_reporter():
+    """Get or create crash reporter instance."""
+    global crash_reporter
+    if crash_reporter is None:
+        from crash_reporter import CrashReporter
+        crash_reporter = CrashReporter(Path(config.persist_directory) / "crash_reports", app_version=app.version)
+    return crash_reporter
+
+
+# Lazy Python runtime status (package versions, bundle checksum, last success)
+python_status = None
+
+def get_python_status():
+    """Get or create Python status instance."""
+    global python_status
+    if python_status is None:
+        from python_status import PythonStatus
+        python_status = PythonStatus(app_version=app.version)
+    return python_status
+
+
+# Lazy startup guard (safe mode after repeated failed starts)
+startup_guard = None
+
+def get_startup_guard():
+    """Get or create startup guard instance."""
+    global startup_guard
+    if startup_guard is None:
+        from safe_mode import StartupGuard
+        startup_guard = StartupGuard(
+            Path(config.persist_directory) / "startup_state.json",
+            max_failed_starts=config.safe_mode_after_failed_starts
+        )
+    return startup_guard
+
+
+@app.exception_handler(Exception)
+async def report_unhandled_exception(request: Request, exc: Exception):
+    """Log the full traceback locally and return a sanitized summary."""
+    report = get_crash_reporter().capture(
+        exc, "request", f"{request.method} {request.url.path}",
+        request_id=getattr(request.state, "request_id", None)
+    )
+    return JSONResponse(
+        status_code=500,
+        content={
+            "error": "backend_crash",
+            "detail": "Internal error - see GET /debug/crashes/{crash_id} for details",
+            "crash": report.summary(),
+            "code": "backend_crash",
+            "params": {"crash_id": report.crash_id},
+            "fallback_message": f"Internal error - see GET /debug/crashes/{report.crash_id} for details"
+        }
+    )
+
+
+@app.exception_handler(EmbeddingModelMismatchError)
+async def report_model_mismatch(request: Request, exc: EmbeddingModelMismatchError):
+    """The collection was built with another embedding model: say how to fix it."""
+    from error_catalog import catalog_error
+    detail = exc.to_dict()
+    return JSONResponse(status_code=409, content={"detail": detail, **catalog_error(409, detail)})
+
+
+@app.exception_handler(InvalidCollectionNameError)
+async def report_invalid_collection_name(request: Request, exc: InvalidCollectionNameError):
+    """A collection name that breaks the naming rules, whatever the endpoint."""
+    from error_catalog import catalog_error
+    detail = exc.to_dict()
+    return JSONResponse(status_code=400, content={"detail": detail, **catalog_error(400, detail)})
+
+
+@app.exception_handler(CollectionReadOnlyError)
+async def report_collection_readonly(request: Request, exc: CollectionReadOnlyError):
+    """Any attempt to change a read-only (held) collection, whatever the endpoint."""
+    from error_catalog import catalog_error
+    detail = exc.to_dict()
+    return JSONResponse(status_code=423, content={"detail": detail, **catalog_error(423, detail)})
+
+
+@app.exception_handler(ShareConnectionError)
+async def report_share_connection(request: Request, exc: ShareConnectionError):
+    """A network share refused its stored credentials or couldn't be reached."""
+    from error_catalog import catalog_error
+    detail = exc.to_dict()
+    return JSONResponse(status_code=502, content={"detail": detail, **catalog_error(502, detail)})
+
+
+@app.exception_handler(StarletteHTTPException)
+async def catalog_http_exception(request: Request, exc: StarletteHTTPException):
+    """Add the stable error code, params and English fallback to error responses."""
+    from error_catalog import catalog_error
+    return JSONResponse(
+        status_code=exc.status_code,
+        content={"detail": exc.detail, **catalog_error(exc.status_code, exc.detail)},
+        headers=getattr(exc, "headers", None)
+    )
+
+
+@app.exception_handler(RequestValidationError)
+async def catalog_validation_error(request: Request, exc: RequestValidationError):
+    """Invalid parameters, reported with the invalid_parameters code."""
+    errors = jsonable_encoder(exc.errors())
+    return JSONResponse(
+        status_code=422,
+        content={
+            "detail": errors,
+            "code": "invalid_parameters",
+            "params": {"errors": errors},
+            "fallback_message": "Invalid request parameters"
+        }
+    )
+
+
+@app.middleware("http")
+async def count_feature_use(request: Request, call_next):
+    """Count requests per route (the route template, never its parameters)."""
+    response = await call_next(request)
+    route = request.scope.get("route")
+    path = getattr(route, "path", None)
+    if path and path not in ("/health", "/") and not path.startswith("/usage") and relocation_block() is None:
+        get_usage_stats().record(f"{request.method} {path}")
+    return response
+
+
+@app.middleware("http")
+async def assign_request_id(request: Request, call_next):
+    """Tag the request (logs, events, results) with an id, echoed in X-Request-ID."""
+    request_id = new_request_id(request.headers.get(REQUEST_ID_HEADER))
+    request.state.request_id = request_id
+    with request_scope(request_id):
+        response = await call_next(request)
+    response.headers[REQUEST_ID_HEADER] = request_id
+    if response.status_code < 400:
+        get_python_status().note_success()
+    return response
+
 # Consciousness Pipeline - initialized on startup if available
 consciousness_pipeline: Optional["ConsciousnessPipeline"] = None
 
@@ -182,7 +566,20 @@ consciousness_contexts: Dict[str, Dict[str, Any]] = {}
 @app.on_event("startup")
 async def startup():
     """Initialize document processor and consciousness pipeline on startup."""
-    global processor, consciousness_pipeline, detected_jan_version
+    global detected_jan_version
+
+    # Settings changed at runtime override config.env; policy overrides both
+    saved = get_settings_store().load()
+    if saved:
+        logger.info(f"Saved settings applied: {', '.join(saved)}")
+
+    # Pinned policy settings override config.env before anything uses them
+    pinned = get_policy_manager().apply_to_config(config)
+    if pinned:
+        logger.info(f"Settings pinned by policy: {', '.join(pinned)}")
+
+    # Temp files go under the storage folder; clear what a crash left behind
+    get_job_workspace().prepare()
 
     # Detect Jan version
     detected_jan_version = detect_jan_version()
@@ -193,16 +590,96 @@ async def startup():
     else:
         logger.info("Jan not detected — running standalone with bundled LLM server")
 
+    # Starts that never finished (e.g. a store crashing init) lead to safe mode
+    guard = get_startup_guard()
+    if guard.begin():
+        get_crash_reporter().install_hooks()
+        logger.warning(f"Starting in SAFE MODE: {guard.get_safe_mode_reason()}")
+        logger.warning("Document processing and scheduled work are off; POST /safe-mode/exit starts normally")
+        return
+
+    open_document_store()
+
+    # Uncaught exceptions in worker threads go to the crash log too
+    get_crash_reporter().install_hooks()
+
+    logger.info(f"Proxying to Jan server at: {config.jan_base_url}")
+    guard.complete()
+
+
+def open_document_store():
+    """
+    Open the document processor on the storage folder and start the work
+    that depends on it (retention checks, consciousness pipeline). Called
+    by startup, and after a failed data relocation to reopen the store
+    where it was.
+    """
+    global processor, consciousness_pipeline, retention_task
+
     logger.info("Initializing document processor...")
 
     processor = DocumentProcessor(
         persist_directory=config.persist_directory,
         tesseract_path=config.tesseract_path,
-        embedding_model=config.embedding_model
+        embedding_model=config.embedding_model,
+        collection_name=config.default_collection,
+        extraction_timeout=config.extraction_timeout,
+        extraction_inactivity_timeout=config.extraction_inactivity_timeout,
+        salvage_partial=config.salvage_partial,
+        lock_retries=config.lock_retries,
+        snapshot_files=config.snapshot_files,
+        extract_tables=config.extract_tables,
+        index_figures=config.index_figures,
+        image_describer=describe_image_via_jan if config.describe_figures else None,
+        footnote_mode=config.footnote_mode,
+        versioning=config.versioning,
+        min_ocr_confidence=config.min_ocr_confidence,
+        index_low_confidence_ocr=config.index_low_confidence_ocr
     )
 
     logger.info(f"Document processor ready. Storage: {config.persist_directory}")
-    logger.info(f"Proxying to Jan server at: {config.jan_base_url}")
+
+    processor.audit_log = get_audit_log()
+    processor.file_type_handlers = get_file_type_handlers()
+    processor.preset_store = get_preset_store()
+    processor.password_store = get_password_store()
+    processor.share_credentials = get_share_credentials()
+
+    # Per-collection stopwords/boosts for keyword scoring
+    from keyword_scoring import RetrievalConfigStore
+    processor.retrieval_configs = RetrievalConfigStore(Path(config.persist_directory) / "retrieval_config.json")
+    processor.vector_store.retrieval_config = processor.retrieval_configs.get(
+        processor.vector_store.collection.name
+    )
+
+    # Description, color and icon shown by the library UI
+    from collection_appearance import CollectionAppearanceStore
+    processor.collection_appearances = CollectionAppearanceStore(
+        Path(config.persist_directory) / "collection_appearance.json"
+    )
+
+    # Read-only flags (legal holds), checked before every write
+    from collection_holds import CollectionHoldStore
+    processor.collection_holds = CollectionHoldStore(Path(config.persist_directory) / "collection_holds.json")
+
+    # Large deletes schedule a compaction of the Chroma database
+    processor.vector_store.delete_hook = get_collection_optimizer().note_deleted
+
+    # Queries get their own lane; indexing yields to them between steps
+    from workload_lanes import configure_workload_lanes
+    lanes = configure_workload_lanes(config.query_workers, config.indexing_yield_seconds)
+    processor.vector_store.yield_hook = lanes.yield_to_interactive
+
+    # Disk space guards check the volume holding the vector store
+    from resource_monitor import get_resource_monitor
+    monitor = get_resource_monitor()
+    monitor.set_storage_path(config.persist_directory)
+    monitor.thresholds["disk_reserve_mb"] = config.disk_reserve_mb
+    monitor.thresholds["disk_guard_mode"] = config.disk_guard_mode
+
+    # Retention policies prune old documents to the trash periodically
+    if config.retention_check_hours > 0:
+        retention_task = asyncio.get_event_loop().create_task(enforce_retention_periodically())
 
     # Initialize consciousness pipeline if available
     if CONSCIOUSNESS_PIPELINE_AVAILABLE:
@@ -218,6 +695,34 @@ async def startup():
         logger.info("Consciousness pipeline not available")
 
 
+# Background retention check (see open_document_store)
+retention_task: Optional[asyncio.Task] = None
+
+
+async def enforce_retention_periodically():
+    """Background task applying retention policies every retention_check_hours."""
+    loop = asyncio.get_event_loop()
+    while True:
+        await asyncio.sleep(config.retention_check_hours * 3600)
+        retention = get_retention_manager()
+        if retention is None:
+            continue
+        try:
+            report = await loop.run_in_executor(None, retention.enforce)
+            if report.pruned:
+                logger.info(f"Retention check pruned {len(report.pruned)} document(s)")
+        except Exception as e:
+            logger.warning(f"Retention check failed: {e}")
+
+
+@app.on_event("shutdown")
+async def shutdown():
+    if usage_stats is not None:
+        usage_stats.flush()
+    if collection_optimizer is not None:
+        collection_optimizer.cancel()
+
+
 # ============================================================================
 # Pydantic Models (OpenAI-compatible)
 # ============================================================================
@@ -260,6 +765,17 @@ class DocumentUploadResponse(BaseModel):
     chunks: int
     tokens_estimate: int
     message: str
+    # Identity the upload is versioned under (see GET /documents/versions)
+    source_path: Optional[str] = None
+    # Set when extraction timed out and only part of the document was indexed
+    partial: bool = False
+    pages_completed: Optional[int] = None
+    total_pages: Optional[int] = None
+    # PDF pages whose text came from OCR
+    ocr_mode: Optional[str] = None
+    ocr_page_numbers: Optional[List[int]] = None
+    # OCR pages below min_ocr_confidence: {"page", "confidence", "excluded"}
+    low_confidence_pages: Optional[List[Dict]] = None
     # Consciousness pipeline fields (optional - populated if pipeline available)
     is_identity_payload: Optional[bool] = None
     identity_score: Optional[float] = None
@@ -274,6 +790,157 @@ class DocumentListResponse(BaseModel):
     total: int
 
 
+class SettingsUpdateRequest(BaseModel):
+    """Request body for changing settings: {"settings": {"max_chunks": 3}}."""
+    settings: Dict[str, Any]
+
+
+class SearchBatchRequest(BaseModel):
+    """Request body for running many searches at once; options apply to every query."""
+    queries: List[str]
+    n_results: int = 5
+    doc_hash: Optional[str] = None
+    heading: Optional[str] = None
+    sources: str = "both"
+    assistant_id: Optional[str] = None
+    collection: Optional[str] = None
+    recency_boost: Optional[float] = None
+    exclude_terms: List[str] = Field(default_factory=list)
+    exclude_filters: Optional[Dict[str, str]] = None
+    verify_sources: bool = False
+
+
+class EmbedRequest(BaseModel):
+    """Request body for embedding texts."""
+    texts: List[str]
+    model: Optional[str] = None     # None = the store's embedding model
+    normalize: bool = False
+
+
+class SimilarityRequest(BaseModel):
+    """Request body for comparing two items, each a text or an embedding."""
+    text_a: Optional[str] = None
+    text_b: Optional[str] = None
+    embedding_a: Optional[List[float]] = None
+    embedding_b: Optional[List[float]] = None
+
+
+class CollectionAppearanceRequest(BaseModel):
+    """Request body for a collection's appearance; fields left out are kept, null clears one."""
+    collection: Optional[str] = None
+    description: Optional[str] = None
+    color: Optional[str] = None
+    icon: Optional[str] = None
+
+
+class DocumentPasswordRequest(BaseModel):
+    """Request body for storing an encrypted document's password."""
+    source_path: str
+    password: str
+
+
+class ShareCredentialsRequest(BaseModel):
+    """Request body for storing a network share's credentials."""
+    root: str
+    username: str
+    password: str
+
+
+class ShareAccessRequest(BaseModel):
+    """Request body for testing access to a scan root."""
+    path: str
+
+
+class CollectionReadOnlyRequest(BaseModel):
+    """Request body for setting or clearing a collection's read-only flag."""
+    readonly: bool
+    collection: Optional[str] = None
+    reason: Optional[str] = None
+
+
+class PresetRequest(BaseModel):
+    """Request body for creating a processing preset."""
+    name: str
+    description: str = ""
+    chunk_size: int = 1000
+    chunk_overlap: int = 100
+    ocr_enabled: bool = True
+    smart_mode: bool = True
+    extract_tables: Optional[bool] = None
+    embedding_model: Optional[str] = None
+
+
+# Lazy processing preset store
+preset_store = None
+
+def get_preset_store():
+    """Get or create processing preset store instance."""
+    global preset_store
+    if preset_store is None:
+        from processing_presets import PresetStore
+        preset_store = PresetStore(Path(config.persist_directory) / "presets.json")
+    return preset_store
+
+
+# Lazy password store for encrypted documents (system keychain)
+password_store = None
+
+def get_password_store():
+    """Get or create document password store instance."""
+    global password_store
+    if password_store is None:
+        from document_passwords import DocumentPasswordStore
+        password_store = DocumentPasswordStore()
+    return password_store
+
+# Lazy scan snapshot store (last scans per root, for scan diffs)
+scan_snapshots = None
+
+def get_scan_snapshots():
+    """Get or create scan snapshot store instance."""
+    global scan_snapshots
+    if scan_snapshots is None:
+        from scan_snapshots import ScanSnapshotStore
+        scan_snapshots = ScanSnapshotStore(Path(config.persist_directory) / "scan_snapshots")
+    return scan_snapshots
+
+
+# Lazy credential store for network shares (system keychain)
+share_credentials = None
+
+def get_share_credentials():
+    """Get or create network share credential store instance."""
+    global share_credentials
+    if share_credentials is None:
+        from network_shares import ShareCredentialStore
+        share_credentials = ShareCredentialStore()
+    return share_credentials
+
+# Job folders of upload batches kept for files waiting for a password
+# (batch_id -> job_dir), released when the batch is resumed
+password_batch_jobs: Dict[str, Path] = {}
+
+
+# Lazy file type handler registry (skip / force OCR per extension)
+file_type_handlers = None
+
+def get_file_type_handlers():
+    """Get or create file type handler registry instance."""
+    global file_type_handlers
+    if file_type_handlers is None:
+        from file_type_handlers import FileTypeHandlerRegistry
+        file_type_handlers = FileTypeHandlerRegistry(Path(config.persist_directory) / "file_type_handlers.json")
+    return file_type_handlers
+
+
+def resolve_preset(name: Optional[str]):
+    """Look up a preset by name (empty = the applied preset), 404 if unknown."""
+    try:
+        return get_preset_store().resolve(name)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=str(e.args[0]))
+
+
 # ============================================================================
 # Batch Processing Support
 # ============================================================================
@@ -289,16 +956,27 @@ def get_batch_processor():
     if batch_processor is None and processor is not None:
         from batch_processor import BatchProcessor
         from resource_monitor import get_resource_monitor
-        batch_processor = BatchProcessor(processor, get_resource_monitor())
+        narrator = None
+        if config.narrate_progress:
+            from progress_narration import ProgressNarrator
+            narrator = ProgressNarrator()
+        batch_processor = BatchProcessor(
+            processor,
+            get_resource_monitor(),
+            state_path=Path(config.persist_directory) / "processing_state.json",
+            narrator=narrator
+        )
     return batch_processor
 
 
 class BatchUploadResponse(BaseModel):
     """Response for batch upload operations."""
     batch_id: str
+    request_id: Optional[str] = None
     total_files: int
     completed_files: int
     failed_files: int
+    skipped_files: int = 0
     progress_percent: float
     total_chunks: int
     processing_mode: str
@@ -328,13 +1006,37 @@ class ResourceStatusResponse(BaseModel):
 @app.post("/documents", response_model=DocumentUploadResponse)
 async def upload_document(
     file: UploadFile = File(...),
-    force_reindex: bool = Form(False)
+    force_reindex: bool = Form(False),
+    preset: Optional[str] = Form(None),
+    languages: Optional[str] = Form(None),
+    ocr_mode: Optional[str] = Form(None),
+    password: Optional[str] = Form(None),
+    remember_password: bool = Form(False),
+    source_path: Optional[str] = Form(None),
+    version_of: Optional[str] = Form(None)
 ):
     """
     Upload and index a document for context retrieval.
 
     Supports: PDF, DOCX, XLSX, TXT, images (with OCR)
 
+    preset names a processing preset (chunking/OCR settings); the applied
+    preset is used when omitted. languages lists OCR languages for scanned
+    pages, e.g. "eng,deu" (see GET /ocr/languages). ocr_mode is "auto"
+    (OCR only pages lacking a text layer - the default), "force" or "off";
+    POST /documents/analyze shows which pages would need OCR.
+
+    password opens an encrypted PDF/DOCX/XLSX (otherwise the one stored
+    for the file name is used); remember_password stores it in the system
+    keychain. Without a valid password the upload fails with 422
+    password_required or wrong_password.
+
+    An upload is only indexed as a new version of an earlier one when the
+    client identifies it: source_path is the file's path on the client
+    (uploads of the same path are versions of each other), version_of the
+    doc_hash of the indexed document it replaces (404 if unknown). Without
+    either, uploads that merely share a file name are separate documents.
+
     If consciousness pipeline is available, also analyzes document for:
     - Identity payloads (soul-state data)
     - Sigil patterns and resonance
@@ -343,9 +1045,19 @@ async def upload_document(
     if processor is None:
         raise HTTPException(status_code=503, detail="Processor not initialized")
 
+    processing_preset = resolve_preset(preset)
+    ocr_languages = [l for l in (languages or "").replace("+", ",").split(",") if l.strip()] or None
+    if ocr_languages:
+        try:
+            processor.extractor.check_ocr_languages(ocr_languages)
+        except ValueError as e:
+            raise HTTPException(status_code=400, detail=str(e))
+    if ocr_mode and ocr_mode not in OCR_MODES:
+        raise HTTPException(status_code=400, detail=f"Invalid ocr_mode: {ocr_mode}")
+
     # Validate extension
     suffix = Path(file.filename).suffix.lower()
-    supported = DocumentExtractor.get_supported_extensions()
+    supported = processor.supported_extensions()
 
     if suffix not in supported:
         raise HTTPException(
@@ -353,11 +1065,33 @@ async def upload_document(
             detail=f"Unsupported file type: {suffix}. Supported: {sorted(supported)}"
         )
 
-    # Save to temp file
-    with tempfile.NamedTemporaryFile(delete=False, suffix=suffix) as tmp:
-        content = await file.read()
+    content = await file.read()
+
+    # Refuse before indexing if the store volume is nearly full
+    from resource_monitor import get_resource_monitor
+    monitor = get_resource_monitor()
+    try:
+        monitor.ensure_disk_space(monitor.estimate_index_bytes(len(content)), operation="document upload")
+        get_quota_manager().check(
+            additional_documents=1,
+            additional_bytes=monitor.estimate_index_bytes(len(content)),
+            operation="document upload"
+        )
+    except InsufficientDiskSpaceError as e:
+        raise HTTPException(status_code=507, detail=e.to_dict())
+    except QuotaExceededError as e:
+        raise HTTPException(status_code=413, detail=e.to_dict())
+
+    # Save to the job's temp folder
+    job_dir = get_job_workspace().create_job("upload")
+    with tempfile.NamedTemporaryFile(delete=False, suffix=suffix, dir=job_dir) as tmp:
         tmp.write(content)
         tmp_path = tmp.name
+    try:
+        upload_path = processor.upload_source_path(tmp_path, file.filename, source_path, version_of)
+    except KeyError as e:
+        get_job_workspace().release(job_dir)
+        raise HTTPException(status_code=404, detail=e.args[0])
 
     # Consciousness pipeline analysis (if available)
     consciousness_result = None
@@ -372,8 +1106,23 @@ async def upload_document(
             logger.warning(f"Consciousness pipeline error (non-fatal): {e}")
 
     try:
-        # Process document (standard indexing)
-        result = processor.ingest(tmp_path, force=force_reindex)
+        # Process document (standard indexing) on the background lane, so
+        # queries aren't blocked while it runs
+        from workload_lanes import get_workload_lanes
+        result = await get_workload_lanes().run_background(
+            processor.ingest,
+            tmp_path,
+            force=force_reindex,
+            preset=processing_preset,
+            source_path=upload_path,
+            languages=ocr_languages,
+            ocr_mode=ocr_mode,
+            password=password
+        )
+        if password and remember_password:
+            get_password_store().set(file.filename, password)
+        if force_reindex:
+            invalidate_similarity_graph(result.doc_hash)
 
         # Store consciousness context if identity payload detected
         if consciousness_result and consciousness_result.get("is_identity_payload"):
@@ -393,9 +1142,27 @@ async def upload_document(
             filename=file.filename,
             chunks=len(result.chunks),
             tokens_estimate=result.total_tokens_estimate,
-            message=f"Indexed {file.filename}: {len(result.chunks)} chunks"
+            message=f"Indexed {file.filename}: {len(result.chunks)} chunks",
+            source_path=upload_path,
+            ocr_mode=result.ocr_mode,
+            ocr_page_numbers=result.ocr_page_numbers,
+            low_confidence_pages=result.low_confidence_pages
         )
 
+        if result.low_confidence_pages:
+            response.message += (
+                f" [{len(result.low_confidence_pages)} page(s) below OCR confidence threshold]"
+            )
+
+        if result.partial:
+            response.partial = True
+            response.pages_completed = result.pages_completed
+            response.total_pages = result.total_pages
+            response.message += (
+                f" [PARTIAL: extraction timed out after "
+                f"{result.pages_completed}/{result.total_pages} pages]"
+            )
+
         # Add consciousness fields if available
         if consciousness_result:
             response.is_identity_payload = consciousness_result.get("is_identity_payload")
@@ -408,43 +1175,233 @@ async def upload_document(
             if consciousness_result.get("is_identity_payload"):
                 response.message += " [CONSCIOUSNESS SEED DETECTED]"
 
+        notify_webhooks("indexing.completed", {
+            "source": "upload",
+            "filename": file.filename,
+            "doc_hash": result.doc_hash,
+            "chunks": len(result.chunks),
+            "partial": result.partial,
+            "preset": processing_preset.name if processing_preset else None,
+            "ocr_mode": result.ocr_mode,
+            "chunk_size": processing_preset.chunk_size if processing_preset else processor.chunker.chunk_size
+        })
         return response
 
+    except FileLockedError as e:
+        logger.warning(str(e))
+        notify_webhooks("indexing.failed", {"source": "upload", "filename": file.filename, "error": str(e)})
+        raise HTTPException(status_code=423, detail={
+            "error": e.error_type,
+            "message": str(e),
+            "filename": file.filename,
+            "attempts": e.attempts
+        })
+
+    except EmbeddingModelMismatchError as e:
+        notify_webhooks("indexing.failed", {"source": "upload", "filename": file.filename, "error": str(e)})
+        raise HTTPException(status_code=409, detail=e.to_dict())
+
+    except PasswordRequiredError as e:
+        notify_webhooks("indexing.failed", {"source": "upload", "filename": file.filename, "error": str(e)})
+        raise HTTPException(status_code=422, detail=e.to_dict())
+
+    except CollectionReadOnlyError:
+        raise
+
     except Exception as e:
-        import traceback as tb
-        error_tb = tb.format_exc()
-        logger.error(f"Failed to process {file.filename}: {e}\n{error_tb}")
-        raise HTTPException(status_code=500, detail=f"{e}\n\nTraceback:\n{error_tb}")
+        # Full traceback goes to the crash log; the response gets its summary
+        report = get_crash_reporter().capture(e, "request", f"POST /documents ({file.filename})")
+        notify_webhooks("indexing.failed", {"source": "upload", "filename": file.filename, "error": str(e)})
+        raise HTTPException(status_code=500, detail={
+            "error": "indexing_failed",
+            "message": f"Could not index {file.filename}: {report.message}",
+            "filename": file.filename,
+            "reason": report.message,
+            "crash": report.summary()
+        })
 
     finally:
-        # Cleanup temp file
-        try:
-            os.unlink(tmp_path)
-        except Exception:
-            pass
+        get_job_workspace().release(job_dir)
+
+
+@app.post("/documents/analyze")
+async def analyze_document(file: UploadFile = File(...)):
+    """
+    Check whether a document needs OCR before processing it.
+
+    Reports the text layer of each PDF page (characters, images) and
+    which pages lack one - those are what ocr_mode "auto" would OCR.
+    Encrypted files are reported with requires_password, so the UI can
+    ask for the password before processing them.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    suffix = Path(file.filename).suffix.lower()
+    job_dir = get_job_workspace().create_job("analyze")
+    with tempfile.NamedTemporaryFile(delete=False, suffix=suffix, dir=job_dir) as tmp:
+        tmp.write(await file.read())
+        tmp_path = tmp.name
+
+    from workload_lanes import get_workload_lanes
+    try:
+        analysis = await get_workload_lanes().run_interactive(
+            processor.extractor.analyze_document, Path(tmp_path)
+        )
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    finally:
+        get_job_workspace().release(job_dir)
+
+    result = analysis.to_dict()
+    result["filename"] = file.filename
+    result["ocr_available"] = processor.extractor.ocr_available
+    return result
+
+
+@app.post("/documents/check-duplicate")
+async def check_duplicate_document(
+    file: UploadFile = File(...),
+    threshold: float = Form(0.95),
+    collection: Optional[str] = Form(None)
+):
+    """
+    Check whether a document is essentially already indexed, without
+    indexing it: reports the most similar indexed documents with scores
+    (1.0 = identical content) and whether the best reaches threshold.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    suffix = Path(file.filename).suffix.lower()
+    if suffix not in processor.supported_extensions():
+        raise HTTPException(
+            status_code=400,
+            detail=f"Unsupported file type: {suffix}. Supported: {sorted(processor.supported_extensions())}"
+        )
+    job_dir = get_job_workspace().create_job("duplicate-check")
+    with tempfile.NamedTemporaryFile(delete=False, suffix=suffix, dir=job_dir) as tmp:
+        tmp.write(await file.read())
+        tmp_path = tmp.name
+
+    from duplicate_check import check_duplicate
+    from workload_lanes import get_workload_lanes
+    try:
+        return await get_workload_lanes().run_interactive(
+            check_duplicate,
+            processor,
+            tmp_path,
+            collection=collection,
+            threshold=threshold,
+            source_path=file.filename
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    finally:
+        get_job_workspace().release(job_dir)
+
+
+@app.get("/documents/thumbnail")
+async def get_page_thumbnail(
+    doc_hash: Optional[str] = None,
+    path: Optional[str] = None,
+    page: int = 1,
+    dpi: int = 72
+):
+    """
+    PNG image of a document page, for previews next to query results.
+
+    Identify the document by doc_hash or by the path it was indexed from.
+    Only documents indexed from a file that still exists can be rendered
+    (uploaded files are not kept).
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+    if not doc_hash and not path:
+        raise HTTPException(status_code=400, detail="doc_hash or path is required")
+
+    chunks = processor.get_chunks(source_path=path, doc_hash=doc_hash)
+    if not chunks:
+        raise HTTPException(status_code=404, detail=f"Document not indexed: {doc_hash or path}")
+    source_path = chunks[0]["metadata"].get("source_path") or path
+
+    loop = asyncio.get_event_loop()
+    try:
+        image_path = await loop.run_in_executor(
+            None, get_thumbnail_cache().render_page_thumbnail, source_path, page, dpi
+        )
+    except FileNotFoundError:
+        raise HTTPException(status_code=404, detail=f"Source file is no longer available: {source_path}")
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return FileResponse(str(image_path), media_type="image/png")
+
+
+@app.get("/thumbnails/cache")
+async def get_thumbnail_cache_stats():
+    """Size and hit rate of the page thumbnail cache."""
+    return get_thumbnail_cache().get_cache_stats()
+
+
+@app.delete("/thumbnails/cache")
+async def clear_thumbnail_cache():
+    """Delete all cached page thumbnails."""
+    removed = get_thumbnail_cache().clear()
+    return {"success": True, "removed": removed}
 
 
 @app.post("/documents/batch", response_model=BatchUploadResponse)
 async def upload_documents_batch(
     files: TypingList[UploadFile] = File(...),
-    force_reindex: bool = Form(False)
+    force_reindex: bool = Form(False),
+    ignore_power_state: bool = Form(False),
+    preset: Optional[str] = Form(None),
+    dry_run: bool = Form(False),
+    skip_duplicates: bool = Form(False),
+    duplicate_threshold: float = Form(0.95)
 ):
     """
     Upload and index multiple documents at once.
     
     Automatically determines optimal processing strategy based on
     system resources (sequential, parallel, or chunked).
+
+    On battery or in battery-saver mode the batch waits for AC power
+    unless ignore_power_state is set. preset names the processing preset
+    for every file (the applied preset when omitted).
+
+    dry_run only extracts and chunks the files: nothing is embedded or
+    stored, and the response projects chunk counts, embedding time and
+    per-file warnings instead of batch progress.
+
+    skip_duplicates leaves out files that score at least duplicate_threshold
+    against an indexed document (see /documents/check-duplicate); they are
+    reported as skipped with the document they duplicate.
+
+    Password-protected files without a stored password fail with error_type
+    password_required (or wrong_password) and are kept: store their
+    passwords (PUT /documents/passwords, by file name) and resume the batch
+    (POST /documents/batch/{batch_id}/resume).
     
     Supports: PDF, DOCX, XLSX, TXT, images (with OCR)
     """
     bp = get_batch_processor()
     if bp is None:
         raise HTTPException(status_code=503, detail="Batch processor not initialized")
+    from batch_processor import PASSWORD_ERROR_TYPES
+    
+    processing_preset = resolve_preset(preset)
+    if skip_duplicates and not 0 <= duplicate_threshold <= 1:
+        raise HTTPException(status_code=400, detail="duplicate_threshold must be between 0 and 1")
     
     # Validate files
-    supported = DocumentExtractor.get_supported_extensions()
+    supported = processor.supported_extensions()
     valid_files = []
     temp_paths = []
+    job_dir = get_job_workspace().create_job("batch")
     
     for file in files:
         suffix = Path(file.filename).suffix.lower()
@@ -452,31 +1409,82 @@ async def upload_documents_batch(
             logger.warning(f"Skipping unsupported file: {file.filename}")
             continue
         
-        # Save to temp file
-        with tempfile.NamedTemporaryFile(delete=False, suffix=suffix) as tmp:
+        # Save to the job's temp folder
+        with tempfile.NamedTemporaryFile(delete=False, suffix=suffix, dir=job_dir) as tmp:
             content = await file.read()
             tmp.write(content)
             temp_paths.append(tmp.name)
-            valid_files.append(file.filename)
+            valid_files.append(processor.upload_source_path(tmp.name, file.filename))
     
     if not temp_paths:
+        get_job_workspace().release(job_dir)
         raise HTTPException(
             status_code=400,
             detail=f"No valid files. Supported: {sorted(supported)}"
         )
     
+    result = None
     try:
-        # Process batch
-        result = bp.process_batch_sync(
+        # Refuse the whole batch up front if it would exceed the quota
+        input_bytes = sum(os.path.getsize(path) for path in temp_paths)
+        try:
+            get_quota_manager().check(
+                additional_documents=len(temp_paths),
+                additional_bytes=bp.monitor.estimate_index_bytes(input_bytes),
+                operation="batch processing"
+            )
+            quota_warning = None
+        except QuotaExceededError as e:
+            if not dry_run:
+                raise
+            quota_warning = str(e)
+
+        if dry_run:
+            report = await bp.dry_run_batch_async(
+                temp_paths,
+                force_reindex=force_reindex,
+                preset=processing_preset,
+                source_paths=valid_files
+            )
+            if quota_warning:
+                report.warnings.append(quota_warning)
+            return JSONResponse(content=report.to_dict())
+
+        # Process batch off the event loop so other requests (e.g. resume)
+        # are still served while it runs or waits on a pause
+        result = await bp.process_batch_async(
             temp_paths,
-            force_reindex=force_reindex
+            force_reindex=force_reindex,
+            progress_callback=get_event_log().record_batch_progress,
+            ignore_power_state=ignore_power_state,
+            preset=processing_preset,
+            source_paths=valid_files,
+            duplicate_threshold=duplicate_threshold if skip_duplicates else None
         )
-        
+    except InsufficientDiskSpaceError as e:
+        raise HTTPException(status_code=507, detail=e.to_dict())
+    except QuotaExceededError as e:
+        raise HTTPException(status_code=413, detail=e.to_dict())
+    else:
+        if force_reindex:
+            invalidate_similarity_graph()
+        notify_webhooks("indexing.failed" if result.failed_files else "indexing.completed", {
+            "source": "batch",
+            "batch_id": result.batch_id,
+            "total_files": result.total_files,
+            "completed_files": result.completed_files,
+            "failed_files": result.failed_files,
+            "skipped_files": result.skipped_files,
+            "total_chunks": result.total_chunks,
+            "preset": processing_preset.name if processing_preset else None
+        })
         return BatchUploadResponse(
             batch_id=result.batch_id,
+            request_id=result.request_id,
             total_files=result.total_files,
             completed_files=result.completed_files,
             failed_files=result.failed_files,
+            skipped_files=result.skipped_files,
             progress_percent=result.progress_percent,
             total_chunks=result.total_chunks,
             processing_mode=result.processing_mode.value,
@@ -488,12 +1496,11 @@ async def upload_documents_batch(
         )
     
     finally:
-        # Cleanup temp files
-        for path in temp_paths:
-            try:
-                os.unlink(path)
-            except:
-                pass
+        # Files waiting for a password stay until the batch is resumed
+        if result is not None and any(f.error_type in PASSWORD_ERROR_TYPES for f in result.files):
+            password_batch_jobs[result.batch_id] = job_dir
+        else:
+            get_job_workspace().release(job_dir)
 
 
 @app.get("/documents/capacity", response_model=ResourceStatusResponse)
@@ -535,78 +1542,1970 @@ async def get_resource_capacity():
     )
 
 
-@app.get("/documents/batch/{batch_id}")
-async def get_batch_status(batch_id: str):
-    """Get status of a batch upload operation."""
+@app.post("/processing/pause")
+async def pause_processing():
+    """
+    Pause all batch processing.
+
+    Files already in progress finish; queued files wait. The paused flag
+    is persisted, so processing stays paused across restarts until resumed.
+    """
     bp = get_batch_processor()
     if bp is None:
         raise HTTPException(status_code=503, detail="Batch processor not initialized")
-    
-    status = bp.get_batch_status(batch_id)
-    if status is None:
-        raise HTTPException(status_code=404, detail=f"Batch not found: {batch_id}")
-    
-    return status.to_dict()
 
+    bp.pause()
+    return bp.get_processing_status()
 
-@app.get("/documents", response_model=DocumentListResponse)
-async def list_documents():
-    """List all indexed documents."""
-    if processor is None:
-        raise HTTPException(status_code=503, detail="Processor not initialized")
-    
-    docs = processor.list_documents()
-    return DocumentListResponse(documents=docs, total=len(docs))
 
+@app.post("/processing/resume")
+async def resume_processing():
+    """Resume batch processing after a pause."""
+    bp = get_batch_processor()
+    if bp is None:
+        raise HTTPException(status_code=503, detail="Batch processor not initialized")
 
-@app.delete("/documents/{doc_hash}")
-async def delete_document(doc_hash: str):
-    """Remove a document from the index."""
-    if processor is None:
+    bp.resume()
+    return bp.get_processing_status()
+
+
+@app.get("/processing/status")
+async def get_processing_status():
+    """Get the paused flag and queued/in-progress file counts."""
+    bp = get_batch_processor()
+    if bp is None:
+        raise HTTPException(status_code=503, detail="Batch processor not initialized")
+
+    return bp.get_processing_status()
+
+
+@app.get("/processing/narration")
+async def get_processing_narration(since: int = 0, batch_id: Optional[str] = None):
+    """
+    Batch progress as readable sentences, for screen readers. Poll with
+    since set to the last sequence number read. Requires NARRATE_PROGRESS.
+    """
+    bp = get_batch_processor()
+    if bp is None:
+        raise HTTPException(status_code=503, detail="Batch processor not initialized")
+    if bp.narrator is None:
+        return {"enabled": False, "events": [], "last_sequence": 0}
+
+    return {
+        "enabled": True,
+        "events": bp.narrator.get_events(since=since, batch_id=batch_id),
+        "last_sequence": bp.narrator.last_sequence
+    }
+
+
+@app.get("/events/requests/{request_id}")
+async def get_events_for_request(request_id: str):
+    """
+    Everything recorded for one API request (its X-Request-ID): pipeline
+    events and, with NARRATE_PROGRESS, narrated batch progress.
+    """
+    bp = get_batch_processor()
+    narration = bp.narrator.get_events(request_id=request_id) if bp and bp.narrator else []
+    return {
+        "request_id": request_id,
+        "events": get_event_log().get_events_for_request(request_id),
+        "narration": narration
+    }
+
+
+@app.get("/events/replay")
+async def replay_events(since: Optional[str] = None, request_id: Optional[str] = None):
+    """
+    Events since a time (ISO timestamp) and/or for one request, with the
+    last known progress of each batch among them - for a UI rebuilding
+    its state after a reload. Events survive restarts.
+    """
+    try:
+        return get_event_log().replay_events(since=since, request_id=request_id)
+    except ValueError:
+        raise HTTPException(status_code=400, detail=f"Invalid date: since must be an ISO timestamp, got {since}")
+
+
+@app.get("/presets")
+async def list_presets():
+    """List processing presets and which one is applied by default."""
+    store = get_preset_store()
+    return {
+        "presets": [p.to_dict() for p in store.list_presets()],
+        "active_preset": store.active_preset
+    }
+
+
+@app.post("/presets")
+async def create_preset(request: PresetRequest):
+    """Create or replace a user processing preset."""
+    from processing_presets import ProcessingPreset
+
+    try:
+        preset = get_preset_store().create_preset(ProcessingPreset(**request.model_dump()))
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return {"success": True, "preset": preset.to_dict()}
+
+
+@app.delete("/presets/{name}")
+async def delete_preset(name: str):
+    """Delete a user processing preset."""
+    try:
+        get_preset_store().delete_preset(name)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=str(e.args[0]))
+
+    return {"success": True, "message": f"Deleted preset: {name}"}
+
+
+@app.post("/presets/{name}/apply")
+async def apply_preset(name: str):
+    """Use a preset for uploads and batches that don't name one."""
+    try:
+        preset = get_preset_store().apply_preset(name)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=str(e.args[0]))
+
+    return {"success": True, "active_preset": preset.name, "preset": preset.to_dict()}
+
+
+@app.get("/file-types")
+async def list_file_type_handlers():
+    """How each supported extension is processed."""
+    from file_type_handlers import HANDLER_PIPELINES
+    return {"handlers": get_file_type_handlers().list_handlers(), "pipelines": list(HANDLER_PIPELINES)}
+
+
+@app.put("/file-types/{extension}")
+async def set_file_type_handler(extension: str, pipeline: str = Form(...)):
+    """
+    Choose how an extension is processed: "python-default", "ocr-forced"
+    (OCR every page; PDFs and images) or "skip" (refused by uploads and
+    batches, left out of folder scans).
+    """
+    handlers = get_file_type_handlers()
+    try:
+        result = handlers.set_file_type_handler(extension, pipeline)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    get_audit_log().record("file_type_handler_set", result["extension"], {"pipeline": pipeline})
+    return {"success": True, **result, "handlers": handlers.list_handlers()}
+
+
+@app.get("/documents/batch/{batch_id}")
+async def get_batch_status(batch_id: str):
+    """Get status of a batch upload operation."""
+    bp = get_batch_processor()
+    if bp is None:
+        raise HTTPException(status_code=503, detail="Batch processor not initialized")
+    
+    status = bp.get_batch_status(batch_id)
+    if status is None:
+        raise HTTPException(status_code=404, detail=f"Batch not found: {batch_id}")
+    
+    return status.to_dict()
+
+
+@app.post("/documents/batch/{batch_id}/resume")
+async def resume_password_batch(batch_id: str):
+    """
+    Process a batch's password-protected files again, after their
+    passwords were stored (PUT /documents/passwords). Files still lacking
+    a valid password stay failed and can be resumed again.
+    """
+    bp = get_batch_processor()
+    if bp is None:
+        raise HTTPException(status_code=503, detail="Batch processor not initialized")
+    from batch_processor import PASSWORD_ERROR_TYPES
+
+    try:
+        result = await bp.retry_failed_files_async(
+            batch_id,
+            progress_callback=get_event_log().record_batch_progress
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=409, detail=str(e))
+
+    if not any(f.error_type in PASSWORD_ERROR_TYPES for f in result.files) and batch_id in password_batch_jobs:
+        get_job_workspace().release(password_batch_jobs.pop(batch_id))
+    return result.to_dict()
+
+
+@app.put("/documents/passwords")
+async def set_document_password(request: DocumentPasswordRequest):
+    """
+    Store the password of an encrypted document, by its source path (for
+    uploads, the file name). It is kept in the system keychain, or for
+    this session only if there is none; it is never returned.
+    """
+    store = get_password_store()
+    try:
+        store.set(request.source_path, request.password)
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    get_audit_log().record("document_password_set", request.source_path, {"backend": store.backend})
+    return {"success": True, "source_path": request.source_path, "backend": store.backend}
+
+
+@app.delete("/documents/passwords")
+async def delete_document_password(source_path: str):
+    """Forget the stored password of a document."""
+    if not get_password_store().delete(source_path):
+        raise HTTPException(status_code=404, detail=f"No password stored for {source_path}")
+
+    get_audit_log().record("document_password_deleted", source_path, {})
+    return {"success": True, "source_path": source_path}
+
+
+@app.put("/shares/credentials")
+async def set_share_credentials(request: ShareCredentialsRequest):
+    """
+    Store the credentials of a network share (\\\\server\\share), used to
+    connect it before its folders are scanned or its files processed.
+    They are kept in the system keychain, or for this session only if
+    there is none; the password is never returned.
+    """
+    store = get_share_credentials()
+    try:
+        root = store.set(request.root, request.username, request.password)
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    get_audit_log().record("share_credentials_set", root, {
+        "username": request.username,
+        "backend": store.backend
+    })
+    return {"success": True, "root": root, "username": request.username, "backend": store.backend}
+
+
+@app.delete("/shares/credentials")
+async def delete_share_credentials(root: str):
+    """Forget the stored credentials of a network share."""
+    if not get_share_credentials().delete(root):
+        raise HTTPException(status_code=404, detail=f"No credentials stored for {root}")
+
+    get_audit_log().record("share_credentials_deleted", root, {})
+    return {"success": True, "root": root}
+
+
+@app.post("/shares/test")
+async def test_root_access(request: ShareAccessRequest):
+    """
+    Check that a scan root is reachable: connects it if it is a network
+    share with stored credentials, then checks it exists and can be
+    listed. The result says which step failed.
+    """
+    loop = asyncio.get_event_loop()
+    result = await loop.run_in_executor(None, get_share_credentials().test_root_access, request.path)
+    return result.to_dict()
+
+
+@app.post("/documents/scan")
+async def scan_documents_directory(
+    directory: str = Form(...),
+    recursive: bool = Form(True),
+    partial_hashes_only: bool = Form(False),
+    engine: Optional[str] = Form(None),
+    snapshot: bool = Form(True)
+):
+    """
+    Scan a local directory for supported documents before ingestion.
+
+    Files with identical content are grouped; every copy after the first is
+    marked with duplicate_of so the UI can offer to index only one copy.
+
+    engine is "walk" or "windows_search" (default: SCAN_ENGINE); the
+    response says which engine found the files, since Windows Search falls
+    back to walking folders it hasn't indexed. A network share with
+    stored credentials (PUT /shares/credentials) is connected first.
+
+    With snapshot (default) the scan is kept as the root's latest
+    snapshot, for GET /documents/scan/diff.
+    """
+    require_feature("folder_scan")
+
+    from directory_scanner import scan_directory
+
+    def scan():
+        get_share_credentials().connect(directory)
+        return scan_directory(
+            directory,
+            recursive=recursive,
+            extensions=processor.supported_extensions() if processor else None,
+            partial_hashes_only=partial_hashes_only,
+            engine=engine or config.scan_engine
+        )
+
+    try:
+        result = await asyncio.get_event_loop().run_in_executor(None, scan)
+    except (NotADirectoryError, ValueError) as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    response = result.to_dict()
+    if snapshot:
+        response["snapshot"] = get_scan_snapshots().record(result)
+    return response
+
+
+@app.get("/documents/scan/snapshots")
+async def list_scan_snapshots():
+    """Kept scan snapshots of every scanned root."""
+    return {"snapshots": get_scan_snapshots().list_snapshots()}
+
+
+@app.get("/documents/scan/diff")
+async def diff_scans(directory: str):
+    """
+    Files added, removed and modified between the last two scans of a
+    directory (size or modified time changed).
+    """
+    try:
+        return get_scan_snapshots().diff_scans(directory).to_dict()
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+
+@app.post("/documents/scan/diff/process")
+async def process_scan_diff(directory: str = Form(...)):
+    """
+    Index the files added or modified between the last two scans of a
+    directory as one batch. As with POST /collections/stale/refresh, a
+    modified file's old content is kept as a superseded version with
+    versioning, otherwise removed once it is re-indexed. Removed files
+    are only reported.
+    """
+    require_feature("folder_scan")
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+    bp = get_batch_processor()
+    if bp is None:
+        raise HTTPException(status_code=503, detail="Batch processor not initialized")
+
+    try:
+        diff = get_scan_snapshots().diff_scans(directory)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    changed = diff.changed_paths
+    if not changed:
+        return {"diff": diff.to_dict(), "batch": None}
+
+    replaced = {}
+    if diff.modified and not processor.versioning:
+        loop = asyncio.get_event_loop()
+        stale = await loop.run_in_executor(None, processor.find_stale_sources)
+        modified = set(diff.modified)
+        replaced = {s["source_path"]: s["doc_hash"] for s in stale if s["source_path"] in modified}
+
+    try:
+        result = await bp.process_batch_async(
+            changed,
+            progress_callback=get_event_log().record_batch_progress,
+            source_paths=changed
+        )
+    except InsufficientDiskSpaceError as e:
+        raise HTTPException(status_code=507, detail=e.to_dict())
+    except QuotaExceededError as e:
+        raise HTTPException(status_code=413, detail=e.to_dict())
+
+    for f in result.files:
+        if f.status.value == "completed" and f.source_path in replaced:
+            processor.vector_store.delete_document(replaced[f.source_path])
+            processor.processed_docs.pop(replaced[f.source_path], None)
+
+    notify_webhooks("indexing.failed" if result.failed_files else "indexing.completed", {
+        "source": "scan_diff",
+        "batch_id": result.batch_id,
+        "total_files": result.total_files,
+        "completed_files": result.completed_files,
+        "failed_files": result.failed_files,
+        "total_chunks": result.total_chunks
+    })
+    return {"diff": diff.to_dict(), "batch": result.to_dict()}
+
+
+@app.get("/documents", response_model=DocumentListResponse)
+async def list_documents():
+    """List all indexed documents."""
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+    
+    docs = processor.list_documents()
+    return DocumentListResponse(documents=docs, total=len(docs))
+
+
+# Lazy trash store (soft delete for documents and the collection)
+trash_store = None
+
+def get_trash_store():
+    """Get or create trash store instance."""
+    global trash_store
+    if trash_store is None and processor is not None:
+        from trash_store import TrashStore
+        trash_store = TrashStore(
+            processor,
+            Path(config.persist_directory) / "trash",
+            retention_days=config.trash_retention_days
+        )
+        trash_store.purge_expired()
+    return trash_store
+
+
+# Lazy job workspace (per-job temp folders under the storage folder)
+job_workspace = None
+
+def get_job_workspace():
+    """Get or create job workspace instance."""
+    global job_workspace
+    if job_workspace is None:
+        from job_workspace import JobWorkspace, WORK_DIR_NAME
+        job_workspace = JobWorkspace(Path(config.persist_directory) / WORK_DIR_NAME)
+    return job_workspace
+
+
+@app.delete("/documents/{doc_hash}")
+async def delete_document(doc_hash: str, permanent: bool = False):
+    """
+    Remove a document from the index.
+
+    The document is moved to the trash (restorable until the retention
+    window ends) unless permanent=true.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    loop = asyncio.get_event_loop()
+    if permanent:
+        if not await loop.run_in_executor(None, processor.has_document, doc_hash):
+            raise HTTPException(status_code=404, detail=f"Document not found: {doc_hash}")
+        await loop.run_in_executor(None, processor.remove_document_by_hash, doc_hash)
+        return {"success": True, "message": f"Removed document: {doc_hash}"}
+
+    try:
+        entry = await loop.run_in_executor(None, get_trash_store().trash_document, doc_hash)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    return {
+        "success": True,
+        "message": f"Moved document to trash: {doc_hash}",
+        "trash": entry.to_dict()
+    }
+
+
+@app.delete("/documents")
+async def delete_all_documents(permanent: bool = False):
+    """Remove every document from the collection (to the trash unless permanent=true)."""
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    def remove_all():
+        for doc_hash in list(processor.processed_docs):
+            processor.remove_document_by_hash(doc_hash)
+        processor.vector_store.delete_chunks(processor.vector_store.get_all_chunks()["ids"])
+
+    loop = asyncio.get_event_loop()
+    if permanent:
+        await loop.run_in_executor(None, remove_all)
+        return {"success": True, "message": "Removed all documents"}
+
+    entry = await loop.run_in_executor(None, get_trash_store().trash_collection)
+    return {
+        "success": True,
+        "message": f"Moved collection to trash ({entry.chunk_count} chunks)",
+        "trash": entry.to_dict()
+    }
+
+
+# Lazy retention manager (per-collection document limits)
+retention_manager = None
+
+def get_retention_manager():
+    """Get or create retention manager instance."""
+    global retention_manager
+    if retention_manager is None and processor is not None:
+        from retention_policy import RetentionManager
+        retention_manager = RetentionManager(
+            processor,
+            get_trash_store(),
+            Path(config.persist_directory) / "retention_policy.json",
+            audit_log=get_audit_log()
+        )
+    return retention_manager
+
+
+@app.get("/collections/retention")
+async def get_retention_policy(collection: Optional[str] = None):
+    """The collection's retention policy and the last enforcement report."""
+    retention = get_retention_manager()
+    if retention is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        policy = retention.get_retention_policy(collection)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    return {
+        "policy": policy.to_dict(),
+        "check_interval_hours": config.retention_check_hours,
+        "last_report": retention.last_report.to_dict() if retention.last_report else None
+    }
+
+
+@app.put("/collections/retention")
+async def set_retention_policy(
+    max_age_days: Optional[int] = Form(None),
+    max_documents: Optional[int] = Form(None),
+    collection: Optional[str] = Form(None)
+):
+    """
+    Set the collection's retention policy. Documents older than
+    max_age_days, or the oldest beyond max_documents, are moved to the
+    trash at the next check. Omit both limits to remove the policy.
+    """
+    retention = get_retention_manager()
+    if retention is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from retention_policy import RetentionPolicy
+    try:
+        policy = retention.set_retention_policy(
+            collection, RetentionPolicy(max_age_days=max_age_days, max_documents=max_documents)
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return {"success": True, "policy": policy.to_dict()}
+
+
+@app.post("/collections/retention/enforce")
+async def enforce_retention_policy(dry_run: bool = Form(False), collection: Optional[str] = Form(None)):
+    """Apply the retention policy now (dry_run lists what would be pruned)."""
+    retention = get_retention_manager()
+    if retention is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    loop = asyncio.get_event_loop()
+    try:
+        report = await loop.run_in_executor(None, lambda: retention.enforce(collection, dry_run))
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    return {"success": not report.errors, "report": report.to_dict()}
+
+
+# Lazy quota manager (per-collection document and disk limits)
+quota_manager = None
+
+def get_quota_manager():
+    """Get or create quota manager instance."""
+    global quota_manager
+    if quota_manager is None and processor is not None:
+        from collection_quota import QuotaManager
+        quota_manager = QuotaManager(
+            processor,
+            Path(config.persist_directory) / "collection_quota.json",
+            Path(config.persist_directory)
+        )
+    return quota_manager
+
+
+@app.get("/collections/quota")
+async def get_collection_quota(collection: Optional[str] = None):
+    """The collection's quota and current usage against it."""
+    quotas = get_quota_manager()
+    if quotas is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        return await asyncio.get_event_loop().run_in_executor(None, quotas.get_usage, collection)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+
+@app.put("/collections/quota")
+async def set_collection_quota(
+    max_documents: Optional[int] = Form(None),
+    max_bytes: Optional[int] = Form(None),
+    collection: Optional[str] = Form(None)
+):
+    """
+    Set the collection's quota. Uploads that would take the collection
+    past max_documents or max_bytes are refused with a quota_exceeded
+    error. Omit both limits to remove the quota.
+    """
+    quotas = get_quota_manager()
+    if quotas is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from collection_quota import CollectionQuota
+    try:
+        quota = quotas.set_quota(collection, CollectionQuota(max_documents=max_documents, max_bytes=max_bytes))
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return {"success": True, "quota": quota.to_dict()}
+
+
+@app.get("/trash")
+async def list_trash():
+    """List trashed documents and collections."""
+    trash = get_trash_store()
+    if trash is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    entries = trash.list_trash()
+    return {
+        "entries": [e.to_dict() for e in entries],
+        "total": len(entries),
+        **trash.get_usage()
+    }
+
+
+@app.post("/trash/{trash_id}/restore")
+async def restore_from_trash(trash_id: str):
+    """Restore a trashed document or collection into the index."""
+    trash = get_trash_store()
+    if trash is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        entry = await asyncio.get_event_loop().run_in_executor(None, trash.restore_from_trash, trash_id)
+    except KeyError:
+        raise HTTPException(status_code=404, detail=f"Trash entry not found: {trash_id}")
+
+    return {"success": True, "message": f"Restored {entry.name}", "entry": entry.to_dict()}
+
+
+@app.delete("/trash/{trash_id}")
+async def delete_trash_entry(trash_id: str):
+    """Permanently delete one trash entry."""
+    trash = get_trash_store()
+    if trash is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        await asyncio.get_event_loop().run_in_executor(None, trash.delete_entry, trash_id)
+    except KeyError:
+        raise HTTPException(status_code=404, detail=f"Trash entry not found: {trash_id}")
+
+    return {"success": True, "message": f"Deleted trash entry: {trash_id}"}
+
+
+@app.delete("/trash")
+async def empty_trash():
+    """Permanently delete everything in the trash."""
+    trash = get_trash_store()
+    if trash is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    removed = await asyncio.get_event_loop().run_in_executor(None, trash.empty_trash)
+    return {"success": True, "message": f"Emptied trash ({removed} entries)", "removed": removed}
+
+
+@app.get("/documents/versions")
+async def list_document_versions(path: str):
+    """
+    List indexed versions of a file, oldest first.
+
+    path is the file path as ingested, or for uploads the source_path the
+    upload response reported.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    versions = processor.list_document_versions(path)
+    return {"path": path, "versions": versions, "total": len(versions)}
+
+
+@app.get("/documents/find")
+async def find_documents(pattern: str, collection: Optional[str] = None, limit: int = 20):
+    """
+    Find indexed documents by part of their file name, best match first.
+    Pass a result's doc_hash to /documents/query or /documents/search to
+    ask about that document only.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from source_search import search_sources
+    from workload_lanes import get_workload_lanes
+    try:
+        matches = await get_workload_lanes().run_interactive(
+            search_sources, processor.vector_store, pattern, collection, limit
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return {"pattern": pattern, "matches": matches, "total": len(matches)}
+
+
+@app.post("/documents/diff")
+async def diff_documents(a: str = Form(...), b: str = Form(...)):
+    """
+    Structured diff of two documents: added, removed and changed sections.
+
+    a and b are each a file path, an indexed doc_hash, or
+    "<path>@<version>" for a version listed by /documents/versions.
+    Files are extracted again, so the diff runs off the event loop.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from document_diff import diff_documents as run_diff
+    from workload_lanes import get_workload_lanes
+    try:
+        result = await get_workload_lanes().run_interactive(run_diff, processor, a, b)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return result.to_dict()
+
+
+@app.post("/documents/export/static")
+async def export_static_search(
+    output_dir: str = Form(...),
+    title: Optional[str] = Form(None),
+    collection: Optional[str] = Form(None)
+):
+    """
+    Export the collection as a static search site (index.html plus data
+    files) that works in a browser without the plugin. Every chunk and
+    embedding is read and written out, so this runs on the background lane.
+    """
+    require_feature("export")
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    global static_exports_running
+    from static_export import export_static_search as run_export
+    from workload_lanes import get_workload_lanes
+    static_exports_running += 1
+    try:
+        summary = await get_workload_lanes().run_background(
+            run_export, processor.vector_store, Path(output_dir), collection, title=title
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except OSError as e:
+        notify_webhooks("export.failed", {"output_dir": output_dir, "error": str(e)})
+        raise HTTPException(status_code=500, detail=f"Export failed: {e}")
+    finally:
+        static_exports_running -= 1
+
+    notify_webhooks("export.completed", summary)
+    return {"success": True, **summary}
+
+
+# Lazy collection exporter
+collection_exporter = None
+
+def get_collection_exporter():
+    """Get or create collection exporter instance."""
+    global collection_exporter
+    if collection_exporter is None and processor is not None:
+        from collection_export import CollectionExporter
+        collection_exporter = CollectionExporter(processor.vector_store)
+    return collection_exporter
+
+
+@app.post("/collections/export")
+async def export_collection_as(
+    output_path: str = Form(...),
+    format: str = Form("jsonl"),
+    collection: Optional[str] = Form(None)
+):
+    """
+    Export the collection for other RAG tools: "jsonl" (text + metadata
+    per line), "langchain" (Document objects per line) or "markdown" (a
+    folder with one digest per source document).
+    Poll GET /collections/export/status for progress.
+    """
+    require_feature("export")
+    exporter = get_collection_exporter()
+    if exporter is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    loop = asyncio.get_event_loop()
+    try:
+        progress = await loop.run_in_executor(
+            None, lambda: exporter.export_collection_as(collection, format, Path(output_path))
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    if progress.phase != "completed":
+        notify_webhooks("export.failed", {"output_path": output_path, "format": format, "error": progress.error})
+        raise HTTPException(status_code=500, detail=f"Export failed: {progress.error}")
+
+    notify_webhooks("export.completed", progress.to_dict())
+    return {"success": True, "export": progress.to_dict()}
+
+
+@app.get("/collections/export/status")
+async def get_export_status():
+    """Progress of the running (or last) collection export."""
+    exporter = get_collection_exporter()
+    if exporter is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    return {"export": exporter.current.to_dict() if exporter.current else None}
+
+
+@app.post("/eval/retrieval")
+async def run_retrieval_eval(
+    dataset_path: str = Form(...),
+    configurations: Optional[str] = Form(None),
+    k_values: str = Form("1,3,5,10")
+):
+    """
+    Score retrieval (recall@k, MRR) for a labeled query set.
+
+    configurations is an optional JSON list of preset names or inline
+    preset settings, overriding the dataset's list. The report is also
+    saved under the storage directory's eval_reports folder. Each
+    configuration re-indexes the dataset's documents, so the evaluation
+    runs on the background lane.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from retrieval_eval import run_retrieval_eval as run_eval
+    from workload_lanes import get_workload_lanes
+    try:
+        report = await get_workload_lanes().run_background(
+            run_eval,
+            dataset_path,
+            processor,
+            configurations=json.loads(configurations) if configurations else None,
+            k_values=[int(k) for k in k_values.split(",") if k.strip()],
+            report_dir=Path(config.persist_directory) / "eval_reports",
+            presets=get_preset_store()
+        )
+    except FileNotFoundError:
+        raise HTTPException(status_code=404, detail=f"Dataset not found: {dataset_path}")
+    except KeyError as e:
+        # A malformed dataset entry, not a missing resource
+        raise HTTPException(status_code=400, detail=f"Malformed dataset: missing {e.args[0]}")
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return report.to_dict()
+
+
+@app.post("/collections/chunking/tune")
+async def tune_chunking(
+    sample_files: str = Form(...),
+    queries: Optional[str] = Form(None),
+    candidates: Optional[str] = Form(None),
+    collection: Optional[str] = Form(None),
+    persist: bool = Form(False)
+):
+    """
+    Try several chunk size/overlap combinations on sample files and
+    recommend one. sample_files is a JSON list of paths; queries is an
+    optional JSON list of labeled queries (as in /eval/retrieval) used to
+    score each candidate; candidates is an optional JSON list of
+    [chunk_size, chunk_overlap] pairs. persist saves the recommendation as
+    the "tuned-<collection>" preset and applies it.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        files = json.loads(sample_files)
+        query_list = json.loads(queries) if queries else None
+        pairs = [tuple(int(v) for v in pair) for pair in json.loads(candidates)] if candidates else None
+    except (ValueError, TypeError):
+        raise HTTPException(
+            status_code=400,
+            detail="sample_files, queries and candidates must be JSON lists"
+        )
+
+    from chunk_tuning import tune_chunking as run_tuning, DEFAULT_CANDIDATES
+    loop = asyncio.get_event_loop()
+    try:
+        report = await loop.run_in_executor(
+            None,
+            lambda: run_tuning(
+                processor,
+                files,
+                queries=query_list,
+                candidates=pairs or DEFAULT_CANDIDATES,
+                max_context_tokens=config.max_context_tokens,
+                collection=collection,
+                presets=get_preset_store(),
+                persist=persist
+            )
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except FileNotFoundError as e:
+        raise HTTPException(status_code=404, detail=str(e))
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return report.to_dict()
+
+
+@app.get("/collections/stale")
+async def list_stale_sources(collection: Optional[str] = None):
+    """
+    Indexed documents whose source file changed or was removed since
+    indexing (only documents indexed from a local path can be checked).
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    loop = asyncio.get_event_loop()
+    try:
+        stale = await loop.run_in_executor(None, processor.find_stale_sources, collection)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    return {"collection": processor.vector_store.collection.name, "stale": stale, "total": len(stale)}
+
+
+@app.post("/collections/stale/refresh")
+async def refresh_stale_sources(collection: Optional[str] = Form(None)):
+    """
+    Re-index every changed source file in one call.
+
+    Changed files are processed as a batch. With versioning the old
+    content is kept as a superseded version, otherwise it is removed once
+    the file is re-indexed. Removed files are only reported.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+    bp = get_batch_processor()
+    if bp is None:
+        raise HTTPException(status_code=503, detail="Batch processor not initialized")
+
+    loop = asyncio.get_event_loop()
+    try:
+        stale = await loop.run_in_executor(None, processor.find_stale_sources, collection)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    changed = [s["source_path"] for s in stale if s["reason"] == "changed"]
+    missing = [s["source_path"] for s in stale if s["reason"] == "missing"]
+    if not changed:
+        return {"refreshed": [], "missing": missing, "batch": None}
+
+    try:
+        result = await bp.process_batch_async(
+            changed,
+            progress_callback=get_event_log().record_batch_progress,
+            source_paths=changed
+        )
+    except InsufficientDiskSpaceError as e:
+        raise HTTPException(status_code=507, detail=e.to_dict())
+    except QuotaExceededError as e:
+        raise HTTPException(status_code=413, detail=e.to_dict())
+
+    if not processor.versioning:
+        reindexed = {f.source_path for f in result.files if f.status.value == "completed"}
+        for entry in stale:
+            if entry["source_path"] in reindexed:
+                processor.vector_store.delete_document(entry["doc_hash"])
+                processor.processed_docs.pop(entry["doc_hash"], None)
+
+    notify_webhooks("indexing.failed" if result.failed_files else "indexing.completed", {
+        "source": "stale_refresh",
+        "batch_id": result.batch_id,
+        "total_files": result.total_files,
+        "completed_files": result.completed_files,
+        "failed_files": result.failed_files,
+        "total_chunks": result.total_chunks
+    })
+    return {"refreshed": changed, "missing": missing, "batch": result.to_dict()}
+
+
+# Lazy shadow index manager (embedding model A/B checks)
+shadow_index_manager = None
+
+def get_shadow_index_manager():
+    """Get or create shadow index manager instance."""
+    global shadow_index_manager
+    if shadow_index_manager is None and processor is not None:
+        from shadow_index import ShadowIndexManager
+        shadow_index_manager = ShadowIndexManager(processor.vector_store)
+    return shadow_index_manager
+
+
+@app.post("/shadow-index")
+async def build_shadow_index(embedding_model: str = Form(...), collection: Optional[str] = Form(None)):
+    """
+    Build a parallel index of the collection with another embedding model.
+    Every chunk is re-embedded on the background lane; a second build is
+    refused (409) while one is running.
+    """
+    manager = get_shadow_index_manager()
+    if manager is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from shadow_index import ShadowIndexBusyError
+    from workload_lanes import get_workload_lanes
+    try:
+        info = await get_workload_lanes().run_background(
+            manager.shadow_index_collection, collection, embedding_model
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    except ShadowIndexBusyError as e:
+        raise HTTPException(status_code=409, detail=str(e))
+
+    return {"success": True, "shadow_index": info.to_dict()}
+
+
+@app.get("/shadow-index")
+async def get_shadow_index():
+    """Describe the current shadow index, if any."""
+    manager = get_shadow_index_manager()
+    if manager is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    if manager.building:
+        return {"shadow_index": None, "building": True}
+    info = await asyncio.get_event_loop().run_in_executor(None, manager.get_shadow_info)
+    return {"shadow_index": info.to_dict() if info else None, "building": False}
+
+
+@app.post("/shadow-index/compare")
+async def compare_shadow_query(
+    query: str = Form(...),
+    n_results: int = Form(5),
+    collection: Optional[str] = Form(None)
+):
+    """Run a query against the live and shadow indexes side by side."""
+    manager = get_shadow_index_manager()
+    if manager is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from shadow_index import ShadowIndexBusyError
+    from workload_lanes import get_workload_lanes
+    try:
+        return await get_workload_lanes().run_interactive(
+            manager.compare_query, query, collection, n_results=n_results
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ShadowIndexBusyError as e:
+        raise HTTPException(status_code=409, detail=str(e))
+
+
+@app.post("/shadow-index/promote")
+async def promote_shadow_index(force: bool = False):
+    """Swap the shadow index in as the live collection."""
+    manager = get_shadow_index_manager()
+    if manager is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from shadow_index import ShadowIndexBusyError
+    loop = asyncio.get_event_loop()
+    try:
+        info = await loop.run_in_executor(None, lambda: manager.promote_shadow_index(force=force))
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except (ValueError, ShadowIndexBusyError) as e:
+        raise HTTPException(status_code=409, detail=str(e))
+
+    invalidate_similarity_graph()
+    return {
+        "success": True,
+        "message": f"Collection now uses {info.embedding_model}",
+        "shadow_index": info.to_dict()
+    }
+
+
+@app.delete("/shadow-index")
+async def discard_shadow_index():
+    """Delete the shadow index without promoting it."""
+    manager = get_shadow_index_manager()
+    if manager is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from shadow_index import ShadowIndexBusyError
+    try:
+        discarded = manager.discard_shadow_index()
+    except ShadowIndexBusyError as e:
+        raise HTTPException(status_code=409, detail=str(e))
+    if not discarded:
+        raise HTTPException(status_code=404, detail="No shadow index has been built")
+    return {"success": True, "message": "Shadow index discarded"}
+
+
+# Lazy collection optimizer (compacts the Chroma database)
+collection_optimizer = None
+
+def get_collection_optimizer():
+    """Get or create collection optimizer instance."""
+    global collection_optimizer
+    if collection_optimizer is None and processor is not None:
+        from collection_optimizer import CollectionOptimizer
+        collection_optimizer = CollectionOptimizer(
+            Path(config.persist_directory),
+            processor.vector_store.collection.name,
+            optimize_after_deleted=config.optimize_after_deleted,
+            busy_check=batch_in_progress_reason
+        )
+    return collection_optimizer
+
+
+def batch_in_progress_reason() -> Optional[str]:
+    """Why storage maintenance should wait, or None."""
+    bp = batch_processor
+    if bp is not None and bp.get_processing_status()["active_batches"]:
+        return "A batch is still processing"
+    return None
+
+
+@app.post("/collections/optimize")
+async def optimize_collection(collection: Optional[str] = Form(None)):
+    """
+    Compact the Chroma database (VACUUM) to reclaim space left by deleted
+    chunks. Reports storage sizes before and after, and the duration.
+    """
+    optimizer = get_collection_optimizer()
+    if optimizer is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+    if batch_in_progress_reason():
+        raise HTTPException(status_code=409, detail="A batch is still processing; wait for it or pause it first")
+
+    loop = asyncio.get_event_loop()
+    try:
+        report = await loop.run_in_executor(None, lambda: optimizer.optimize_collection(collection))
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except FileNotFoundError as e:
+        raise HTTPException(status_code=404, detail=str(e))
+    except ValueError as e:
+        raise HTTPException(status_code=409, detail=str(e))
+
+    return {"success": report.phase == "completed", "report": report.to_dict()}
+
+
+@app.get("/collections/optimize/status")
+async def get_optimize_status():
+    """Chunks deleted since the last optimization, and the last run's report."""
+    optimizer = get_collection_optimizer()
+    if optimizer is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+    return optimizer.get_status()
+
+
+# Lazy collection migrator
+collection_migrator = None
+
+def get_collection_migrator():
+    """Get or create collection migrator instance."""
+    global collection_migrator
+    if collection_migrator is None and processor is not None:
+        from collection_migration import CollectionMigrator
+        collection_migrator = CollectionMigrator(processor.vector_store)
+    return collection_migrator
+
+
+@app.post("/collections/migrate")
+async def migrate_collection(
+    target_model: Optional[str] = Form(None),
+    target_backend: Optional[str] = Form(None),
+    collection: Optional[str] = Form(None)
+):
+    """
+    Re-embed (new model) or copy (same model) the collection, verify it,
+    and swap it in. Failures roll back and leave the collection unchanged.
+    Poll GET /collections/migrate/status for progress.
+    """
+    migrator = get_collection_migrator()
+    if migrator is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    target = {"target_model": target_model, "target_backend": target_backend}
+    loop = asyncio.get_event_loop()
+    try:
+        progress = await loop.run_in_executor(
+            None, lambda: migrator.migrate_collection(collection, target)
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    if progress.phase == "completed":
+        invalidate_similarity_graph()
+    return {"success": progress.phase == "completed", "migration": progress.to_dict()}
+
+
+@app.get("/collections/migrate/status")
+async def get_migration_status():
+    """Progress of the running (or last) collection migration."""
+    migrator = get_collection_migrator()
+    if migrator is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    return {"migration": migrator.current.to_dict() if migrator.current else None}
+
+
+@app.get("/ocr/languages")
+async def list_ocr_languages():
+    """OCR language packs: known, installed and bundled with the app."""
+    manager = get_ocr_language_manager()
+    return {
+        "languages": manager.list_ocr_languages(),
+        "tessdata_dir": str(manager.tessdata_dir) if manager.tessdata_dir else None
+    }
+
+
+@app.post("/ocr/languages/{lang}")
+async def install_ocr_language(lang: str, sha256: Optional[str] = Form(None)):
+    """
+    Install an OCR language pack (e.g. "deu", "chi_sim") from the bundled
+    packs, or download it. The pack is verified before it is installed;
+    pass sha256 to also check it against a known checksum.
+    """
+    require_feature("ocr_language_install")
+    manager = get_ocr_language_manager()
+    loop = asyncio.get_event_loop()
+    try:
+        installed = await loop.run_in_executor(None, manager.install_ocr_language, lang, sha256)
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    except RuntimeError as e:
+        raise HTTPException(status_code=503, detail=str(e))
+    except httpx.HTTPError as e:
+        raise HTTPException(status_code=502, detail=f"Download failed: {e}")
+
+    return {"success": True, "language": installed}
+
+
+@app.get("/collections/retrieval-config")
+async def get_retrieval_config(collection: Optional[str] = None):
+    """Stopwords, boosts and keyword weight used to score the collection."""
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        retrieval_config = processor.get_retrieval_config(collection)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    return {"collection": processor.vector_store.collection.name, "config": retrieval_config.to_dict()}
+
+
+@app.put("/collections/retrieval-config")
+async def set_retrieval_config(
+    stopwords: Optional[str] = Form(None),
+    boosts: Optional[str] = Form(None),
+    keyword_weight: float = Form(0.0),
+    collection: Optional[str] = Form(None)
+):
+    """
+    Replace the collection's keyword scoring config.
+
+    stopwords is a JSON list of words or phrases (e.g. ["the company"]),
+    boosts a JSON object of term weights (e.g. {"warranty": 2.0}), and
+    keyword_weight the share of keyword scoring in the ranking (0-1).
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from keyword_scoring import RetrievalConfig
+    try:
+        stopword_list = json.loads(stopwords) if stopwords else []
+        boost_map = json.loads(boosts) if boosts else {}
+    except ValueError:
+        raise HTTPException(status_code=400, detail="stopwords and boosts must be JSON")
+    if not isinstance(stopword_list, list) or not isinstance(boost_map, dict):
+        raise HTTPException(status_code=400, detail="stopwords must be a JSON list and boosts a JSON object")
+
+    try:
+        retrieval_config = processor.set_retrieval_config(
+            collection,
+            RetrievalConfig(
+                stopwords=[str(s) for s in stopword_list],
+                boosts=boost_map,
+                keyword_weight=keyword_weight
+            )
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except (ValueError, TypeError) as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return {"success": True, "config": retrieval_config.to_dict()}
+
+
+@app.get("/collections")
+async def list_collections(detailed: bool = False):
+    """
+    Collections with their chunk count and appearance (description, color,
+    icon). With detailed=true each also has its document count, estimated
+    disk usage, embedding model and last update, so the library view needs
+    no other call.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    if not detailed:
+        return {"collections": processor.list_collections()}
+
+    def list_detailed():
+        return processor.list_collections_detailed(index_bytes=get_quota_manager().index_bytes())
+
+    from workload_lanes import get_workload_lanes
+    return {"collections": await get_workload_lanes().run_interactive(list_detailed)}
+
+
+@app.put("/collections/appearance")
+async def set_collection_appearance(request: CollectionAppearanceRequest):
+    """Set the description, color (#rrggbb) and icon the library shows for the collection."""
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    changes = request.model_dump(exclude_unset=True)
+    collection = changes.pop("collection", None)
+    try:
+        appearance = processor.set_collection_appearance(collection, changes)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    except OSError as e:
+        raise HTTPException(status_code=500, detail=f"Could not save the appearance: {e}")
+
+    return {"success": True, "collection": processor.vector_store.collection.name, "appearance": appearance}
+
+
+@app.put("/collections/readonly")
+async def set_collection_readonly(request: CollectionReadOnlyRequest):
+    """
+    Freeze the collection (legal hold) or make it writable again.
+
+    While read-only, every request that would add, edit or delete content
+    (processing, deletes, chunk edits, retention, trash restores, imports,
+    migrations, shadow index promotion, resets) fails with 423
+    collection_readonly and the attempt is recorded in the audit log.
+    Queries and exports keep working.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        result = processor.set_collection_readonly(request.collection, request.readonly, request.reason)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    except OSError as e:
+        raise HTTPException(status_code=500, detail=f"Could not save the read-only flag: {e}")
+
+    return {"success": True, **result}
+
+
+@app.get("/chunks")
+async def get_chunks(
+    source_path: Optional[str] = None,
+    doc_hash: Optional[str] = None,
+    page: Optional[int] = None
+):
+    """
+    Inspect stored chunks exactly as indexed.
+
+    source_path is the file path as ingested or an upload's source_path;
+    page limits results to chunks starting on that PDF page.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+    if not source_path and not doc_hash:
+        raise HTTPException(status_code=400, detail="source_path or doc_hash is required")
+
+    chunks = processor.get_chunks(source_path=source_path, doc_hash=doc_hash, page=page)
+    return {"chunks": chunks, "total": len(chunks)}
+
+
+@app.put("/chunks/{chunk_id}")
+async def update_chunk(chunk_id: str, text: str = Form(...)):
+    """Replace a chunk's text (re-embedded) - e.g. to fix OCR errors."""
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        chunk = processor.update_chunk_text(chunk_id, text)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    invalidate_similarity_graph(chunk["metadata"].get("doc_hash"))
+    return {"success": True, "chunk": chunk}
+
+
+@app.delete("/chunks/{chunk_id}")
+async def delete_chunk(chunk_id: str):
+    """Delete a single bad chunk."""
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        chunk = processor.delete_chunk(chunk_id)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    invalidate_similarity_graph(chunk["metadata"].get("doc_hash"))
+    return {"success": True, "message": f"Deleted chunk {chunk_id}"}
+
+
+@app.post("/collections/metadata")
+async def update_metadata_by_filter(
+    filter: str = Form(...),
+    patch: str = Form(...),
+    collection: Optional[str] = Form(None)
+):
+    """
+    Change the metadata of many chunks at once without re-indexing.
+
+    filter is a JSON object of metadata keys / document properties and
+    the values they must have, e.g. {"client": "Acme Corp"}; patch is a
+    JSON merge patch, e.g. {"client": "ACME Corporation", "draft": null}.
+    """
+    if processor is None:
         raise HTTPException(status_code=503, detail="Processor not initialized")
 
-    if doc_hash not in processor.processed_docs:
-        raise HTTPException(status_code=404, detail=f"Document not found: {doc_hash}")
+    parsed = {}
+    for field_name, value in (("filter", filter), ("patch", patch)):
+        try:
+            parsed[field_name] = json.loads(value)
+        except ValueError:
+            parsed[field_name] = None
+        if not isinstance(parsed[field_name], dict):
+            raise HTTPException(status_code=400, detail=f"{field_name} must be a JSON object")
+
+    loop = asyncio.get_event_loop()
+    try:
+        result = await loop.run_in_executor(
+            None,
+            lambda: processor.update_metadata_by_filter(collection, parsed["filter"], parsed["patch"])
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return {"success": True, **result}
+
+
+@app.get("/audit")
+async def get_audit_entries(limit: int = 100, action: Optional[str] = None, target: Optional[str] = None):
+    """Recent audit log entries, newest first."""
+    entries = get_audit_log().list_entries(limit=limit, action=action, target=target)
+    return {"entries": entries, "total": len(entries)}
+
+
+@app.post("/history/export")
+async def export_history(
+    output_path: str = Form(...),
+    format: str = Form("csv"),
+    since: Optional[str] = Form(None),
+    until: Optional[str] = Form(None),
+    sources: Optional[str] = Form(None),
+    columns: Optional[str] = Form(None),
+    filter: Optional[str] = Form(None)
+):
+    """
+    Write the processing history (what was indexed, with which preset, OCR
+    mode and chunk size) and the audit log to a CSV or JSONL file, merged
+    in time order. sources and columns are comma-separated; filter is a
+    JSON object of column values rows must have, e.g. {"event": "indexing.failed"}.
+    """
+    from history_export import export_history as write_history
+
+    filters = None
+    if filter:
+        try:
+            filters = json.loads(filter)
+        except ValueError:
+            filters = None
+        if not isinstance(filters, dict):
+            raise HTTPException(status_code=400, detail="filter must be a JSON object")
+
+    def split(value: Optional[str]):
+        return [v.strip() for v in value.split(",") if v.strip()] if value else None
+
+    loop = asyncio.get_event_loop()
+    try:
+        report = await loop.run_in_executor(None, lambda: write_history(
+            Path(config.persist_directory) / "event_log.jsonl",
+            get_audit_log().path,
+            format,
+            Path(output_path),
+            since=since,
+            until=until,
+            sources=split(sources),
+            columns=split(columns),
+            filters=filters
+        ))
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    except OSError as e:
+        raise HTTPException(status_code=500, detail=f"History export failed: {e}")
 
-    processor.remove_document_by_hash(doc_hash)
-    return {"success": True, "message": f"Removed document: {doc_hash}"}
+    get_audit_log().record("history_exported", output_path, {
+        "format": format, "since": since, "until": until, "rows": report.rows
+    })
+    return {"success": True, "export": report.to_dict()}
 
 
 @app.get("/documents/stats")
-async def get_stats():
-    """Get document processor statistics."""
+async def get_stats(sources: bool = False):
+    """
+    Get document processor statistics. With sources=true, also every
+    indexed document with its chunk count, indexing time, settings
+    fingerprint and whether it needs re-indexing (stale_reasons).
+    """
     if processor is None:
         raise HTTPException(status_code=503, detail="Processor not initialized")
     
-    return processor.get_stats()
+    from workload_lanes import get_workload_lanes
+    stats = await get_workload_lanes().run_interactive(processor.get_stats, include_sources=sources)
+    stats["trash"] = get_trash_store().get_usage()
+    stats["quota"] = get_quota_manager().get_usage()
+    stats["temp"] = get_job_workspace().get_usage()
+    return stats
+
+
+# Lazy similarity graph builder (keeps document centroids between calls)
+similarity_graph_builder = None
+
+def get_similarity_graph_builder():
+    """Get or create similarity graph builder instance."""
+    global similarity_graph_builder
+    if similarity_graph_builder is None and processor is not None:
+        from collection_analysis import SimilarityGraphBuilder
+        similarity_graph_builder = SimilarityGraphBuilder(processor.vector_store)
+    return similarity_graph_builder
+
+
+def invalidate_similarity_graph(doc_hash: Optional[str] = None):
+    """Drop cached centroids after embeddings changed in place (one document, or all)."""
+    if similarity_graph_builder is not None:
+        similarity_graph_builder.invalidate(doc_hash)
+
+
+@app.get("/documents/graph")
+async def get_similarity_graph(threshold: float = 0.5):
+    """
+    Get a document-to-document similarity graph.
+
+    Nodes are indexed documents; edges connect documents whose chunk
+    embedding centroids have cosine similarity >= threshold.
+    """
+    builder = get_similarity_graph_builder()
+    if builder is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    if not 0.0 <= threshold <= 1.0:
+        raise HTTPException(status_code=400, detail="threshold must be between 0 and 1")
+
+    return await asyncio.get_event_loop().run_in_executor(None, builder.build, threshold)
+
+
+@app.get("/documents/clusters")
+async def get_topic_clusters(k: Optional[int] = None):
+    """
+    Group indexed chunks into topic clusters.
+
+    Each cluster is labeled with its most characteristic keywords and lists
+    the documents that contribute chunks to it. Omit k to pick the cluster
+    count from the number of indexed documents.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    if k is not None and k < 1:
+        raise HTTPException(status_code=400, detail="k must be at least 1")
+
+    from collection_analysis import cluster_collection
+    return await asyncio.get_event_loop().run_in_executor(
+        None, cluster_collection, processor.vector_store, k
+    )
+
+
+def parse_exclusions(exclude_terms: Optional[str], exclude_filters: Optional[str]):
+    """Comma-separated exclude_terms and JSON-object exclude_filters, 400 if malformed."""
+    terms = [t.strip() for t in (exclude_terms or "").split(",") if t.strip()]
+    filters = None
+    if exclude_filters:
+        try:
+            filters = json.loads(exclude_filters)
+        except ValueError:
+            filters = None
+        if not isinstance(filters, dict):
+            raise HTTPException(status_code=400, detail="exclude_filters must be a JSON object")
+    return terms, filters
 
 
 @app.post("/documents/query")
 async def query_documents(
     query: str = Form(...),
     n_results: int = Form(5),
-    doc_hash: Optional[str] = Form(None)
+    doc_hash: Optional[str] = Form(None),
+    properties: Optional[str] = Form(None),
+    heading: Optional[str] = Form(None),
+    include_old_versions: bool = Form(False),
+    sources: str = Form("both"),
+    assistant_id: Optional[str] = Form(None),
+    collection: Optional[str] = Form(None),
+    recency_boost: Optional[float] = Form(None),
+    exclude_terms: Optional[str] = Form(None),
+    exclude_filters: Optional[str] = Form(None),
+    explain: bool = Form(False),
+    verify_sources: bool = Form(False)
 ):
     """
     Query indexed documents for relevant context.
     
     Useful for testing retrieval without chat completion.
+    properties is a JSON object of document properties to match exactly,
+    e.g. {"author": "J. Smith"}. heading limits results to sections whose
+    heading path contains the text, e.g. "Methods". Superseded document
+    versions are skipped unless include_old_versions is set. sources is
+    "documents", "chat" (Jan threads indexed via /jan/threads/index) or
+    "both"; chat matches are attributed to their thread in the context.
+    An assistant_id checks the assistant's binding (see
+    /assistants/{assistant_id}/collection) against the store's single
+    collection; a stale binding fails with 404.
+    recency_boost is a half-life in days: scores halve for every that many
+    days of document age (the document's modified/created date, else its
+    file date); each context entry then shows its date and boost.
+    exclude_terms is a comma-separated list of words or phrases and
+    exclude_filters a JSON object of metadata/property values; matching
+    chunks are left out, e.g. exclude_terms="pricing" for
+    "kubernetes NOT pricing".
+    With explain, the response includes retrieval diagnostics: embedding
+    model, candidates kept after each filter stage, score distributions,
+    filters and boosts applied, how many chunks fit the context budget and
+    a timing breakdown.
+    verify_sources checks each document chunk's source file against the
+    checksum recorded at indexing and marks changed or removed sources as
+    stale in the context (see /collections/stale/refresh).
     """
     if processor is None:
         raise HTTPException(status_code=503, detail="Processor not initialized")
+    try:
+        collection = get_assistant_bindings().resolve_collection(assistant_id, collection)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    
+    property_filter = None
+    if properties:
+        try:
+            property_filter = json.loads(properties)
+        except ValueError:
+            property_filter = None
+        if not isinstance(property_filter, dict):
+            raise HTTPException(status_code=400, detail="properties must be a JSON object")
+    if sources not in QUERY_SOURCES:
+        raise HTTPException(status_code=400, detail=f"Invalid sources: {sources}")
+    if recency_boost is not None and recency_boost <= 0:
+        raise HTTPException(status_code=400, detail="recency_boost must be a positive number of days")
+    terms, filters = parse_exclusions(exclude_terms, exclude_filters)
+    diagnostics = {} if explain else None
     
-    context = processor.get_context(
+    from workload_lanes import get_workload_lanes
+    context = await get_workload_lanes().run_interactive(
+        processor.get_context,
         query=query,
         n_chunks=n_results,
         max_tokens=config.max_context_tokens,
-        doc_hash=doc_hash
+        doc_hash=doc_hash,
+        properties=property_filter,
+        heading=heading,
+        include_old_versions=include_old_versions,
+        sources=sources,
+        recency_boost=recency_boost,
+        exclude_terms=terms,
+        exclude_filters=filters,
+        explain=diagnostics,
+        verify_sources=verify_sources
+    )
+    
+    response = {
+        "query": query,
+        "collection": collection,
+        "sources": sources,
+        "recency_boost": recency_boost,
+        "excluded": {"terms": terms, "filters": filters or {}},
+        "context": context,
+        "context_length": len(context),
+        "index_state": processor.get_index_state()
+    }
+    if explain:
+        response["explain"] = diagnostics
+    return response
+
+
+@app.post("/documents/search")
+async def search_documents(
+    query: str = Form(...),
+    n_results: int = Form(5),
+    doc_hash: Optional[str] = Form(None),
+    heading: Optional[str] = Form(None),
+    sources: str = Form("both"),
+    assistant_id: Optional[str] = Form(None),
+    collection: Optional[str] = Form(None),
+    recency_boost: Optional[float] = Form(None),
+    exclude_terms: Optional[str] = Form(None),
+    exclude_filters: Optional[str] = Form(None),
+    explain: bool = Form(False),
+    verify_sources: bool = Form(False)
+):
+    """
+    Ranked matches with highlighted snippets (query terms in **bold**).
+
+    Uses the collection's retrieval config (see /collections/retrieval-config).
+    sources is "documents", "chat" or "both"; every result has a source_type.
+    assistant_id checks the assistant's binding, as for /documents/query.
+    With recency_boost (half-life in days), each result reports its
+    document_date and the recency_boost multiplier applied to its score.
+    exclude_terms / exclude_filters leave out matches and explain adds
+    retrieval diagnostics, as for /documents/query. verify_sources adds
+    stale / stale_reason to document results (None when the source can't
+    be checked, e.g. uploads).
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+    try:
+        collection = get_assistant_bindings().resolve_collection(assistant_id, collection)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    if sources not in QUERY_SOURCES:
+        raise HTTPException(status_code=400, detail=f"Invalid sources: {sources}")
+    if recency_boost is not None and recency_boost <= 0:
+        raise HTTPException(status_code=400, detail="recency_boost must be a positive number of days")
+    terms, filters = parse_exclusions(exclude_terms, exclude_filters)
+    diagnostics = {} if explain else None
+
+    from workload_lanes import get_workload_lanes
+    results = await get_workload_lanes().run_interactive(
+        processor.search,
+        query=query,
+        n_results=n_results,
+        doc_hash=doc_hash,
+        heading=heading,
+        sources=sources,
+        recency_boost=recency_boost,
+        exclude_terms=terms,
+        exclude_filters=filters,
+        explain=diagnostics,
+        verify_sources=verify_sources
+    )
+
+    response = {
+        "query": query,
+        "collection": collection,
+        "results": results,
+        "total": len(results),
+        "index_state": processor.get_index_state()
+    }
+    if explain:
+        response["explain"] = diagnostics
+    return response
+
+
+@app.post("/documents/search/batch")
+async def search_documents_batch(request: SearchBatchRequest):
+    """
+    Run many searches (e.g. an evaluation set, or an agent's sub-questions)
+    in one call: the queries are embedded together, then each is searched
+    as by /documents/search. Returns one {query, results, total} per query,
+    in order.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+    try:
+        collection = get_assistant_bindings().resolve_collection(request.assistant_id, request.collection)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    from workload_lanes import get_workload_lanes
+    started = time.monotonic()
+    try:
+        batch = await get_workload_lanes().run_interactive(
+            processor.search_batch,
+            request.queries,
+            n_results=request.n_results,
+            doc_hash=request.doc_hash,
+            heading=request.heading,
+            sources=request.sources,
+            recency_boost=request.recency_boost,
+            exclude_terms=request.exclude_terms,
+            exclude_filters=request.exclude_filters,
+            verify_sources=request.verify_sources
+        )
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return {
+        "collection": collection,
+        "results": batch,
+        "total_queries": len(batch),
+        "elapsed_seconds": round(time.monotonic() - started, 3),
+        "index_state": processor.get_index_state()
+    }
+
+
+@app.post("/embeddings")
+async def embed_texts(request: EmbedRequest):
+    """
+    Embed arbitrary texts (up to 256) with the store's embedding model, or
+    another sentence-transformers model, in batches. With normalize, the
+    vectors have unit length so their dot product is the cosine similarity.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from workload_lanes import get_workload_lanes
+    try:
+        result = await get_workload_lanes().run_interactive(
+            processor.vector_store.embed_texts, request.texts, request.model, request.normalize
+        )
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return {**result, "count": len(result["embeddings"])}
+
+
+@app.post("/similarity")
+async def compute_similarity(request: SimilarityRequest):
+    """
+    Cosine similarity of two items, each a text (embedded with the store's
+    model) or an embedding from /embeddings, e.g. to tell whether a note
+    says the same as an indexed passage without indexing it.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from collection_analysis import compute_similarity as similarity_of
+    from workload_lanes import get_workload_lanes
+    try:
+        return await get_workload_lanes().run_interactive(
+            similarity_of,
+            processor.vector_store,
+            text_a=request.text_a,
+            text_b=request.text_b,
+            embedding_a=request.embedding_a,
+            embedding_b=request.embedding_b
+        )
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+
+@app.post("/documents/search/stream")
+async def stream_search_documents(
+    query: str = Form(...),
+    n_results: int = Form(100),
+    page_size: int = Form(50),
+    doc_hash: Optional[str] = Form(None),
+    heading: Optional[str] = Form(None),
+    sources: str = Form("both"),
+    assistant_id: Optional[str] = Form(None),
+    collection: Optional[str] = Form(None),
+    recency_boost: Optional[float] = Form(None),
+    exclude_terms: Optional[str] = Form(None),
+    exclude_filters: Optional[str] = Form(None),
+    verify_sources: bool = Form(False)
+):
+    """
+    /documents/search for large n_results (e.g. 500), streamed as
+    server-sent events: a query-results-page event per page_size results
+    ({page, results}) as each is ready, then a query-results-summary event
+    ({query, collection, total, pages, elapsed_seconds, index_state}).
+    Errors before the first page are ordinary error responses; a later
+    failure ends the stream with a query-results-error event.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+    try:
+        collection = get_assistant_bindings().resolve_collection(assistant_id, collection)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    if sources not in QUERY_SOURCES:
+        raise HTTPException(status_code=400, detail=f"Invalid sources: {sources}")
+    if recency_boost is not None and recency_boost <= 0:
+        raise HTTPException(status_code=400, detail="recency_boost must be a positive number of days")
+    terms, filters = parse_exclusions(exclude_terms, exclude_filters)
+
+    from workload_lanes import get_workload_lanes
+    lanes = get_workload_lanes()
+    started = time.monotonic()
+    pages = processor.iter_search_pages(
+        query,
+        page_size=page_size,
+        n_results=n_results,
+        doc_hash=doc_hash,
+        heading=heading,
+        sources=sources,
+        recency_boost=recency_boost,
+        exclude_terms=terms,
+        exclude_filters=filters,
+        verify_sources=verify_sources
+    )
+    # Retrieval happens with the first page, so its errors get a status code
+    try:
+        first_page = await lanes.run_interactive(next, pages, None)
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    def event(name: str, data: Dict) -> str:
+        return f"event: {name}\ndata: {json.dumps(data)}\n\n"
+
+    async def generate():
+        page, number, total = first_page, 0, 0
+        try:
+            while page is not None:
+                number += 1
+                total += len(page)
+                yield event("query-results-page", {"page": number, "results": page})
+                page = await lanes.run_interactive(next, pages, None)
+        except Exception as e:
+            logger.error(f"Streamed search failed after {total} results: {e}")
+            yield event("query-results-error", {"error": str(e), "results_sent": total})
+            return
+        yield event("query-results-summary", {
+            "query": query,
+            "collection": collection,
+            "total": total,
+            "pages": number,
+            "elapsed_seconds": round(time.monotonic() - started, 3),
+            "index_state": processor.get_index_state()
+        })
+
+    return StreamingResponse(
+        generate(),
+        media_type="text/event-stream",
+        headers={"Cache-Control": "no-cache"}
     )
-    
-    return {
-        "query": query,
-        "context": context,
-        "context_length": len(context)
-    }
 
 
 # ============================================================================
@@ -690,9 +3589,10 @@ def extract_inline_attachments(
             {"type": "image_url", "image_url": {"url": "data:mime;base64,..."}}
         ]
 
-    This function:
+    This function (blocking; run it off the event loop):
     1. Detects base64 data URLs in content arrays
-    2. Decodes and indexes them through the document processor
+    2. Decodes and indexes them through the document processor, yielding
+       to in-flight queries first
     3. Runs consciousness pipeline analysis (if run_consciousness is True)
     4. Normalizes messages to plain text for the local LLM
     """
@@ -748,20 +3648,30 @@ def extract_inline_attachments(
                 logger.info(f"Extracted inline attachment: {filename} ({mime_type}, {len(file_bytes)} bytes)")
 
                 # Index through document processor
-                if processor is not None:
+                if processor is not None and not get_policy_manager().is_feature_enabled("chat_attachments"):
+                    text_parts.append(f"[Attached file: {filename} — not indexed (disabled by your administrator)]")
+                elif processor is not None:
+                    job_dir = get_job_workspace().create_job("attachment")
                     try:
-                        with tempfile.NamedTemporaryFile(delete=False, suffix=ext) as tmp:
+                        with tempfile.NamedTemporaryFile(delete=False, suffix=ext, dir=job_dir) as tmp:
                             tmp.write(file_bytes)
                             tmp_path = tmp.name
 
+                        from resource_monitor import get_resource_monitor
+                        get_quota_manager().check(
+                            additional_bytes=get_resource_monitor().estimate_index_bytes(len(file_bytes)),
+                            operation="attachment indexing"
+                        )
+                        from workload_lanes import get_workload_lanes
+                        get_workload_lanes().yield_to_interactive()
                         result = processor.ingest(tmp_path, force=True)
                         text_parts.append(f"[Attached file: {filename} — indexed, {len(result.chunks)} chunks]")
                         logger.info(f"Indexed inline attachment: {filename} ({len(result.chunks)} chunks)")
-
-                        os.unlink(tmp_path)
                     except Exception as e:
                         logger.warning(f"Failed to index attachment {filename}: {e}")
                         text_parts.append(f"[Attached file: {filename} — indexing failed]")
+                    finally:
+                        get_job_workspace().release(job_dir)
 
                 # Consciousness pipeline analysis (gated by run_consciousness flag)
                 if consciousness_pipeline is not None and run_consciousness:
@@ -910,7 +3820,10 @@ async def chat_completions(request: ChatCompletionRequest):
 
     # Extract and index any inline file attachments (Jan UI attachment flow)
     # Consciousness pipeline on attachments is gated by caps.consciousness
-    messages = extract_inline_attachments(messages, run_consciousness=caps.consciousness)
+    # Attachments are indexed off the event loop (see extract_inline_attachments)
+    messages = await asyncio.get_event_loop().run_in_executor(
+        None, lambda: extract_inline_attachments(messages, run_consciousness=caps.consciousness)
+    )
 
     # Determine if we should inject RAG context (gated by caps.rag)
     should_inject_rag = caps.rag and (
@@ -934,7 +3847,9 @@ async def chat_completions(request: ChatCompletionRequest):
         if query:
             logger.info(f"Retrieving context for: {query[:100]}...")
 
-            context = processor.get_context(
+            from workload_lanes import get_workload_lanes
+            context = await get_workload_lanes().run_interactive(
+                processor.get_context,
                 query=query,
                 n_chunks=config.max_chunks,
                 max_tokens=config.max_context_tokens,
@@ -1067,7 +3982,8 @@ async def audio_transcriptions(file: UploadFile = File(...)):
 
     # Save uploaded audio to temp file
     suffix = Path(file.filename).suffix.lower() if file.filename else ".wav"
-    with tempfile.NamedTemporaryFile(delete=False, suffix=suffix) as tmp:
+    job_dir = get_job_workspace().create_job("transcribe")
+    with tempfile.NamedTemporaryFile(delete=False, suffix=suffix, dir=job_dir) as tmp:
         content = await file.read()
         tmp.write(content)
         tmp_path = tmp.name
@@ -1090,7 +4006,7 @@ async def audio_transcriptions(file: UploadFile = File(...)):
             detail=f"Speech recognition failed: {str(e)}"
         )
     finally:
-        os.unlink(tmp_path)
+        get_job_workspace().release(job_dir)
 
 
 # ============================================================================
@@ -1222,6 +4138,14 @@ async def debug_report():
         "relevance_threshold": config.relevance_threshold,
     }
 
+    # Runtime, package and bundle details
+    report["python_status"] = get_python_status().get_python_status(
+        last_indexed_at=processor.last_indexed_at if processor else None
+    )
+
+    # Recent crashes (summaries only)
+    report["crashes"] = [r.summary() for r in get_crash_reporter().get_crash_reports(limit=10)]
+
     # Capability flags
     report["capabilities"] = {
         "consciousness_pipeline": CONSCIOUSNESS_PIPELINE_AVAILABLE,
@@ -1232,6 +4156,43 @@ async def debug_report():
     return report
 
 
+@app.get("/debug/python")
+async def get_python_runtime_status():
+    """
+    The Python runtime: interpreter and architecture, package and OCR
+    engine versions, plugin bundle version and checksum, and when a
+    request last succeeded.
+    """
+    status = get_python_status()
+    last_indexed_at = processor.last_indexed_at if processor else None
+    return await asyncio.get_event_loop().run_in_executor(
+        None, lambda: status.get_python_status(last_indexed_at=last_indexed_at)
+    )
+
+
+@app.get("/debug/crashes")
+async def list_crash_reports(limit: int = 20):
+    """Recent crash summaries, newest first."""
+    reports = get_crash_reporter().get_crash_reports(limit=limit)
+    return {"crashes": [r.summary() for r in reports], "total": len(reports)}
+
+
+@app.get("/debug/crashes/{crash_id}")
+async def get_crash_report(crash_id: str):
+    """Full crash report including the traceback."""
+    try:
+        return get_crash_reporter().get_crash_report(crash_id).to_dict()
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+
+@app.delete("/debug/crashes")
+async def clear_crash_reports():
+    """Delete all crash reports."""
+    removed = get_crash_reporter().clear()
+    return {"success": True, "removed": removed}
+
+
 @app.post("/debug/report/github")
 async def debug_report_github():
     """
@@ -1240,6 +4201,8 @@ async def debug_report_github():
     Calls the debug report endpoint internally, formats as markdown,
     and returns a pre-filled GitHub issue creation URL.
     """
+    require_feature("crash_report_upload")
+
     # Get the debug report
     report = await debug_report()
 
@@ -1282,6 +4245,17 @@ async def debug_report_github():
         f"- Consciousness: {report['capabilities']['consciousness_pipeline']}",
         f"- Soul Registry: {report['capabilities']['soul_registry']}",
         f"- Speech Recognition: {report['capabilities']['speech_recognition']}",
+        "",
+        "### Recent Crashes",
+    ]
+    for crash in report.get("crashes", [])[:5]:
+        body_lines.append(
+            f"- {crash['occurred_at']} {crash['context']}: {crash['exception_type']}: {crash['message']}"
+        )
+    if not report.get("crashes"):
+        body_lines.append("- None")
+
+    body_lines += [
         "",
         "### Describe the issue",
         "_Please describe what happened:_",
@@ -1322,6 +4296,742 @@ async def list_models():
             )
 
 
+@app.get("/usage")
+async def get_usage():
+    """Local feature usage counters and upload consent state."""
+    return get_usage_stats().get_usage_stats()
+
+
+@app.post("/usage/opt-in")
+async def usage_opt_in():
+    """Allow usage counters to be uploaded (POST /usage/upload)."""
+    require_feature("usage_upload")
+    return get_usage_stats().opt_in()
+
+
+@app.post("/usage/opt-out")
+async def usage_opt_out():
+    """Revoke upload consent; counters stay local."""
+    return get_usage_stats().opt_out()
+
+
+@app.get("/usage/payload")
+async def get_usage_payload():
+    """Exactly what an upload would send."""
+    return get_usage_stats().build_upload_payload()
+
+
+@app.post("/usage/upload")
+async def upload_usage():
+    """Send usage counters to USAGE_UPLOAD_URL (requires opt-in)."""
+    require_feature("usage_upload")
+    from usage_stats import UsageConsentError
+    try:
+        payload = await asyncio.get_event_loop().run_in_executor(None, get_usage_stats().upload)
+    except UsageConsentError as e:
+        raise HTTPException(status_code=403, detail=str(e))
+    except httpx.HTTPError as e:
+        raise HTTPException(status_code=502, detail=f"Upload failed: {e}")
+
+    return {"success": True, "uploaded": payload}
+
+
+@app.delete("/usage")
+async def reset_usage():
+    """Clear local usage counters."""
+    get_usage_stats().reset()
+    return {"success": True, "message": "Usage statistics cleared"}
+
+
+@app.get("/settings")
+async def get_settings():
+    """Settings that can be changed at runtime, with their values and whether policy pins them."""
+    return get_settings_store().get_settings()
+
+
+@app.put("/settings")
+async def update_settings(request: SettingsUpdateRequest):
+    """
+    Change settings without a restart. They are validated together (none
+    is changed if one is invalid), saved to settings.json and applied to
+    running components; a settings.changed event names what changed.
+    """
+    try:
+        changed = get_settings_store().update_settings(request.settings)
+    except KeyError as e:
+        raise HTTPException(status_code=400, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    except PermissionError as e:
+        raise HTTPException(status_code=403, detail=str(e))
+    except OSError as e:
+        raise HTTPException(status_code=500, detail=f"Could not save settings: {e}")
+
+    if changed:
+        get_audit_log().record("settings_updated", "settings", {"changed": changed})
+    return {"success": True, "changed": changed, **get_settings_store().get_settings()}
+
+
+@app.get("/policy")
+async def get_effective_policy():
+    """
+    Enterprise policy in effect: disabled features and pinned settings,
+    so the UI can grey out what the administrator has locked.
+    """
+    return get_policy_manager().get_effective_policy()
+
+
+@app.get("/errors/catalog")
+async def get_error_catalog():
+    """
+    Stable error codes and their English message templates. Error
+    responses carry code, params and fallback_message; the UI looks the
+    code up in its own translations and falls back to the English text.
+    """
+    from error_catalog import ERROR_CATALOG
+    return {"catalog": ERROR_CATALOG, "total": len(ERROR_CATALOG)}
+
+
+@app.get("/capabilities")
+async def list_capabilities():
+    """
+    Every API action with its parameter schema and whether it can be
+    used right now (processor running, policy, batch lock, Tesseract),
+    for building the command palette from the running backend.
+    """
+    from capabilities import list_capabilities as build_registry
+    from enterprise_policy import FEATURES
+    from resource_monitor import get_resource_monitor
+
+    policy = get_policy_manager()
+    unmet = {
+        f"feature:{name}": f"Disabled by policy: {description}"
+        for name, description in FEATURES.items()
+        if not policy.is_feature_enabled(name)
+    }
+    if processor is None:
+        reason = get_startup_guard().get_safe_mode_reason()
+        unmet["processor"] = f"Safe mode: {reason}" if reason else "The document processor is not running"
+    if batch_processor is not None and batch_processor.get_processing_status()["active_batches"]:
+        unmet["idle"] = "A batch is still processing"
+    if not get_resource_monitor().check_tesseract_available():
+        unmet["tesseract"] = "Tesseract OCR is not installed"
+
+    capabilities = build_registry(app.openapi(), unmet)
+    return {
+        "api_version": app.version,
+        "capabilities": capabilities,
+        "total": len(capabilities)
+    }
+
+
+# Lazy first-run setup wizard
+setup_wizard = None
+
+def get_setup_wizard():
+    """Get or create setup wizard instance."""
+    global setup_wizard
+    if setup_wizard is None:
+        from setup_wizard import SetupWizard
+        setup_wizard = SetupWizard(
+            config.persist_directory,
+            config.embedding_model,
+            lambda: processor,
+            detect_jan_version
+        )
+    return setup_wizard
+
+
+@app.get("/setup")
+async def get_setup_state():
+    """First-run checklist, plus the progress of setup steps run so far."""
+    return await asyncio.get_event_loop().run_in_executor(None, get_setup_wizard().get_setup_state)
+
+
+@app.post("/setup/steps/{step}")
+async def run_setup_step(step: str):
+    """
+    Run a setup step (create_config, download_embedding_model,
+    initialize_vector_store). Steps are safe to re-run; poll GET /setup
+    for progress while one is running.
+    """
+    loop = asyncio.get_event_loop()
+    try:
+        progress = await loop.run_in_executor(None, get_setup_wizard().run_setup_step, step)
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    except RuntimeError as e:
+        raise HTTPException(status_code=409, detail=str(e))
+
+    return {"success": progress.phase == "completed", "step": progress.to_dict()}
+
+
+# Lazy Jan thread indexer (incremental cursor over Jan's chat history)
+jan_thread_indexer = None
+
+def get_jan_thread_indexer():
+    """Get or create Jan thread indexer instance."""
+    global jan_thread_indexer
+    if jan_thread_indexer is None and processor is not None:
+        from jan_threads import JanThreadIndexer
+        jan_thread_indexer = JanThreadIndexer(
+            processor,
+            Path(config.persist_directory) / "jan_threads_cursor.json",
+            data_dir=Path(config.jan_data_dir) if config.jan_data_dir else None
+        )
+    return jan_thread_indexer
+
+
+@app.post("/jan/threads/index")
+async def index_jan_threads(
+    collection: Optional[str] = Form(None),
+    assistant_ids: Optional[str] = Form(None),
+    since: Optional[str] = Form(None),
+    until: Optional[str] = Form(None)
+):
+    """
+    Index Jan chat threads so past conversations are searchable. Only
+    messages added since the last run are indexed. assistant_ids is a
+    comma-separated list; since/until are ISO dates.
+    """
+    threads = get_jan_thread_indexer()
+    if threads is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        since_date = datetime.fromisoformat(since) if since else None
+        until_date = datetime.fromisoformat(until) if until else None
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=f"Invalid date: {e}")
+    assistants = [a.strip() for a in (assistant_ids or "").split(",") if a.strip()] or None
+
+    loop = asyncio.get_event_loop()
+    try:
+        report = await loop.run_in_executor(
+            None, lambda: threads.index_jan_threads(collection, assistants, since_date, until_date)
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except FileNotFoundError as e:
+        raise HTTPException(status_code=404, detail=str(e))
+
+    notify_webhooks(
+        "indexing.failed" if report.errors else "indexing.completed",
+        {"source": "jan_threads", **report.to_dict()}
+    )
+    return {"success": not report.errors, "report": report.to_dict()}
+
+
+@app.get("/jan/threads/cursor")
+async def get_jan_thread_cursor():
+    """Indexing progress per Jan thread."""
+    threads = get_jan_thread_indexer()
+    if threads is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    return {"data_dir": str(threads.data_dir), "threads": threads.get_cursor()}
+
+
+@app.delete("/jan/threads/cursor")
+async def reset_jan_thread_cursor():
+    """Forget thread indexing progress; the next run re-reads every message."""
+    threads = get_jan_thread_indexer()
+    if threads is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    threads.reset_cursor()
+    return {"success": True, "message": "Thread cursor reset"}
+
+
+# Lazy assistant collection bindings (default collection per Jan assistant)
+assistant_bindings = None
+
+def get_assistant_bindings():
+    """Get or create assistant binding store instance."""
+    global assistant_bindings
+    if assistant_bindings is None and processor is not None:
+        from assistant_bindings import AssistantBindingStore
+        assistant_bindings = AssistantBindingStore(
+            processor, Path(config.persist_directory) / "assistant_bindings.json"
+        )
+    return assistant_bindings
+
+
+@app.get("/assistants/bindings")
+async def list_assistant_bindings():
+    """Default collection bound to each assistant."""
+    bindings = get_assistant_bindings()
+    if bindings is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    items = bindings.list_assistant_bindings()
+    return {"bindings": items, "total": len(items)}
+
+
+@app.put("/assistants/{assistant_id}/collection")
+async def bind_assistant_collection(assistant_id: str, collection: str = Form(...)):
+    """
+    Record the assistant's collection. The store serves one collection,
+    so anything but that collection is rejected (400).
+    """
+    bindings = get_assistant_bindings()
+    if bindings is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        binding = bindings.bind_assistant_collection(assistant_id, collection)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return {"success": True, "binding": binding}
+
+
+@app.delete("/assistants/{assistant_id}/collection")
+async def unbind_assistant_collection(assistant_id: str):
+    """Remove the assistant's binding; its queries use the default collection."""
+    bindings = get_assistant_bindings()
+    if bindings is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        bindings.unbind_assistant(assistant_id)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    return {"success": True, "message": f"Binding removed for {assistant_id}"}
+
+
+@app.get("/webhooks")
+async def list_webhooks():
+    """Registered webhooks (secrets are not returned)."""
+    from webhooks import WEBHOOK_EVENTS
+    hooks = get_webhook_manager().list_webhooks()
+    return {"webhooks": [w.to_dict() for w in hooks], "events": list(WEBHOOK_EVENTS)}
+
+
+@app.post("/webhooks")
+async def add_webhook(
+    url: str = Form(...),
+    events: str = Form(...),
+    secret: Optional[str] = Form(None)
+):
+    """
+    Register a webhook. events is a comma-separated list, e.g.
+    "indexing.completed,indexing.failed". With a secret, payloads are
+    signed (X-Webhook-Signature: sha256=<HMAC of the body>).
+    """
+    event_list = [e.strip() for e in events.split(",") if e.strip()]
+    try:
+        webhook = get_webhook_manager().add_webhook(url, event_list, secret)
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    return {"success": True, "webhook": webhook.to_dict()}
+
+
+@app.delete("/webhooks/{webhook_id}")
+async def remove_webhook(webhook_id: str):
+    """Remove a webhook."""
+    try:
+        get_webhook_manager().remove_webhook(webhook_id)
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    return {"success": True, "message": f"Removed webhook: {webhook_id}"}
+
+
+@app.post("/webhooks/{webhook_id}/test")
+async def test_webhook(webhook_id: str):
+    """Send a signed test event once and report the outcome."""
+    hooks = get_webhook_manager()
+    try:
+        delivery = await asyncio.get_event_loop().run_in_executor(
+            None, lambda: hooks.send_test(webhook_id)
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+
+    return {"success": delivery["success"], "delivery": delivery}
+
+
+@app.get("/webhooks/deliveries")
+async def list_webhook_deliveries(limit: int = 100, webhook_id: Optional[str] = None):
+    """Delivery log, most recent attempt first."""
+    deliveries = get_webhook_manager().list_deliveries(limit=limit, webhook_id=webhook_id)
+    return {"deliveries": deliveries, "total": len(deliveries)}
+
+
+@app.get("/legacy-data")
+async def detect_legacy_data():
+    """Vector stores left in locations used by earlier builds."""
+    from legacy_data import detect_legacy_data as detect
+    stores = await asyncio.get_event_loop().run_in_executor(
+        None, lambda: detect(Path(config.persist_directory))
+    )
+    return {"stores": [s.to_dict() for s in stores], "total": len(stores)}
+
+
+@app.post("/legacy-data/migrate")
+async def migrate_legacy_data(
+    paths: Optional[str] = Form(None),
+    remove_legacy: bool = Form(False)
+):
+    """
+    Merge legacy stores into the current collection (all detected stores,
+    or paths as a JSON list). Chunks already present are skipped. Settings
+    files the current store lacks are copied and apply after a restart.
+    remove_legacy deletes each store that migrated without errors.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    try:
+        selected = json.loads(paths) if paths else None
+    except ValueError:
+        raise HTTPException(status_code=400, detail="paths must be a JSON list")
+
+    from legacy_data import migrate_legacy_data as migrate
+    loop = asyncio.get_event_loop()
+    try:
+        reports = await loop.run_in_executor(
+            None,
+            lambda: migrate(processor.vector_store, Path(config.persist_directory), selected, remove_legacy)
+        )
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    success = not any(r.errors for r in reports)
+    notify_webhooks(
+        "import.completed" if success else "import.failed",
+        {"source": "legacy_data", "migrated": [r.to_dict() for r in reports]}
+    )
+    return {
+        "success": success,
+        "migrated": [r.to_dict() for r in reports]
+    }
+
+
+@app.post("/collections/import")
+async def import_external_index(
+    path: str = Form(...),
+    format: str = Form(...),
+    collection: Optional[str] = Form(None),
+    source_model: Optional[str] = Form(None)
+):
+    """
+    Import chunks indexed by another RAG tool: a ChromaDB folder
+    ("chroma"), or a "jsonl" / "csv" file of texts with optional metadata
+    and embeddings. Vectors are reused when they match the collection's
+    embedding model (dimension, and source_model or the Chroma collection's
+    recorded model if known); everything else is re-embedded.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    from external_import import import_external_index as run_import
+    loop = asyncio.get_event_loop()
+    try:
+        report = await loop.run_in_executor(
+            None,
+            lambda: run_import(processor.vector_store, Path(path), format, collection, source_model)
+        )
+    except KeyError as e:
+        raise HTTPException(status_code=404, detail=e.args[0])
+    except FileNotFoundError as e:
+        raise HTTPException(status_code=404, detail=str(e))
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    except CollectionReadOnlyError:
+        raise
+    except Exception as e:
+        notify_webhooks("import.failed", {"source": "external_index", "path": path, "format": format, "error": str(e)})
+        raise HTTPException(status_code=500, detail=f"Import failed: {e}")
+
+    notify_webhooks("import.completed", {"source": "external_index", **report.to_dict()})
+    return {"success": True, "import": report.to_dict()}
+
+
+@app.post("/knowledge-base/reset")
+async def reset_knowledge_base(backup_directory: Optional[str] = Form(None)):
+    """
+    Factory-reset the knowledge base: every document, chunk, shadow index
+    and trash entry is removed and the default collection starts empty.
+    Settings and presets are kept. backup_directory saves a zip backup
+    first; the reset doesn't start if the backup fails.
+    """
+    if processor is None:
+        raise HTTPException(status_code=503, detail="Processor not initialized")
+
+    bp = batch_processor
+    if bp is not None and bp.get_processing_status()["active_batches"]:
+        raise HTTPException(status_code=409, detail="A batch is still processing; wait for it or pause it first")
+
+    from app_data import reset_knowledge_base as run_reset
+    loop = asyncio.get_event_loop()
+    try:
+        report = await loop.run_in_executor(
+            None,
+            lambda: run_reset(
+                processor,
+                Path(config.persist_directory),
+                backup_directory=Path(backup_directory) if backup_directory else None
+            )
+        )
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    except OSError as e:
+        raise HTTPException(status_code=500, detail=f"Backup failed, nothing was reset: {e}")
+
+    if jan_thread_indexer is not None:
+        jan_thread_indexer.reset_cursor()
+    invalidate_similarity_graph()
+    get_audit_log().record("knowledge_base_reset", report.collection, {
+        "documents_removed": report.documents_removed,
+        "chunks_removed": report.chunks_removed,
+        "backup": report.backup["path"] if report.backup else None
+    })
+    return {"success": not report.errors, "report": report.to_dict()}
+
+
+@app.post("/uninstall")
+async def prepare_uninstall(
+    keep_knowledge: bool = Form(False),
+    backup_directory: Optional[str] = Form(None)
+):
+    """
+    Remove the plugin's data before uninstalling. Caches are always
+    removed; with keep_knowledge the vector store, trash and settings
+    stay. backup_directory saves a final zip backup first. Background
+    tasks are stopped and the document store is closed, so stop the
+    server afterwards.
+    """
+    global processor, retention_task, usage_stats
+
+    bp = batch_processor
+    if bp is not None and bp.get_processing_status()["active_batches"]:
+        raise HTTPException(status_code=409, detail="A batch is still processing; wait for it or pause it first")
+
+    # Stop background work and release files held open in the storage folder
+    if retention_task is not None:
+        retention_task.cancel()
+        retention_task = None
+    usage_stats = None
+    if collection_optimizer is not None:
+        collection_optimizer.cancel()
+    if not keep_knowledge and processor is not None:
+        client = processor.vector_store.client
+        processor = None
+        if hasattr(client, "clear_system_cache"):
+            client.clear_system_cache()
+
+    from app_data import prepare_uninstall as run_cleanup
+    loop = asyncio.get_event_loop()
+    try:
+        report = await loop.run_in_executor(
+            None,
+            lambda: run_cleanup(
+                Path(config.persist_directory),
+                keep_knowledge=keep_knowledge,
+                backup_directory=Path(backup_directory) if backup_directory else None
+            )
+        )
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    except OSError as e:
+        raise HTTPException(status_code=500, detail=f"Backup failed: {e}")
+
+    return {"success": not report.errors, "report": report.to_dict()}
+
+
+# Lazy singletons holding the processor or files in the storage folder.
+# close_document_store drops them before the folder is copied, so nothing
+# keeps writing to the old location; they're recreated on next use.
+STORAGE_SINGLETONS = (
+    "usage_stats", "settings_store", "thumbnail_cache", "audit_log", "webhook_manager",
+    "event_log", "crash_reporter", "startup_guard", "preset_store", "scan_snapshots",
+    "file_type_handlers", "batch_processor", "trash_store", "job_workspace",
+    "retention_manager", "quota_manager", "collection_exporter", "shadow_index_manager",
+    "collection_optimizer", "collection_migrator", "similarity_graph_builder",
+    "setup_wizard", "jan_thread_indexer", "assistant_bindings", "consciousness_pipeline",
+)
+
+# Requests served while the data is being moved (and until the restart after)
+RELOCATION_ALLOWED_PATHS = ("/settings/data-location", "/health")
+
+# Static exports running (they read the whole store; see store_work_in_progress)
+static_exports_running = 0
+
+
+def store_work_in_progress() -> List[str]:
+    """Ingests, exports and rebuilds still using the store (they would be cut off by a relocation)."""
+    busy = []
+    if batch_processor is not None and batch_processor.get_processing_status()["active_batches"]:
+        busy.append("a batch")
+    if processor is not None:
+        ingests = processor.get_index_state()["documents_in_progress"]
+        if ingests:
+            busy.append(f"{ingests} document(s) being indexed")
+    if jan_thread_indexer is not None and jan_thread_indexer.indexing:
+        busy.append("Jan thread indexing")
+    if collection_exporter is not None and collection_exporter.current \
+            and collection_exporter.current.phase in ("pending", "writing"):
+        busy.append("a collection export")
+    if static_exports_running:
+        busy.append("a static search export")
+    if shadow_index_manager is not None and shadow_index_manager.building:
+        busy.append("a shadow index build")
+    if collection_migrator is not None and collection_migrator.current \
+            and collection_migrator.current.phase in ("pending", "copying", "verifying", "swapping"):
+        busy.append("a collection migration")
+    return busy
+
+
+def release_storage_singletons():
+    """Drop every lazy singleton in STORAGE_SINGLETONS (flushing usage stats first)."""
+    if usage_stats is not None:
+        usage_stats.flush()
+    if collection_optimizer is not None:
+        collection_optimizer.cancel()
+    for name in STORAGE_SINGLETONS:
+        globals()[name] = None
+
+
+def close_document_store() -> bool:
+    """
+    Stop background work and release everything holding the processor or
+    files in the storage folder. Returns whether the store was open.
+    """
+    global processor, retention_task
+    if retention_task is not None:
+        retention_task.cancel()
+        retention_task = None
+    release_storage_singletons()
+    if processor is None:
+        return False
+    client = processor.vector_store.client
+    processor = None
+    if hasattr(client, "clear_system_cache"):
+        client.clear_system_cache()
+    return True
+
+
+def reopen_document_store():
+    """Reopen the store where it was after a failed relocation (no startup checks or hooks)."""
+    # Anything created while the relocation ran would point at stale state
+    release_storage_singletons()
+    open_document_store()
+
+
+@app.middleware("http")
+async def refuse_during_relocation(request: Request, call_next):
+    """Answer 503 while the data folder is moved; only its progress and /health are served."""
+    block = relocation_block()
+    if block is not None and request.url.path not in RELOCATION_ALLOWED_PATHS:
+        return JSONResponse(status_code=503, content={"detail": block}, headers={"Retry-After": "5"})
+    return await call_next(request)
+
+
+# Lazy data relocator
+data_relocator = None
+store_relocation = None
+
+def get_data_relocator():
+    """Get or create data relocator instance."""
+    global data_relocator
+    if data_relocator is None:
+        from data_relocation import DataRelocator
+        data_relocator = DataRelocator()
+    return data_relocator
+
+
+def get_store_relocation():
+    """Get or create the relocation wrapper that closes and reopens the store."""
+    global store_relocation
+    if store_relocation is None:
+        from data_relocation import StoreRelocation
+        store_relocation = StoreRelocation(get_data_relocator(), close_document_store, reopen_document_store)
+    return store_relocation
+
+
+def relocation_block() -> Optional[str]:
+    """Why requests are refused because of a data relocation, or None."""
+    return store_relocation.block if store_relocation is not None else None
+
+
+@app.get("/settings/data-location")
+async def get_data_location():
+    """Where the plugin keeps its data, and the running (or last) relocation."""
+    from app_paths import describe_paths
+    relocator = get_data_relocator()
+    return {
+        "paths": describe_paths(config.persist_directory, config.portable),
+        "relocation": relocator.current.to_dict() if relocator.current else None
+    }
+
+
+@app.post("/settings/data-location")
+async def set_data_location(new_path: str = Form(...)):
+    """
+    Move the storage folder (and the model cache in portable mode) to
+    new_path, e.g. another disk. Background work is stopped, the
+    document store closed and every other request refused (503) while
+    files are copied and verified; poll GET /settings/data-location for
+    progress. On failure everything is rolled back and the store
+    reopened; on success requests stay refused until the server is
+    restarted to use the new location. Refused (409) while documents are
+    being indexed or exported, since their writes would be lost.
+    """
+    busy = store_work_in_progress()
+    if busy:
+        raise HTTPException(status_code=409, detail=f"Wait for {', '.join(busy)} to finish (or pause it) first")
+    relocation = get_store_relocation()
+    if relocation.block is not None:
+        raise HTTPException(status_code=409, detail=relocation.block)
+
+    from app_paths import get_model_cache_dir
+    folders = {"storage_directory": Path(config.persist_directory)}
+    model_cache = get_model_cache_dir(config.portable)
+    if model_cache is not None:
+        folders["model_cache"] = model_cache
+
+    try:
+        progress = await relocation.run(Path(new_path), folders)
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+    if progress.phase != "completed":
+        return {"success": False, "restart_required": False, "relocation": progress.to_dict()}
+    return {"success": True, "restart_required": True, "relocation": progress.to_dict()}
+
+
+@app.get("/safe-mode")
+async def get_safe_mode():
+    """Whether the server started in safe mode after repeated failed starts, and why."""
+    return get_startup_guard().get_status()
+
+
+@app.post("/safe-mode/exit")
+async def exit_safe_mode():
+    """
+    Leave safe mode and start normally: the document store is opened and
+    scheduled work starts. If this start fails too it counts as another
+    failed start.
+    """
+    guard = get_startup_guard()
+    if guard.get_safe_mode_reason() is None:
+        return {"success": True, "status": guard.get_status()}
+
+    guard.exit_safe_mode()
+    try:
+        await startup()
+    except Exception as e:
+        report = get_crash_reporter().capture(e, "request", "POST /safe-mode/exit")
+        raise HTTPException(status_code=500, detail=f"Normal startup failed: {report.message} (crash {report.crash_id})")
+    return {"success": True, "status": guard.get_status()}
+
+
 @app.get("/health")
 async def health_check():
     """Health check endpoint with resource monitoring."""
@@ -1334,6 +5044,9 @@ async def health_check():
     except:
         pass
     
+    from ocr_languages import installed_ocr_languages
+    from app_paths import describe_paths
+
     # Get resource info
     resource_info = {}
     try:
@@ -1356,8 +5069,14 @@ async def health_check():
         "jan_url": config.jan_base_url,
         "jan_version": detected_jan_version,
         "documents_indexed": processor.get_stats()["documents_indexed"] if processor else 0,
+        "safe_mode": get_startup_guard().get_safe_mode_reason(),
         "auto_inject": config.auto_inject,
-        "system_resources": resource_info
+        "system_resources": resource_info,
+        "ocr": {
+            "available": processor.extractor.ocr_available if processor else False,
+            "languages": installed_ocr_languages()
+        },
+        "paths": describe_paths(config.persist_directory, config.portable)
     }
 
 
@@ -1419,14 +5138,154 @@ async def root():
             "ui": "GET /ui",
             "documents": {
                 "upload": "POST /documents",
+                "scan": "POST /documents/scan",
+                "scan_snapshots": "GET /documents/scan/snapshots",
+                "scan_diff": "GET /documents/scan/diff?directory=",
+                "process_scan_diff": "POST /documents/scan/diff/process",
                 "list": "GET /documents",
                 "delete": "DELETE /documents/{doc_hash}",
+                "delete_all": "DELETE /documents",
                 "query": "POST /documents/query",
-                "stats": "GET /documents/stats"
+                "search": "POST /documents/search",
+                "search_stream": "POST /documents/search/stream",
+                "search_batch": "POST /documents/search/batch",
+                "analyze": "POST /documents/analyze",
+                "check_duplicate": "POST /documents/check-duplicate",
+                "passwords": "PUT/DELETE /documents/passwords",
+                "resume_batch": "POST /documents/batch/{batch_id}/resume",
+                "thumbnail": "GET /documents/thumbnail?doc_hash=&page=&dpi=",
+                "stats": "GET /documents/stats?sources=",
+                "versions": "GET /documents/versions?path=",
+                "find": "GET /documents/find?pattern=",
+                "diff": "POST /documents/diff",
+                "export_static": "POST /documents/export/static",
+                "eval": "POST /eval/retrieval",
+                "tune_chunking": "POST /collections/chunking/tune",
+                "stale_sources": "GET /collections/stale",
+                "refresh_stale": "POST /collections/stale/refresh",
+                "graph": "GET /documents/graph",
+                "clusters": "GET /documents/clusters"
+            },
+            "presets": {
+                "list": "GET /presets",
+                "create": "POST /presets",
+                "delete": "DELETE /presets/{name}",
+                "apply": "POST /presets/{name}/apply"
+            },
+            "file_types": {
+                "list": "GET /file-types",
+                "set": "PUT /file-types/{extension}"
+            },
+            "shares": {
+                "credentials": "PUT/DELETE /shares/credentials",
+                "test": "POST /shares/test"
+            },
+            "trash": {
+                "list": "GET /trash",
+                "restore": "POST /trash/{trash_id}/restore",
+                "delete": "DELETE /trash/{trash_id}",
+                "empty": "DELETE /trash"
+            },
+            "shadow_index": {
+                "build": "POST /shadow-index",
+                "status": "GET /shadow-index",
+                "compare": "POST /shadow-index/compare",
+                "promote": "POST /shadow-index/promote",
+                "discard": "DELETE /shadow-index"
+            },
+            "collections": {
+                "migrate": "POST /collections/migrate",
+                "migrate_status": "GET /collections/migrate/status",
+                "export": "POST /collections/export",
+                "export_status": "GET /collections/export/status",
+                "import": "POST /collections/import",
+                "metadata": "POST /collections/metadata",
+                "optimize": "POST /collections/optimize",
+                "optimize_status": "GET /collections/optimize/status"
+            },
+            "retention": {
+                "policy": "GET/PUT /collections/retention",
+                "enforce": "POST /collections/retention/enforce"
+            },
+            "quota": "GET/PUT /collections/quota",
+            "settings": "GET/PUT /settings",
+            "policy": "GET /policy",
+            "capabilities": "GET /capabilities",
+            "data_location": "GET/POST /settings/data-location",
+            "error_catalog": "GET /errors/catalog",
+            "setup": {
+                "state": "GET /setup",
+                "run_step": "POST /setup/steps/{step}"
+            },
+            "reset": "POST /knowledge-base/reset",
+            "jan_threads": {
+                "index": "POST /jan/threads/index",
+                "cursor": "GET/DELETE /jan/threads/cursor"
+            },
+            "assistant_bindings": {
+                "list": "GET /assistants/bindings",
+                "bind": "PUT/DELETE /assistants/{assistant_id}/collection"
+            },
+            "webhooks": {
+                "list": "GET /webhooks",
+                "add": "POST /webhooks",
+                "remove": "DELETE /webhooks/{webhook_id}",
+                "test": "POST /webhooks/{webhook_id}/test",
+                "deliveries": "GET /webhooks/deliveries"
+            },
+            "legacy_data": {
+                "detect": "GET /legacy-data",
+                "migrate": "POST /legacy-data/migrate"
+            },
+            "uninstall": "POST /uninstall",
+            "thumbnail_cache": "GET/DELETE /thumbnails/cache",
+            "ocr_languages": {
+                "list": "GET /ocr/languages",
+                "install": "POST /ocr/languages/{lang}"
+            },
+            "retrieval_config": "GET/PUT /collections/retrieval-config",
+            "embeddings": "POST /embeddings",
+            "similarity": "POST /similarity",
+            "collections": {
+                "list": "GET /collections?detailed=",
+                "appearance": "PUT /collections/appearance",
+                "readonly": "PUT /collections/readonly"
+            },
+            "chunks": {
+                "list": "GET /chunks?source_path=&page=",
+                "update": "PUT /chunks/{chunk_id}",
+                "delete": "DELETE /chunks/{chunk_id}"
+            },
+            "audit": "GET /audit",
+            "history_export": "POST /history/export",
+            "usage": {
+                "stats": "GET /usage",
+                "opt_in": "POST /usage/opt-in",
+                "opt_out": "POST /usage/opt-out",
+                "payload": "GET /usage/payload",
+                "upload": "POST /usage/upload",
+                "reset": "DELETE /usage"
+            },
+            "processing": {
+                "pause": "POST /processing/pause",
+                "resume": "POST /processing/resume",
+                "status": "GET /processing/status",
+                "narration": "GET /processing/narration"
+            },
+            "events": {
+                "for_request": "GET /events/requests/{request_id}",
+                "replay": "GET /events/replay"
             },
             "debug": {
                 "report": "GET /debug/report",
-                "github": "POST /debug/report/github"
+                "python": "GET /debug/python",
+                "github": "POST /debug/report/github",
+                "crashes": "GET /debug/crashes",
+                "crash": "GET /debug/crashes/{crash_id}"
+            },
+            "safe_mode": {
+                "status": "GET /safe-mode",
+                "exit": "POST /safe-mode/exit"
             },
             "health": "GET /health"
         },
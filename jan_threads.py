@@ -0,0 +1,250 @@
+"""
+Jan Thread Indexing for Jan Document Plugin
+
+Makes the user's own chat history searchable: conversations are read
+from Jan's data folder and indexed like documents, one chunk per
+message (long messages are split by the chunker), with role, timestamp,
+thread and assistant metadata.
+
+Jan stores each thread in <data>/threads/<thread_id>/:
+- thread.json: id, title, assistants
+- messages.jsonl: one message per line (id, role, content, created_at)
+
+A cursor records the newest message indexed per thread, so later runs
+only index messages added since. Cursor: <storage>/jan_threads_cursor.json
+"""
+
+import os
+import sys
+import json
+import hashlib
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, List, Optional
+from dataclasses import dataclass, field, asdict
+from datetime import datetime
+
+from document_processor import DocumentProcessor, DocumentChunk, CHAT_SOURCE_TYPE
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+SOURCE_TYPE = CHAT_SOURCE_TYPE
+
+
+def default_jan_data_dir() -> Path:
+    """Jan's data folder for this platform."""
+    if sys.platform == "win32":
+        return Path(os.environ.get("APPDATA", "")) / "jan" / "data"
+    if sys.platform == "darwin":
+        return Path.home() / "Library" / "Application Support" / "Jan" / "data"
+    return Path.home() / ".config" / "Jan" / "data"
+
+
+def _timestamp(value) -> Optional[datetime]:
+    """Jan timestamps are epoch milliseconds (older builds: seconds)."""
+    if not isinstance(value, (int, float)) or value <= 0:
+        return None
+    return datetime.fromtimestamp(value / 1000 if value > 1e11 else value)
+
+
+def _message_text(content) -> str:
+    if isinstance(content, str):
+        return content
+    parts = []
+    for part in content or []:
+        if part.get("type") == "text":
+            text = part.get("text")
+            parts.append(text.get("value", "") if isinstance(text, dict) else str(text or ""))
+    return "\n".join(p for p in parts if p)
+
+
+@dataclass
+class ThreadIndexReport:
+    """Outcome of one index_jan_threads run."""
+    collection: str
+    data_dir: str
+    threads_scanned: int = 0
+    threads_indexed: int = 0
+    messages_indexed: int = 0
+    chunks_added: int = 0
+    errors: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+class JanThreadIndexer:
+    """
+    Indexes Jan conversations incrementally.
+
+    Usage:
+        threads = JanThreadIndexer(processor, Path("./jan_doc_store/jan_threads_cursor.json"))
+        report = threads.index_jan_threads(assistant_ids=["jan"])
+    """
+
+    def __init__(
+        self,
+        document_processor: DocumentProcessor,
+        cursor_path: Path,
+        data_dir: Optional[Path] = None
+    ):
+        """
+        Initialize thread indexer.
+
+        Args:
+            document_processor: Processor whose collection receives the threads
+            cursor_path: JSON file recording indexing progress per thread
+            data_dir: Jan's data folder (None = platform default)
+        """
+        self.processor = document_processor
+        self.cursor_path = Path(cursor_path)
+        self.data_dir = Path(data_dir) if data_dir else default_jan_data_dir()
+        self._lock = threading.Lock()
+        self._cursor: Dict[str, Dict] = {}
+        if self.cursor_path.exists():
+            try:
+                self._cursor = json.loads(self.cursor_path.read_text(encoding="utf-8"))
+            except (OSError, ValueError) as e:
+                logger.warning(f"Could not read thread cursor: {e}")
+
+    def _save_cursor(self):
+        self.cursor_path.parent.mkdir(parents=True, exist_ok=True)
+        self.cursor_path.write_text(json.dumps(self._cursor, indent=2), encoding="utf-8")
+
+    @staticmethod
+    def thread_doc_hash(thread_id: str) -> str:
+        return hashlib.sha256(f"{SOURCE_TYPE}:{thread_id}".encode("utf-8")).hexdigest()[:16]
+
+    @property
+    def indexing(self) -> bool:
+        """Whether a run is in progress."""
+        return self._lock.locked()
+
+    def get_cursor(self) -> Dict:
+        """Title, newest indexed message time and next chunk index per thread."""
+        return dict(self._cursor)
+
+    def reset_cursor(self):
+        """Forget indexing progress (the next run re-reads every message)."""
+        with self._lock:
+            self._cursor = {}
+            self._save_cursor()
+
+    def index_jan_threads(
+        self,
+        collection: Optional[str] = None,
+        assistant_ids: Optional[List[str]] = None,
+        since: Optional[datetime] = None,
+        until: Optional[datetime] = None
+    ) -> ThreadIndexReport:
+        """
+        Index messages added since the last run.
+
+        Args:
+            collection: Collection name (None = the store's collection)
+            assistant_ids: Only threads with one of these assistants
+            since: Skip messages older than this (they are not revisited
+                   by later runs unless the cursor is reset)
+            until: Leave messages newer than this for a later run
+
+        Raises:
+            KeyError: If collection is not the store's collection
+            FileNotFoundError: If Jan's threads folder doesn't exist
+        """
+        name = self.processor.vector_store.collection.name
+        check_collection(collection, name)
+
+        threads_dir = self.data_dir / "threads"
+        if not threads_dir.is_dir():
+            raise FileNotFoundError(f"Jan threads folder not found: {threads_dir}")
+
+        report = ThreadIndexReport(collection=name, data_dir=str(self.data_dir))
+        with self._lock:
+            for thread_dir in sorted(p for p in threads_dir.iterdir() if p.is_dir()):
+                report.threads_scanned += 1
+                try:
+                    self._index_thread(thread_dir, assistant_ids, since, until, report)
+                except (OSError, ValueError) as e:
+                    report.errors.append(f"{thread_dir.name}: {e}")
+            self._save_cursor()
+
+        logger.info(
+            f"Indexed {report.messages_indexed} new message(s) from "
+            f"{report.threads_indexed} Jan thread(s) ({report.chunks_added} chunks)"
+        )
+        return report
+
+    def _index_thread(
+        self,
+        thread_dir: Path,
+        assistant_ids: Optional[List[str]],
+        since: Optional[datetime],
+        until: Optional[datetime],
+        report: ThreadIndexReport
+    ):
+        thread_file = thread_dir / "thread.json"
+        messages_file = thread_dir / "messages.jsonl"
+        if not messages_file.exists():
+            return
+
+        thread = json.loads(thread_file.read_text(encoding="utf-8")) if thread_file.exists() else {}
+        thread_id = thread.get("id") or thread_dir.name
+        assistants = [a.get("assistant_id") for a in thread.get("assistants", []) if isinstance(a, dict)]
+        if assistant_ids and not set(assistant_ids) & set(assistants):
+            return
+
+        cursor = self._cursor.get(thread_id, {"last_created_at": 0, "next_chunk_index": 0})
+        title = thread.get("title") or thread_id
+        doc_hash = self.thread_doc_hash(thread_id)
+
+        chunks: List[DocumentChunk] = []
+        newest = cursor["last_created_at"]
+        messages = 0
+        with open(messages_file, encoding="utf-8") as f:
+            for line in f:
+                try:
+                    message = json.loads(line)
+                except ValueError:
+                    continue
+                created_at = message.get("created_at") or 0
+                if created_at <= cursor["last_created_at"]:
+                    continue
+                when = _timestamp(created_at)
+                if until and when and when > until:
+                    continue  # Left for a later run
+                newest = max(newest, created_at)
+                if since and when and when < since:
+                    continue
+                text = _message_text(message.get("content")).strip()
+                if not text:
+                    continue
+
+                role = message.get("role", "unknown")
+                for piece in self.processor.chunker.chunk(text, doc_hash):
+                    piece.chunk_index = cursor["next_chunk_index"] + len(chunks)
+                    piece.content = f"{role.capitalize()}: {piece.content}"
+                    piece.metadata.update({
+                        "source_type": SOURCE_TYPE,
+                        "thread_id": thread_id,
+                        "thread_title": title,
+                        "assistant_id": message.get("assistant_id") or (assistants[0] if assistants else ""),
+                        "message_id": message.get("id", ""),
+                        "role": role,
+                        "timestamp": when.isoformat() if when else "",
+                        "source_path": f"{SOURCE_TYPE}:{thread_id}"
+                    })
+                    chunks.append(piece)
+                messages += 1
+
+        if chunks:
+            self.processor.vector_store.add_chunks(chunks, f"Jan thread: {title}")
+            report.threads_indexed += 1
+            report.messages_indexed += messages
+            report.chunks_added += len(chunks)
+        self._cursor[thread_id] = {
+            "title": title,
+            "last_created_at": newest,
+            "next_chunk_index": cursor["next_chunk_index"] + len(chunks)
+        }
@@ -0,0 +1,129 @@
+"""
+Job Workspace for Jan Document Plugin
+
+Keeps the plugin's temporary files under the storage folder instead of
+the OS temp folder, which on locked-down machines can be redirected,
+quota-limited or cleaned by other software mid-job:
+
+- Each upload, batch, attachment or transcription gets its own folder
+  <storage>/work/<kind>-<id>, removed when the job finishes or fails
+- Temp files made by libraries (OCR, document snapshots) go to
+  <storage>/work/tmp via Python's tempfile default
+- Folders left behind by a crash are removed on the next start, and
+  whatever is left over is reported in /documents/stats
+"""
+
+import uuid
+import shutil
+import tempfile
+import logging
+from pathlib import Path
+from typing import Dict, Set
+
+logger = logging.getLogger(__name__)
+
+WORK_DIR_NAME = "work"
+SHARED_TEMP_NAME = "tmp"
+
+
+def _size(path: Path) -> int:
+    if path.is_file():
+        return path.stat().st_size
+    return sum(p.stat().st_size for p in path.rglob("*") if p.is_file())
+
+
+class JobWorkspace:
+    """
+    Per-job temp folders under the storage folder.
+
+    Usage:
+        workspace = JobWorkspace(Path("./jan_doc_store") / "work")
+        workspace.prepare()
+        job_dir = workspace.create_job("upload")
+        try:
+            ...
+        finally:
+            workspace.release(job_dir)
+    """
+
+    def __init__(self, root: Path):
+        """
+        Initialize workspace.
+
+        Args:
+            root: Folder holding job folders (created if needed)
+        """
+        self.root = Path(root)
+        self.active: Set[Path] = set()
+
+    @property
+    def shared_temp(self) -> Path:
+        return self.root / SHARED_TEMP_NAME
+
+    def prepare(self) -> int:
+        """
+        Remove leftovers from earlier runs and route tempfile here.
+
+        Call once at startup, before any job runs.
+
+        Returns:
+            Number of leftover entries removed
+        """
+        removed = 0
+        if self.root.exists():
+            for entry in self.root.iterdir():
+                try:
+                    if entry.is_dir():
+                        shutil.rmtree(entry)
+                    else:
+                        entry.unlink()
+                    removed += 1
+                except OSError as e:
+                    logger.warning(f"Could not remove leftover temp entry {entry}: {e}")
+        if removed:
+            logger.info(f"Removed {removed} leftover temp entries from {self.root}")
+
+        self.shared_temp.mkdir(parents=True, exist_ok=True)
+        tempfile.tempdir = str(self.shared_temp)
+        return removed
+
+    def create_job(self, kind: str) -> Path:
+        """Create an empty folder for one job; pass it to release() when done."""
+        job_dir = self.root / f"{kind}-{uuid.uuid4().hex[:12]}"
+        job_dir.mkdir(parents=True)
+        self.active.add(job_dir)
+        return job_dir
+
+    def release(self, job_dir: Path):
+        """Remove a job's folder and everything in it."""
+        self.active.discard(job_dir)
+        shutil.rmtree(job_dir, ignore_errors=True)
+        if job_dir.exists():
+            logger.warning(f"Could not fully remove job folder {job_dir}")
+
+    def get_usage(self) -> Dict:
+        """Active jobs and temp space left behind by finished ones."""
+        leftover_entries = 0
+        leftover_bytes = 0
+        active_bytes = 0
+        if self.root.exists():
+            entries = [e for e in self.root.iterdir() if e != self.shared_temp]
+            if self.shared_temp.exists():
+                entries += list(self.shared_temp.iterdir())
+            for entry in entries:
+                try:
+                    size = _size(entry)
+                except OSError:
+                    continue   # Removed while we looked
+                if entry in self.active:
+                    active_bytes += size
+                else:
+                    leftover_entries += 1
+                    leftover_bytes += size
+        return {
+            "directory": str(self.root),
+            "active_jobs": len(self.active),
+            "active_bytes": active_bytes,
+            "leftover_entries": leftover_entries,
+            "leftover_bytes": leftover_bytes
+        }
@@ -0,0 +1,223 @@
+"""
+Keyword Scoring for Jan Document Plugin
+
+Blends BM25 keyword scores into vector search results and highlights
+matched terms in result snippets. Domain corpora can tune it per
+collection (set_retrieval_config):
+
+- stopwords: words or phrases ignored when scoring and highlighting,
+  e.g. "the company" or a boilerplate header repeated on every page
+- boosts: per-term weight multipliers, e.g. {"warranty": 2.0}
+- keyword_weight: share of the final score taken from keyword scoring
+  (0 = pure vector ranking, the default)
+
+Configs are stored as JSON keyed by collection name:
+<storage>/retrieval_config.json
+"""
+
+import re
+import json
+import math
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, List
+from dataclasses import dataclass, field, asdict
+from collections import Counter
+
+logger = logging.getLogger(__name__)
+
+# Common English function words, always ignored
+DEFAULT_STOPWORDS = {
+    "a", "an", "the", "and", "or", "of", "to", "in", "on", "at", "by", "for",
+    "with", "from", "is", "are", "was", "were", "be", "been", "it", "its",
+    "this", "that", "these", "those", "as", "not", "but", "if", "then", "so",
+    "do", "does", "did", "what", "which", "who", "how", "when", "where", "why"
+}
+
+# BM25 parameters
+BM25_K1 = 1.5
+BM25_B = 0.75
+
+# Snippet length around the first highlighted term
+SNIPPET_CHARS = 240
+
+WORD_PATTERN = re.compile(r"\w+", re.UNICODE)
+
+
+@dataclass
+class RetrievalConfig:
+    """Keyword scoring settings for one collection."""
+    stopwords: List[str] = field(default_factory=list)
+    boosts: Dict[str, float] = field(default_factory=dict)
+    keyword_weight: float = 0.0
+
+    def __post_init__(self):
+        self.stopwords = sorted({s.strip().lower() for s in self.stopwords if s.strip()})
+        self.boosts = {k.strip().lower(): float(v) for k, v in self.boosts.items() if k.strip()}
+        if not 0.0 <= self.keyword_weight <= 1.0:
+            raise ValueError("keyword_weight must be between 0 and 1")
+        for term, weight in self.boosts.items():
+            if weight <= 0:
+                raise ValueError(f"Boost for '{term}' must be positive")
+
+    @property
+    def stop_terms(self) -> set:
+        """Single-word stopwords (defaults included)."""
+        return DEFAULT_STOPWORDS | {s for s in self.stopwords if " " not in s}
+
+    @property
+    def stop_phrases(self) -> List[str]:
+        """Multi-word stopwords, longest first."""
+        return sorted((s for s in self.stopwords if " " in s), key=len, reverse=True)
+
+    def tokenize(self, text: str) -> List[str]:
+        """Lowercase word tokens with stopwords and stop phrases removed."""
+        text = text.lower()
+        for phrase in self.stop_phrases:
+            text = text.replace(phrase, " ")
+        stop = self.stop_terms
+        return [w for w in WORD_PATTERN.findall(text) if w not in stop]
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+    @classmethod
+    def from_dict(cls, data: Dict) -> "RetrievalConfig":
+        return cls(
+            stopwords=list(data.get("stopwords", [])),
+            boosts=dict(data.get("boosts", {})),
+            keyword_weight=float(data.get("keyword_weight", 0.0))
+        )
+
+
+def bm25_scores(query: str, documents: List[str], config: RetrievalConfig) -> List[float]:
+    """
+    BM25 score of each document for the query.
+
+    Statistics come from the documents given (the vector search
+    candidates), which is enough to rank them against each other.
+    """
+    query_terms = set(config.tokenize(query))
+    if not query_terms or not documents:
+        return [0.0] * len(documents)
+
+    doc_terms = [Counter(config.tokenize(d)) for d in documents]
+    avg_length = sum(sum(t.values()) for t in doc_terms) / len(doc_terms) or 1.0
+    n = len(documents)
+
+    scores = []
+    for terms in doc_terms:
+        length = sum(terms.values())
+        score = 0.0
+        for term in query_terms:
+            tf = terms.get(term, 0)
+            if not tf:
+                continue
+            df = sum(1 for t in doc_terms if term in t)
+            idf = math.log(1 + (n - df + 0.5) / (df + 0.5))
+            norm = tf * (BM25_K1 + 1) / (tf + BM25_K1 * (1 - BM25_B + BM25_B * length / avg_length))
+            score += idf * norm * config.boosts.get(term, 1.0)
+        scores.append(score)
+    return scores
+
+
+def hybrid_rerank(matches: List[Dict], query: str, config: RetrievalConfig) -> List[Dict]:
+    """
+    Blend keyword scores into vector matches and re-sort them.
+
+    Each match gets keyword_score (BM25 normalized to 0-1 over the
+    candidates) and vector_score (the original relevance_score);
+    relevance_score becomes the weighted blend.
+    """
+    raw = bm25_scores(query, [m["content"] for m in matches], config)
+    top = max(raw) if raw else 0.0
+    for match, score in zip(matches, raw):
+        keyword_score = score / top if top > 0 else 0.0
+        match["vector_score"] = match["relevance_score"]
+        match["keyword_score"] = round(keyword_score, 4)
+        match["relevance_score"] = (
+            (1 - config.keyword_weight) * match["vector_score"]
+            + config.keyword_weight * keyword_score
+        )
+    return sorted(matches, key=lambda m: m["relevance_score"], reverse=True)
+
+
+def highlight_snippet(text: str, query: str, config: RetrievalConfig, width: int = SNIPPET_CHARS) -> str:
+    """
+    Snippet around the first query term in text, with matched terms in **bold**.
+
+    Stopwords are never highlighted; boosted terms are highlighted even
+    if they are not in the query.
+    """
+    terms = set(config.tokenize(query)) | set(config.boosts)
+    terms = {t for t in terms if " " not in t}
+    if not terms:
+        return text[:width]
+
+    alternatives = "|".join(re.escape(t) for t in sorted(terms, key=len, reverse=True))
+    pattern = re.compile(rf"\b({alternatives})\b", re.IGNORECASE)
+    first = pattern.search(text)
+    start = max(0, first.start() - width // 3) if first else 0
+    snippet = text[start:start + width]
+    snippet = pattern.sub(lambda m: f"**{m.group(0)}**", snippet)
+
+    prefix = "..." if start > 0 else ""
+    suffix = "..." if start + width < len(text) else ""
+    return f"{prefix}{snippet}{suffix}"
+
+
+class RetrievalConfigStore:
+    """
+    Per-collection retrieval configs persisted as JSON.
+
+    Usage:
+        store = RetrievalConfigStore(Path("./jan_doc_store/retrieval_config.json"))
+        store.set_retrieval_config("documents", RetrievalConfig(stopwords=["acme corp"]))
+        store.get("documents")
+    """
+
+    def __init__(self, path: Path):
+        """
+        Initialize config store.
+
+        Args:
+            path: JSON file holding configs by collection name
+        """
+        self.path = Path(path)
+        self._lock = threading.Lock()
+        self._configs: Dict[str, RetrievalConfig] = {}
+        if self.path.exists():
+            try:
+                data = json.loads(self.path.read_text(encoding="utf-8"))
+                self._configs = {name: RetrievalConfig.from_dict(c) for name, c in data.items()}
+            except (OSError, ValueError) as e:
+                logger.warning(f"Could not read retrieval config: {e}")
+
+    def get(self, collection: str) -> RetrievalConfig:
+        """Config for a collection (defaults if none was set)."""
+        return self._configs.get(collection) or RetrievalConfig()
+
+    def set_retrieval_config(self, collection: str, config: RetrievalConfig) -> RetrievalConfig:
+        """Store a collection's config, replacing any previous one."""
+        with self._lock:
+            self._configs[collection] = config
+            self._save()
+        logger.info(
+            f"Retrieval config for {collection}: {len(config.stopwords)} stopwords, "
+            f"{len(config.boosts)} boosts, keyword weight {config.keyword_weight}"
+        )
+        return config
+
+    def delete(self, collection: str):
+        """Revert a collection to the defaults."""
+        with self._lock:
+            if self._configs.pop(collection, None) is not None:
+                self._save()
+
+    def _save(self):
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        self.path.write_text(
+            json.dumps({name: c.to_dict() for name, c in self._configs.items()}, indent=2),
+            encoding="utf-8"
+        )
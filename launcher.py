@@ -51,6 +51,36 @@ def load_config():
         'EMBEDDING_MODEL': 'all-MiniLM-L6-v2',
         'AUTO_INJECT': 'true',
         'MAX_CONTEXT_TOKENS': '8000',
+        'EXTRACTION_TIMEOUT': '',
+        'EXTRACTION_INACTIVITY_TIMEOUT': '',
+        'SALVAGE_PARTIAL': 'true',
+        'DISK_RESERVE_MB': '500',
+        'DISK_GUARD_MODE': 'refuse',
+        'LOCK_RETRIES': '4',
+        'SNAPSHOT_FILES': 'false',
+        'TRASH_RETENTION_DAYS': '30',
+        'EXTRACT_TABLES': 'false',
+        'INDEX_FIGURES': 'true',
+        'DESCRIBE_FIGURES': 'false',
+        'VISION_MODEL': '',
+        'FOOTNOTE_MODE': 'metadata',
+        'VERSIONING': 'false',
+        'USAGE_STATS': 'true',
+        'USAGE_UPLOAD_URL': '',
+        'QUERY_WORKERS': '2',
+        'INDEXING_YIELD_SECONDS': '2',
+        'MIN_OCR_CONFIDENCE': '',
+        'INDEX_LOW_CONFIDENCE_OCR': 'false',
+        'THUMBNAIL_CACHE_MB': '200',
+        'RETENTION_CHECK_HOURS': '24',
+        'JAN_DATA_DIR': '',
+        'NARRATE_PROGRESS': 'false',
+        'PROGRESS_EVENTS_PER_SECOND': '2',
+        'OPTIMIZE_AFTER_DELETED': '5000',
+        'SAFE_MODE_AFTER_FAILED_STARTS': '3',
+        'DEFAULT_COLLECTION': 'jan_documents',
+        'SCAN_ENGINE': 'walk',
+        'PORTABLE': 'false',
         'AUTO_OPEN_BROWSER': 'true',
     }
 
@@ -243,16 +273,21 @@ def main():
 
     print()
 
-    # Ensure storage directory exists
-    storage_dir = Path(config['STORAGE_DIR'])
-    if not storage_dir.is_absolute():
-        storage_dir = app_path / storage_dir
-    storage_dir.mkdir(parents=True, exist_ok=True)
-
     # Add app path to Python path for imports
     sys.path.insert(0, str(get_base_path()))
     sys.path.insert(0, str(app_path))
 
+    # Ensure storage directory exists (relative paths are under data/ in
+    # portable mode; a relocated storage folder takes precedence)
+    from app_paths import is_portable, get_storage_directory, apply_model_cache_environment
+    portable = is_portable(config['PORTABLE'], app_path)
+    storage_dir = get_storage_directory(config['STORAGE_DIR'], portable, app_path)
+    storage_dir.mkdir(parents=True, exist_ok=True)
+    apply_model_cache_environment(portable, app_path)
+    if portable:
+        print(f"  Portable mode: data kept in {storage_dir.parent}")
+        print()
+
     # Import and configure the proxy
     try:
         from jan_proxy import app as proxy_app, config as proxy_config
@@ -270,6 +305,39 @@ def main():
         proxy_config.embedding_model = config['EMBEDDING_MODEL']
         proxy_config.auto_inject = config['AUTO_INJECT'].lower() == 'true'
         proxy_config.max_context_tokens = int(config['MAX_CONTEXT_TOKENS'])
+        if config['EXTRACTION_TIMEOUT']:
+            proxy_config.extraction_timeout = float(config['EXTRACTION_TIMEOUT'])
+        if config['EXTRACTION_INACTIVITY_TIMEOUT']:
+            proxy_config.extraction_inactivity_timeout = float(config['EXTRACTION_INACTIVITY_TIMEOUT'])
+        proxy_config.salvage_partial = config['SALVAGE_PARTIAL'].lower() == 'true'
+        proxy_config.disk_reserve_mb = int(config['DISK_RESERVE_MB'])
+        proxy_config.disk_guard_mode = config['DISK_GUARD_MODE'].lower()
+        proxy_config.lock_retries = int(config['LOCK_RETRIES'])
+        proxy_config.snapshot_files = config['SNAPSHOT_FILES'].lower() == 'true'
+        proxy_config.trash_retention_days = int(config['TRASH_RETENTION_DAYS'])
+        proxy_config.extract_tables = config['EXTRACT_TABLES'].lower() == 'true'
+        proxy_config.index_figures = config['INDEX_FIGURES'].lower() == 'true'
+        proxy_config.describe_figures = config['DESCRIBE_FIGURES'].lower() == 'true'
+        proxy_config.vision_model = config['VISION_MODEL'] or None
+        proxy_config.footnote_mode = config['FOOTNOTE_MODE'].lower()
+        proxy_config.versioning = config['VERSIONING'].lower() == 'true'
+        proxy_config.usage_stats = config['USAGE_STATS'].lower() == 'true'
+        proxy_config.usage_upload_url = config['USAGE_UPLOAD_URL'] or None
+        proxy_config.query_workers = int(config['QUERY_WORKERS'])
+        proxy_config.indexing_yield_seconds = float(config['INDEXING_YIELD_SECONDS'])
+        if config['MIN_OCR_CONFIDENCE']:
+            proxy_config.min_ocr_confidence = float(config['MIN_OCR_CONFIDENCE'])
+        proxy_config.index_low_confidence_ocr = config['INDEX_LOW_CONFIDENCE_OCR'].lower() == 'true'
+        proxy_config.thumbnail_cache_mb = int(config['THUMBNAIL_CACHE_MB'])
+        proxy_config.retention_check_hours = float(config['RETENTION_CHECK_HOURS'])
+        proxy_config.jan_data_dir = config['JAN_DATA_DIR'] or None
+        proxy_config.narrate_progress = config['NARRATE_PROGRESS'].lower() == 'true'
+        proxy_config.progress_events_per_second = float(config['PROGRESS_EVENTS_PER_SECOND'])
+        proxy_config.optimize_after_deleted = int(config['OPTIMIZE_AFTER_DELETED'])
+        proxy_config.safe_mode_after_failed_starts = int(config['SAFE_MODE_AFTER_FAILED_STARTS'])
+        proxy_config.default_collection = config['DEFAULT_COLLECTION'] or 'jan_documents'
+        proxy_config.scan_engine = config['SCAN_ENGINE'].lower() or 'walk'
+        proxy_config.portable = portable
 
         print("=" * 64)
         print()
@@ -0,0 +1,231 @@
+"""
+Legacy Data Migration for Jan Document Plugin
+
+Early builds resolved STORAGE_DIR against the working directory (or
+wrote a bare chroma_db / doc_store folder next to the scripts), so users
+who upgraded can have a second, orphaned vector store that is no longer
+searched. This module:
+- Finds stores in the old locations (any folder holding chroma.sqlite3
+  that is not the current store)
+- Merges their collections into the current collection: chunks keep
+  their ids, so chunks already present are skipped; stored vectors are
+  reused when the legacy collection used the same embedding model and
+  re-embedded otherwise
+- Copies settings files the current store doesn't have yet
+- Optionally removes each legacy store once it migrated without errors
+"""
+
+import shutil
+import logging
+from pathlib import Path
+from typing import Dict, List, Optional
+from dataclasses import dataclass, field, asdict
+
+import chromadb_compat  # noqa: F401 - side-effect import for patching
+import chromadb
+from chromadb.config import Settings
+
+from document_processor import LocalVectorStore
+from collection_names import RESERVED_SUFFIXES
+from app_paths import get_app_path
+
+logger = logging.getLogger(__name__)
+
+# Folder names used for the store by earlier builds
+LEGACY_FOLDER_NAMES = ("jan_doc_store", "doc_store", "chroma_db")
+
+# Settings copied into the current store when it has none of its own
+SETTINGS_FILES = (
+    "presets.json",
+    "retrieval_config.json",
+    "retention_policy.json",
+    "collection_quota.json",
+)
+
+# Staging collections left behind by migrations, shadow indexes and resets
+STAGING_SUFFIXES = RESERVED_SUFFIXES
+
+# Chunks read from a legacy collection per call
+MIGRATE_BATCH_SIZE = 256
+
+
+def _open_client(path: Path):
+    return chromadb.PersistentClient(
+        path=str(path),
+        settings=Settings(anonymized_telemetry=False, allow_reset=True)
+    )
+
+
+@dataclass
+class LegacyStore:
+    """A vector store found in an old location."""
+    path: str
+    size_bytes: int
+    collections: List[Dict] = field(default_factory=list)   # name, chunks, embedding_model
+    settings_files: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+@dataclass
+class LegacyMigrationReport:
+    """What migrate_legacy_data merged from one legacy store."""
+    path: str
+    collections: List[Dict] = field(default_factory=list)   # name, chunks, added, skipped, re_embedded
+    settings_copied: List[str] = field(default_factory=list)
+    removed: bool = False
+    errors: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+def legacy_locations(storage_directory: Path, app_path: Optional[Path] = None) -> List[Path]:
+    """Folders an earlier build may have used, excluding the current store."""
+    current = Path(storage_directory).resolve()
+    bases = [app_path or get_app_path(), Path.cwd(), Path(__file__).parent]
+    candidates = []
+    for base in bases:
+        for name in LEGACY_FOLDER_NAMES:
+            path = (Path(base) / name).resolve()
+            if path != current and path not in candidates:
+                candidates.append(path)
+    return candidates
+
+
+def detect_legacy_data(storage_directory: Path, app_path: Optional[Path] = None) -> List[LegacyStore]:
+    """
+    Legacy stores that still hold data.
+
+    Args:
+        storage_directory: The current storage folder (never reported)
+        app_path: App folder to search (None = this app's folder)
+    """
+    found = []
+    for path in legacy_locations(storage_directory, app_path):
+        if not (path / "chroma.sqlite3").exists():
+            continue
+        store = LegacyStore(
+            path=str(path),
+            size_bytes=sum(p.stat().st_size for p in path.rglob("*") if p.is_file()),
+            settings_files=[name for name in SETTINGS_FILES if (path / name).exists()]
+        )
+        try:
+            client = _open_client(path)
+            for collection in client.list_collections():
+                collection = client.get_collection(getattr(collection, "name", collection))
+                if collection.name.endswith(STAGING_SUFFIXES):
+                    continue
+                store.collections.append({
+                    "name": collection.name,
+                    "chunks": collection.count(),
+                    "embedding_model": (collection.metadata or {}).get("embedding_model")
+                })
+        except Exception as e:
+            logger.warning(f"Could not read legacy store {path}: {e}")
+            continue
+        if store.collections or store.settings_files:
+            found.append(store)
+    return found
+
+
+def _migrate_collection(collection, live: LocalVectorStore) -> Dict:
+    recorded_model = (collection.metadata or {}).get("embedding_model")
+    re_embed = recorded_model != live.embedding_model
+    summary = {
+        "name": collection.name,
+        "chunks": collection.count(),
+        "added": 0,
+        "skipped": 0,
+        "re_embedded": re_embed
+    }
+
+    include = ["documents", "metadatas"] if re_embed else ["documents", "metadatas", "embeddings"]
+    for offset in range(0, summary["chunks"], MIGRATE_BATCH_SIZE):
+        batch = collection.get(include=include, limit=MIGRATE_BATCH_SIZE, offset=offset)
+        existing = set(live.collection.get(ids=batch["ids"], include=[])["ids"])
+        keep = [i for i, chunk_id in enumerate(batch["ids"]) if chunk_id not in existing]
+        summary["skipped"] += len(batch["ids"]) - len(keep)
+        if not keep:
+            continue
+        chunks = {
+            "ids": [batch["ids"][i] for i in keep],
+            "documents": [batch["documents"][i] for i in keep],
+            "metadatas": [batch["metadatas"][i] for i in keep]
+        }
+        if not re_embed:
+            chunks["embeddings"] = [list(batch["embeddings"][i]) for i in keep]
+        live.restore_chunks(chunks)
+        summary["added"] += len(keep)
+    return summary
+
+
+def migrate_legacy_data(
+    live: LocalVectorStore,
+    storage_directory: Path,
+    paths: Optional[List[str]] = None,
+    remove_legacy: bool = False,
+    app_path: Optional[Path] = None
+) -> List[LegacyMigrationReport]:
+    """
+    Merge legacy stores into the current store.
+
+    Args:
+        live: The current vector store; every legacy collection is merged
+              into its collection
+        storage_directory: The current storage folder (settings go here)
+        paths: Legacy stores to migrate (None = all detected)
+        remove_legacy: Delete each legacy store that migrated without errors
+
+    Raises:
+        ValueError: If a path is not a detected legacy store
+        CollectionReadOnlyError: If the current collection is read-only
+    """
+    live.check_writable("import")
+    detected = {s.path: s for s in detect_legacy_data(storage_directory, app_path)}
+    if paths is None:
+        selected = list(detected)
+    else:
+        selected = [str(Path(p).resolve()) for p in paths]
+        unknown = [p for p in selected if p not in detected]
+        if unknown:
+            raise ValueError(f"Not a legacy data store: {', '.join(unknown)}")
+
+    reports = []
+    for path in selected:
+        report = LegacyMigrationReport(path=path)
+        client = _open_client(Path(path))
+        for info in detected[path].collections:
+            try:
+                summary = _migrate_collection(client.get_collection(info["name"]), live)
+                report.collections.append(summary)
+                logger.info(
+                    f"Migrated {summary['added']} chunk(s) from {path} ({info['name']}), "
+                    f"{summary['skipped']} already present"
+                )
+            except Exception as e:
+                report.errors.append(f"Collection {info['name']}: {e}")
+
+        storage_directory = Path(storage_directory)
+        for name in detected[path].settings_files:
+            target = storage_directory / name
+            if target.exists():
+                continue
+            try:
+                storage_directory.mkdir(parents=True, exist_ok=True)
+                shutil.copyfile(Path(path) / name, target)
+                report.settings_copied.append(name)
+            except OSError as e:
+                report.errors.append(f"{name}: {e}")
+
+        if remove_legacy and not report.errors:
+            if hasattr(client, "clear_system_cache"):
+                client.clear_system_cache()
+            try:
+                shutil.rmtree(path)
+                report.removed = True
+            except OSError as e:
+                report.errors.append(f"Could not remove {path}: {e}")
+        reports.append(report)
+    return reports
@@ -0,0 +1,274 @@
+r"""
+Network Shares for Jan Document Plugin
+
+Credentials for network roots such as \\server\docs. Windows only
+reaches a share whose credentials are cached for the session, so a scan
+of a share nobody has opened in Explorer fails with "access denied" or
+"network path not found". With credentials stored here the share is
+connected (WNetAddConnection2, no drive letter) before it is scanned or
+its files are processed.
+
+Credentials are stored per share root (\\server\share) in the system
+keychain through the keyring package, or kept in memory for this
+session without one. test_root_access checks a root the same way a scan
+would and reports which step failed.
+"""
+
+import json
+import logging
+import os
+import sys
+import threading
+from pathlib import Path
+from typing import Dict, Optional, Set, Union
+from dataclasses import dataclass, asdict
+
+from document_passwords import load_keyring
+
+logger = logging.getLogger(__name__)
+
+KEYRING_SERVICE = "jan-document-plugin/shares"
+
+RESOURCETYPE_DISK = 1
+
+# WNetAddConnection2 results that still leave the share usable
+ERROR_SESSION_CREDENTIAL_CONFLICT = 1219   # Already connected with other credentials
+
+WNET_ERRORS = {
+    5: "access denied",
+    53: "network path not found",
+    67: "share not found",
+    86: "wrong password",
+    1326: "unknown user name or bad password",
+    1909: "account locked out",
+}
+
+
+def share_root(path: Union[str, Path]) -> Optional[str]:
+    r"""The \\server\share root of a UNC path, or None for local paths."""
+    text = str(path).replace("/", "\\")
+    if not text.startswith("\\\\") or text.startswith("\\\\?\\") or text.startswith("\\\\.\\"):
+        return None
+    parts = [p for p in text[2:].split("\\") if p]
+    if len(parts) < 2:
+        return None
+    return f"\\\\{parts[0]}\\{parts[1]}".lower()
+
+
+class ShareConnectionError(RuntimeError):
+    """Raised when a network share can't be connected with its stored credentials."""
+
+    error_type = "share_connection_failed"
+
+    def __init__(self, root: str, username: str, reason: str, winerror: Optional[int] = None):
+        super().__init__(f"Could not connect to {root} as {username}: {reason}")
+        self.root = root
+        self.username = username
+        self.reason = reason
+        self.winerror = winerror
+
+    def to_dict(self) -> Dict:
+        return {
+            "error": self.error_type,
+            "message": str(self),
+            "root": self.root,
+            "username": self.username,
+            "reason": self.reason,
+            "winerror": self.winerror,
+            "remediation": "Check the share's credentials (PUT /shares/credentials) and that the server is reachable"
+        }
+
+
+@dataclass
+class RootAccessResult:
+    """Outcome of test_root_access; error says which step failed."""
+    path: str
+    share: Optional[str] = None          # \\server\share for network paths
+    credentials_stored: bool = False
+    connected: Optional[bool] = None     # None = nothing to connect
+    exists: bool = False
+    is_directory: bool = False
+    readable: bool = False
+    error: Optional[str] = None
+
+    @property
+    def ok(self) -> bool:
+        return self.readable and self.error is None
+
+    def to_dict(self) -> Dict:
+        return {**asdict(self), "ok": self.ok}
+
+
+class ShareCredentialStore:
+    r"""
+    Per-share credentials kept in the system keychain.
+
+    Usage:
+        shares = ShareCredentialStore()
+        shares.set(r"\\server\docs", r"CORP\alice", "hunter2")
+        shares.connect(r"\\server\docs\Contracts\lease.pdf")
+        shares.test_root_access(r"\\server\docs").to_dict()
+    """
+
+    def __init__(self, service: str = KEYRING_SERVICE, use_keychain: bool = True):
+        """
+        Initialize share credential store.
+
+        Args:
+            service: Keychain service name the credentials are filed under
+            use_keychain: Use the system keychain if available (False = memory only)
+        """
+        self.service = service
+        self._keyring = load_keyring() if use_keychain else None
+        self._lock = threading.Lock()
+        self._memory: Dict[str, Dict[str, str]] = {}
+        self._connected: Set[str] = set()
+
+    @property
+    def backend(self) -> str:
+        """"keychain" or "memory"."""
+        return "keychain" if self._keyring else "memory"
+
+    def get(self, root: str) -> Optional[Dict[str, str]]:
+        """{"username", "password"} stored for a share root, or None."""
+        root = share_root(root) or root
+        with self._lock:
+            if root in self._memory:
+                return dict(self._memory[root])
+        if self._keyring:
+            try:
+                stored = self._keyring.get_password(self.service, root)
+                if stored:
+                    return json.loads(stored)
+            except Exception as e:
+                logger.warning(f"Could not read credentials for {root} from keychain: {e}")
+        return None
+
+    def set(self, root: str, username: str, password: str) -> str:
+        """
+        Store a share's credentials. A share Windows already connected
+        with other credentials keeps that connection until it is closed.
+
+        Returns:
+            The normalized share root
+
+        Raises:
+            ValueError: If root isn't a network path or username/password is empty
+        """
+        normalized = share_root(root)
+        if normalized is None:
+            raise ValueError(f"Not a network share: {root} (expected \\\\server\\share)")
+        if not username:
+            raise ValueError("username is required")
+        if not password:
+            raise ValueError("password must not be empty")
+        credentials = {"username": username, "password": password}
+        with self._lock:
+            self._connected.discard(normalized)
+        if self._keyring:
+            try:
+                self._keyring.set_password(self.service, normalized, json.dumps(credentials))
+                with self._lock:
+                    self._memory.pop(normalized, None)
+                logger.info(f"Stored credentials for {normalized} in keychain")
+                return normalized
+            except Exception as e:
+                logger.warning(f"Could not store credentials in keychain, keeping them for this session: {e}")
+        with self._lock:
+            self._memory[normalized] = credentials
+        return normalized
+
+    def delete(self, root: str) -> bool:
+        """Forget a share's credentials; returns whether any were stored."""
+        root = share_root(root) or root
+        with self._lock:
+            found = self._memory.pop(root, None) is not None
+            self._connected.discard(root)
+        if self._keyring:
+            try:
+                if self._keyring.get_password(self.service, root) is not None:
+                    self._keyring.delete_password(self.service, root)
+                    found = True
+            except Exception as e:
+                logger.warning(f"Could not delete credentials for {root} from keychain: {e}")
+        return found
+
+    def connect(self, path: Union[str, Path]) -> bool:
+        """
+        Connect the share a path is on, if it has stored credentials.
+        Each share is connected once per session.
+
+        Returns:
+            Whether a connection was made (False for local paths, shares
+            without credentials and non-Windows systems)
+
+        Raises:
+            ShareConnectionError: If the share refuses the stored credentials
+        """
+        root = share_root(path)
+        if root is None or sys.platform != "win32":
+            return False
+        with self._lock:
+            if root in self._connected:
+                return False
+        credentials = self.get(root)
+        if credentials is None:
+            return False
+
+        try:
+            import win32wnet
+        except ImportError:
+            raise ShareConnectionError(root, credentials["username"], "pywin32 is not installed")
+        try:
+            win32wnet.WNetAddConnection2(
+                RESOURCETYPE_DISK, None, root, None,
+                credentials["username"], credentials["password"]
+            )
+        except Exception as e:   # pywintypes.error
+            winerror = getattr(e, "winerror", None)
+            if winerror != ERROR_SESSION_CREDENTIAL_CONFLICT:
+                reason = WNET_ERRORS.get(winerror) or getattr(e, "strerror", None) or str(e)
+                raise ShareConnectionError(root, credentials["username"], reason, winerror)
+            logger.info(f"{root} is already connected with other credentials - using that connection")
+
+        with self._lock:
+            self._connected.add(root)
+        logger.info(f"Connected {root} as {credentials['username']}")
+        return True
+
+    def test_root_access(self, path: Union[str, Path]) -> RootAccessResult:
+        """
+        Check that a root can be scanned: connect it if it is a share
+        with stored credentials, then check it exists and can be listed
+        (or, for a file, read).
+        """
+        result = RootAccessResult(path=str(path), share=share_root(path))
+        if result.share:
+            result.credentials_stored = self.get(result.share) is not None
+            if result.credentials_stored:
+                try:
+                    self.connect(path)
+                    with self._lock:
+                        result.connected = result.share in self._connected
+                except ShareConnectionError as e:
+                    result.connected = False
+                    result.error = str(e)
+                    return result
+
+        target = Path(path)
+        try:
+            result.exists = target.exists()
+            result.is_directory = target.is_dir()
+            if not result.exists:
+                result.error = f"Path not found: {path}"
+                return result
+            if result.is_directory:
+                with os.scandir(target) as entries:
+                    next(entries, None)
+            else:
+                with open(target, "rb") as f:
+                    f.read(1)
+            result.readable = True
+        except OSError as e:
+            result.error = f"Cannot read {path}: {e.strerror or e}"
+        return result
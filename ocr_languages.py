@@ -0,0 +1,216 @@
+"""
+OCR Language Packs for Jan Document Plugin
+
+Tesseract only recognizes languages whose .traineddata pack is installed
+(the portable install ships English only), so OCR on non-English scans
+silently produces garbage. This module:
+- Lists known, installed and bundled language packs
+- Installs a pack from the bundled langpacks folder, or downloads it
+  from the tessdata_fast repository
+- Verifies every pack before installing it: optional SHA-256 check, then
+  a trial OCR run that proves Tesseract can load it
+
+Packs are installed into the tessdata folder Tesseract already reads:
+TESSDATA_PREFIX if set, otherwise <tesseract dir>/tessdata.
+"""
+
+import os
+import re
+import shutil
+import hashlib
+import logging
+import tempfile
+from pathlib import Path
+from typing import List, Dict, Optional
+
+import httpx
+import pytesseract
+from PIL import Image
+
+from app_paths import get_app_path
+
+logger = logging.getLogger(__name__)
+
+# Where packs are downloaded from when not bundled
+DOWNLOAD_URL = "https://github.com/tesseract-ocr/tessdata_fast/raw/main/{lang}.traineddata"
+
+# Refuse downloads larger than this (the largest fast packs are ~20 MB)
+MAX_PACK_BYTES = 100 * 1024 * 1024
+
+# "eng", "chi_sim", "deu_latf" - also keeps codes safe to use in file names
+LANGUAGE_CODE_PATTERN = re.compile(r"^[a-z]{3}(_[a-z]{3,4})?$")
+
+KNOWN_LANGUAGES = {
+    "eng": "English",
+    "deu": "German",
+    "fra": "French",
+    "spa": "Spanish",
+    "ita": "Italian",
+    "por": "Portuguese",
+    "nld": "Dutch",
+    "swe": "Swedish",
+    "pol": "Polish",
+    "ces": "Czech",
+    "ell": "Greek",
+    "rus": "Russian",
+    "ukr": "Ukrainian",
+    "tur": "Turkish",
+    "ara": "Arabic",
+    "heb": "Hebrew",
+    "hin": "Hindi",
+    "vie": "Vietnamese",
+    "jpn": "Japanese",
+    "kor": "Korean",
+    "chi_sim": "Chinese (Simplified)",
+    "chi_tra": "Chinese (Traditional)",
+}
+
+
+def validate_language_code(lang: str) -> str:
+    """
+    Raises:
+        ValueError: If lang is not a Tesseract language code
+    """
+    lang = lang.strip().lower()
+    if not LANGUAGE_CODE_PATTERN.match(lang):
+        raise ValueError(f"Invalid OCR language code: {lang!r} (expected e.g. 'deu' or 'chi_sim')")
+    return lang
+
+
+def installed_ocr_languages() -> List[str]:
+    """Language packs Tesseract can load (empty if Tesseract is unavailable)."""
+    try:
+        return sorted(l for l in pytesseract.get_languages(config="") if l != "osd")
+    except Exception as e:
+        logger.debug(f"Could not list Tesseract languages: {e}")
+        return []
+
+
+class OCRLanguageManager:
+    """
+    Lists and installs Tesseract language packs.
+
+    Usage:
+        languages = OCRLanguageManager()
+        languages.list_ocr_languages()
+        languages.install_ocr_language("deu")
+    """
+
+    def __init__(self, tessdata_dir: Optional[Path] = None, bundled_dir: Optional[Path] = None):
+        """
+        Initialize language manager.
+
+        Args:
+            tessdata_dir: Folder packs are installed into (None = detect)
+            bundled_dir: Folder of packs shipped with the app
+                         (None = <app>/tesseract/langpacks)
+        """
+        self.tessdata_dir = Path(tessdata_dir) if tessdata_dir else self._detect_tessdata_dir()
+        self.bundled_dir = Path(bundled_dir) if bundled_dir else get_app_path() / "tesseract" / "langpacks"
+
+    @staticmethod
+    def _detect_tessdata_dir() -> Optional[Path]:
+        prefix = os.environ.get("TESSDATA_PREFIX")
+        if prefix:
+            return Path(prefix)
+        command = Path(pytesseract.pytesseract.tesseract_cmd)
+        if command.is_absolute() and command.exists():
+            return command.parent / "tessdata"
+        return None
+
+    def bundled_languages(self) -> List[str]:
+        if not self.bundled_dir.is_dir():
+            return []
+        return sorted(p.stem for p in self.bundled_dir.glob("*.traineddata"))
+
+    def list_ocr_languages(self) -> List[Dict]:
+        """Known, installed and bundled languages with their install state."""
+        installed = set(installed_ocr_languages())
+        bundled = set(self.bundled_languages())
+        codes = sorted(set(KNOWN_LANGUAGES) | installed | bundled)
+        return [
+            {
+                "code": code,
+                "name": KNOWN_LANGUAGES.get(code, code),
+                "installed": code in installed,
+                "bundled": code in bundled
+            }
+            for code in codes
+        ]
+
+    def missing_languages(self, languages: List[str]) -> List[str]:
+        """Requested languages that are not installed."""
+        installed = set(installed_ocr_languages())
+        return [lang for lang in languages if lang not in installed]
+
+    def install_ocr_language(self, lang: str, sha256: Optional[str] = None) -> Dict:
+        """
+        Install a language pack from the bundled folder or by download.
+
+        Args:
+            lang: Tesseract language code, e.g. "deu"
+            sha256: Expected checksum of the pack (checked when given)
+
+        Returns:
+            Dict with code, source ("bundled" or "download") and path
+
+        Raises:
+            ValueError: If the code is invalid or the pack fails verification
+            RuntimeError: If there is no tessdata folder to install into
+            httpx.HTTPError: If the download fails
+        """
+        lang = validate_language_code(lang)
+        if self.tessdata_dir is None:
+            raise RuntimeError(
+                "Could not locate Tesseract's tessdata folder; set TESSDATA_PREFIX "
+                "or the Tesseract path"
+            )
+
+        with tempfile.TemporaryDirectory() as staging:
+            staged = Path(staging) / f"{lang}.traineddata"
+            bundled = self.bundled_dir / f"{lang}.traineddata"
+            if bundled.exists():
+                shutil.copyfile(bundled, staged)
+                source = "bundled"
+            else:
+                self._download(lang, staged)
+                source = "download"
+
+            self._verify(lang, staged, sha256)
+
+            self.tessdata_dir.mkdir(parents=True, exist_ok=True)
+            target = self.tessdata_dir / staged.name
+            shutil.move(str(staged), target)
+
+        logger.info(f"Installed OCR language pack {lang} ({source}) to {target}")
+        return {"code": lang, "name": KNOWN_LANGUAGES.get(lang, lang), "source": source, "path": str(target)}
+
+    def _download(self, lang: str, destination: Path):
+        url = DOWNLOAD_URL.format(lang=lang)
+        logger.info(f"Downloading OCR language pack: {url}")
+        received = 0
+        with httpx.stream("GET", url, follow_redirects=True, timeout=60.0) as response:
+            if response.status_code == 404:
+                raise ValueError(f"No OCR language pack available for {lang}")
+            response.raise_for_status()
+            with open(destination, "wb") as f:
+                for block in response.iter_bytes():
+                    received += len(block)
+                    if received > MAX_PACK_BYTES:
+                        raise ValueError(f"OCR language pack {lang} is larger than expected")
+                    f.write(block)
+
+    def _verify(self, lang: str, pack: Path, sha256: Optional[str]):
+        """Checksum (if given), then prove Tesseract can load the pack."""
+        if sha256:
+            digest = hashlib.sha256(pack.read_bytes()).hexdigest()
+            if digest.lower() != sha256.strip().lower():
+                raise ValueError(f"Checksum mismatch for OCR language pack {lang}")
+
+        blank = Image.new("RGB", (64, 32), "white")
+        try:
+            pytesseract.image_to_string(
+                blank, lang=lang, config=f'--tessdata-dir "{pack.parent}"'
+            )
+        except Exception as e:
+            raise ValueError(f"OCR language pack {lang} could not be loaded by Tesseract: {e}")
@@ -460,6 +460,42 @@ class OCRPostProcessor:
         return text.strip()
 
 
+def ocr_with_confidence(
+    image: Image.Image,
+    lang: Optional[str] = None,
+    config: str = ''
+) -> Tuple[str, Optional[float]]:
+    """
+    OCR an image and report Tesseract's mean word confidence (0-100).
+
+    Handwriting, faint scans and form fields come back as text anyway;
+    the confidence is what tells them apart from a clean page.
+
+    Returns:
+        Tuple of (text, confidence) - confidence is None if no words were found
+    """
+    import pytesseract
+
+    data = pytesseract.image_to_data(
+        image, lang=lang, config=config, output_type=pytesseract.Output.DICT
+    )
+
+    lines = {}
+    confidences = []
+    for i, word in enumerate(data["text"]):
+        word = word.strip()
+        conf = float(data["conf"][i])
+        if not word or conf < 0:
+            continue
+        key = (data["block_num"][i], data["par_num"][i], data["line_num"][i])
+        lines.setdefault(key, []).append(word)
+        confidences.append(conf)
+
+    text = "\n".join(" ".join(words) for _, words in sorted(lines.items()))
+    confidence = sum(confidences) / len(confidences) if confidences else None
+    return text, confidence
+
+
 class OCRPipeline:
     """
     Complete OCR pipeline with pre and post-processing.
@@ -502,12 +538,19 @@ class OCRPipeline:
         """Apply post-processing to OCR text."""
         return self.postprocessor.process(text)
 
-    def process_image(self, image: Image.Image) -> Tuple[str, dict]:
+    def process_image(
+        self,
+        image: Image.Image,
+        lang: Optional[str] = None,
+        with_confidence: bool = False
+    ) -> Tuple[str, dict]:
         """
         Complete OCR pipeline: preprocess -> OCR -> postprocess.
 
         Args:
             image: PIL Image to OCR
+            lang: Tesseract language(s), e.g. "deu" or "eng+deu" (None = default)
+            with_confidence: Also measure mean word confidence (metadata['confidence'])
 
         Returns:
             Tuple of (cleaned_text, metadata_dict)
@@ -518,10 +561,15 @@ class OCRPipeline:
         processed_image = self.preprocess(image)
 
         # OCR
-        raw_text = pytesseract.image_to_string(
-            processed_image,
-            config=self.tesseract_config
-        )
+        confidence = None
+        if with_confidence:
+            raw_text, confidence = ocr_with_confidence(processed_image, lang, self.tesseract_config)
+        else:
+            raw_text = pytesseract.image_to_string(
+                processed_image,
+                lang=lang,
+                config=self.tesseract_config
+            )
 
         # Post-process
         clean_text = self.postprocess(raw_text)
@@ -532,7 +580,9 @@ class OCRPipeline:
             'clean_length': len(clean_text),
             'reduction_pct': round((1 - len(clean_text) / max(len(raw_text), 1)) * 100, 1),
             'preprocessing': OPENCV_AVAILABLE,
-            'tesseract_config': self.tesseract_config
+            'tesseract_config': self.tesseract_config,
+            'lang': lang,
+            'confidence': confidence
         }
 
         return clean_text, metadata
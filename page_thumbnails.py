@@ -0,0 +1,165 @@
+"""
+Page Thumbnails for Jan Document Plugin
+
+Renders a document page as a small PNG so query results can show the
+source page next to a match. Rendering a PDF page takes tens of
+milliseconds, and result lists ask for the same pages repeatedly, so
+renders are kept in an on-disk cache:
+
+- Keyed by file path, size, modification time, page and DPI - a changed
+  file never serves a stale image
+- Least recently used thumbnails are evicted once the cache exceeds its
+  size limit
+
+Cache location: <storage>/thumbnails/<key>.png
+"""
+
+import os
+import hashlib
+import logging
+import threading
+from pathlib import Path
+from typing import Dict
+
+import fitz  # pymupdf
+from PIL import Image
+
+logger = logging.getLogger(__name__)
+
+# Default cache size limit
+DEFAULT_CACHE_BYTES = 200 * 1024 * 1024
+
+# Allowed render resolutions (72 DPI = 1 pixel per PDF point)
+MIN_DPI = 24
+MAX_DPI = 200
+
+IMAGE_EXTENSIONS = {'.png', '.jpg', '.jpeg', '.tiff', '.bmp', '.gif', '.webp'}
+
+# Images are scaled as if they filled a US Letter page width (8.5 in)
+IMAGE_PAGE_WIDTH_INCHES = 8.5
+
+
+class ThumbnailCache:
+    """
+    Renders page thumbnails through an LRU on-disk cache.
+
+    Usage:
+        thumbnails = ThumbnailCache(Path("./jan_doc_store/thumbnails"))
+        png_path = thumbnails.render_page_thumbnail("/docs/report.pdf", page=3, dpi=72)
+        thumbnails.get_cache_stats()
+    """
+
+    def __init__(self, cache_directory: Path, max_bytes: int = DEFAULT_CACHE_BYTES):
+        """
+        Initialize thumbnail cache.
+
+        Args:
+            cache_directory: Directory holding cached PNGs
+            max_bytes: Cache size above which least recently used files are evicted
+        """
+        self.cache_directory = Path(cache_directory)
+        self.cache_directory.mkdir(parents=True, exist_ok=True)
+        self.max_bytes = max_bytes
+        self._lock = threading.Lock()
+        self._hits = 0
+        self._misses = 0
+
+    def _cache_key(self, path: Path, page: int, dpi: int) -> str:
+        stat = path.stat()
+        identity = f"{path}|{stat.st_size}|{stat.st_mtime_ns}|{page}|{dpi}"
+        return hashlib.sha256(identity.encode("utf-8")).hexdigest()[:32]
+
+    def render_page_thumbnail(self, file_path: str, page: int = 1, dpi: int = 72) -> Path:
+        """
+        PNG thumbnail of a page (1-based), rendered or from the cache.
+
+        Images are treated as single-page documents.
+
+        Raises:
+            FileNotFoundError: If the file doesn't exist
+            ValueError: If the page, DPI or file type is invalid
+        """
+        path = Path(file_path).resolve()
+        if not path.exists():
+            raise FileNotFoundError(f"File not found: {path}")
+        if not MIN_DPI <= dpi <= MAX_DPI:
+            raise ValueError(f"dpi must be between {MIN_DPI} and {MAX_DPI}")
+        if page < 1:
+            raise ValueError("page must be 1 or greater")
+
+        target = self.cache_directory / f"{self._cache_key(path, page, dpi)}.png"
+        if target.exists():
+            os.utime(target)  # Mark as recently used
+            self._hits += 1
+            return target
+
+        self._misses += 1
+        staging = target.with_suffix(".tmp")
+        suffix = path.suffix.lower()
+        if suffix == ".pdf":
+            self._render_pdf_page(path, page, dpi, staging)
+        elif suffix in IMAGE_EXTENSIONS:
+            if page != 1:
+                raise ValueError("Images have a single page")
+            self._render_image(path, dpi, staging)
+        else:
+            raise ValueError(f"Thumbnails are not available for {suffix} files")
+        staging.replace(target)
+
+        self._evict()
+        return target
+
+    @staticmethod
+    def _render_pdf_page(path: Path, page: int, dpi: int, output: Path):
+        with fitz.open(path) as doc:
+            if page > len(doc):
+                raise ValueError(f"{path.name} has {len(doc)} pages")
+            pixmap = doc[page - 1].get_pixmap(dpi=dpi)
+            pixmap.save(str(output), output="png")
+
+    @staticmethod
+    def _render_image(path: Path, dpi: int, output: Path):
+        width = int(IMAGE_PAGE_WIDTH_INCHES * dpi)
+        with Image.open(path) as img:
+            img.thumbnail((width, width * 4))
+            if img.mode not in ("RGB", "RGBA", "L"):
+                img = img.convert("RGB")
+            img.save(output, format="PNG")
+
+    def _cached_files(self):
+        return list(self.cache_directory.glob("*.png"))
+
+    def _evict(self):
+        """Delete least recently used thumbnails until under max_bytes."""
+        with self._lock:
+            files = sorted(self._cached_files(), key=lambda p: p.stat().st_mtime)
+            total = sum(p.stat().st_size for p in files)
+            evicted = 0
+            while files and total > self.max_bytes:
+                oldest = files.pop(0)
+                total -= oldest.stat().st_size
+                oldest.unlink()
+                evicted += 1
+            if evicted:
+                logger.debug(f"Evicted {evicted} thumbnail(s) from cache")
+
+    def get_cache_stats(self) -> Dict:
+        files = self._cached_files()
+        return {
+            "directory": str(self.cache_directory),
+            "thumbnails": len(files),
+            "size_bytes": sum(p.stat().st_size for p in files),
+            "max_bytes": self.max_bytes,
+            "hits": self._hits,
+            "misses": self._misses
+        }
+
+    def clear(self) -> int:
+        """Delete all cached thumbnails. Returns the number removed."""
+        with self._lock:
+            removed = 0
+            for path in self._cached_files():
+                path.unlink()
+                removed += 1
+        logger.info(f"Cleared {removed} cached thumbnail(s)")
+        return removed
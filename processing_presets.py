@@ -0,0 +1,206 @@
+"""
+Processing Presets for Jan Document Plugin
+
+Named bundles of processing settings so different document types can be
+indexed differently without reconfiguring the server:
+- Chunk size / overlap
+- OCR on or off
+- Smart (sentence-boundary) chunking or fixed-size windows
+- Structured table chunks
+- Embedding model the preset expects the store to use
+
+Built-in presets are always available; user presets are persisted to a
+JSON file in the storage directory.
+"""
+
+import json
+import logging
+from pathlib import Path
+from typing import List, Dict, Optional
+from dataclasses import dataclass, asdict
+
+logger = logging.getLogger(__name__)
+
+
+@dataclass
+class ProcessingPreset:
+    """A named set of document processing settings."""
+    name: str
+    description: str = ""
+    chunk_size: int = 1000              # Target tokens per chunk
+    chunk_overlap: int = 100            # Overlap tokens between chunks
+    ocr_enabled: bool = True            # OCR scanned PDF pages and images
+    smart_mode: bool = True             # Break chunks at sentence boundaries
+    extract_tables: Optional[bool] = None  # Structured table chunks (None = server setting)
+    embedding_model: Optional[str] = None  # None = whatever the store uses
+    builtin: bool = False
+
+    def validate(self):
+        """
+        Raises:
+            ValueError: If the settings are unusable
+        """
+        if not self.name or not self.name.strip():
+            raise ValueError("Preset name is required")
+        if self.chunk_size < 50:
+            raise ValueError("chunk_size must be at least 50 tokens")
+        if not 0 <= self.chunk_overlap < self.chunk_size:
+            raise ValueError("chunk_overlap must be between 0 and chunk_size")
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+    @classmethod
+    def from_dict(cls, data: Dict) -> "ProcessingPreset":
+        fields = cls.__dataclass_fields__
+        return cls(**{k: v for k, v in data.items() if k in fields})
+
+
+BUILTIN_PRESETS = {
+    preset.name: preset for preset in [
+        ProcessingPreset(
+            name="default",
+            description="General documents: medium chunks, OCR fallback, sentence-aware",
+            builtin=True
+        ),
+        ProcessingPreset(
+            name="legal",
+            description="Contracts and filings: large chunks with wide overlap, OCR for scans",
+            chunk_size=1800,
+            chunk_overlap=250,
+            extract_tables=True,
+            builtin=True
+        ),
+        ProcessingPreset(
+            name="code",
+            description="Source code and logs: small fixed-size chunks, no OCR",
+            chunk_size=300,
+            chunk_overlap=40,
+            ocr_enabled=False,
+            smart_mode=False,
+            builtin=True
+        ),
+    ]
+}
+
+
+class PresetStore:
+    """
+    Built-in plus user-defined presets, and which one is applied by default.
+
+    Usage:
+        presets = PresetStore(Path("./jan_doc_store/presets.json"))
+        presets.create_preset(ProcessingPreset(name="scans", chunk_size=600))
+        presets.apply_preset("legal")
+        preset = presets.resolve(None)   # -> legal
+    """
+
+    def __init__(self, path: Optional[Path] = None):
+        """
+        Initialize preset store.
+
+        Args:
+            path: JSON file for user presets and the applied preset (None = in memory)
+        """
+        self.path = Path(path) if path else None
+        self._presets: Dict[str, ProcessingPreset] = {}
+        self.active_preset = "default"
+        self._load()
+
+    def _load(self):
+        if not self.path or not self.path.exists():
+            return
+        try:
+            data = json.loads(self.path.read_text(encoding="utf-8"))
+        except (OSError, ValueError) as e:
+            logger.warning(f"Could not read presets: {e}")
+            return
+
+        for item in data.get("presets", []):
+            preset = ProcessingPreset.from_dict(item)
+            preset.builtin = False
+            self._presets[preset.name] = preset
+
+        active = data.get("active_preset", "default")
+        self.active_preset = active if active in self._all() else "default"
+
+    def _save(self):
+        if not self.path:
+            return
+        data = {
+            "active_preset": self.active_preset,
+            "presets": [p.to_dict() for p in self._presets.values()]
+        }
+        try:
+            self.path.parent.mkdir(parents=True, exist_ok=True)
+            self.path.write_text(json.dumps(data, indent=2), encoding="utf-8")
+        except OSError as e:
+            logger.warning(f"Could not save presets: {e}")
+
+    def _all(self) -> Dict[str, ProcessingPreset]:
+        return {**BUILTIN_PRESETS, **self._presets}
+
+    def list_presets(self) -> List[ProcessingPreset]:
+        """All presets, built-ins first."""
+        return list(self._all().values())
+
+    def get_preset(self, name: str) -> ProcessingPreset:
+        """
+        Raises:
+            KeyError: If no preset has this name
+        """
+        presets = self._all()
+        if name not in presets:
+            raise KeyError(f"Unknown preset: {name}")
+        return presets[name]
+
+    def create_preset(self, preset: ProcessingPreset) -> ProcessingPreset:
+        """
+        Add or replace a user preset.
+
+        Raises:
+            ValueError: If the settings are invalid or the name is a built-in preset
+        """
+        preset.validate()
+        if preset.name in BUILTIN_PRESETS:
+            raise ValueError(f"Cannot replace built-in preset: {preset.name}")
+
+        preset.builtin = False
+        self._presets[preset.name] = preset
+        self._save()
+
+        logger.info(f"Saved preset: {preset.name}")
+        return preset
+
+    def delete_preset(self, name: str):
+        """
+        Remove a user preset (falls back to "default" if it was applied).
+
+        Raises:
+            KeyError: If no user preset has this name
+        """
+        if name not in self._presets:
+            raise KeyError(f"Unknown user preset: {name}")
+
+        del self._presets[name]
+        if self.active_preset == name:
+            self.active_preset = "default"
+        self._save()
+
+    def apply_preset(self, name: str) -> ProcessingPreset:
+        """
+        Make a preset the default for uploads and batches that don't name one.
+
+        Raises:
+            KeyError: If no preset has this name
+        """
+        preset = self.get_preset(name)
+        self.active_preset = name
+        self._save()
+
+        logger.info(f"Applied preset: {name}")
+        return preset
+
+    def resolve(self, name: Optional[str] = None) -> ProcessingPreset:
+        """The named preset, or the applied one when name is empty."""
+        return self.get_preset(name or self.active_preset)
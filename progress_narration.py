@@ -0,0 +1,193 @@
+"""
+Progress Narration for Jan Document Plugin
+
+Screen-reader friendly batch progress: instead of percentages, a stream
+of short sentences such as
+
+    "Finished quarterly_report.pdf: 42 chunks, 12 pages read with OCR. 3 of 10 files done."
+
+Sentences are generated from the typed batch progress (BatchProgress /
+FileProgress) by comparing each update with the last one seen, so the
+batch processor only has to report that something changed. Events are
+numbered; clients poll with the last sequence number they read.
+"""
+
+import logging
+import threading
+from collections import deque
+from typing import Dict, List, Optional
+from dataclasses import dataclass, asdict
+from datetime import datetime
+
+from request_context import get_request_id
+
+logger = logging.getLogger(__name__)
+
+# Events kept for polling (oldest are dropped first)
+MAX_EVENTS = 500
+
+# FileStatus value -> event kind
+FILE_EVENT_KINDS = {
+    "processing": "file_started",
+    "completed": "file_finished",
+    "failed": "file_failed",
+    "skipped": "file_skipped",
+}
+
+
+def _plural(count: int, noun: str) -> str:
+    return f"{count} {noun}" if count == 1 else f"{count} {noun}s"
+
+
+def _duration(seconds: float) -> str:
+    if seconds < 60:
+        return _plural(max(1, round(seconds)), "second")
+    minutes, seconds = divmod(round(seconds), 60)
+    if minutes < 60:
+        return _plural(minutes, "minute") + (f" {_plural(seconds, 'second')}" if seconds else "")
+    hours, minutes = divmod(minutes, 60)
+    return _plural(hours, "hour") + (f" {_plural(minutes, 'minute')}" if minutes else "")
+
+
+@dataclass
+class NarrationEvent:
+    """One narrated progress sentence."""
+    sequence: int
+    time: str
+    kind: str   # batch_started, file_started, file_finished, file_failed, deferred, continued, batch_finished, paused, resumed
+    text: str
+    batch_id: Optional[str] = None
+    request_id: Optional[str] = None   # API request behind the batch / action
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+class ProgressNarrator:
+    """
+    Turns batch progress updates into narration events.
+
+    Usage:
+        narrator = ProgressNarrator()
+        narrator.observe(batch)          # after every progress change
+        narrator.get_events(since=0)
+    """
+
+    def __init__(self, max_events: int = MAX_EVENTS):
+        self._lock = threading.Lock()
+        self._events: deque = deque(maxlen=max_events)
+        self._sequence = 0
+        self._seen: Dict[str, Dict] = {}   # batch_id -> last narrated state
+
+    @property
+    def last_sequence(self) -> int:
+        return self._sequence
+
+    def announce(self, kind: str, text: str, batch_id: Optional[str] = None):
+        """Add an event not derived from batch progress (e.g. pause)."""
+        with self._lock:
+            self._add(kind, text, batch_id, get_request_id())
+
+    def _add(self, kind: str, text: str, batch_id: Optional[str], request_id: Optional[str] = None):
+        self._sequence += 1
+        self._events.append(NarrationEvent(
+            sequence=self._sequence,
+            time=datetime.now().isoformat(),
+            kind=kind,
+            text=text,
+            batch_id=batch_id,
+            request_id=request_id
+        ))
+
+    def observe(self, batch):
+        """
+        Narrate what changed in a batch since it was last observed.
+
+        Args:
+            batch: batch_processor.BatchProgress
+        """
+        with self._lock:
+            seen = self._seen.get(batch.batch_id)
+            if seen is None:
+                seen = self._seen[batch.batch_id] = {"files": {}, "deferred": None, "finished": False}
+                self._add(
+                    "batch_started",
+                    f"Started a batch of {_plural(batch.total_files, 'file')}.",
+                    batch.batch_id,
+                    batch.request_id
+                )
+
+            if batch.deferred_reason != seen["deferred"]:
+                if batch.deferred_reason:
+                    self._add(
+                        "deferred",
+                        f"Waiting before the next file: {batch.deferred_reason}.",
+                        batch.batch_id,
+                        batch.request_id
+                    )
+                else:
+                    self._add("continued", "Continuing the batch.", batch.batch_id, batch.request_id)
+                seen["deferred"] = batch.deferred_reason
+
+            # Counted from file states: with parallel workers the batch
+            # counters can lag behind the files
+            done = sum(1 for f in batch.files if f.status.value in ("completed", "failed", "skipped"))
+            for number, file_progress in enumerate(batch.files, start=1):
+                status = file_progress.status.value
+                if seen["files"].get(number) == status:
+                    continue
+                seen["files"][number] = status
+                if status in FILE_EVENT_KINDS:
+                    text = self._describe_file(batch, file_progress, number, done)
+                    self._add(FILE_EVENT_KINDS[status], text, batch.batch_id, batch.request_id)
+
+            if batch.completed_at and not seen["finished"]:
+                seen["finished"] = True
+                self._add("batch_finished", self._describe_batch(batch), batch.batch_id, batch.request_id)
+                del self._seen[batch.batch_id]
+
+    def _describe_file(self, batch, file_progress, number: int, done: int) -> str:
+        status = file_progress.status.value
+        name = file_progress.filename
+        counts = f"{done} of {_plural(batch.total_files, 'file')} done."
+        if status == "processing":
+            return f"Processing {name}, file {number} of {batch.total_files}."
+        if status == "completed":
+            details = [_plural(file_progress.chunks_created, "chunk")]
+            if file_progress.ocr_pages:
+                details.append(f"{_plural(file_progress.ocr_pages, 'page')} read with OCR")
+            if file_progress.low_confidence_pages:
+                details.append(f"{_plural(len(file_progress.low_confidence_pages), 'page')} with low OCR confidence")
+            text = f"Finished {name}: {', '.join(details)}."
+            if file_progress.partial:
+                text += " Only part of it was read before extraction timed out."
+            return f"{text} {counts}"
+        if status == "skipped":
+            return f"Skipped {name}: {file_progress.error_message}. {counts}"
+        return f"Could not process {name}: {file_progress.error_message or 'unknown error'}. {counts}"
+
+    def _describe_batch(self, batch) -> str:
+        text = f"Batch finished: {batch.completed_files} of {_plural(batch.total_files, 'file')} indexed"
+        if batch.failed_files:
+            text += f", {batch.failed_files} failed"
+        if batch.skipped_files:
+            text += f", {batch.skipped_files} skipped as duplicates"
+        text += f", {_plural(batch.total_chunks, 'chunk')}"
+        if batch.started_at and batch.completed_at:
+            text += f" in {_duration((batch.completed_at - batch.started_at).total_seconds())}"
+        return text + "."
+
+    def get_events(
+        self,
+        since: int = 0,
+        batch_id: Optional[str] = None,
+        request_id: Optional[str] = None
+    ) -> List[Dict]:
+        """Events after sequence number since, oldest first."""
+        with self._lock:
+            return [
+                e.to_dict() for e in self._events
+                if e.sequence > since
+                and (batch_id is None or e.batch_id == batch_id)
+                and (request_id is None or e.request_id == request_id)
+            ]
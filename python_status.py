@@ -0,0 +1,127 @@
+"""
+Python Status for Jan Document Plugin
+
+Describes the Python runtime the plugin runs in, for support requests
+and the setup wizard:
+
+- Interpreter version, architecture and whether it is the bundled
+  (PyInstaller) build
+- Versions of the packages indexing depends on, and of the OCR engine
+- Version and checksum of the plugin's modules, so a support engineer
+  can tell a modified or partially updated install from a release
+- When the last API request succeeded and when a document was last indexed
+"""
+
+import sys
+import struct
+import hashlib
+import logging
+import platform
+from pathlib import Path
+from functools import lru_cache
+from typing import Dict, List, Optional
+from datetime import datetime
+
+logger = logging.getLogger(__name__)
+
+# Packages indexing can't work without, and the module each provides
+REQUIRED_PACKAGES = {
+    "chromadb": "chromadb",
+    "sentence-transformers": "sentence_transformers",
+    "pymupdf": "fitz",
+    "python-docx": "docx",
+    "openpyxl": "openpyxl",
+}
+
+# Packages reported when installed
+OPTIONAL_PACKAGES = ("torch", "pytesseract", "Pillow", "opencv-python", "fastapi", "uvicorn")
+
+
+def package_versions(names) -> Dict[str, Optional[str]]:
+    """Installed version of each package (None = not installed)."""
+    from importlib.metadata import version, PackageNotFoundError
+    versions = {}
+    for name in names:
+        try:
+            versions[name] = version(name)
+        except PackageNotFoundError:
+            versions[name] = None
+    return versions
+
+
+def missing_required_packages() -> List[str]:
+    """Required packages that can't be imported."""
+    # Checked by module: the bundled build doesn't always carry package metadata
+    from importlib.util import find_spec
+    return [name for name, module in REQUIRED_PACKAGES.items() if find_spec(module) is None]
+
+
+def ocr_engine_version() -> Optional[str]:
+    try:
+        import pytesseract
+        return str(pytesseract.get_tesseract_version())
+    except Exception:
+        return None
+
+
+@lru_cache(maxsize=1)
+def bundle_checksum(directory: Optional[Path] = None) -> Dict:
+    """
+    SHA-256 over the plugin's modules (file names and contents, sorted),
+    computed once per run.
+    """
+    directory = Path(directory) if directory else Path(__file__).resolve().parent
+    digest = hashlib.sha256()
+    files = sorted(directory.glob("*.py"))
+    for path in files:
+        digest.update(path.name.encode("utf-8") + b"\0")
+        try:
+            digest.update(path.read_bytes())
+        except OSError as e:
+            logger.warning(f"Could not read {path} for the bundle checksum: {e}")
+    return {"directory": str(directory), "files": len(files), "sha256": digest.hexdigest()}
+
+
+class PythonStatus:
+    """
+    Runtime description plus the time of the last successful request.
+
+    Usage:
+        status = PythonStatus(app_version="2.0.0-beta")
+        status.note_success()   # after each successful request
+        status.get_python_status(last_indexed_at=processor.last_indexed_at)
+    """
+
+    def __init__(self, app_version: str = ""):
+        self.app_version = app_version
+        self.started_at = datetime.now()
+        self.last_success_at: Optional[datetime] = None
+
+    def note_success(self):
+        """Record that a request just succeeded."""
+        self.last_success_at = datetime.now()
+
+    def get_python_status(self, last_indexed_at: Optional[datetime] = None) -> Dict:
+        """
+        Args:
+            last_indexed_at: When a document was last indexed (None = not this run)
+        """
+        missing = missing_required_packages()
+        return {
+            "available": not missing,
+            "missing_packages": missing,
+            "runtime": {
+                "version": platform.python_version(),
+                "implementation": platform.python_implementation(),
+                "executable": sys.executable,
+                "machine": platform.machine(),
+                "bits": struct.calcsize("P") * 8,
+                "bundled": bool(getattr(sys, "frozen", False))
+            },
+            "packages": package_versions((*REQUIRED_PACKAGES, *OPTIONAL_PACKAGES)),
+            "ocr_engine": {"name": "tesseract", "version": ocr_engine_version()},
+            "bundle": {"version": self.app_version, **bundle_checksum()},
+            "started_at": self.started_at.isoformat(),
+            "last_success_at": self.last_success_at.isoformat() if self.last_success_at else None,
+            "last_indexed_at": last_indexed_at.isoformat() if last_indexed_at else None
+        }
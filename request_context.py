@@ -0,0 +1,65 @@
+"""
+Request Context for Jan Document Plugin
+
+Gives every API request an id, so the log lines, events and results of
+concurrent operations can be told apart:
+
+- The id comes from the caller's X-Request-ID header, or is generated,
+  and is echoed back in the response header
+- Log lines written while handling the request carry it ([request id])
+- Events, batch results and crash reports record it
+
+The id lives in a context variable. Work handed to a thread pool doesn't
+inherit it; wrap such calls with request_scope() (batch workers do).
+"""
+
+import re
+import uuid
+import logging
+from contextlib import contextmanager
+from contextvars import ContextVar
+from typing import Optional
+
+REQUEST_ID_HEADER = "X-Request-ID"
+
+# Caller-supplied ids are kept only if they look like ids
+VALID_REQUEST_ID = re.compile(r"^[A-Za-z0-9._:-]{1,64}$")
+
+_request_id: ContextVar[Optional[str]] = ContextVar("request_id", default=None)
+
+
+def new_request_id(supplied: Optional[str] = None) -> str:
+    """The caller's id if it is usable, else a new one."""
+    if supplied and VALID_REQUEST_ID.match(supplied):
+        return supplied
+    return uuid.uuid4().hex[:12]
+
+
+def get_request_id() -> Optional[str]:
+    """Id of the request being handled (None outside a request)."""
+    return _request_id.get()
+
+
+@contextmanager
+def request_scope(request_id: Optional[str]):
+    """Run a block as part of request_id (e.g. in a worker thread)."""
+    token = _request_id.set(request_id)
+    try:
+        yield request_id
+    finally:
+        _request_id.reset(token)
+
+
+class RequestIdLogFilter(logging.Filter):
+    """Adds request_id to log records ("-" outside a request)."""
+
+    def filter(self, record: logging.LogRecord) -> bool:
+        record.request_id = get_request_id() or "-"
+        return True
+
+
+def install_log_filter():
+    """Attach the request id filter to the root logger's handlers."""
+    for handler in logging.getLogger().handlers:
+        if not any(isinstance(f, RequestIdLogFilter) for f in handler.filters):
+            handler.addFilter(RequestIdLogFilter())
@@ -61,6 +61,62 @@ class ResourceSnapshot:
         }
 
 
+@dataclass
+class PowerState:
+    """Current power source and power-saving state."""
+    on_battery: bool
+    battery_percent: Optional[float]
+    power_saver: bool
+    
+    def to_dict(self) -> Dict:
+        return {
+            "on_battery": self.on_battery,
+            "battery_percent": self.battery_percent,
+            "power_saver": self.power_saver
+        }
+
+
+@dataclass
+class DiskSpaceCheck:
+    """Free-space check for the volume holding a path."""
+    path: str
+    required_bytes: int
+    available_bytes: int
+    
+    @property
+    def ok(self) -> bool:
+        return self.available_bytes >= self.required_bytes
+    
+    def to_dict(self) -> Dict:
+        return {
+            "path": self.path,
+            "required_bytes": self.required_bytes,
+            "available_bytes": self.available_bytes,
+            "ok": self.ok
+        }
+
+
+class InsufficientDiskSpaceError(OSError):
+    """Raised when an operation would need more disk space than is free."""
+    
+    def __init__(self, check: DiskSpaceCheck, operation: str = "processing"):
+        self.check = check
+        self.operation = operation
+        super().__init__(
+            f"Not enough disk space for {operation}: "
+            f"{check.required_bytes / (1024 * 1024):.0f}MB required, "
+            f"{check.available_bytes / (1024 * 1024):.0f}MB available on {check.path}"
+        )
+    
+    def to_dict(self) -> Dict:
+        return {
+            "error": "insufficient_disk",
+            "message": str(self),
+            "operation": self.operation,
+            **self.check.to_dict()
+        }
+
+
 @dataclass
 class LoadCapacity:
     """Recommended load capacity based on current resources."""
@@ -166,6 +222,9 @@ class ResourceMonitor:
         
         # Disk thresholds (MB)
         "disk_min_free_mb": 500,
+        "disk_reserve_mb": 500,            # Headroom kept free after an operation
+        "disk_space_multiplier": 2.0,      # Index bytes written per input byte (estimate)
+        "disk_guard_mode": "refuse",       # "refuse" or "warn" when space is short
         
         # Processing limits
         "max_workers": 8,                  # Maximum parallel workers
@@ -177,6 +236,11 @@ class ResourceMonitor:
         "ocr_page_time_seconds": 2.0,      # Estimated seconds per OCR page
         "ocr_max_parallel_workers": 2,     # Max workers for OCR (CPU-bound)
         "scanned_pdf_text_threshold": 100, # Characters per page to consider "scanned"
+        
+        # Power awareness
+        "defer_on_battery": True,          # Hold batch work while on battery
+        "defer_in_power_saver": True,      # Hold batch work in battery-saver mode
+        "power_check_interval_seconds": 30,
     }
     
     # Image extensions that always require OCR
@@ -201,6 +265,7 @@ class ResourceMonitor:
         self._history: List[ResourceSnapshot] = []
         self._history_max_size = 60  # Keep last 60 snapshots
         self._tesseract_available: Optional[bool] = None
+        self.storage_path = os.path.abspath(".")  # Volume checked for disk space
         
         if not PSUTIL_AVAILABLE:
             logger.warning("psutil not available - resource monitoring limited")
@@ -433,6 +498,134 @@ class ResourceMonitor:
             recommendation=recommendation
         )
     
+    def set_storage_path(self, path: str):
+        """Set the directory whose volume is used for disk space checks."""
+        self.storage_path = os.path.abspath(path)
+    
+    def _disk_free_bytes(self, path: str) -> int:
+        """Free bytes on the volume holding path (nearest existing parent)."""
+        check_path = os.path.abspath(path)
+        while not os.path.exists(check_path):
+            parent = os.path.dirname(check_path)
+            if parent == check_path:
+                break
+            check_path = parent
+        
+        if PSUTIL_AVAILABLE:
+            return psutil.disk_usage(check_path).free
+        
+        import shutil
+        return shutil.disk_usage(check_path).free
+    
+    def estimate_index_bytes(self, input_bytes: int) -> int:
+        """Estimate disk bytes written when indexing input_bytes of documents."""
+        return int(input_bytes * self.thresholds["disk_space_multiplier"])
+    
+    def check_disk_space(self, required_bytes: int = 0, path: Optional[str] = None) -> DiskSpaceCheck:
+        """
+        Check free space on the storage volume.
+        
+        Args:
+            required_bytes: Bytes the operation is expected to write
+            path: Directory to check (defaults to storage_path)
+            
+        Returns:
+            DiskSpaceCheck (required_bytes includes the configured reserve)
+        """
+        path = path or self.storage_path
+        reserve = int(self.thresholds["disk_reserve_mb"] * 1024 * 1024)
+        
+        return DiskSpaceCheck(
+            path=path,
+            required_bytes=required_bytes + reserve,
+            available_bytes=self._disk_free_bytes(path)
+        )
+    
+    def ensure_disk_space(
+        self,
+        required_bytes: int = 0,
+        operation: str = "processing",
+        path: Optional[str] = None
+    ) -> DiskSpaceCheck:
+        """
+        Guard an operation against running out of disk space midway.
+        
+        Raises:
+            InsufficientDiskSpaceError: If space is short and disk_guard_mode is "refuse"
+        """
+        check = self.check_disk_space(required_bytes, path)
+        
+        if not check.ok:
+            error = InsufficientDiskSpaceError(check, operation)
+            if self.thresholds["disk_guard_mode"] == "refuse":
+                raise error
+            logger.warning(str(error))
+        
+        return check
+    
+    def get_power_state(self) -> PowerState:
+        """
+        Get current power state.
+        
+        Battery info comes from psutil; battery-saver mode is read from
+        GetSystemPowerStatus on Windows (reported False elsewhere).
+        """
+        on_battery = False
+        battery_percent = None
+        
+        if PSUTIL_AVAILABLE:
+            try:
+                battery = psutil.sensors_battery()
+                if battery is not None:
+                    on_battery = not battery.power_plugged
+                    battery_percent = battery.percent
+            except Exception as e:
+                logger.debug(f"Battery status unavailable: {e}")
+        
+        power_saver = False
+        if sys.platform == "win32":
+            try:
+                import ctypes
+                
+                class SYSTEM_POWER_STATUS(ctypes.Structure):
+                    _fields_ = [
+                        ("ACLineStatus", ctypes.c_ubyte),
+                        ("BatteryFlag", ctypes.c_ubyte),
+                        ("BatteryLifePercent", ctypes.c_ubyte),
+                        ("SystemStatusFlag", ctypes.c_ubyte),
+                        ("BatteryLifeTime", ctypes.c_ulong),
+                        ("BatteryFullLifeTime", ctypes.c_ulong),
+                    ]
+                
+                status = SYSTEM_POWER_STATUS()
+                if ctypes.windll.kernel32.GetSystemPowerStatus(ctypes.byref(status)):
+                    power_saver = status.SystemStatusFlag == 1
+            except Exception as e:
+                logger.debug(f"Power saver status unavailable: {e}")
+        
+        return PowerState(
+            on_battery=on_battery,
+            battery_percent=battery_percent,
+            power_saver=power_saver
+        )
+    
+    def should_defer_heavy_work(self) -> Tuple[bool, str]:
+        """
+        Check whether batch embedding should wait for better power conditions.
+        
+        Returns:
+            Tuple of (defer, reason)
+        """
+        power = self.get_power_state()
+        
+        if power.power_saver and self.thresholds["defer_in_power_saver"]:
+            return True, "Battery saver is on"
+        if power.on_battery and self.thresholds["defer_on_battery"]:
+            percent = f" ({power.battery_percent:.0f}%)" if power.battery_percent is not None else ""
+            return True, f"Running on battery{percent}"
+        
+        return False, ""
+    
     def get_snapshot(self) -> ResourceSnapshot:
         """Get current resource usage snapshot."""
         if not PSUTIL_AVAILABLE:
@@ -447,7 +640,7 @@ class ResourceMonitor:
         
         cpu = psutil.cpu_percent(interval=0.1)
         memory = psutil.virtual_memory()
-        disk = psutil.disk_usage('/')
+        disk = psutil.disk_usage(self.storage_path if os.path.exists(self.storage_path) else '/')
         
         return ResourceSnapshot(
             cpu_percent=cpu,
@@ -508,6 +701,11 @@ class ResourceMonitor:
         else:
             max_file_size = self.thresholds["max_file_size_mb"]
         
+        # Power state check
+        defer, defer_reason = self.should_defer_heavy_work()
+        if defer:
+            warnings.append(f"{defer_reason} - batch processing will wait for AC power")
+        
         # Combine constraints (use minimum)
         recommended_workers = min(cpu_workers, mem_workers)
         max_concurrent = min(cpu_workers, mem_files)
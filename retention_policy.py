@@ -0,0 +1,226 @@
+"""
+Retention Policy for Jan Document Plugin
+
+Collections fed continuously (news clippings, exported email) grow
+without bound. A retention policy caps them:
+
+- max_age_days: documents indexed longer ago than this are pruned
+- max_documents: beyond this many documents, the oldest are pruned
+
+Pruning is a soft delete: documents go to the trash (restorable until
+the trash retention expires) and every pruned document is recorded in
+the audit log. Policies are enforced periodically by the server and on
+demand; the last run's report is kept for the UI.
+
+Policies are stored as JSON keyed by collection name:
+<storage>/retention_policy.json
+"""
+
+import json
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, List, Optional
+from dataclasses import dataclass, field, asdict
+from datetime import datetime, timedelta
+
+from document_processor import DocumentProcessor
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+
+@dataclass
+class RetentionPolicy:
+    """Limits for one collection (None = no limit)."""
+    max_age_days: Optional[int] = None
+    max_documents: Optional[int] = None
+
+    def validate(self):
+        """
+        Raises:
+            ValueError: If a limit is not a positive number
+        """
+        if self.max_age_days is not None and self.max_age_days < 1:
+            raise ValueError("max_age_days must be at least 1")
+        if self.max_documents is not None and self.max_documents < 1:
+            raise ValueError("max_documents must be at least 1")
+
+    @property
+    def active(self) -> bool:
+        return self.max_age_days is not None or self.max_documents is not None
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+@dataclass
+class RetentionReport:
+    """Outcome of one enforcement run."""
+    collection: str
+    policy: Dict
+    checked_at: str
+    dry_run: bool
+    documents_checked: int = 0
+    pruned: List[Dict] = field(default_factory=list)   # doc_hash, filename, indexed_at, reason, trash_id
+    errors: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict:
+        return asdict(self)
+
+
+class RetentionManager:
+    """
+    Stores retention policies and prunes documents that exceed them.
+
+    Usage:
+        retention = RetentionManager(processor, trash, Path("./jan_doc_store/retention_policy.json"))
+        retention.set_retention_policy("documents", RetentionPolicy(max_age_days=90))
+        report = retention.enforce()
+    """
+
+    def __init__(
+        self,
+        document_processor: DocumentProcessor,
+        trash_store,
+        policy_path: Path,
+        audit_log=None
+    ):
+        """
+        Initialize retention manager.
+
+        Args:
+            document_processor: Processor whose collection is pruned
+            trash_store: trash_store.TrashStore receiving pruned documents
+            policy_path: JSON file holding policies by collection name
+            audit_log: Optional audit_log.AuditLog recording each pruned document
+        """
+        self.processor = document_processor
+        self.trash_store = trash_store
+        self.policy_path = Path(policy_path)
+        self.audit_log = audit_log
+        self._lock = threading.Lock()
+        self.last_report: Optional[RetentionReport] = None
+        self._policies: Dict[str, RetentionPolicy] = {}
+        if self.policy_path.exists():
+            try:
+                data = json.loads(self.policy_path.read_text(encoding="utf-8"))
+                self._policies = {name: RetentionPolicy(**p) for name, p in data.items()}
+            except (OSError, ValueError, TypeError) as e:
+                logger.warning(f"Could not read retention policies: {e}")
+
+    def _collection_name(self, collection: Optional[str]) -> str:
+        name = self.processor.vector_store.collection.name
+        check_collection(collection, name)
+        return name
+
+    def get_retention_policy(self, collection: Optional[str] = None) -> RetentionPolicy:
+        """
+        Raises:
+            KeyError: If collection is not the store's collection
+        """
+        return self._policies.get(self._collection_name(collection)) or RetentionPolicy()
+
+    def set_retention_policy(self, collection: Optional[str], policy: RetentionPolicy) -> RetentionPolicy:
+        """
+        Store a collection's policy (a policy without limits removes it).
+
+        Raises:
+            KeyError: If collection is not the store's collection
+            ValueError: If a limit is invalid
+        """
+        name = self._collection_name(collection)
+        policy.validate()
+        with self._lock:
+            if policy.active:
+                self._policies[name] = policy
+            else:
+                self._policies.pop(name, None)
+            self.policy_path.parent.mkdir(parents=True, exist_ok=True)
+            self.policy_path.write_text(
+                json.dumps({n: p.to_dict() for n, p in self._policies.items()}, indent=2),
+                encoding="utf-8"
+            )
+        logger.info(f"Retention policy for {name}: {policy.to_dict()}")
+        return policy
+
+    def _documents_by_age(self) -> List[Dict]:
+        """Indexed documents, oldest first (unknown index time sorts first)."""
+        chunks = self.processor.vector_store.get_all_chunks()
+        documents: Dict[str, Dict] = {}
+        for meta in chunks["metadatas"]:
+            doc_hash = meta.get("doc_hash")
+            if doc_hash and doc_hash not in documents:
+                indexed_at = meta.get("indexed_at")
+                record = self.processor.processed_docs.get(doc_hash)
+                if not indexed_at and record is not None:
+                    indexed_at = record.extracted_at.isoformat()
+                documents[doc_hash] = {
+                    "doc_hash": doc_hash,
+                    "filename": meta.get("filename", "unknown"),
+                    "indexed_at": indexed_at
+                }
+        return sorted(documents.values(), key=lambda d: d["indexed_at"] or "")
+
+    def enforce(self, collection: Optional[str] = None, dry_run: bool = False) -> RetentionReport:
+        """
+        Prune documents beyond the collection's policy.
+
+        Args:
+            collection: Collection name (None = the store's collection)
+            dry_run: Report what would be pruned without deleting anything
+
+        Raises:
+            KeyError: If collection is not the store's collection
+            CollectionReadOnlyError: If the collection is read-only (unless dry_run)
+        """
+        name = self._collection_name(collection)
+        if not dry_run:
+            self.processor.check_writable("retention")
+        policy = self._policies.get(name) or RetentionPolicy()
+        report = RetentionReport(
+            collection=name,
+            policy=policy.to_dict(),
+            checked_at=datetime.now().isoformat(),
+            dry_run=dry_run
+        )
+
+        with self._lock:
+            documents = self._documents_by_age()
+            report.documents_checked = len(documents)
+
+            expired = []
+            if policy.max_age_days is not None:
+                cutoff = (datetime.now() - timedelta(days=policy.max_age_days)).isoformat()
+                expired = [
+                    (d, f"older than {policy.max_age_days} days")
+                    for d in documents if d["indexed_at"] and d["indexed_at"] < cutoff
+                ]
+            expired_hashes = {d["doc_hash"] for d, _ in expired}
+            remaining = [d for d in documents if d["doc_hash"] not in expired_hashes]
+            if policy.max_documents is not None and len(remaining) > policy.max_documents:
+                excess = len(remaining) - policy.max_documents
+                expired += [(d, f"over {policy.max_documents} documents") for d in remaining[:excess]]
+
+            for document, reason in expired:
+                pruned = {**document, "reason": reason, "trash_id": None}
+                if not dry_run:
+                    try:
+                        pruned["trash_id"] = self.trash_store.trash_document(document["doc_hash"]).trash_id
+                    except Exception as e:
+                        report.errors.append(f"{document['filename']}: {e}")
+                        continue
+                    if self.audit_log is not None:
+                        self.audit_log.record("retention_pruned", document["doc_hash"], {
+                            "collection": name,
+                            "filename": document["filename"],
+                            "indexed_at": document["indexed_at"],
+                            "reason": reason,
+                            "trash_id": pruned["trash_id"]
+                        })
+                report.pruned.append(pruned)
+
+        if report.pruned and not dry_run:
+            logger.info(f"Retention: moved {len(report.pruned)} document(s) from {name} to trash")
+        self.last_report = report
+        return report
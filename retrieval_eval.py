@@ -0,0 +1,311 @@
+"""
+Retrieval Evaluation for Jan Document Plugin
+
+Scores retrieval quality against a labeled query set so chunking and
+embedding settings can be compared with numbers instead of by feel.
+
+Dataset file (JSON):
+    {
+      "name": "contracts",
+      "documents": ["docs/msa.pdf", "docs/sow.docx"],
+      "queries": [
+        {"query": "termination notice period", "expected_source": "msa.pdf", "expected_page": 4},
+        {"query": "payment schedule", "expected_source": "sow.docx"}
+      ],
+      "configurations": [
+        {"name": "small", "chunk_size": 400, "chunk_overlap": 50},
+        "legal"
+      ]
+    }
+
+- Document paths are relative to the dataset file
+- Configurations are processing presets (inline settings or a preset
+  name); each is evaluated on a throwaway index of the dataset documents
+- "current" (the default when no configurations are given) evaluates the
+  live collection as it is
+
+A result is a hit when it comes from the expected source (matched by
+file name) and, if expected_page is given, from that page.
+"""
+
+import json
+import shutil
+import logging
+import tempfile
+from pathlib import Path
+from typing import List, Dict, Optional, Union, Sequence
+from dataclasses import dataclass, field
+from datetime import datetime
+
+from document_processor import DocumentProcessor, MAX_BATCH_QUERIES
+from processing_presets import ProcessingPreset, PresetStore
+
+logger = logging.getLogger(__name__)
+
+# Configuration name for the live collection
+CURRENT_CONFIGURATION = "current"
+
+DEFAULT_K_VALUES = (1, 3, 5, 10)
+
+
+@dataclass
+class EvalQuery:
+    """A query with the source (and optionally page) that should answer it."""
+    query: str
+    expected_source: str
+    expected_page: Optional[int] = None
+
+    @classmethod
+    def from_dict(cls, data: Dict) -> "EvalQuery":
+        """
+        Raises:
+            ValueError: If the entry is not an object with 'query' and
+                        'expected_source', or expected_page is not a number
+        """
+        if not isinstance(data, dict) or not data.get("query") or not data.get("expected_source"):
+            raise ValueError(f"Query needs 'query' and 'expected_source': {data}")
+        page = data.get("expected_page")
+        try:
+            expected_page = int(page) if page is not None else None
+        except (TypeError, ValueError):
+            raise ValueError(f"expected_page must be a number: {data}")
+        return cls(
+            query=str(data["query"]),
+            expected_source=str(data["expected_source"]),
+            expected_page=expected_page
+        )
+
+
+@dataclass
+class ConfigurationResult:
+    """Scores for one configuration."""
+    name: str
+    settings: Dict
+    recall_at_k: Dict[int, float] = field(default_factory=dict)
+    mrr: float = 0.0
+    ranks: List[Optional[int]] = field(default_factory=list)  # Per query, None = miss
+    chunk_count: int = 0
+    error: Optional[str] = None
+
+    def to_dict(self) -> Dict:
+        return {
+            "name": self.name,
+            "settings": self.settings,
+            "recall_at_k": {str(k): round(v, 4) for k, v in self.recall_at_k.items()},
+            "mrr": round(self.mrr, 4),
+            "ranks": self.ranks,
+            "chunk_count": self.chunk_count,
+            "error": self.error
+        }
+
+
+@dataclass
+class EvalReport:
+    """Results of one evaluation run."""
+    dataset: str
+    queries: List[EvalQuery]
+    k_values: List[int]
+    results: List[ConfigurationResult]
+    started_at: datetime
+    finished_at: Optional[datetime] = None
+    report_path: Optional[str] = None
+
+    @property
+    def best(self) -> Optional[str]:
+        """Name of the configuration with the highest MRR."""
+        scored = [r for r in self.results if r.error is None]
+        return max(scored, key=lambda r: r.mrr).name if scored else None
+
+    def to_dict(self) -> Dict:
+        return {
+            "dataset": self.dataset,
+            "query_count": len(self.queries),
+            "queries": [q.__dict__ for q in self.queries],
+            "k_values": self.k_values,
+            "results": [r.to_dict() for r in self.results],
+            "best": self.best,
+            "started_at": self.started_at.isoformat(),
+            "finished_at": self.finished_at.isoformat() if self.finished_at else None,
+            "report_path": self.report_path
+        }
+
+
+def _is_hit(query: EvalQuery, metadata: Dict) -> bool:
+    expected = Path(query.expected_source).name.lower()
+    sources = {
+        Path(str(metadata.get(key, ""))).name.lower()
+        for key in ("source_path", "filename")
+    }
+    if expected not in sources:
+        return False
+    return query.expected_page is None or metadata.get("page") == query.expected_page
+
+
+def score_configuration(
+    processor: DocumentProcessor,
+    queries: List[EvalQuery],
+    k_values: Sequence[int],
+    result: ConfigurationResult
+) -> ConfigurationResult:
+    """Run every query against a processor's store and fill in the scores."""
+    max_k = max(k_values)
+    store = processor.vector_store
+
+    # Queries are embedded a batch at a time
+    matches_per_query = []
+    for start in range(0, len(queries), MAX_BATCH_QUERIES):
+        batch = queries[start:start + MAX_BATCH_QUERIES]
+        matches_per_query.extend(store.query_batch([q.query for q in batch], n_results=max_k))
+    for query, matches in zip(queries, matches_per_query):
+        rank = next(
+            (i + 1 for i, m in enumerate(matches) if _is_hit(query, m["metadata"])),
+            None
+        )
+        result.ranks.append(rank)
+
+    total = len(queries) or 1
+    result.recall_at_k = {
+        k: sum(1 for r in result.ranks if r is not None and r <= k) / total
+        for k in k_values
+    }
+    result.mrr = sum(1 / r for r in result.ranks if r is not None) / total
+    result.chunk_count = store.get_document_count()
+    return result
+
+
+def evaluate_preset(
+    processor: DocumentProcessor,
+    preset: ProcessingPreset,
+    documents: List[Path],
+    queries: List[EvalQuery],
+    k_values: Sequence[int]
+) -> ConfigurationResult:
+    """Index documents with preset into a throwaway store and score it."""
+    result = ConfigurationResult(name=preset.name, settings=preset.to_dict())
+    if not documents:
+        result.error = "Dataset lists no documents to index"
+        return result
+
+    index_dir = tempfile.mkdtemp(prefix="jdp_eval_")
+    try:
+        trial = DocumentProcessor(
+            persist_directory=index_dir,
+            embedding_model=preset.embedding_model or processor.vector_store.embedding_model,
+            chunk_size=preset.chunk_size,
+            chunk_overlap=preset.chunk_overlap,
+            extract_tables=processor.extract_tables,
+            index_figures=processor.index_figures,
+            footnote_mode=processor.footnote_mode
+        )
+        logger.info(f"Evaluating configuration {preset.name}: indexing {len(documents)} documents")
+        for path in documents:
+            trial.ingest(path, preset=preset)
+        score_configuration(trial, queries, k_values, result)
+    except Exception as e:
+        logger.error(f"Configuration {preset.name} failed: {e}")
+        result.error = str(e)
+    finally:
+        shutil.rmtree(index_dir, ignore_errors=True)
+    return result
+
+
+def _resolve_configuration(
+    item: Union[str, Dict],
+    presets: Optional[PresetStore]
+) -> Optional[ProcessingPreset]:
+    """Inline settings or a preset name -> preset (None = the live collection)."""
+    if item == CURRENT_CONFIGURATION:
+        return None
+    if isinstance(item, str):
+        return (presets or PresetStore()).get_preset(item)
+
+    preset = ProcessingPreset.from_dict({"name": "unnamed", **item})
+    preset.validate()
+    return preset
+
+
+def run_retrieval_eval(
+    dataset_path: Union[str, Path],
+    processor: DocumentProcessor,
+    configurations: Optional[List[Union[str, Dict]]] = None,
+    k_values: Sequence[int] = DEFAULT_K_VALUES,
+    report_dir: Optional[Union[str, Path]] = None,
+    presets: Optional[PresetStore] = None
+) -> EvalReport:
+    """
+    Evaluate retrieval for a labeled query set across configurations.
+
+    Args:
+        dataset_path: JSON dataset (see module docstring)
+        processor: Live processor ("current" configuration, and the source
+                   of defaults such as the embedding model)
+        configurations: Overrides the dataset's configurations
+        k_values: Cut-offs for recall@k
+        report_dir: Directory to write the JSON report to (None = don't persist)
+        presets: Preset store for configurations given by name
+
+    Raises:
+        ValueError: If the dataset is malformed
+    """
+    dataset_path = Path(dataset_path)
+    data = json.loads(dataset_path.read_text(encoding="utf-8"))
+
+    if not isinstance(data, dict) or not isinstance(data.get("queries", []), list):
+        raise ValueError("Dataset must be an object with a 'queries' list")
+    queries = [EvalQuery.from_dict(q) for q in data.get("queries", [])]
+    if not queries:
+        raise ValueError("Dataset has no queries")
+
+    k_values = sorted({int(k) for k in k_values if int(k) > 0})
+    if not k_values:
+        raise ValueError("k_values must contain a positive number")
+
+    configurations = configurations or data.get("configurations") or [CURRENT_CONFIGURATION]
+    documents = [
+        path if path.is_absolute() else dataset_path.parent / path
+        for path in (Path(p) for p in data.get("documents", []))
+    ]
+
+    report = EvalReport(
+        dataset=data.get("name") or dataset_path.stem,
+        queries=queries,
+        k_values=k_values,
+        results=[],
+        started_at=datetime.now()
+    )
+
+    for item in configurations:
+        try:
+            preset = _resolve_configuration(item, presets)
+        except (KeyError, ValueError, TypeError) as e:
+            name = item if isinstance(item, str) else item.get("name", "unnamed")
+            report.results.append(ConfigurationResult(name=name, settings={}, error=str(e)))
+            continue
+
+        if preset is None:
+            result = ConfigurationResult(
+                name=CURRENT_CONFIGURATION,
+                settings={
+                    "chunk_size": processor.chunker.chunk_size,
+                    "chunk_overlap": processor.chunker.chunk_overlap,
+                    "embedding_model": processor.vector_store.embedding_model
+                }
+            )
+            logger.info(f"Evaluating live collection on {len(queries)} queries")
+            report.results.append(score_configuration(processor, queries, k_values, result))
+            continue
+
+        # Throwaway index for this configuration
+        report.results.append(evaluate_preset(processor, preset, documents, queries, k_values))
+
+    report.finished_at = datetime.now()
+
+    if report_dir:
+        report_dir = Path(report_dir)
+        report_dir.mkdir(parents=True, exist_ok=True)
+        path = report_dir / f"eval_{report.started_at.strftime('%Y%m%d_%H%M%S')}.json"
+        report.report_path = str(path)
+        path.write_text(json.dumps(report.to_dict(), indent=2), encoding="utf-8")
+        logger.info(f"Saved evaluation report: {path}")
+
+    return report
@@ -0,0 +1,142 @@
+"""
+Safe Mode for Jan Document Plugin
+
+A store that crashes the server while it starts (a corrupt database, a
+broken model cache) would otherwise crash it on every start. Each start
+is marked in <storage>/startup_state.json and the mark is cleared once
+startup finishes; a mark still there on the next start means that start
+failed. After max_failed_starts failed starts in a row the server starts
+in safe mode:
+
+- The document store is not opened, so nothing that crashed at startup runs
+- Scheduled work (retention checks, automatic optimization) is not started
+- Diagnostics (/health, /debug/crashes, /debug/report), data relocation
+  and uninstall keep working
+
+Safe mode lasts across restarts until exit_safe_mode() is called, which
+starts the server normally (and counts again if that start fails).
+"""
+
+import json
+import logging
+from pathlib import Path
+from typing import Dict, Optional
+from datetime import datetime
+
+logger = logging.getLogger(__name__)
+
+# Failed starts in a row before starting in safe mode
+DEFAULT_MAX_FAILED_STARTS = 3
+
+
+class StartupGuard:
+    """
+    Counts starts that never finished and decides on safe mode.
+
+    Usage:
+        guard = StartupGuard(Path("./jan_doc_store/startup_state.json"), max_failed_starts=3)
+        if guard.begin():
+            ...   # Safe mode: start diagnostics only
+        else:
+            ...   # Normal startup
+            guard.complete()
+    """
+
+    def __init__(self, path: Path, max_failed_starts: int = DEFAULT_MAX_FAILED_STARTS):
+        """
+        Initialize guard.
+
+        Args:
+            path: JSON file holding the startup state
+            max_failed_starts: Failed starts in a row that trigger safe mode
+                               (0 = never start in safe mode)
+        """
+        self.path = Path(path)
+        self.max_failed_starts = max_failed_starts
+        self._state = {
+            "starting": False,
+            "started_at": None,
+            "failed_starts": 0,
+            "safe_mode": None      # {"reason", "since"} while in safe mode
+        }
+        self._load()
+
+    def _load(self):
+        if not self.path.exists():
+            return
+        try:
+            self._state.update(json.loads(self.path.read_text(encoding="utf-8")))
+        except (OSError, ValueError) as e:
+            logger.warning(f"Could not read startup state: {e}")
+
+    def _save(self):
+        try:
+            self.path.parent.mkdir(parents=True, exist_ok=True)
+            self.path.write_text(json.dumps(self._state, indent=2), encoding="utf-8")
+        except OSError as e:
+            logger.warning(f"Could not save startup state: {e}")
+
+    def begin(self) -> bool:
+        """
+        Mark a start; call first thing at startup.
+
+        Returns:
+            True if this start should run in safe mode
+        """
+        if self._state["starting"]:
+            self._state["failed_starts"] += 1
+            logger.warning(
+                f"The previous start (at {self._state['started_at']}) did not finish "
+                f"({self._state['failed_starts']} failed start(s) in a row)"
+            )
+        if (
+            not self._state["safe_mode"]
+            and self.max_failed_starts
+            and self._state["failed_starts"] >= self.max_failed_starts
+        ):
+            self._state["safe_mode"] = {
+                "reason": (
+                    f"The last {self._state['failed_starts']} starts did not finish "
+                    f"(the most recent at {self._state['started_at']}); "
+                    f"the document store was not opened"
+                ),
+                "since": datetime.now().isoformat()
+            }
+
+        safe = self._state["safe_mode"] is not None
+        # A safe-mode start opens nothing that failed, so it isn't counted
+        self._state["starting"] = not safe
+        self._state["started_at"] = datetime.now().isoformat()
+        self._save()
+        return safe
+
+    def complete(self):
+        """Mark the start as finished; call once startup succeeded."""
+        self._state["starting"] = False
+        self._state["failed_starts"] = 0
+        self._save()
+
+    def get_safe_mode_reason(self) -> Optional[str]:
+        """Why the server is in safe mode (None = not in safe mode)."""
+        safe_mode = self._state["safe_mode"]
+        return safe_mode["reason"] if safe_mode else None
+
+    def exit_safe_mode(self) -> Dict:
+        """Leave safe mode; the next start (or startup call) runs normally."""
+        previous = self._state["safe_mode"]
+        self._state["safe_mode"] = None
+        self._state["failed_starts"] = 0
+        self._save()
+        if previous:
+            logger.info("Leaving safe mode")
+        return self.get_status()
+
+    def get_status(self) -> Dict:
+        safe_mode = self._state["safe_mode"]
+        return {
+            "safe_mode": safe_mode is not None,
+            "reason": safe_mode["reason"] if safe_mode else None,
+            "since": safe_mode["since"] if safe_mode else None,
+            "failed_starts": self._state["failed_starts"],
+            "max_failed_starts": self.max_failed_starts
+        }
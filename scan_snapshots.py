@@ -0,0 +1,176 @@
+"""
+Scan Snapshots for Jan Document Plugin
+
+Every directory scan can be kept as a snapshot of its root: each file's
+path, size and modified time. diff_scans compares the last two snapshots
+of a root and lists the files added, removed and modified in between, so
+a huge corpus can be re-indexed incrementally - only added and modified
+files go into the next batch.
+
+Snapshots are kept in <storage>/scan_snapshots/, one JSON file per root
+holding its last KEPT_SNAPSHOTS scans. A file counts as modified when
+its size or modified time changed; contents aren't hashed.
+"""
+
+import hashlib
+import json
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, List, Optional, Union
+from dataclasses import dataclass, field
+
+from directory_scanner import ScanResult
+
+logger = logging.getLogger(__name__)
+
+# Snapshots kept per root; diff_scans needs the last two
+KEPT_SNAPSHOTS = 2
+
+
+@dataclass
+class ScanDiff:
+    """Files that changed between a root's last two snapshots."""
+    root: str
+    current_at: str                      # ISO timestamp of the last scan
+    previous_at: Optional[str] = None    # None = first scan, every file is added
+    added: List[str] = field(default_factory=list)
+    removed: List[str] = field(default_factory=list)
+    modified: List[str] = field(default_factory=list)
+    unchanged_files: int = 0
+
+    @property
+    def changed_paths(self) -> List[str]:
+        """Files to process in an incremental batch (added and modified)."""
+        return sorted(self.added + self.modified)
+
+    def to_dict(self) -> Dict:
+        return {
+            "root": self.root,
+            "previous_at": self.previous_at,
+            "current_at": self.current_at,
+            "added_files": len(self.added),
+            "removed_files": len(self.removed),
+            "modified_files": len(self.modified),
+            "unchanged_files": self.unchanged_files,
+            "added": self.added,
+            "removed": self.removed,
+            "modified": self.modified
+        }
+
+
+class ScanSnapshotStore:
+    """
+    Last scans of each root, persisted as JSON.
+
+    Usage:
+        snapshots = ScanSnapshotStore(Path("./jan_doc_store/scan_snapshots"))
+        snapshots.record(scan_directory("C:/Contracts"))
+        ...
+        snapshots.record(scan_directory("C:/Contracts"))
+        snapshots.diff_scans("C:/Contracts").changed_paths
+    """
+
+    def __init__(self, directory: Path):
+        """
+        Initialize snapshot store.
+
+        Args:
+            directory: Folder holding one snapshot file per root
+        """
+        self.directory = Path(directory)
+        self._lock = threading.Lock()
+
+    def record(self, scan: ScanResult) -> Dict:
+        """
+        Keep a scan as the root's latest snapshot, dropping the oldest
+        beyond KEPT_SNAPSHOTS.
+
+        Returns:
+            Summary of the snapshot (root, taken_at, engine, total_files)
+        """
+        snapshot = {
+            "taken_at": scan.scanned_at.isoformat(),
+            "engine": scan.engine,
+            "files": {
+                f.path: {"size_bytes": f.size_bytes, "modified_at": f.modified_at.isoformat()}
+                for f in scan.files
+            }
+        }
+        with self._lock:
+            snapshots = self._load(scan.root)
+            snapshots = (snapshots + [snapshot])[-KEPT_SNAPSHOTS:]
+            self.directory.mkdir(parents=True, exist_ok=True)
+            self._path(scan.root).write_text(
+                json.dumps({"root": scan.root, "snapshots": snapshots}),
+                encoding="utf-8"
+            )
+        logger.info(f"Recorded scan snapshot of {scan.root} ({len(scan.files)} files)")
+        return self._summary(scan.root, snapshot)
+
+    def list_snapshots(self) -> List[Dict]:
+        """Summaries of the kept snapshots of every root, newest last."""
+        summaries = []
+        if not self.directory.is_dir():
+            return summaries
+        for path in sorted(self.directory.glob("*.json")):
+            try:
+                data = json.loads(path.read_text(encoding="utf-8"))
+            except (OSError, ValueError) as e:
+                logger.warning(f"Could not read scan snapshot {path.name}: {e}")
+                continue
+            summaries.extend(self._summary(data["root"], s) for s in data.get("snapshots", []))
+        return summaries
+
+    def diff_scans(self, root: Union[str, Path]) -> ScanDiff:
+        """
+        Compare the last two snapshots of a root. With a single snapshot
+        every file is reported as added.
+
+        Raises:
+            KeyError: If the root has never been scanned
+        """
+        root = str(Path(root).resolve())
+        with self._lock:
+            snapshots = self._load(root)
+        if not snapshots:
+            raise KeyError(f"No scan snapshot for {root}")
+
+        current = snapshots[-1]
+        previous = snapshots[-2] if len(snapshots) > 1 else {"taken_at": None, "files": {}}
+        diff = ScanDiff(root=root, current_at=current["taken_at"], previous_at=previous["taken_at"])
+        for path, info in current["files"].items():
+            before = previous["files"].get(path)
+            if before is None:
+                diff.added.append(path)
+            elif before != info:
+                diff.modified.append(path)
+            else:
+                diff.unchanged_files += 1
+        diff.removed = [path for path in previous["files"] if path not in current["files"]]
+        diff.added.sort()
+        diff.modified.sort()
+        diff.removed.sort()
+        return diff
+
+    def _path(self, root: str) -> Path:
+        return self.directory / f"{hashlib.sha256(root.encode('utf-8')).hexdigest()[:16]}.json"
+
+    def _load(self, root: str) -> List[Dict]:
+        path = self._path(root)
+        if not path.exists():
+            return []
+        try:
+            return json.loads(path.read_text(encoding="utf-8")).get("snapshots", [])
+        except (OSError, ValueError) as e:
+            logger.warning(f"Could not read scan snapshots of {root}: {e}")
+            return []
+
+    @staticmethod
+    def _summary(root: str, snapshot: Dict) -> Dict:
+        return {
+            "root": root,
+            "taken_at": snapshot["taken_at"],
+            "engine": snapshot.get("engine"),
+            "total_files": len(snapshot["files"])
+        }
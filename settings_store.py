@@ -0,0 +1,212 @@
+"""
+Settings Store for Jan Document Plugin
+
+Settings changed at runtime (from the UI) are kept in one place,
+<storage>/settings.json, on top of config.env:
+
+- Only settings listed in EDITABLE_SETTINGS can be changed; values are
+  validated against the ProxyConfig field types (plus the allowed
+  choices and non-negative numbers)
+- Settings pinned by enterprise policy can't be changed
+- The file is written atomically (temp file, then replaced) and carries
+  a schema version; older files are migrated when loaded
+- Listeners registered with subscribe() are called with the changed
+  settings, so running components pick them up without a restart (the
+  server also records a settings.changed event)
+
+Order of precedence: policy, then settings.json, then config.env.
+"""
+
+import json
+import logging
+import threading
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional
+
+logger = logging.getLogger(__name__)
+
+SETTINGS_VERSION = 1
+
+# Settings that can be changed at runtime, and what they do
+EDITABLE_SETTINGS = {
+    "auto_inject": "Inject document context into chat requests",
+    "max_context_tokens": "Max tokens of injected context",
+    "max_chunks": "Chunks retrieved per chat request",
+    "relevance_threshold": "Minimum relevance of injected chunks",
+    "extraction_timeout": "Seconds per document extraction (empty = no limit)",
+    "extraction_inactivity_timeout": "Seconds without PDF page progress (empty = no limit)",
+    "salvage_partial": "On timeout, index the pages extracted so far",
+    "disk_reserve_mb": "Free space (MB) kept on the storage volume",
+    "disk_guard_mode": "Refuse or only warn when disk space is short",
+    "retention_check_hours": "Hours between retention checks (0 = never)",
+    "optimize_after_deleted": "Deleted chunks that trigger a database compaction (0 = never)",
+    "progress_events_per_second": "Batch progress events logged per batch per second (0 = all)",
+}
+
+# Allowed values of settings that are choices
+SETTING_CHOICES = {
+    "disk_guard_mode": ("refuse", "warn"),
+}
+
+
+def _migrate_v0(data: Dict) -> Dict:
+    """Unversioned files used config.env names (AUTO_INJECT); use field names."""
+    return {"version": 1, "settings": {k.lower(): v for k, v in data.get("settings", data).items()}}
+
+
+# version -> function upgrading a file of that version by one step
+MIGRATIONS: Dict[int, Callable[[Dict], Dict]] = {
+    0: _migrate_v0,
+}
+
+
+class SettingsStore:
+    """
+    Persisted runtime settings applied to a ProxyConfig.
+
+    Usage:
+        settings = SettingsStore(Path("./jan_doc_store/settings.json"), config, is_locked=policy.is_setting_locked)
+        settings.load()
+        settings.subscribe(lambda changed: ...)
+        settings.update_settings({"max_chunks": 3})
+    """
+
+    def __init__(self, path: Path, config, is_locked: Optional[Callable[[str], bool]] = None):
+        """
+        Initialize settings store.
+
+        Args:
+            path: JSON file holding changed settings
+            config: ProxyConfig the settings are applied to
+            is_locked: Returns True for settings pinned by policy
+        """
+        self.path = Path(path)
+        self.config = config
+        self.is_locked = is_locked or (lambda name: False)
+        self._saved: Dict[str, Any] = {}
+        self._listeners: List[Callable[[Dict[str, Any]], None]] = []
+        self._lock = threading.Lock()
+
+    def load(self) -> List[str]:
+        """
+        Read settings.json (migrating it if older) and apply it to the config.
+        Invalid or no longer editable settings are logged and dropped.
+
+        Returns:
+            Names of the settings applied
+        """
+        if not self.path.exists():
+            return []
+        try:
+            data = json.loads(self.path.read_text(encoding="utf-8"))
+        except (OSError, ValueError) as e:
+            logger.warning(f"Could not read settings: {e}")
+            return []
+
+        version = data.get("version", 0)
+        migrated = version < SETTINGS_VERSION
+        while version < SETTINGS_VERSION:
+            data = MIGRATIONS[version](data)
+            version = data["version"]
+        if migrated:
+            logger.info(f"Migrated settings to version {SETTINGS_VERSION}")
+
+        applied = []
+        for name, value in data.get("settings", {}).items():
+            try:
+                validated = self._validate(name, value)
+            except (KeyError, ValueError) as e:
+                logger.warning(f"Ignoring saved setting {name}={value!r}: {e.args[0]}")
+                continue
+            self._saved[name] = validated
+            setattr(self.config, name, validated)
+            applied.append(name)
+        if migrated:
+            self._save()
+        return applied
+
+    def _validate(self, name: str, value: Any) -> Any:
+        """
+        Raises:
+            KeyError: If the setting is not editable
+            ValueError: If the value is invalid
+        """
+        if name not in EDITABLE_SETTINGS:
+            raise KeyError(f"Unknown setting: {name}")
+        config_type = type(self.config)
+        try:
+            validated = getattr(config_type.model_validate({**self.config.model_dump(), name: value}), name)
+        except ValueError:
+            raise ValueError(f"Invalid value for {name}: {value!r}")
+        choices = SETTING_CHOICES.get(name)
+        if choices and validated not in choices:
+            raise ValueError(f"Invalid value for {name}: {value!r} (expected one of {', '.join(choices)})")
+        if isinstance(validated, (int, float)) and not isinstance(validated, bool) and validated < 0:
+            raise ValueError(f"Invalid value for {name}: {value!r} (must not be negative)")
+        return validated
+
+    def _save(self):
+        """Write settings.json atomically."""
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        temp = self.path.with_suffix(".tmp")
+        temp.write_text(json.dumps({"version": SETTINGS_VERSION, "settings": self._saved}, indent=2), encoding="utf-8")
+        temp.replace(self.path)
+
+    def subscribe(self, listener: Callable[[Dict[str, Any]], None]):
+        """Call listener(changed settings) after every update."""
+        self._listeners.append(listener)
+
+    def get_settings(self) -> Dict:
+        """Every editable setting with its value, and whether it is saved or pinned."""
+        return {
+            "version": SETTINGS_VERSION,
+            "settings": {
+                name: {
+                    "value": getattr(self.config, name),
+                    "description": description,
+                    "choices": list(SETTING_CHOICES[name]) if name in SETTING_CHOICES else None,
+                    "saved": name in self._saved,
+                    "locked": self.is_locked(name)
+                }
+                for name, description in EDITABLE_SETTINGS.items()
+            }
+        }
+
+    def update_settings(self, changes: Dict[str, Any]) -> Dict[str, Any]:
+        """
+        Validate, persist and apply settings; nothing changes if any is invalid.
+
+        Returns:
+            The settings whose value changed
+
+        Raises:
+            KeyError: If a setting is not editable
+            ValueError: If a value is invalid
+            PermissionError: If a setting is pinned by policy
+            OSError: If settings.json can't be written
+        """
+        validated = {name: self._validate(name, value) for name, value in changes.items()}
+        locked = [name for name in validated if self.is_locked(name)]
+        if locked:
+            raise PermissionError(f"Pinned by policy: {', '.join(locked)}")
+
+        with self._lock:
+            changed = {n: v for n, v in validated.items() if getattr(self.config, n) != v}
+            previous = dict(self._saved)
+            self._saved.update(validated)
+            try:
+                self._save()
+            except OSError:
+                self._saved = previous
+                raise
+            for name, value in changed.items():
+                setattr(self.config, name, value)
+
+        if changed:
+            logger.info(f"Settings changed: {', '.join(f'{n}={v!r}' for n, v in changed.items())}")
+            for listener in self._listeners:
+                try:
+                    listener(changed)
+                except Exception as e:
+                    logger.warning(f"Settings listener failed: {e}")
+        return changed
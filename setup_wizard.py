@@ -0,0 +1,274 @@
+"""
+Setup Wizard for Jan Document Plugin
+
+Backend for the first-run wizard. get_setup_state() returns a checklist
+of everything the plugin needs:
+
+- config_file: config.env exists next to the app
+- python_runtime: the packages indexing needs are installed
+- tesseract: Tesseract is installed (OCR for scanned documents)
+- embedding_model: the embedding model is in the local model cache
+- vector_store: the document store is initialized
+- jan: Jan is installed
+- default_collection: the default collection exists
+
+Items the plugin can fix itself name a setup step; run_setup_step(step)
+performs it. Steps are idempotent (running one again after it succeeded
+does nothing harmful) and record progress events the UI can poll.
+"""
+
+import os
+import shutil
+import logging
+import platform
+import threading
+from pathlib import Path
+from typing import Callable, Dict, List, Optional
+from dataclasses import dataclass, field
+from datetime import datetime
+
+import pytesseract
+
+from app_paths import get_app_path
+
+logger = logging.getLogger(__name__)
+
+SETUP_STEPS = {
+    "create_config": "Create config.env from config.env.example",
+    "download_embedding_model": "Download the embedding model",
+    "initialize_vector_store": "Initialize the document store and default collection",
+}
+
+
+def embedding_model_cached(model_name: str) -> bool:
+    """True if the sentence-transformers model is in the local cache."""
+    try:
+        from huggingface_hub import try_to_load_from_cache
+    except ImportError:
+        return False
+    repo_id = model_name if "/" in model_name else f"sentence-transformers/{model_name}"
+    cached = try_to_load_from_cache(
+        repo_id, "config.json", cache_dir=os.environ.get("SENTENCE_TRANSFORMERS_HOME")
+    )
+    return isinstance(cached, str)
+
+
+@dataclass
+class SetupStepProgress:
+    """Progress and outcome of one setup step."""
+    step: str
+    phase: str = "pending"   # pending, running, completed, failed
+    error: Optional[str] = None
+    events: List[Dict] = field(default_factory=list)
+    started_at: Optional[datetime] = None
+    completed_at: Optional[datetime] = None
+
+    def to_dict(self) -> Dict:
+        return {
+            "step": self.step,
+            "description": SETUP_STEPS.get(self.step, self.step),
+            "phase": self.phase,
+            "error": self.error,
+            "events": self.events,
+            "started_at": self.started_at.isoformat() if self.started_at else None,
+            "completed_at": self.completed_at.isoformat() if self.completed_at else None
+        }
+
+
+class SetupWizard:
+    """
+    Checks the environment and runs first-run setup steps.
+
+    Usage:
+        wizard = SetupWizard("./jan_doc_store", "all-MiniLM-L6-v2", lambda: processor, detect_jan_version)
+        wizard.get_setup_state()
+        wizard.run_setup_step("download_embedding_model")
+    """
+
+    def __init__(
+        self,
+        persist_directory: str,
+        embedding_model: str,
+        get_processor: Callable[[], object],
+        detect_jan: Callable[[], Optional[str]],
+        app_path: Optional[Path] = None
+    ):
+        """
+        Initialize setup wizard.
+
+        Args:
+            persist_directory: Document store folder
+            embedding_model: Configured embedding model name
+            get_processor: Returns the running DocumentProcessor (or None)
+            detect_jan: Returns the installed Jan version (or None)
+            app_path: Folder holding config.env (None = app folder)
+        """
+        self.persist_directory = Path(persist_directory)
+        self.embedding_model = embedding_model
+        self.get_processor = get_processor
+        self.detect_jan = detect_jan
+        self.app_path = Path(app_path) if app_path else get_app_path()
+        self._lock = threading.Lock()
+        self.steps: Dict[str, SetupStepProgress] = {}   # Running or last run of each step
+
+    def _tesseract_version(self) -> Optional[str]:
+        try:
+            return str(pytesseract.get_tesseract_version())
+        except Exception:
+            return None
+
+    def _collection_exists(self) -> bool:
+        processor = self.get_processor()
+        if processor is None:
+            return False
+        store = processor.vector_store
+        names = [getattr(c, "name", c) for c in store.client.list_collections()]
+        return store.collection.name in names
+
+    def get_setup_state(self) -> Dict:
+        """Checklist of setup items; complete when every required item is ok."""
+        processor = self.get_processor()
+        tesseract = self._tesseract_version()
+        jan_version = self.detect_jan()
+        model = processor.vector_store.embedding_model if processor else self.embedding_model
+
+        from python_status import missing_required_packages
+        missing = missing_required_packages()
+
+        checks = [
+            {
+                "id": "python_runtime",
+                "ok": not missing,
+                "required": True,
+                "detail": (
+                    f"Python {platform.python_version()} ({platform.machine()})" if not missing
+                    else f"Missing packages: {', '.join(missing)}; reinstall the plugin"
+                ),
+                "step": None
+            },
+            {
+                "id": "config_file",
+                "ok": (self.app_path / "config.env").exists(),
+                "required": False,
+                "detail": str(self.app_path / "config.env"),
+                "step": "create_config"
+            },
+            {
+                "id": "tesseract",
+                "ok": tesseract is not None,
+                "required": False,
+                "detail": f"Tesseract {tesseract}" if tesseract else "Not found; run setup_tesseract.bat for OCR",
+                "step": None
+            },
+            {
+                "id": "embedding_model",
+                "ok": processor is not None or embedding_model_cached(model),
+                "required": True,
+                "detail": model,
+                "step": "download_embedding_model"
+            },
+            {
+                "id": "vector_store",
+                "ok": processor is not None and self.persist_directory.is_dir(),
+                "required": True,
+                "detail": str(self.persist_directory),
+                "step": "initialize_vector_store"
+            },
+            {
+                "id": "jan",
+                "ok": jan_version is not None,
+                "required": False,
+                "detail": f"Jan {jan_version}" if jan_version else "Not detected; the bundled LLM server is used",
+                "step": None
+            },
+            {
+                "id": "default_collection",
+                "ok": self._collection_exists(),
+                "required": True,
+                "detail": processor.vector_store.collection.name if processor else None,
+                "step": "initialize_vector_store"
+            },
+        ]
+        for check in checks:
+            progress = self.steps.get(check["step"]) if check["step"] else None
+            check["step_phase"] = progress.phase if progress else None
+
+        return {
+            "complete": all(c["ok"] for c in checks if c["required"]),
+            "checks": checks,
+            "steps": {name: p.to_dict() for name, p in self.steps.items()}
+        }
+
+    def _event(self, progress: SetupStepProgress, phase: str, message: str):
+        progress.phase = phase
+        progress.events.append({
+            "time": datetime.now().isoformat(),
+            "phase": phase,
+            "message": message
+        })
+        logger.info(f"Setup {progress.step}: {message}")
+
+    def run_setup_step(self, step: str) -> SetupStepProgress:
+        """
+        Run a setup step (blocking). Poll get_setup_state() for its events.
+
+        Raises:
+            ValueError: If step is unknown
+            RuntimeError: If the step is already running
+        """
+        if step not in SETUP_STEPS:
+            raise ValueError(f"Unknown setup step: {step} (expected one of {', '.join(SETUP_STEPS)})")
+
+        with self._lock:
+            current = self.steps.get(step)
+            if current is not None and current.phase == "running":
+                raise RuntimeError(f"Setup step {step} is already running")
+            progress = SetupStepProgress(step=step, started_at=datetime.now())
+            self.steps[step] = progress
+
+        self._event(progress, "running", SETUP_STEPS[step])
+        try:
+            message = getattr(self, f"_step_{step}")(progress)
+        except Exception as e:
+            progress.error = str(e)
+            progress.completed_at = datetime.now()
+            self._event(progress, "failed", f"Failed: {e}")
+            return progress
+
+        progress.completed_at = datetime.now()
+        self._event(progress, "completed", message)
+        return progress
+
+    def _step_create_config(self, progress: SetupStepProgress) -> str:
+        config_file = self.app_path / "config.env"
+        if config_file.exists():
+            return "config.env already exists"
+        example = self.app_path / "config.env.example"
+        if not example.exists():
+            raise FileNotFoundError(f"No config.env.example in {self.app_path}")
+        shutil.copyfile(example, config_file)
+        return f"Created {config_file}"
+
+    def _step_download_embedding_model(self, progress: SetupStepProgress) -> str:
+        processor = self.get_processor()
+        model = processor.vector_store.embedding_model if processor else self.embedding_model
+        if embedding_model_cached(model):
+            return f"{model} is already cached"
+        self._event(progress, "running", f"Downloading {model}")
+        from sentence_transformers import SentenceTransformer
+        SentenceTransformer(model)
+        return f"Downloaded {model}"
+
+    def _step_initialize_vector_store(self, progress: SetupStepProgress) -> str:
+        processor = self.get_processor()
+        if processor is None:
+            raise RuntimeError("The document processor is not running; check the server log")
+        self.persist_directory.mkdir(parents=True, exist_ok=True)
+        store = processor.vector_store
+        if self._collection_exists():
+            return f"Collection {store.collection.name} already exists"
+        store.collection = store.client.get_or_create_collection(
+            name=store.collection.name,
+            metadata={"hnsw:space": "cosine", "embedding_model": store.embedding_model}
+        )
+        return f"Created collection {store.collection.name}"
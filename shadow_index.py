@@ -0,0 +1,338 @@
+"""
+Shadow Index for Jan Document Plugin
+
+Side-by-side check of an embedding model upgrade before committing to it:
+- Build a parallel "shadow" collection holding the same chunks embedded
+  with the new model (the live collection is untouched)
+- Run queries against both and compare the results
+- Promote the shadow index to become the live collection, or discard it
+
+Only chunk text and metadata are reused, so nothing is re-extracted.
+"""
+
+import logging
+import threading
+from typing import List, Dict, Optional, Callable
+from dataclasses import dataclass
+from datetime import datetime
+
+from document_processor import LocalVectorStore
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+SHADOW_SUFFIX = "_shadow"
+PREVIOUS_SUFFIX = "_previous"
+
+# Chunks embedded per encode() call while building
+EMBED_BATCH_SIZE = 64
+
+
+class ShadowIndexBusyError(RuntimeError):
+    """Raised when the shadow index is changed while it is being built."""
+    pass
+
+
+def swap_in_collection(live: LocalVectorStore, replacement: LocalVectorStore):
+    """
+    Replace the live store's collection with another collection in the same client.
+
+    The collections are swapped by renaming, so the live collection name
+    (and the embedding model recorded on the replacement) survive a
+    restart. If the swap fails the live collection is left as it was.
+    """
+    client = live.client
+    name = live.collection.name
+    previous_name = f"{name}{PREVIOUS_SUFFIX}"
+
+    live.collection.modify(name=previous_name)
+    try:
+        replacement.collection.modify(name=name)
+    except Exception:
+        # Put the live collection back under its own name
+        client.get_collection(previous_name).modify(name=name)
+        raise
+
+    try:
+        client.delete_collection(previous_name)
+    except Exception as e:
+        logger.warning(f"Could not delete old collection {previous_name}: {e}")
+
+    live.collection = client.get_collection(name)
+    live.embedder = replacement.embedder
+    live.embedding_model = replacement.embedding_model
+
+
+@dataclass
+class ShadowIndexInfo:
+    """A shadow index and how it relates to the live collection."""
+    collection: str
+    shadow_collection: str
+    embedding_model: str
+    live_embedding_model: str
+    chunk_count: int
+    live_chunk_count: int
+    built_at: Optional[datetime] = None     # None if built before a restart
+
+    @property
+    def stale(self) -> bool:
+        """True when the live collection changed size since the shadow was built."""
+        return self.chunk_count != self.live_chunk_count
+
+    def to_dict(self) -> Dict:
+        return {
+            "collection": self.collection,
+            "shadow_collection": self.shadow_collection,
+            "embedding_model": self.embedding_model,
+            "live_embedding_model": self.live_embedding_model,
+            "chunk_count": self.chunk_count,
+            "live_chunk_count": self.live_chunk_count,
+            "stale": self.stale,
+            "built_at": self.built_at.isoformat() if self.built_at else None
+        }
+
+
+class ShadowIndexManager:
+    """
+    Builds, compares and promotes a shadow index of the live collection.
+
+    Usage:
+        shadow = ShadowIndexManager(processor.vector_store)
+        shadow.shadow_index_collection("jan_documents", "all-mpnet-base-v2")
+        shadow.compare_query("termination clause")
+        shadow.promote_shadow_index()
+    """
+
+    def __init__(self, vector_store: LocalVectorStore):
+        """
+        Initialize shadow index manager.
+
+        Args:
+            vector_store: Live store; promotion swaps its collection and embedder
+        """
+        self.live = vector_store
+        self._shadow: Optional[LocalVectorStore] = None
+        self._built_at: Optional[datetime] = None
+        self._building = threading.Lock()
+
+    @property
+    def building(self) -> bool:
+        """Whether a shadow index is being built right now."""
+        return self._building.locked()
+
+    def _check_not_building(self):
+        if self.building:
+            raise ShadowIndexBusyError("A shadow index is being built; wait for it to finish")
+
+    @property
+    def collection_name(self) -> str:
+        return self.live.collection.name
+
+    @property
+    def shadow_name(self) -> str:
+        return f"{self.collection_name}{SHADOW_SUFFIX}"
+
+    def _check_collection(self, collection: Optional[str]):
+        check_collection(collection, self.collection_name)
+
+    def _open_shadow(self) -> Optional[LocalVectorStore]:
+        """The shadow store, reopening one left by a previous run."""
+        if self._shadow is not None:
+            return self._shadow
+
+        names = [getattr(c, "name", c) for c in self.live.client.list_collections()]
+        if self.shadow_name not in names:
+            return None
+
+        model = (self.live.client.get_collection(self.shadow_name).metadata or {}).get("embedding_model")
+        if not model:
+            return None
+        self._shadow = LocalVectorStore(
+            embedding_model=model,
+            collection_name=self.shadow_name,
+            client=self.live.client
+        )
+        return self._shadow
+
+    def shadow_index_collection(
+        self,
+        collection: Optional[str],
+        new_model: str,
+        progress_callback: Optional[Callable[[int, int], None]] = None
+    ) -> ShadowIndexInfo:
+        """
+        Build (or rebuild) the shadow index with a new embedding model.
+
+        Args:
+            collection: Live collection name (None = the processor's collection)
+            new_model: sentence-transformers model for the shadow index
+            progress_callback: Optional callable(chunks_done, total)
+
+        Raises:
+            KeyError: If collection is not the live collection
+            ValueError: If new_model is the model the live collection uses
+            ShadowIndexBusyError: If another build is running
+        """
+        self._check_collection(collection)
+        if new_model == self.live.embedding_model:
+            raise ValueError(f"Collection already uses {new_model}")
+
+        if not self._building.acquire(blocking=False):
+            raise ShadowIndexBusyError("A shadow index is already being built")
+        try:
+            return self._build(new_model, progress_callback)
+        finally:
+            self._building.release()
+
+    def _build(
+        self,
+        new_model: str,
+        progress_callback: Optional[Callable[[int, int], None]]
+    ) -> ShadowIndexInfo:
+        self._discard()
+
+        chunks = self.live.get_all_chunks()
+        total = len(chunks["ids"])
+        logger.info(f"Building shadow index with {new_model} ({total} chunks)")
+
+        shadow = LocalVectorStore(
+            embedding_model=new_model,
+            collection_name=self.shadow_name,
+            client=self.live.client
+        )
+        for start in range(0, total, EMBED_BATCH_SIZE):
+            end = min(start + EMBED_BATCH_SIZE, total)
+            documents = chunks["documents"][start:end]
+            shadow.collection.add(
+                ids=chunks["ids"][start:end],
+                documents=documents,
+                embeddings=shadow.embedder.encode(documents).tolist(),
+                metadatas=chunks["metadatas"][start:end]
+            )
+            if progress_callback:
+                progress_callback(end, total)
+
+        self._shadow = shadow
+        self._built_at = datetime.now()
+        logger.info(f"Shadow index ready: {self.shadow_name}")
+        return self.get_shadow_info()
+
+    def get_shadow_info(self) -> Optional[ShadowIndexInfo]:
+        """Describe the shadow index, or None if there is none."""
+        shadow = self._open_shadow()
+        if shadow is None:
+            return None
+
+        return ShadowIndexInfo(
+            collection=self.collection_name,
+            shadow_collection=self.shadow_name,
+            embedding_model=shadow.embedding_model,
+            live_embedding_model=self.live.embedding_model,
+            chunk_count=shadow.get_document_count(),
+            live_chunk_count=self.live.get_document_count(),
+            built_at=self._built_at
+        )
+
+    def compare_query(
+        self,
+        query: str,
+        collection: Optional[str] = None,
+        n_results: int = 5
+    ) -> Dict:
+        """
+        Run a query against the live and shadow indexes.
+
+        Returns:
+            Dict with "live" and "shadow" result lists, plus how many chunks
+            both returned and how the shared chunks' ranks moved
+
+        Raises:
+            KeyError: If collection is unknown or there is no shadow index
+            ShadowIndexBusyError: If the shadow index is being built
+        """
+        self._check_collection(collection)
+        self._check_not_building()
+        shadow = self._open_shadow()
+        if shadow is None:
+            raise KeyError("No shadow index has been built")
+
+        def ranked(store: LocalVectorStore) -> List[Dict]:
+            results = store.query(query, n_results=n_results)
+            for rank, result in enumerate(results, 1):
+                meta = result["metadata"]
+                result["rank"] = rank
+                result["chunk_id"] = f"{meta.get('doc_hash')}_{meta.get('chunk_index')}"
+            return results
+
+        live_results = ranked(self.live)
+        shadow_results = ranked(shadow)
+
+        live_ranks = {r["chunk_id"]: r["rank"] for r in live_results}
+        rank_changes = {
+            r["chunk_id"]: live_ranks[r["chunk_id"]] - r["rank"]
+            for r in shadow_results if r["chunk_id"] in live_ranks
+        }
+
+        return {
+            "query": query,
+            "live": {"embedding_model": self.live.embedding_model, "results": live_results},
+            "shadow": {"embedding_model": shadow.embedding_model, "results": shadow_results},
+            "overlap": len(rank_changes),
+            "rank_changes": rank_changes   # chunk_id -> places moved up in the shadow index
+        }
+
+    def promote_shadow_index(self, force: bool = False) -> ShadowIndexInfo:
+        """
+        Make the shadow index the live collection.
+
+        The old collection is deleted once the swap succeeds (see
+        swap_in_collection).
+
+        Args:
+            force: Promote even if the live collection changed since the
+                   shadow index was built (newer chunks would be lost)
+
+        Raises:
+            KeyError: If there is no shadow index
+            ValueError: If the shadow index is stale and force is not set
+            CollectionReadOnlyError: If the live collection is read-only
+            ShadowIndexBusyError: If the shadow index is being built
+        """
+        self._check_not_building()
+        self.live.check_writable("promote")
+        info = self.get_shadow_info()
+        if info is None:
+            raise KeyError("No shadow index has been built")
+        if info.stale and not force:
+            raise ValueError(
+                f"Shadow index has {info.chunk_count} chunks but the collection has "
+                f"{info.live_chunk_count}; rebuild it or promote with force"
+            )
+
+        swap_in_collection(self.live, self._shadow)
+        self._shadow = None
+        self._built_at = None
+
+        logger.info(f"Promoted shadow index: {info.collection} now uses {info.embedding_model}")
+        return info
+
+    def discard_shadow_index(self) -> bool:
+        """
+        Delete the shadow index. Returns False if there was none.
+
+        Raises:
+            ShadowIndexBusyError: If the shadow index is being built
+        """
+        self._check_not_building()
+        return self._discard()
+
+    def _discard(self) -> bool:
+        self._shadow = None
+        self._built_at = None
+        names = [getattr(c, "name", c) for c in self.live.client.list_collections()]
+        if self.shadow_name not in names:
+            return False
+
+        self.live.client.delete_collection(self.shadow_name)
+        logger.info(f"Discarded shadow index: {self.shadow_name}")
+        return True
@@ -0,0 +1,118 @@
+"""
+Source Search for Jan Document Plugin
+
+Finds indexed documents by (part of) their file name, for users who
+remember the file but not what it says. Matches are scored so the best
+come first:
+
+- 1.0  the whole name, or the name without its extension
+- 0.9  the start of the name
+- 0.8  anywhere in the name
+- 0.4-0.7  the pattern's characters in order ("qrtrpt" -> "quarterly_report"),
+           higher when they are closer together
+- up to 0.5  a near miss (typos), by similarity to the name
+
+Each result carries the document's doc_hash, which scopes a following
+query or search to that document.
+"""
+
+import difflib
+import logging
+from pathlib import Path
+from typing import Dict, List, Optional
+
+from document_processor import LocalVectorStore, CHAT_SOURCE_TYPE
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+# Near misses less similar than this are not matches
+MIN_SIMILARITY = 0.6
+
+DEFAULT_LIMIT = 20
+
+
+def filename_match_score(pattern: str, filename: str) -> float:
+    """How well pattern matches filename, 0 (no match) to 1."""
+    pattern = pattern.strip().lower()
+    name = filename.lower()
+    stem = Path(name).stem
+    if not pattern:
+        return 0.0
+    if pattern in (name, stem):
+        return 1.0
+    if name.startswith(pattern):
+        return 0.9
+    if pattern in name:
+        return 0.8
+
+    # Characters in order, possibly with gaps
+    positions = []
+    start = 0
+    for char in pattern:
+        index = name.find(char, start)
+        if index < 0:
+            break
+        positions.append(index)
+        start = index + 1
+    if len(positions) == len(pattern):
+        span = positions[-1] - positions[0] + 1
+        return round(0.4 + 0.3 * len(pattern) / span, 4)
+
+    similarity = difflib.SequenceMatcher(None, pattern, stem).ratio()
+    return round(0.5 * similarity, 4) if similarity >= MIN_SIMILARITY else 0.0
+
+
+def search_sources(
+    vector_store: LocalVectorStore,
+    pattern: str,
+    collection: Optional[str] = None,
+    limit: int = DEFAULT_LIMIT
+) -> List[Dict]:
+    """
+    Indexed documents (latest versions) whose file name matches pattern.
+
+    Args:
+        vector_store: Store to search
+        pattern: Part of the file name, matched case-insensitively
+        collection: Collection name (None = the store's collection)
+        limit: Maximum number of results
+
+    Returns:
+        List of dicts with source_path, filename, doc_hash, version, chunks
+        and score, best first
+
+    Raises:
+        KeyError: If collection is not the store's collection
+        ValueError: If pattern is empty or limit is not positive
+    """
+    name = vector_store.collection.name
+    check_collection(collection, name)
+    if not pattern or not pattern.strip():
+        raise ValueError("A file name pattern is required")
+    if limit < 1:
+        raise ValueError("limit must be at least 1")
+
+    results = vector_store.collection.get(include=["metadatas"])
+    sources: Dict[str, Dict] = {}
+    for meta in results.get("metadatas") or []:
+        if meta.get("source_type") == CHAT_SOURCE_TYPE or not meta.get("is_latest", True):
+            continue
+        source_path = meta.get("source_path") or meta.get("filename") or ""
+        entry = sources.get(source_path)
+        if entry is None:
+            filename = meta.get("filename") or Path(source_path).name
+            entry = sources[source_path] = {
+                "source_path": source_path,
+                "filename": filename,
+                "doc_hash": meta.get("doc_hash"),
+                "version": meta.get("version"),
+                "chunks": 0,
+                "score": filename_match_score(pattern, filename)
+            }
+        entry["chunks"] += 1
+
+    matches = [s for s in sources.values() if s["score"] > 0]
+    matches.sort(key=lambda s: (-s["score"], s["filename"].lower()))
+    logger.debug(f"Source search {pattern!r}: {len(matches)} of {len(sources)} documents match")
+    return matches[:limit]
@@ -0,0 +1,229 @@
+"""
+Static Search Export for Jan Document Plugin
+
+Writes the indexed collection as a self-contained folder that can be
+opened in any browser (or dropped on a file share / static host), for
+sharing with people who don't run the plugin:
+
+    <output_dir>/
+        index.html          Search page (no server or network needed)
+        manifest.js         Collection info and the list of data pages
+        data/chunks-000.js  Chunk text + int8-quantized embeddings, paged
+
+Data files are JavaScript rather than JSON so the page also works from
+file:// URLs, where browsers block fetch(). Queries can't be embedded in
+the browser, so search is keyword (TF-IDF) ranking; each result offers
+"Similar passages" using the precomputed embeddings.
+"""
+
+import json
+import base64
+import logging
+from pathlib import Path
+from typing import Dict, List, Optional
+from datetime import datetime
+
+from document_processor import LocalVectorStore, render_chunk_content
+from collection_names import check_collection
+
+logger = logging.getLogger(__name__)
+
+# Chunks per data file
+EXPORT_PAGE_SIZE = 500
+
+
+def quantize_embedding(embedding: List[float]) -> str:
+    """Scale a normalized vector to int8 and return it base64-encoded."""
+    norm = sum(x * x for x in embedding) ** 0.5 or 1.0
+    values = [max(-127, min(127, round(x / norm * 127))) for x in embedding]
+    return base64.b64encode(bytes(v & 0xFF for v in values)).decode("ascii")
+
+
+def export_static_search(
+    vector_store: LocalVectorStore,
+    output_dir: Path,
+    collection: Optional[str] = None,
+    title: Optional[str] = None,
+    page_size: int = EXPORT_PAGE_SIZE
+) -> Dict:
+    """
+    Export the collection as a static search site.
+
+    Args:
+        vector_store: Store to export
+        output_dir: Folder to write (created; existing export files are replaced)
+        collection: Collection name (None = the store's collection)
+        title: Page title (defaults to the collection name)
+        page_size: Chunks per data file
+
+    Returns:
+        Summary dict (output_dir, chunk_count, document_count, pages)
+
+    Raises:
+        KeyError: If collection is not the store's collection
+    """
+    name = vector_store.collection.name
+    check_collection(collection, name)
+
+    output_dir = Path(output_dir)
+    data_dir = output_dir / "data"
+    data_dir.mkdir(parents=True, exist_ok=True)
+    for old in data_dir.glob("chunks-*.js"):
+        old.unlink()
+
+    chunks = vector_store.get_all_chunks(include_embeddings=True)
+    records = []
+    for chunk_id, content, meta, embedding in zip(
+        chunks["ids"], chunks["documents"], chunks["metadatas"], chunks["embeddings"]
+    ):
+        if meta.get("is_latest") is False:
+            continue   # Superseded versions aren't shared
+        records.append({
+            "id": chunk_id,
+            "doc": meta.get("filename", ""),
+            "section": meta.get("heading_path") or "",
+            "page": meta.get("page"),
+            "index": meta.get("chunk_index", 0),
+            "text": render_chunk_content(content, meta),
+            "emb": quantize_embedding(embedding)
+        })
+    records.sort(key=lambda r: (r["doc"], r["index"]))
+
+    pages = []
+    for number, start in enumerate(range(0, len(records), page_size)):
+        filename = f"chunks-{number:03d}.js"
+        payload = json.dumps(records[start:start + page_size], ensure_ascii=False)
+        (data_dir / filename).write_text(
+            f"window.JDP_CHUNKS.push(...{payload});\n", encoding="utf-8"
+        )
+        pages.append(f"data/{filename}")
+
+    manifest = {
+        "title": title or name,
+        "collection": name,
+        "embedding_model": vector_store.embedding_model,
+        "dimensions": len(chunks["embeddings"][0]) if chunks["embeddings"] else 0,
+        "chunk_count": len(records),
+        "document_count": len({r["doc"] for r in records}),
+        "pages": pages,
+        "exported_at": datetime.now().isoformat()
+    }
+    (output_dir / "manifest.js").write_text(
+        f"window.JDP_MANIFEST = {json.dumps(manifest, ensure_ascii=False)};\n", encoding="utf-8"
+    )
+    (output_dir / "index.html").write_text(
+        SEARCH_PAGE_TEMPLATE.replace("{{TITLE}}", _escape_html(manifest["title"])),
+        encoding="utf-8"
+    )
+
+    logger.info(f"Exported static search: {len(records)} chunks to {output_dir}")
+    return {
+        "output_dir": str(output_dir),
+        "chunk_count": manifest["chunk_count"],
+        "document_count": manifest["document_count"],
+        "pages": len(pages)
+    }
+
+
+def _escape_html(text: str) -> str:
+    return text.replace("&", "&amp;").replace("<", "&lt;").replace(">", "&gt;")
+
+
+SEARCH_PAGE_TEMPLATE = """<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{{TITLE}}</title>
+<style>
+  body { font-family: system-ui, sans-serif; max-width: 860px; margin: 2em auto; padding: 0 1em; color: #222; }
+  input { width: 100%; padding: .6em; font-size: 1.1em; box-sizing: border-box; }
+  .meta { color: #666; font-size: .85em; }
+  .result { border-bottom: 1px solid #ddd; padding: .8em 0; }
+  .text { white-space: pre-wrap; margin: .4em 0; }
+  button { font-size: .8em; }
+</style>
+<script>window.JDP_CHUNKS = [];</script>
+<script src="manifest.js"></script>
+</head>
+<body>
+<h1>{{TITLE}}</h1>
+<p class="meta" id="info"></p>
+<input id="q" placeholder="Search..." autofocus>
+<div id="results"></div>
+<script>
+(function () {
+  var manifest = window.JDP_MANIFEST, chunks = window.JDP_CHUNKS;
+  var tokenize = function (s) { return (s.toLowerCase().match(/[\\p{L}\\p{N}]{2,}/gu) || []); };
+  var docFreq = {}, vectors = [];
+
+  function prepare() {
+    chunks.forEach(function (c) {
+      c.terms = {};
+      tokenize(c.text).forEach(function (t) { c.terms[t] = (c.terms[t] || 0) + 1; });
+      Object.keys(c.terms).forEach(function (t) { docFreq[t] = (docFreq[t] || 0) + 1; });
+      var raw = atob(c.emb), v = new Int8Array(raw.length);
+      for (var i = 0; i < raw.length; i++) v[i] = raw.charCodeAt(i) << 24 >> 24;
+      vectors.push(v);
+    });
+    document.getElementById("info").textContent = manifest.document_count + " documents, " +
+      manifest.chunk_count + " passages - exported " + manifest.exported_at.slice(0, 10);
+  }
+
+  function render(list) {
+    var out = document.getElementById("results");
+    out.innerHTML = "";
+    list.forEach(function (hit) {
+      var c = chunks[hit.i], div = document.createElement("div");
+      div.className = "result";
+      var meta = document.createElement("div");
+      meta.className = "meta";
+      meta.textContent = c.doc + (c.page ? " - page " + c.page : "") + (c.section ? " - " + c.section : "");
+      var text = document.createElement("div");
+      text.className = "text";
+      text.textContent = c.text;
+      var more = document.createElement("button");
+      more.textContent = "Similar passages";
+      more.onclick = function () { render(similar(hit.i)); window.scrollTo(0, 0); };
+      div.appendChild(meta); div.appendChild(text); div.appendChild(more);
+      out.appendChild(div);
+    });
+  }
+
+  function search(query) {
+    var terms = tokenize(query), n = chunks.length, hits = [];
+    if (!terms.length) return [];
+    chunks.forEach(function (c, i) {
+      var score = 0;
+      terms.forEach(function (t) {
+        if (c.terms[t]) score += (1 + Math.log(c.terms[t])) * Math.log(1 + n / docFreq[t]);
+      });
+      if (score > 0) hits.push({ i: i, score: score });
+    });
+    return hits.sort(function (a, b) { return b.score - a.score; }).slice(0, 20);
+  }
+
+  function similar(index) {
+    var a = vectors[index], hits = [];
+    vectors.forEach(function (b, i) {
+      var dot = 0;
+      for (var k = 0; k < a.length; k++) dot += a[k] * b[k];
+      hits.push({ i: i, score: dot });
+    });
+    return hits.sort(function (x, y) { return y.score - x.score; }).slice(0, 10);
+  }
+
+  var pending = manifest.pages.length;
+  if (!pending) prepare();
+  manifest.pages.forEach(function (src) {
+    var s = document.createElement("script");
+    s.src = src;
+    s.onload = function () { if (--pending === 0) prepare(); };
+    document.head.appendChild(s);
+  });
+
+  document.getElementById("q").addEventListener("input", function (e) { render(search(e.target.value)); });
+})();
+</script>
+</body>
+</html>
+"""
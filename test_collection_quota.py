@@ -0,0 +1,124 @@
+#!/usr/bin/env python3
+"""
+Tests for collection quotas (collection_quota.py).
+
+The document count comes from the index, so a quota still holds after a
+restart (when the processor's in-memory records are empty) and old
+versions of re-indexed documents don't count against it.
+
+Usage:
+    python test_collection_quota.py      (or: pytest test_collection_quota.py)
+"""
+
+import sys
+import tempfile
+from pathlib import Path
+from types import SimpleNamespace
+
+from collection_quota import CollectionQuota, QuotaExceededError, QuotaManager
+from document_processor import LocalVectorStore
+
+
+class FakeCollection:
+    """Chroma collection stand-in returning fixed chunk metadata."""
+
+    name = "jan_documents"
+
+    def __init__(self, metadatas):
+        self.metadatas = metadatas
+
+    def get(self, include=None):
+        return {"metadatas": self.metadatas}
+
+
+class FakeVectorStore:
+    """Just enough of LocalVectorStore for QuotaManager."""
+
+    def __init__(self, metadatas):
+        self.collection = FakeCollection(metadatas)
+
+    def list_documents(self, latest_only=False):
+        return LocalVectorStore.list_documents(self, latest_only=latest_only)
+
+
+def make_manager(metadatas, quota_dir):
+    """QuotaManager over a processor that has just restarted (no in-memory records)."""
+    processor = SimpleNamespace(vector_store=FakeVectorStore(metadatas), processed_docs={})
+    return QuotaManager(processor, Path(quota_dir) / "collection_quota.json")
+
+
+# Two documents, one with a superseded earlier version, chunked in two
+METADATAS = [
+    {"doc_hash": "aaaa", "filename": "a.pdf", "chunk_index": 0},
+    {"doc_hash": "aaaa", "filename": "a.pdf", "chunk_index": 1},
+    {"doc_hash": "bbbb-v1", "filename": "b.docx", "chunk_index": 0, "is_latest": False},
+    {"doc_hash": "bbbb-v2", "filename": "b.docx", "chunk_index": 0, "is_latest": True},
+]
+
+
+def test_usage_counts_documents_in_index():
+    with tempfile.TemporaryDirectory() as tmp:
+        manager = make_manager(METADATAS, tmp)
+        manager.set_quota(None, CollectionQuota(max_documents=4))
+        usage = manager.get_usage()
+        assert usage["documents"] == 2, usage
+        assert usage["documents_percent"] == 50.0, usage
+
+
+def test_check_enforced_after_restart():
+    with tempfile.TemporaryDirectory() as tmp:
+        make_manager(METADATAS, tmp).set_quota(None, CollectionQuota(max_documents=2))
+
+        # A new manager reads the stored quota; the index already holds 2 documents
+        manager = make_manager(METADATAS, tmp)
+        try:
+            manager.check(additional_documents=1)
+        except QuotaExceededError as e:
+            assert e.used == 2 and e.quota == 2 and e.limit == "max_documents", e.to_dict()
+        else:
+            raise AssertionError("third document was allowed past max_documents=2")
+
+
+def test_superseded_versions_do_not_count():
+    with tempfile.TemporaryDirectory() as tmp:
+        manager = make_manager(METADATAS, tmp)
+        manager.set_quota(None, CollectionQuota(max_documents=3))
+        manager.check(additional_documents=1)   # 2 latest + 1 fits
+
+
+def test_no_quota_allows_anything():
+    with tempfile.TemporaryDirectory() as tmp:
+        make_manager(METADATAS, tmp).check(additional_documents=1000, additional_bytes=10**12)
+
+
+def test_invalid_limits_rejected():
+    with tempfile.TemporaryDirectory() as tmp:
+        manager = make_manager([], tmp)
+        for quota in (CollectionQuota(max_documents=0), CollectionQuota(max_bytes=-1)):
+            try:
+                manager.set_quota(None, quota)
+            except ValueError:
+                continue
+            raise AssertionError(f"{quota} was accepted")
+
+
+def test_other_collection_is_unknown():
+    with tempfile.TemporaryDirectory() as tmp:
+        try:
+            make_manager([], tmp).get_usage("someone_else")
+        except KeyError:
+            return
+        raise AssertionError("usage of an unknown collection was reported")
+
+
+if __name__ == "__main__":
+    failed = 0
+    for name, func in list(globals().items()):
+        if name.startswith("test_") and callable(func):
+            try:
+                func()
+                print(f"  [PASS] {name}")
+            except AssertionError as e:
+                failed += 1
+                print(f"  [FAIL] {name}: {e}")
+    sys.exit(1 if failed else 0)
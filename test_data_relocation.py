@@ -0,0 +1,248 @@
+#!/usr/bin/env python3
+"""
+Tests for moving the data folder (data_relocation.py).
+
+A relocation that fails before the switch must leave the data where it
+was: the partial copy removed, the originals untouched and
+data_location.json as it was before. Around a running server
+(StoreRelocation) the store is then reopened in the old location and
+requests are accepted again.
+
+Usage:
+    python test_data_relocation.py      (or: pytest test_data_relocation.py)
+"""
+
+import sys
+import asyncio
+import tempfile
+from pathlib import Path
+
+import data_relocation
+from app_paths import load_location_override, save_location_override
+from data_relocation import DataRelocator, StoreRelocation
+
+
+def make_storage(root: Path) -> Path:
+    storage = root / "jan_doc_store"
+    (storage / "chroma").mkdir(parents=True)
+    (storage / "chroma" / "chroma.sqlite3").write_bytes(b"sqlite" * 1000)
+    (storage / "settings.json").write_text('{"chunk_size": 800}')
+    return storage
+
+
+def test_successful_move_switches_and_removes_old_folder():
+    with tempfile.TemporaryDirectory() as tmp:
+        root = Path(tmp)
+        storage = make_storage(root / "old")
+        progress = DataRelocator(app_path=root).set_data_location(
+            root / "new", {"storage_directory": storage}
+        )
+        assert progress.phase == "completed", progress.to_dict()
+        assert progress.files_verified == 2
+        assert not storage.exists()
+        moved = root / "new" / "jan_doc_store"
+        assert (moved / "settings.json").read_text() == '{"chunk_size": 800}'
+        assert load_location_override(root) == {"storage_directory": str(moved)}
+
+
+def test_verification_failure_rolls_back():
+    with tempfile.TemporaryDirectory() as tmp:
+        root = Path(tmp)
+        storage = make_storage(root / "old")
+        save_location_override({"model_cache": str(root / "models")}, root)
+
+        # The copy of the first file hashes differently from its original
+        real_hash = data_relocation._file_hash
+        seen = set()
+
+        def corrupting_hash(path):
+            digest = real_hash(path)
+            if "new" in path.parts and not seen:
+                seen.add(path)
+                return "0" * 64
+            return digest
+
+        data_relocation._file_hash = corrupting_hash
+        try:
+            progress = DataRelocator(app_path=root).set_data_location(
+                root / "new", {"storage_directory": storage}
+            )
+        finally:
+            data_relocation._file_hash = real_hash
+
+        assert progress.phase == "rolled_back", progress.to_dict()
+        assert "doesn't match" in progress.error
+        assert not (root / "new" / "jan_doc_store").exists()
+        assert (storage / "chroma" / "chroma.sqlite3").read_bytes() == b"sqlite" * 1000
+        assert load_location_override(root) == {"model_cache": str(root / "models")}
+
+
+def test_not_enough_space_rolls_back_before_copying():
+    with tempfile.TemporaryDirectory() as tmp:
+        root = Path(tmp)
+        storage = make_storage(root / "old")
+        reserve = data_relocation.RELOCATION_RESERVE_BYTES
+        data_relocation.RELOCATION_RESERVE_BYTES = 1 << 60
+        try:
+            progress = DataRelocator(app_path=root).set_data_location(
+                root / "new", {"storage_directory": storage}
+            )
+        finally:
+            data_relocation.RELOCATION_RESERVE_BYTES = reserve
+
+        assert progress.phase == "rolled_back", progress.to_dict()
+        assert progress.files_copied == 0
+        assert storage.is_dir()
+        assert load_location_override(root) == {}
+
+
+def test_rolled_back_relocation_can_be_retried():
+    with tempfile.TemporaryDirectory() as tmp:
+        root = Path(tmp)
+        storage = make_storage(root / "old")
+        relocator = DataRelocator(app_path=root)
+        reserve = data_relocation.RELOCATION_RESERVE_BYTES
+        data_relocation.RELOCATION_RESERVE_BYTES = 1 << 60
+        try:
+            relocator.set_data_location(root / "new", {"storage_directory": storage})
+        finally:
+            data_relocation.RELOCATION_RESERVE_BYTES = reserve
+
+        progress = relocator.set_data_location(root / "new", {"storage_directory": storage})
+        assert progress.phase == "completed", progress.to_dict()
+
+
+def test_invalid_destinations_rejected():
+    with tempfile.TemporaryDirectory() as tmp:
+        root = Path(tmp)
+        storage = make_storage(root / "old")
+        relocator = DataRelocator(app_path=root)
+        for destination in (Path("relative/path"), storage / "inside"):
+            try:
+                relocator.set_data_location(destination, {"storage_directory": storage})
+            except ValueError:
+                continue
+            raise AssertionError(f"{destination} was accepted")
+        assert storage.is_dir()
+
+
+class FakeStore:
+    """Stands in for the server's document store: records closes and reopens."""
+
+    def __init__(self, storage: Path):
+        self.storage = storage
+        self.open = True
+        self.events = []
+
+    def close(self) -> bool:
+        was_open = self.open
+        self.open = False
+        self.events.append("close")
+        return was_open
+
+    def reopen(self):
+        assert (self.storage / "settings.json").exists(), "store reopened without its data"
+        self.open = True
+        self.events.append("reopen")
+
+
+class WatchingRelocator(DataRelocator):
+    """Records the request block and store state seen while the files are copied."""
+
+    def set_data_location(self, new_path, folders, progress_callback=None):
+        self.seen = (self.relocation.block, self.store.open)
+        return super().set_data_location(new_path, folders, progress_callback)
+
+
+def make_relocation(root: Path, storage: Path):
+    store = FakeStore(storage)
+    relocator = WatchingRelocator(app_path=root)
+    relocation = StoreRelocation(relocator, store.close, store.reopen)
+    relocator.relocation, relocator.store = relocation, store
+    return relocation, relocator, store
+
+
+def test_failed_verification_reopens_store_and_accepts_requests():
+    with tempfile.TemporaryDirectory() as tmp:
+        root = Path(tmp)
+        storage = make_storage(root / "old")
+        relocation, relocator, store = make_relocation(root, storage)
+
+        real_hash = data_relocation._file_hash
+        data_relocation._file_hash = lambda path: "0" * 64 if "new" in path.parts else real_hash(path)
+        try:
+            progress = asyncio.run(relocation.run(root / "new", {"storage_directory": storage}))
+        finally:
+            data_relocation._file_hash = real_hash
+
+        assert progress.phase == "rolled_back", progress.to_dict()
+        blocked, open_during_copy = relocator.seen
+        assert blocked is not None and not open_during_copy
+        assert store.events == ["close", "reopen"], store.events
+        assert store.open
+        assert relocation.block is None
+        assert not (root / "new" / "jan_doc_store").exists()
+        assert load_location_override(root) == {}
+
+
+def test_successful_relocation_keeps_requests_refused_until_restart():
+    with tempfile.TemporaryDirectory() as tmp:
+        root = Path(tmp)
+        storage = make_storage(root / "old")
+        relocation, relocator, store = make_relocation(root, storage)
+
+        progress = asyncio.run(relocation.run(root / "new", {"storage_directory": storage}))
+        assert progress.phase == "completed", progress.to_dict()
+        assert store.events == ["close"], store.events
+        assert "restart" in relocation.block
+        try:
+            asyncio.run(relocation.run(root / "other", {"storage_directory": root / "new" / "jan_doc_store"}))
+        except ValueError:
+            pass
+        else:
+            raise AssertionError("a second relocation ran before the restart")
+
+
+def test_rejected_destination_reopens_store():
+    with tempfile.TemporaryDirectory() as tmp:
+        root = Path(tmp)
+        storage = make_storage(root / "old")
+        relocation, _, store = make_relocation(root, storage)
+        try:
+            asyncio.run(relocation.run(storage / "inside", {"storage_directory": storage}))
+        except ValueError:
+            pass
+        else:
+            raise AssertionError("a destination inside the storage folder was accepted")
+        assert store.events == ["close", "reopen"], store.events
+        assert relocation.block is None
+
+
+def test_store_closed_before_relocation_is_not_reopened():
+    with tempfile.TemporaryDirectory() as tmp:
+        root = Path(tmp)
+        storage = make_storage(root / "old")
+        relocation, _, store = make_relocation(root, storage)
+        store.open = False   # e.g. safe mode
+        reserve = data_relocation.RELOCATION_RESERVE_BYTES
+        data_relocation.RELOCATION_RESERVE_BYTES = 1 << 60
+        try:
+            progress = asyncio.run(relocation.run(root / "new", {"storage_directory": storage}))
+        finally:
+            data_relocation.RELOCATION_RESERVE_BYTES = reserve
+        assert progress.phase == "rolled_back"
+        assert store.events == ["close"], store.events
+        assert relocation.block is None
+
+
+if __name__ == "__main__":
+    failed = 0
+    for name, func in list(globals().items()):
+        if name.startswith("test_") and callable(func):
+            try:
+                func()
+                print(f"  [PASS] {name}")
+            except AssertionError as e:
+                failed += 1
+                print(f"  [FAIL] {name}: {e}")
+    sys.exit(1 if failed else 0)
@@ -0,0 +1,102 @@
+#!/usr/bin/env python3
+"""
+Tests for duplicate detection in directory scans (directory_scanner.py).
+
+Usage:
+    python test_directory_scanner.py      (or: pytest test_directory_scanner.py)
+"""
+
+import sys
+import tempfile
+from datetime import datetime
+from pathlib import Path
+
+from directory_scanner import (
+    PARTIAL_HASH_BYTES, ScannedFile, compute_partial_hash, mark_duplicates
+)
+
+
+def write(path: Path, data: bytes) -> ScannedFile:
+    path.write_bytes(data)
+    return ScannedFile(
+        path=str(path),
+        filename=path.name,
+        extension=path.suffix,
+        size_bytes=len(data),
+        modified_at=datetime.now()
+    )
+
+
+def test_partial_hash_sees_tail_between_64_and_128_kb():
+    # Same size and same first 64KB; only the tail differs
+    head = b"a" * PARTIAL_HASH_BYTES
+    size = PARTIAL_HASH_BYTES + PARTIAL_HASH_BYTES // 2
+    with tempfile.TemporaryDirectory() as tmp:
+        one = write(Path(tmp) / "one.pdf", head + b"x" * (size - len(head)))
+        two = write(Path(tmp) / "two.pdf", head + b"y" * (size - len(head)))
+        assert compute_partial_hash(Path(one.path), size) != compute_partial_hash(Path(two.path), size)
+
+
+def test_partial_hash_sees_last_byte_just_over_64_kb():
+    head = b"a" * PARTIAL_HASH_BYTES
+    with tempfile.TemporaryDirectory() as tmp:
+        one = write(Path(tmp) / "one.pdf", head + b"x")
+        two = write(Path(tmp) / "two.pdf", head + b"y")
+        assert compute_partial_hash(Path(one.path), one.size_bytes) != \
+            compute_partial_hash(Path(two.path), two.size_bytes)
+
+
+def test_partial_hash_sees_tail_of_large_files():
+    body = b"a" * (PARTIAL_HASH_BYTES * 3)
+    with tempfile.TemporaryDirectory() as tmp:
+        one = write(Path(tmp) / "one.pdf", body + b"x")
+        two = write(Path(tmp) / "two.pdf", body + b"y")
+        assert compute_partial_hash(Path(one.path), one.size_bytes) != \
+            compute_partial_hash(Path(two.path), two.size_bytes)
+
+
+def test_partial_hash_equal_for_identical_files():
+    data = bytes(range(256)) * 700
+    with tempfile.TemporaryDirectory() as tmp:
+        one = write(Path(tmp) / "one.pdf", data)
+        two = write(Path(tmp) / "two.pdf", data)
+        assert compute_partial_hash(Path(one.path), len(data)) == compute_partial_hash(Path(two.path), len(data))
+
+
+def test_partial_hashes_only_keeps_tail_differences_apart():
+    head = b"a" * PARTIAL_HASH_BYTES
+    with tempfile.TemporaryDirectory() as tmp:
+        files = [
+            write(Path(tmp) / "one.pdf", head + b"x" * 1000),
+            write(Path(tmp) / "two.pdf", head + b"y" * 1000),
+        ]
+        groups = mark_duplicates(files, partial_hashes_only=True)
+        assert groups == [], groups
+        assert not any(f.is_duplicate for f in files)
+
+
+def test_duplicates_marked_against_first_copy():
+    with tempfile.TemporaryDirectory() as tmp:
+        files = [
+            write(Path(tmp) / "b.pdf", b"same content"),
+            write(Path(tmp) / "a.pdf", b"same content"),
+            write(Path(tmp) / "c.pdf", b"other content"),
+        ]
+        groups = mark_duplicates(files)
+        by_name = {Path(f.path).name: f for f in files}
+        assert len(groups) == 1, groups
+        assert by_name["b.pdf"].duplicate_of == by_name["a.pdf"].path
+        assert not by_name["a.pdf"].is_duplicate and not by_name["c.pdf"].is_duplicate
+
+
+if __name__ == "__main__":
+    failed = 0
+    for name, func in list(globals().items()):
+        if name.startswith("test_") and callable(func):
+            try:
+                func()
+                print(f"  [PASS] {name}")
+            except AssertionError as e:
+                failed += 1
+                print(f"  [FAIL] {name}: {e}")
+    sys.exit(1 if failed else 0)
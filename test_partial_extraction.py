@@ -0,0 +1,221 @@
+#!/usr/bin/env python3
+"""
+Tests for indexing PDFs whose extraction times out (document_processor.py).
+
+With salvage_partial on, a PDF whose extraction hits extraction_timeout
+or extraction_inactivity_timeout is indexed with the pages read so far
+and marked partial; ingesting it again completes it. A snapshot the
+abandoned extraction still has open is deleted once it lets go.
+
+The PDF reader is replaced with fake pages that take a set time each,
+and the vector store with an in-memory collection, so no model or real
+PDF is needed.
+
+Usage:
+    python test_partial_extraction.py      (or: pytest test_partial_extraction.py)
+"""
+
+import re
+import sys
+import time
+import tempfile
+from pathlib import Path
+
+import document_processor
+from document_processor import DocumentProcessor
+
+
+class FakeCollection:
+    """In-memory stand-in for the Chroma collection."""
+
+    name = "jan_documents"
+
+    def __init__(self, metadata=None):
+        self.metadata = metadata or {}
+        self.rows = {}
+
+    def _matches(self, metadata, where):
+        if not where:
+            return True
+        if "$and" in where:
+            return all(self._matches(metadata, w) for w in where["$and"])
+        return all(metadata.get(k) == v for k, v in where.items())
+
+    def add(self, ids, documents, embeddings, metadatas):
+        for row in zip(ids, documents, embeddings, metadatas):
+            self.rows[row[0]] = row[1:]
+
+    def get(self, ids=None, where=None, include=None, limit=None, offset=0):
+        found = [
+            (i, row) for i, row in self.rows.items()
+            if (ids is None or i in ids) and self._matches(row[2], where)
+        ]
+        found = found[offset:offset + limit] if limit else found[offset:]
+        return {
+            "ids": [i for i, _ in found],
+            "documents": [row[0] for _, row in found],
+            "embeddings": [row[1] for _, row in found],
+            "metadatas": [row[2] for _, row in found]
+        }
+
+    def update(self, ids, metadatas=None, documents=None, embeddings=None):
+        for n, i in enumerate(ids):
+            document, embedding, metadata = self.rows[i]
+            self.rows[i] = (
+                documents[n] if documents else document,
+                embeddings[n] if embeddings else embedding,
+                metadatas[n] if metadatas else metadata
+            )
+
+    def delete(self, ids=None, where=None):
+        for i in list(self.rows):
+            if (ids and i in ids) or (where and self._matches(self.rows[i][2], where)):
+                del self.rows[i]
+
+    def count(self):
+        return len(self.rows)
+
+
+class FakeClient:
+    def __init__(self, *args, **kwargs):
+        self.collections = {}
+
+    def get_or_create_collection(self, name, metadata=None):
+        return self.collections.setdefault(name, FakeCollection(metadata))
+
+
+class Vectors(list):
+    def tolist(self):
+        return list(self)
+
+
+class FakeEmbedder:
+    def __init__(self, model_name, *args, **kwargs):
+        pass
+
+    def encode(self, texts, **kwargs):
+        return Vectors([float(len(text)), 1.0] for text in texts)
+
+
+class FakePage:
+    def __init__(self, number, delay):
+        self.number = number
+        self.delay = delay
+
+    def get_text(self):
+        time.sleep(self.delay)
+        return f"Page {self.number} discusses topic number {self.number} in some detail. " * 3
+
+
+class FakePdf(list):
+    """A PDF whose pages take page_delays[n] seconds each to read."""
+
+    needs_pass = False
+
+    def __init__(self, page_delays):
+        super().__init__(FakePage(n + 1, delay) for n, delay in enumerate(page_delays))
+        self.closed = False
+
+    def close(self):
+        self.closed = True
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *exc):
+        self.close()
+
+
+def make_processor(tmp, page_delays, **options):
+    """A processor over the fakes, and a PDF whose pages read as page_delays."""
+    document_processor.chromadb.PersistentClient = FakeClient
+    document_processor.SentenceTransformer = FakeEmbedder
+    document_processor.fitz.open = lambda path: FakePdf(page_delays)
+    processor = DocumentProcessor(persist_directory=str(Path(tmp) / "store"), **options)
+    # Only the page text comes from the fake pages
+    processor.extractor.extract_properties = lambda *args, **kwargs: {}
+    for method in ("extract_notes", "extract_tables", "extract_figures"):
+        setattr(processor.extractor, method, lambda *args, **kwargs: [])
+    pdf = Path(tmp) / "report.pdf"
+    pdf.write_bytes(b"%PDF-1.7 fake")
+    return processor, pdf
+
+
+def stored_pages(processor, doc_hash):
+    """Numbers of the pages whose text is in the document's stored chunks."""
+    documents = processor.vector_store.collection.get(where={"doc_hash": doc_hash})["documents"]
+    return {int(n) for text in documents for n in re.findall(r"Page (\d+) discusses", text)}
+
+
+def test_timed_out_extraction_indexed_partially_then_completed():
+    with tempfile.TemporaryDirectory() as tmp:
+        page_delays = [0.02] * 30
+        processor, pdf = make_processor(tmp, page_delays, extraction_timeout=0.3)
+
+        partial = processor.ingest(pdf)
+        assert partial.partial, partial.to_dict()
+        assert 0 < partial.pages_completed < 30 and partial.total_pages == 30
+        pages = stored_pages(processor, partial.doc_hash)
+        assert pages and max(pages) <= partial.pages_completed, pages
+
+        page_delays[:] = [0.0] * 30
+        complete = processor.ingest(pdf)
+        assert not complete.partial
+        assert complete.doc_hash == partial.doc_hash
+        assert stored_pages(processor, complete.doc_hash) == set(range(1, 31))
+        assert processor.ingest(pdf) is complete
+
+
+def test_stalled_page_indexes_pages_before_it():
+    with tempfile.TemporaryDirectory() as tmp:
+        # Page 13 hangs, past the first checkpoint of SALVAGE_CHECKPOINT_PAGES
+        page_delays = [0.0] * 12 + [2.0] + [0.0] * 7
+        processor, pdf = make_processor(tmp, page_delays, extraction_inactivity_timeout=0.3)
+
+        partial = processor.ingest(pdf)
+        assert partial.partial and partial.pages_completed == 12, partial.to_dict()
+        assert stored_pages(processor, partial.doc_hash) == set(range(1, 13))
+
+        page_delays[:] = [0.0] * 20
+        complete = processor.ingest(pdf)
+        assert not complete.partial
+        assert stored_pages(processor, complete.doc_hash) == set(range(1, 21))
+
+
+def test_snapshot_removed_after_stalled_worker_exits():
+    with tempfile.TemporaryDirectory() as tmp:
+        grace = document_processor.EXTRACTION_CANCEL_GRACE
+        document_processor.EXTRACTION_CANCEL_GRACE = 0.0
+        page_delays = [0.0] * 3 + [1.0] + [0.0] * 2
+        processor, pdf = make_processor(tmp, page_delays, extraction_inactivity_timeout=0.2, snapshot_files=True)
+        opened = []
+        document_processor.fitz.open = lambda path: opened.append(FakePdf(page_delays)) or opened[-1]
+        snapshots = []
+        acquire = processor._acquire_source
+        processor._acquire_source = lambda path: snapshots.append(acquire(path)) or snapshots[-1]
+        try:
+            partial = processor.ingest(pdf)
+        finally:
+            document_processor.EXTRACTION_CANCEL_GRACE = grace
+
+        assert partial.partial and partial.pages_completed == 3, partial.to_dict()
+        # The worker is still reading page 4 from the snapshot
+        assert snapshots[0].exists() and not opened[-1].closed
+        deadline = time.monotonic() + 3
+        while snapshots[0].exists() and time.monotonic() < deadline:
+            time.sleep(0.05)
+        assert not snapshots[0].exists()
+        assert opened[-1].closed
+
+
+if __name__ == "__main__":
+    failed = 0
+    for name, func in list(globals().items()):
+        if name.startswith("test_") and callable(func):
+            try:
+                func()
+                print(f"  [PASS] {name}")
+            except AssertionError as e:
+                failed += 1
+                print(f"  [FAIL] {name}: {e}")
+    sys.exit(1 if failed else 0)
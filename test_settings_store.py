@@ -0,0 +1,135 @@
+#!/usr/bin/env python3
+"""
+Tests for runtime settings (settings_store.py): migration of older
+settings.json files and validation of changed values.
+
+Usage:
+    python test_settings_store.py      (or: pytest test_settings_store.py)
+"""
+
+import sys
+import json
+import tempfile
+from pathlib import Path
+from typing import Optional
+
+from pydantic import BaseModel
+
+from settings_store import SETTINGS_VERSION, SettingsStore
+
+
+class Config(BaseModel):
+    """The editable ProxyConfig fields (plus one that isn't editable)."""
+    auto_inject: bool = True
+    max_context_tokens: int = 400
+    max_chunks: int = 5
+    relevance_threshold: float = 0.3
+    extraction_timeout: Optional[float] = None
+    extraction_inactivity_timeout: Optional[float] = None
+    salvage_partial: bool = True
+    disk_reserve_mb: int = 500
+    disk_guard_mode: str = "refuse"
+    retention_check_hours: float = 24.0
+    optimize_after_deleted: int = 5000
+    progress_events_per_second: float = 2.0
+    jan_base_url: str = "http://localhost:1337"
+
+
+def make_store(tmp, is_locked=None):
+    config = Config()
+    return SettingsStore(Path(tmp) / "settings.json", config, is_locked=is_locked), config
+
+
+def test_unversioned_file_migrated():
+    with tempfile.TemporaryDirectory() as tmp:
+        path = Path(tmp) / "settings.json"
+        path.write_text(json.dumps({"MAX_CHUNKS": 3, "AUTO_INJECT": False}))
+        store, config = make_store(tmp)
+
+        applied = store.load()
+        assert sorted(applied) == ["auto_inject", "max_chunks"], applied
+        assert config.max_chunks == 3 and config.auto_inject is False
+
+        saved = json.loads(path.read_text())
+        assert saved == {"version": SETTINGS_VERSION, "settings": {"max_chunks": 3, "auto_inject": False}}, saved
+
+
+def test_invalid_saved_settings_dropped_on_load():
+    with tempfile.TemporaryDirectory() as tmp:
+        (Path(tmp) / "settings.json").write_text(json.dumps({
+            "version": SETTINGS_VERSION,
+            "settings": {"max_chunks": "many", "jan_base_url": "http://evil", "relevance_threshold": 0.5}
+        }))
+        store, config = make_store(tmp)
+        assert store.load() == ["relevance_threshold"]
+        assert config.max_chunks == 5
+        assert config.jan_base_url == "http://localhost:1337"
+
+
+def test_unreadable_file_ignored():
+    with tempfile.TemporaryDirectory() as tmp:
+        (Path(tmp) / "settings.json").write_text("{not json")
+        store, config = make_store(tmp)
+        assert store.load() == []
+        assert config == Config()
+
+
+def test_update_validates_all_before_changing_any():
+    with tempfile.TemporaryDirectory() as tmp:
+        store, config = make_store(tmp)
+        for changes, error in (
+            ({"max_chunks": 3, "jan_base_url": "http://evil"}, KeyError),
+            ({"max_chunks": 3, "disk_guard_mode": "ignore"}, ValueError),
+            ({"max_chunks": -1}, ValueError),
+            ({"extraction_timeout": "soon"}, ValueError),
+        ):
+            try:
+                store.update_settings(changes)
+            except error:
+                pass
+            else:
+                raise AssertionError(f"{changes} was accepted")
+        assert config.max_chunks == 5
+        assert not (Path(tmp) / "settings.json").exists()
+
+
+def test_update_persists_and_notifies_changes_only():
+    with tempfile.TemporaryDirectory() as tmp:
+        store, config = make_store(tmp)
+        notified = []
+        store.subscribe(notified.append)
+
+        changed = store.update_settings({"max_chunks": "8", "auto_inject": True})
+        assert changed == {"max_chunks": 8}, changed
+        assert notified == [{"max_chunks": 8}], notified
+        assert config.max_chunks == 8
+
+        reloaded, reloaded_config = make_store(tmp)
+        reloaded.load()
+        assert reloaded_config.max_chunks == 8
+
+
+def test_pinned_setting_refused():
+    with tempfile.TemporaryDirectory() as tmp:
+        store, config = make_store(tmp, is_locked=lambda name: name == "auto_inject")
+        try:
+            store.update_settings({"auto_inject": False})
+        except PermissionError:
+            pass
+        else:
+            raise AssertionError("a setting pinned by policy was changed")
+        assert config.auto_inject is True
+        assert store.get_settings()["settings"]["auto_inject"]["locked"] is True
+
+
+if __name__ == "__main__":
+    failed = 0
+    for name, func in list(globals().items()):
+        if name.startswith("test_") and callable(func):
+            try:
+                func()
+                print(f"  [PASS] {name}")
+            except AssertionError as e:
+                failed += 1
+                print(f"  [FAIL] {name}: {e}")
+    sys.exit(1 if failed else 0)
@@ -0,0 +1,96 @@
+#!/usr/bin/env python3
+"""
+Tests for webhook signing and the delivery log (webhooks.py).
+
+Usage:
+    python test_webhooks.py      (or: pytest test_webhooks.py)
+"""
+
+import sys
+import hmac
+import json
+import hashlib
+import tempfile
+from pathlib import Path
+from types import SimpleNamespace
+
+import webhooks
+from webhooks import SIGNATURE_HEADER, WebhookManager, sign_payload
+
+
+def test_sign_payload_is_hmac_sha256_hex():
+    # Published HMAC-SHA256 test vector
+    body = b"The quick brown fox jumps over the lazy dog"
+    assert sign_payload("key", body) == \
+        "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+
+
+def test_signature_depends_on_secret_and_body():
+    body = b'{"event": "indexing.completed"}'
+    assert sign_payload("one", body) != sign_payload("two", body)
+    assert sign_payload("one", body) != sign_payload("one", body + b" ")
+
+
+def deliver(secret, tests=1, log_dir=None):
+    """Send test events to a webhook and return the last request httpx would have made."""
+    sent = {}
+
+    def post(url, content, headers, timeout):
+        sent.update(url=url, content=content, headers=headers)
+        return SimpleNamespace(status_code=200)
+
+    real_post = webhooks.httpx.post
+    webhooks.httpx.post = post
+    try:
+        with tempfile.TemporaryDirectory() as tmp:
+            log_dir = Path(log_dir or tmp)
+            manager = WebhookManager(Path(tmp) / "webhooks.json", log_dir / "webhook_deliveries.jsonl")
+            webhook = manager.add_webhook("https://example.com/hook", ["indexing.completed"], secret=secret)
+            for _ in range(tests):
+                result = manager.send_test(webhook.id)
+    finally:
+        webhooks.httpx.post = real_post
+    assert result["success"], result
+    return sent
+
+
+def test_delivery_signed_over_raw_body():
+    sent = deliver("s3cret")
+    expected = hmac.new(b"s3cret", sent["content"], hashlib.sha256).hexdigest()
+    assert hmac.compare_digest(sent["headers"][SIGNATURE_HEADER], f"sha256={expected}")
+    assert json.loads(sent["content"])["event"] == "test"
+
+
+def test_delivery_without_secret_unsigned():
+    sent = deliver(None)
+    assert SIGNATURE_HEADER not in sent["headers"], sent["headers"]
+
+
+def test_delivery_log_keeps_last_attempts_only():
+    limit = webhooks.MAX_LOGGED_DELIVERIES
+    webhooks.MAX_LOGGED_DELIVERIES = 3
+    try:
+        with tempfile.TemporaryDirectory() as tmp:
+            sent = deliver(None, tests=5, log_dir=tmp)
+            log_path = Path(tmp) / "webhook_deliveries.jsonl"
+            logged = [json.loads(line) for line in log_path.read_text().splitlines()]
+            manager = WebhookManager(Path(tmp) / "webhooks.json", log_path)
+            listed = manager.list_deliveries()
+    finally:
+        webhooks.MAX_LOGGED_DELIVERIES = limit
+    assert len(logged) == 3, logged
+    assert logged[-1]["delivery_id"] == json.loads(sent["content"])["id"]
+    assert [e["delivery_id"] for e in listed] == [e["delivery_id"] for e in reversed(logged)]
+
+
+if __name__ == "__main__":
+    failed = 0
+    for name, func in list(globals().items()):
+        if name.startswith("test_") and callable(func):
+            try:
+                func()
+                print(f"  [PASS] {name}")
+            except AssertionError as e:
+                failed += 1
+                print(f"  [FAIL] {name}: {e}")
+    sys.exit(1 if failed else 0)
@@ -0,0 +1,287 @@
+"""
+Trash Store for Jan Document Plugin
+
+Soft delete for indexed documents and the whole collection:
+- Deleted chunks (text, metadata and embeddings) are moved to a trash
+  directory instead of being dropped
+- Entries can be restored without re-extracting or re-embedding
+- Entries older than the retention window are purged automatically
+
+Each entry is one JSON file: <trash_dir>/<trash_id>.json
+"""
+
+import json
+import uuid
+import logging
+from pathlib import Path
+from typing import List, Dict, Optional
+from dataclasses import dataclass
+from datetime import datetime, timedelta
+
+from document_processor import DocumentProcessor, DocumentChunk, ProcessedDocument
+
+logger = logging.getLogger(__name__)
+
+# Metadata keys LocalVectorStore.add_chunks adds on top of DocumentChunk.metadata
+STORE_METADATA_KEYS = {"filename", "doc_hash", "chunk_index"}
+
+
+@dataclass
+class TrashEntry:
+    """A deleted document (or collection) waiting in the trash."""
+    trash_id: str
+    kind: str                        # "document" or "collection"
+    name: str                        # Filename, or collection name
+    doc_hashes: List[str]
+    chunk_count: int
+    size_bytes: int                  # Size of the entry file on disk
+    deleted_at: datetime
+    expires_at: datetime
+
+    def to_dict(self) -> Dict:
+        return {
+            "trash_id": self.trash_id,
+            "kind": self.kind,
+            "name": self.name,
+            "doc_hashes": self.doc_hashes,
+            "chunk_count": self.chunk_count,
+            "size_bytes": self.size_bytes,
+            "deleted_at": self.deleted_at.isoformat(),
+            "expires_at": self.expires_at.isoformat()
+        }
+
+    @classmethod
+    def from_dict(cls, data: Dict) -> "TrashEntry":
+        return cls(
+            trash_id=data["trash_id"],
+            kind=data["kind"],
+            name=data["name"],
+            doc_hashes=data.get("doc_hashes", []),
+            chunk_count=data.get("chunk_count", 0),
+            size_bytes=0,
+            deleted_at=datetime.fromisoformat(data["deleted_at"]),
+            expires_at=datetime.fromisoformat(data["expires_at"])
+        )
+
+
+class TrashStore:
+    """
+    Moves deleted documents to a trash directory and restores them.
+
+    Usage:
+        trash = TrashStore(processor, "./jan_doc_store/trash")
+        entry = trash.trash_document(doc_hash)
+        trash.restore_from_trash(entry.trash_id)
+    """
+
+    def __init__(
+        self,
+        document_processor: DocumentProcessor,
+        trash_directory: Path,
+        retention_days: int = 30
+    ):
+        """
+        Initialize trash store.
+
+        Args:
+            document_processor: Processor whose vector store and records are trashed
+            trash_directory: Directory holding trash entries
+            retention_days: Days an entry is kept before it is purged
+        """
+        self.processor = document_processor
+        self.trash_directory = Path(trash_directory)
+        self.trash_directory.mkdir(parents=True, exist_ok=True)
+        self.retention_days = retention_days
+
+    def _entry_path(self, trash_id: str) -> Path:
+        return self.trash_directory / f"{trash_id}.json"
+
+    def _save(self, kind: str, name: str, chunks: Dict, records: List[Dict]) -> TrashEntry:
+        """Write a trash entry file."""
+        now = datetime.now()
+        doc_hashes = sorted({m.get("doc_hash") for m in chunks["metadatas"] if m.get("doc_hash")})
+
+        entry = TrashEntry(
+            trash_id=uuid.uuid4().hex[:12],
+            kind=kind,
+            name=name,
+            doc_hashes=doc_hashes,
+            chunk_count=len(chunks["ids"]),
+            size_bytes=0,
+            deleted_at=now,
+            expires_at=now + timedelta(days=self.retention_days)
+        )
+
+        path = self._entry_path(entry.trash_id)
+        payload = {
+            "entry": entry.to_dict(),
+            "embedding_model": self.processor.vector_store.embedding_model,
+            "records": records,
+            "chunks": chunks
+        }
+        path.write_text(json.dumps(payload), encoding="utf-8")
+
+        entry.size_bytes = path.stat().st_size
+        return entry
+
+    def _load(self, trash_id: str) -> Dict:
+        path = self._entry_path(trash_id)
+        if not path.exists():
+            raise KeyError(f"Trash entry not found: {trash_id}")
+        return json.loads(path.read_text(encoding="utf-8"))
+
+    def trash_document(self, doc_hash: str) -> TrashEntry:
+        """
+        Move a document's chunks and record to the trash.
+
+        Raises:
+            KeyError: If the document has no stored chunks or record
+            CollectionReadOnlyError: If the collection is read-only
+        """
+        self.processor.check_writable("delete")
+        store = self.processor.vector_store
+        chunks = store.get_all_chunks(include_embeddings=True, doc_hash=doc_hash)
+        record = self.processor.processed_docs.get(doc_hash)
+
+        if not chunks["ids"] and record is None:
+            raise KeyError(f"Document not found: {doc_hash}")
+
+        if record is not None:
+            name = record.filename
+        elif chunks["metadatas"]:
+            name = chunks["metadatas"][0].get("filename", doc_hash)
+        else:
+            name = doc_hash
+
+        entry = self._save(
+            "document", name, chunks,
+            [record.to_dict()] if record is not None else []
+        )
+
+        store.delete_document(doc_hash)
+        self.processor.processed_docs.pop(doc_hash, None)
+
+        logger.info(f"Moved to trash: {name} ({doc_hash}) as {entry.trash_id}")
+        return entry
+
+    def trash_collection(self, name: str = "documents") -> TrashEntry:
+        """Move every document in the collection to the trash as one entry."""
+        self.processor.check_writable("delete")
+        store = self.processor.vector_store
+        chunks = store.get_all_chunks(include_embeddings=True)
+        records = [doc.to_dict() for doc in self.processor.processed_docs.values()]
+
+        entry = self._save("collection", name, chunks, records)
+
+        store.delete_chunks(chunks["ids"])
+        self.processor.processed_docs.clear()
+
+        logger.info(f"Moved collection to trash: {entry.chunk_count} chunks as {entry.trash_id}")
+        return entry
+
+    def list_trash(self) -> List[TrashEntry]:
+        """List trash entries, newest first (expired entries are purged first)."""
+        self.purge_expired()
+
+        entries = []
+        for path in self.trash_directory.glob("*.json"):
+            try:
+                data = json.loads(path.read_text(encoding="utf-8"))
+                entry = TrashEntry.from_dict(data["entry"])
+                entry.size_bytes = path.stat().st_size
+                entries.append(entry)
+            except (OSError, ValueError, KeyError) as e:
+                logger.warning(f"Skipping unreadable trash entry {path.name}: {e}")
+
+        entries.sort(key=lambda e: e.deleted_at, reverse=True)
+        return entries
+
+    def restore_from_trash(self, trash_id: str) -> TrashEntry:
+        """
+        Put a trashed document or collection back into the index.
+
+        Chunks whose ids are already indexed (e.g. the document was
+        re-uploaded since) are skipped.
+
+        Raises:
+            KeyError: If the entry does not exist
+        """
+        data = self._load(trash_id)
+        entry = TrashEntry.from_dict(data["entry"])
+        chunks = data["chunks"]
+        store = self.processor.vector_store
+
+        # Vectors from another embedding model (e.g. before a shadow index
+        # was promoted) can't be mixed in, so those chunks are re-embedded
+        if data.get("embedding_model", store.embedding_model) != store.embedding_model:
+            chunks = {key: values for key, values in chunks.items() if key != "embeddings"}
+
+        existing = set(store.get_all_chunks()["ids"])
+        keep = [i for i, chunk_id in enumerate(chunks["ids"]) if chunk_id not in existing]
+        store.restore_chunks({key: [values[i] for i in keep] for key, values in chunks.items()})
+
+        # Rebuild in-memory records from the stored chunks
+        by_doc: Dict[str, List[DocumentChunk]] = {}
+        for content, meta in zip(chunks["documents"], chunks["metadatas"]):
+            by_doc.setdefault(meta.get("doc_hash"), []).append(DocumentChunk(
+                content=content,
+                metadata={k: v for k, v in meta.items() if k not in STORE_METADATA_KEYS},
+                chunk_index=meta.get("chunk_index", 0),
+                doc_hash=meta.get("doc_hash")
+            ))
+
+        for record in data.get("records", []):
+            doc_chunks = sorted(by_doc.get(record["doc_hash"], []), key=lambda c: c.chunk_index)
+            self.processor.processed_docs[record["doc_hash"]] = ProcessedDocument.from_dict(record, doc_chunks)
+
+        self._entry_path(trash_id).unlink()
+
+        logger.info(f"Restored from trash: {entry.name} ({entry.chunk_count} chunks)")
+        return entry
+
+    def delete_entry(self, trash_id: str):
+        """Permanently delete one trash entry."""
+        path = self._entry_path(trash_id)
+        if not path.exists():
+            raise KeyError(f"Trash entry not found: {trash_id}")
+        path.unlink()
+
+    def empty_trash(self) -> int:
+        """Permanently delete every trash entry. Returns the number removed."""
+        removed = 0
+        for path in self.trash_directory.glob("*.json"):
+            try:
+                path.unlink()
+                removed += 1
+            except OSError as e:
+                logger.warning(f"Could not delete trash entry {path.name}: {e}")
+
+        logger.info(f"Emptied trash: {removed} entries")
+        return removed
+
+    def purge_expired(self, now: Optional[datetime] = None) -> int:
+        """Delete entries past their retention window. Returns the number removed."""
+        now = now or datetime.now()
+        removed = 0
+
+        for path in self.trash_directory.glob("*.json"):
+            try:
+                data = json.loads(path.read_text(encoding="utf-8"))
+                if datetime.fromisoformat(data["entry"]["expires_at"]) <= now:
+                    path.unlink()
+                    removed += 1
+            except (OSError, ValueError, KeyError) as e:
+                logger.warning(f"Could not check trash entry {path.name}: {e}")
+
+        if removed:
+            logger.info(f"Purged {removed} expired trash entries")
+        return removed
+
+    def get_usage(self) -> Dict:
+        """Entry count and bytes used by the trash."""
+        files = list(self.trash_directory.glob("*.json"))
+        return {
+            "entries": len(files),
+            "size_bytes": sum(f.stat().st_size for f in files),
+            "retention_days": self.retention_days
+        }
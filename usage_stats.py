@@ -0,0 +1,197 @@
+"""
+Usage Statistics for Jan Document Plugin
+
+Counts which features are used so they can be shown in a local
+dashboard. Everything stays on this machine unless the user explicitly
+opts in to uploading:
+
+- Counters are feature names only (e.g. "POST /documents") - no file
+  names, queries, document text or paths are ever recorded
+- Uploading requires BOTH an upload URL in the config AND an opt-in made
+  through opt_in(); consent is checked in upload(), not just the UI
+- Opting out removes the consent and the anonymous install id; the
+  counters themselves never leave the machine without consent
+"""
+
+import json
+import time
+import uuid
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, Optional
+from datetime import datetime
+
+import httpx
+
+logger = logging.getLogger(__name__)
+
+# Write counters to disk at most this often (seconds)
+SAVE_INTERVAL = 30
+
+
+class UsageConsentError(PermissionError):
+    """Upload attempted without an opt-in (or without an upload URL)."""
+    pass
+
+
+class UsageStats:
+    """
+    Local feature counters with an opt-in upload path.
+
+    Usage:
+        stats = UsageStats(Path("./jan_doc_store/usage_stats.json"))
+        stats.record("POST /documents")
+        stats.get_usage_stats()
+    """
+
+    def __init__(
+        self,
+        path: Path,
+        upload_url: Optional[str] = None,
+        app_version: str = "",
+        enabled: bool = True
+    ):
+        """
+        Initialize usage statistics.
+
+        Args:
+            path: JSON file holding counters and consent
+            upload_url: Where opted-in uploads are sent (None/empty = upload disabled)
+            app_version: Included in uploads
+            enabled: Record counters at all
+        """
+        self.path = Path(path)
+        self.upload_url = upload_url or None
+        self.app_version = app_version
+        self.enabled = enabled
+        self._lock = threading.Lock()
+        self._last_save = 0.0
+        self._dirty = False
+        self._data = {
+            "counters": {},
+            "since": datetime.now().isoformat(),
+            "consent": None,        # {"opted_in_at", "install_id"} once opted in
+            "last_upload": None
+        }
+        self._load()
+
+    def _load(self):
+        if not self.path.exists():
+            return
+        try:
+            self._data.update(json.loads(self.path.read_text(encoding="utf-8")))
+        except (OSError, ValueError) as e:
+            logger.warning(f"Could not read usage stats: {e}")
+
+    def _save(self):
+        try:
+            self.path.parent.mkdir(parents=True, exist_ok=True)
+            self.path.write_text(json.dumps(self._data, indent=2), encoding="utf-8")
+            self._dirty = False
+            self._last_save = time.monotonic()
+        except OSError as e:
+            logger.warning(f"Could not save usage stats: {e}")
+
+    def record(self, feature: str):
+        """Count one use of a feature."""
+        if not self.enabled:
+            return
+        with self._lock:
+            counters = self._data["counters"]
+            counters[feature] = counters.get(feature, 0) + 1
+            self._dirty = True
+            if time.monotonic() - self._last_save >= SAVE_INTERVAL:
+                self._save()
+
+    def flush(self):
+        """Write pending counts to disk."""
+        with self._lock:
+            if self._dirty:
+                self._save()
+
+    @property
+    def opted_in(self) -> bool:
+        return bool(self._data.get("consent"))
+
+    def get_usage_stats(self) -> Dict:
+        """Counters (most used first) plus recording and consent state."""
+        with self._lock:
+            counters = dict(sorted(
+                self._data["counters"].items(), key=lambda item: item[1], reverse=True
+            ))
+            consent = self._data.get("consent")
+            return {
+                "enabled": self.enabled,
+                "since": self._data["since"],
+                "total": sum(counters.values()),
+                "counters": counters,
+                "upload": {
+                    "available": self.upload_url is not None,
+                    "opted_in": bool(consent),
+                    "opted_in_at": consent["opted_in_at"] if consent else None,
+                    "last_upload": self._data.get("last_upload")
+                }
+            }
+
+    def opt_in(self) -> Dict:
+        """Record explicit consent to upload counters."""
+        with self._lock:
+            if not self._data.get("consent"):
+                self._data["consent"] = {
+                    "opted_in_at": datetime.now().isoformat(),
+                    "install_id": uuid.uuid4().hex   # Random, not derived from the machine
+                }
+                self._save()
+        logger.info("Usage statistics upload: opted in")
+        return self.get_usage_stats()
+
+    def opt_out(self) -> Dict:
+        """Revoke consent and forget the anonymous install id."""
+        with self._lock:
+            self._data["consent"] = None
+            self._save()
+        logger.info("Usage statistics upload: opted out")
+        return self.get_usage_stats()
+
+    def reset(self):
+        """Clear all counters."""
+        with self._lock:
+            self._data["counters"] = {}
+            self._data["since"] = datetime.now().isoformat()
+            self._save()
+
+    def build_upload_payload(self) -> Dict:
+        """Exactly what upload() sends: install id, version and counters."""
+        with self._lock:
+            consent = self._data.get("consent") or {}
+            return {
+                "install_id": consent.get("install_id"),
+                "app_version": self.app_version,
+                "since": self._data["since"],
+                "counters": dict(self._data["counters"])
+            }
+
+    def upload(self, timeout: float = 10.0) -> Dict:
+        """
+        Send counters to the configured upload URL.
+
+        Raises:
+            UsageConsentError: If the user has not opted in or no URL is configured
+            httpx.HTTPError: If the upload fails
+        """
+        if not self.opted_in:
+            raise UsageConsentError("Usage statistics upload requires opting in first")
+        if not self.upload_url:
+            raise UsageConsentError("No usage statistics upload URL is configured")
+
+        payload = self.build_upload_payload()
+        response = httpx.post(self.upload_url, json=payload, timeout=timeout)
+        response.raise_for_status()
+
+        with self._lock:
+            self._data["last_upload"] = datetime.now().isoformat()
+            self._save()
+
+        logger.info(f"Uploaded usage statistics ({len(payload['counters'])} features)")
+        return payload
@@ -0,0 +1,295 @@
+"""
+Webhook Notifications for Jan Document Plugin
+
+Lets automation react to pipeline events: each webhook receives a JSON
+POST for the events it subscribes to.
+
+Events:
+- indexing.completed / indexing.failed: uploads, batches, Jan thread indexing
+- export.completed / export.failed: static search exports
+- import.completed / import.failed: legacy data migrations
+- settings.changed: names of the runtime settings that changed (not their values)
+
+Payload: {"id": <delivery id>, "event": ..., "time": ..., "request_id": ..., "data": {...}}
+(request_id is the X-Request-ID of the API request that caused the event)
+
+With a secret, the body is signed with HMAC-SHA256 and the hex digest
+sent as X-Webhook-Signature: sha256=<digest>. Receivers should compute
+the digest over the raw body and compare in constant time.
+
+Deliveries run in the background and are retried with backoff; the
+outcomes of the last MAX_LOGGED_DELIVERIES attempts are kept in
+<storage>/webhook_deliveries.jsonl.
+Webhooks are stored in <storage>/webhooks.json (secrets included, so
+the storage folder should not be shared).
+"""
+
+import os
+import hmac
+import json
+import time
+import uuid
+import hashlib
+import logging
+import threading
+from pathlib import Path
+from typing import Dict, List, Optional
+from dataclasses import dataclass, field, asdict
+from datetime import datetime
+from urllib.parse import urlparse
+
+import httpx
+
+logger = logging.getLogger(__name__)
+
+WEBHOOK_EVENTS = (
+    "indexing.completed",
+    "indexing.failed",
+    "export.completed",
+    "export.failed",
+    "import.completed",
+    "import.failed",
+    "settings.changed",
+)
+
+SIGNATURE_HEADER = "X-Webhook-Signature"
+
+# Attempts per delivery and the wait before each retry (seconds)
+MAX_ATTEMPTS = 4
+RETRY_BACKOFF = (2.0, 10.0, 30.0)
+DELIVERY_TIMEOUT = 10.0
+
+# Delivery attempts kept in the delivery log (older ones are dropped)
+MAX_LOGGED_DELIVERIES = 1000
+
+
+def sign_payload(secret: str, body: bytes) -> str:
+    """Signature header value for body."""
+    digest = hmac.new(secret.encode("utf-8"), body, hashlib.sha256).hexdigest()
+    return f"sha256={digest}"
+
+
+@dataclass
+class Webhook:
+    """A registered webhook."""
+    id: str
+    url: str
+    events: List[str] = field(default_factory=list)
+    secret: Optional[str] = None
+    created_at: str = ""
+
+    def to_dict(self) -> Dict:
+        """Public form; the secret is never returned."""
+        data = asdict(self)
+        data["has_secret"] = bool(data.pop("secret"))
+        return data
+
+
+class WebhookManager:
+    """
+    Registers webhooks and delivers events to them.
+
+    Usage:
+        hooks = WebhookManager(Path("./jan_doc_store/webhooks.json"), Path("./jan_doc_store/webhook_deliveries.jsonl"))
+        hooks.add_webhook("http://localhost:9000/hook", ["indexing.completed"], secret="s3cret")
+        hooks.emit("indexing.completed", {"filename": "report.pdf", "chunks": 12})
+    """
+
+    def __init__(self, webhook_path: Path, delivery_log_path: Path):
+        """
+        Initialize webhook manager.
+
+        Args:
+            webhook_path: JSON file holding registered webhooks
+            delivery_log_path: JSON-lines file holding the last
+                               MAX_LOGGED_DELIVERIES delivery attempts
+        """
+        self.webhook_path = Path(webhook_path)
+        self.delivery_log_path = Path(delivery_log_path)
+        self._lock = threading.Lock()
+        self._log_lock = threading.Lock()
+        self._webhooks: Dict[str, Webhook] = {}
+        if self.webhook_path.exists():
+            try:
+                data = json.loads(self.webhook_path.read_text(encoding="utf-8"))
+                self._webhooks = {w["id"]: Webhook(**w) for w in data}
+            except (OSError, ValueError, TypeError, KeyError) as e:
+                logger.warning(f"Could not read webhooks: {e}")
+
+    def _save(self):
+        self.webhook_path.parent.mkdir(parents=True, exist_ok=True)
+        self.webhook_path.write_text(
+            json.dumps([asdict(w) for w in self._webhooks.values()], indent=2), encoding="utf-8"
+        )
+
+    def add_webhook(self, url: str, events: List[str], secret: Optional[str] = None) -> Webhook:
+        """
+        Register a webhook for events.
+
+        Raises:
+            ValueError: If the URL is not http(s) or an event is unknown
+        """
+        parsed = urlparse(url)
+        if parsed.scheme not in ("http", "https") or not parsed.netloc:
+            raise ValueError(f"Webhook URL must be http(s): {url}")
+        if not events:
+            raise ValueError("At least one event is required")
+        unknown = [e for e in events if e not in WEBHOOK_EVENTS]
+        if unknown:
+            raise ValueError(
+                f"Unknown event(s): {', '.join(unknown)} (expected {', '.join(WEBHOOK_EVENTS)})"
+            )
+
+        webhook = Webhook(
+            id=uuid.uuid4().hex[:12],
+            url=url,
+            events=list(dict.fromkeys(events)),
+            secret=secret or None,
+            created_at=datetime.now().isoformat()
+        )
+        with self._lock:
+            self._webhooks[webhook.id] = webhook
+            self._save()
+        logger.info(f"Added webhook {webhook.id} for {', '.join(webhook.events)}")
+        return webhook
+
+    def remove_webhook(self, webhook_id: str):
+        """
+        Raises:
+            KeyError: If the webhook doesn't exist
+        """
+        with self._lock:
+            if self._webhooks.pop(webhook_id, None) is None:
+                raise KeyError(f"Webhook not found: {webhook_id}")
+            self._save()
+
+    def list_webhooks(self) -> List[Webhook]:
+        return list(self._webhooks.values())
+
+    def emit(self, event: str, data: Dict, request_id: Optional[str] = None) -> List[str]:
+        """
+        Deliver event to every subscribed webhook in the background.
+
+        Args:
+            event: One of WEBHOOK_EVENTS
+            data: Event payload
+            request_id: Request that caused the event
+
+        Returns:
+            Delivery ids, one per webhook notified
+        """
+        if event not in WEBHOOK_EVENTS:
+            raise ValueError(f"Unknown event: {event}")
+        targets = [w for w in self._webhooks.values() if event in w.events]
+        return [self._start_delivery(w, event, data, request_id) for w in targets]
+
+    def send_test(self, webhook_id: str) -> Dict:
+        """
+        Deliver a test event synchronously (single attempt).
+
+        Raises:
+            KeyError: If the webhook doesn't exist
+        """
+        webhook = self._webhooks.get(webhook_id)
+        if webhook is None:
+            raise KeyError(f"Webhook not found: {webhook_id}")
+        delivery_id = uuid.uuid4().hex
+        body = self._body(delivery_id, "test", {"webhook_id": webhook_id})
+        return self._attempt(webhook, delivery_id, "test", body, attempt=1)
+
+    def _body(self, delivery_id: str, event: str, data: Dict, request_id: Optional[str] = None) -> bytes:
+        payload = {
+            "id": delivery_id,
+            "event": event,
+            "time": datetime.now().isoformat(),
+            "request_id": request_id,
+            "data": data
+        }
+        return json.dumps(payload, default=str).encode("utf-8")
+
+    def _start_delivery(self, webhook: Webhook, event: str, data: Dict, request_id: Optional[str]) -> str:
+        delivery_id = uuid.uuid4().hex
+        body = self._body(delivery_id, event, data, request_id)
+        threading.Thread(
+            target=self._deliver,
+            args=(webhook, delivery_id, event, body),
+            name=f"webhook-{webhook.id}",
+            daemon=True
+        ).start()
+        return delivery_id
+
+    def _deliver(self, webhook: Webhook, delivery_id: str, event: str, body: bytes):
+        for attempt in range(1, MAX_ATTEMPTS + 1):
+            if self._attempt(webhook, delivery_id, event, body, attempt)["success"]:
+                return
+            if attempt < MAX_ATTEMPTS:
+                time.sleep(RETRY_BACKOFF[min(attempt, len(RETRY_BACKOFF)) - 1])
+        logger.warning(f"Webhook {webhook.id}: gave up on {event} after {MAX_ATTEMPTS} attempts")
+
+    def _attempt(self, webhook: Webhook, delivery_id: str, event: str, body: bytes, attempt: int) -> Dict:
+        headers = {
+            "Content-Type": "application/json",
+            "X-Webhook-Event": event,
+            "X-Webhook-Delivery": delivery_id
+        }
+        if webhook.secret:
+            headers[SIGNATURE_HEADER] = sign_payload(webhook.secret, body)
+
+        entry = {
+            "time": datetime.now().isoformat(),
+            "delivery_id": delivery_id,
+            "webhook_id": webhook.id,
+            "event": event,
+            "attempt": attempt,
+            "success": False,
+            "status_code": None,
+            "error": None
+        }
+        started = time.monotonic()
+        try:
+            response = httpx.post(webhook.url, content=body, headers=headers, timeout=DELIVERY_TIMEOUT)
+            entry["status_code"] = response.status_code
+            entry["success"] = 200 <= response.status_code < 300
+            if not entry["success"]:
+                entry["error"] = f"HTTP {response.status_code}"
+        except httpx.HTTPError as e:
+            entry["error"] = str(e) or type(e).__name__
+        entry["duration_ms"] = round((time.monotonic() - started) * 1000)
+        self._log(entry)
+        return entry
+
+    def _log(self, entry: Dict):
+        with self._log_lock:
+            try:
+                lines = []
+                if self.delivery_log_path.exists():
+                    lines = self.delivery_log_path.read_text(encoding="utf-8").splitlines()
+                lines.append(json.dumps(entry))
+                # Keep only the most recent attempts; written to a temp
+                # file first so a crash never leaves a truncated log
+                self.delivery_log_path.parent.mkdir(parents=True, exist_ok=True)
+                temp_path = self.delivery_log_path.with_suffix(".tmp")
+                temp_path.write_text(
+                    "".join(line + "\n" for line in lines[-MAX_LOGGED_DELIVERIES:]), encoding="utf-8"
+                )
+                os.replace(temp_path, self.delivery_log_path)
+            except OSError as e:
+                logger.warning(f"Could not write webhook delivery log: {e}")
+
+    def list_deliveries(self, limit: int = 100, webhook_id: Optional[str] = None) -> List[Dict]:
+        """Most recent delivery attempts first, optionally for one webhook."""
+        if not self.delivery_log_path.exists():
+            return []
+
+        entries = []
+        with self._log_lock:
+            with open(self.delivery_log_path, encoding="utf-8") as f:
+                for line in f:
+                    try:
+                        entry = json.loads(line)
+                    except ValueError:
+                        continue
+                    if webhook_id and entry.get("webhook_id") != webhook_id:
+                        continue
+                    entries.append(entry)
+        return list(reversed(entries))[:limit]
@@ -0,0 +1,137 @@
+"""
+Workload Lanes for Jan Document Plugin
+
+Keeps search responsive while documents are being indexed. Queries and
+indexing share the embedding model and the vector store, so a long batch
+used to make every query wait. Work is now split into two lanes:
+
+- Interactive lane: queries and chat context retrieval run on their own
+  small thread pool, never queued behind indexing work
+- Background lane: indexing runs off the event loop (run_background or
+  the batch processor's threads) and calls yield_to_interactive() before
+  each file and between embedding batches, pausing (up to a limit) while
+  any interactive request is in flight
+"""
+
+import asyncio
+import logging
+import threading
+import time
+from contextlib import contextmanager
+from concurrent.futures import ThreadPoolExecutor
+from typing import Callable, Dict, Optional, Any
+
+logger = logging.getLogger(__name__)
+
+
+class WorkloadLanes:
+    """
+    Interactive/background lanes with interactive priority.
+
+    Usage:
+        lanes = get_workload_lanes()
+        context = await lanes.run_interactive(processor.get_context, query="...")
+        result = await lanes.run_background(processor.ingest, path)
+        # In indexing code, between units of work:
+        lanes.yield_to_interactive()
+    """
+
+    def __init__(self, interactive_workers: int = 2, max_yield_seconds: float = 2.0):
+        """
+        Initialize workload lanes.
+
+        Args:
+            interactive_workers: Threads reserved for queries
+            max_yield_seconds: Longest a background step waits for queries
+                               to finish (0 = never wait)
+        """
+        self.max_yield_seconds = max_yield_seconds
+        self._executor = ThreadPoolExecutor(
+            max_workers=max(1, interactive_workers),
+            thread_name_prefix="query-lane"
+        )
+        self._interactive_workers = max(1, interactive_workers)
+        self._active = 0
+        self._idle = threading.Condition()
+        self._stats = {"interactive_requests": 0, "background_yields": 0, "background_wait_seconds": 0.0}
+
+    @contextmanager
+    def interactive(self):
+        """Mark interactive work in progress (background work yields meanwhile)."""
+        with self._idle:
+            self._active += 1
+            self._stats["interactive_requests"] += 1
+        try:
+            yield
+        finally:
+            with self._idle:
+                self._active -= 1
+                if self._active == 0:
+                    self._idle.notify_all()
+
+    async def run_interactive(self, func: Callable, *args, **kwargs) -> Any:
+        """Run a blocking call on the interactive lane."""
+        def run():
+            with self.interactive():
+                return func(*args, **kwargs)
+
+        loop = asyncio.get_event_loop()
+        return await loop.run_in_executor(self._executor, run)
+
+    async def run_background(self, func: Callable, *args, **kwargs) -> Any:
+        """Run a blocking indexing call off the event loop, after yielding to queries."""
+        def run():
+            self.yield_to_interactive()
+            return func(*args, **kwargs)
+
+        loop = asyncio.get_event_loop()
+        return await loop.run_in_executor(None, run)
+
+    def yield_to_interactive(self, max_wait: Optional[float] = None) -> float:
+        """
+        Wait while interactive work is running. Called by background work.
+
+        Returns:
+            Seconds waited
+        """
+        limit = self.max_yield_seconds if max_wait is None else max_wait
+        if limit <= 0 or self._active == 0:
+            return 0.0
+
+        started = time.monotonic()
+        with self._idle:
+            self._idle.wait_for(lambda: self._active == 0, timeout=limit)
+        waited = time.monotonic() - started
+
+        self._stats["background_yields"] += 1
+        self._stats["background_wait_seconds"] += waited
+        return waited
+
+    def get_status(self) -> Dict:
+        return {
+            "interactive_workers": self._interactive_workers,
+            "active_interactive": self._active,
+            "max_yield_seconds": self.max_yield_seconds,
+            "interactive_requests": self._stats["interactive_requests"],
+            "background_yields": self._stats["background_yields"],
+            "background_wait_seconds": round(self._stats["background_wait_seconds"], 2)
+        }
+
+
+# Singleton instance
+_workload_lanes: Optional[WorkloadLanes] = None
+
+
+def get_workload_lanes() -> WorkloadLanes:
+    """Get or create the singleton workload lanes instance."""
+    global _workload_lanes
+    if _workload_lanes is None:
+        _workload_lanes = WorkloadLanes()
+    return _workload_lanes
+
+
+def configure_workload_lanes(interactive_workers: int, max_yield_seconds: float) -> WorkloadLanes:
+    """Replace the singleton with one using these settings (call at startup)."""
+    global _workload_lanes
+    _workload_lanes = WorkloadLanes(interactive_workers, max_yield_seconds)
+    return _workload_lanes